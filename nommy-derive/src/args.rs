@@ -0,0 +1,93 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::attr::Arena;
+
+/// The generic parameters of the type being derived
+pub struct Args {
+    /// the lifetime parameters, other than a declared arena lifetime
+    lifetimes: Vec<syn::Lifetime>,
+    /// the lifetime parameters as they're declared on the impl, with their bounds
+    lifetime_params: Vec<TokenStream>,
+    /// the type and const parameters as they're declared on the impl, with their bounds
+    params: Vec<TokenStream>,
+    /// the type and const parameters, as they're passed to the type
+    args: Vec<syn::Ident>,
+    /// the where clause of the type, and the bounds of the arena lifetime
+    predicates: Vec<TokenStream>,
+}
+
+impl Args {
+    /// The declared arena lifetime, which is the first lifetime, is left to the arena code
+    pub fn new(generics: &syn::Generics, arena: &Option<Arena>) -> Self {
+        let declared_arena = matches!(arena, Some(Arena { declared: true, .. }));
+
+        let mut lifetimes = Vec::new();
+        let mut lifetime_params = Vec::new();
+        let mut params = Vec::new();
+        let mut args = Vec::new();
+        let mut predicates = Vec::new();
+        for (i, param) in generics.params.iter().enumerate() {
+            match param {
+                syn::GenericParam::Lifetime(lt) => {
+                    let lifetime = &lt.lifetime;
+                    let bounds = &lt.bounds;
+                    if declared_arena && i == 0 {
+                        if !bounds.is_empty() {
+                            predicates.push(quote! { #lifetime: #bounds });
+                        }
+                        continue;
+                    }
+                    lifetimes.push(lifetime.clone());
+                    lifetime_params.push(quote! { #lifetime: #bounds });
+                }
+                syn::GenericParam::Type(tp) => {
+                    let ident = &tp.ident;
+                    let bounds = &tp.bounds;
+                    params.push(quote! { #ident: #bounds });
+                    args.push(ident.clone());
+                }
+                syn::GenericParam::Const(cp) => {
+                    let ident = &cp.ident;
+                    let ty = &cp.ty;
+                    params.push(quote! { const #ident: #ty });
+                    args.push(ident.clone());
+                }
+            }
+        }
+        if let Some(where_clause) = &generics.where_clause {
+            let clause = &where_clause.predicates;
+            predicates.extend(clause.iter().map(|predicate| quote! { #predicate }));
+        }
+
+        Args {
+            lifetimes,
+            lifetime_params,
+            params,
+            args,
+            predicates,
+        }
+    }
+
+    /// The parameters to declare on an impl block, with `extra` declared after the lifetimes
+    pub fn declare(&self, extra: TokenStream) -> TokenStream {
+        let Args {
+            lifetime_params,
+            params,
+            ..
+        } = self;
+        quote! { #(#lifetime_params,)* #extra #(#params),* }
+    }
+
+    /// The arguments to the type being derived
+    pub fn apply(&self) -> TokenStream {
+        let Args { lifetimes, args, .. } = self;
+        quote! { #(#lifetimes,)* #(#args),* }
+    }
+
+    /// The where clause of an impl block, with the generated bounds in `extra`
+    pub fn where_clause(&self, extra: TokenStream) -> TokenStream {
+        let predicates = &self.predicates;
+        quote! { where #extra #(#predicates,)* }
+    }
+}
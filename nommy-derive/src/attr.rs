@@ -1,4 +1,6 @@
 use proc_macro2::{Delimiter, Span, TokenStream, TokenTree};
+use quote::quote;
+use syn::{punctuated::Punctuated, Token};
 
 #[derive(Default)]
 pub struct GlobalAttr {
@@ -7,12 +9,78 @@ pub struct GlobalAttr {
     pub prefix: Option<syn::Type>,
     pub suffix: Option<syn::Type>,
     pub parse_type: Option<syn::Type>,
+    pub extensible: bool,
+    pub arena: Option<Arena>,
+    /// an expression over the parsed fields that must be true for the variant to be accepted
+    pub guard: Option<syn::Expr>,
+    /// how many fields the peek function checks before assuming the rest match
+    pub peek_limit: Option<usize>,
+    /// variants with a higher priority are tried first. Variants without one have a priority of 0
+    pub priority: Option<i32>,
+    /// every variant is tried, and the one that reads the most input is chosen, from `strategy = "longest"`
+    pub longest: bool,
+    /// the variant is only tried once every other variant has failed, from `default`
+    pub fallback: bool,
+    /// once the prefix is parsed, a failure doesn't let the enum try its other variants
+    pub cut: bool,
+    /// type parameters marked `#[nommy(not_parsed)]`. Fields that mention them are skipped
+    pub not_parsed: Vec<syn::Ident>,
+    /// replaces the generated bounds of the `Parse` impl
+    pub bound: Option<Punctuated<syn::WherePredicate, Token![,]>>,
+    /// the literal given with `tag`, if it's still the prefix
+    pub tag: Option<syn::LitStr>,
+    /// the variant doesn't skip the enum's `ignore` types between its fields
+    pub no_ignore: bool,
+    /// the path to the nommy crate, if it's been renamed or re-exported
+    pub krate: Option<syn::Path>,
+    /// the struct is parsed exactly as its only field
+    pub transparent: bool,
+    /// the function the parsed value is passed through before it's returned
+    pub map: Option<syn::Path>,
+    /// the message that errors from parsing the type or variant are wrapped with
+    pub context: Option<syn::LitStr>,
+    /// the `Parse` impl keeps the default peek function, which parses the value
+    pub no_peek: bool,
+    /// only the peek function is derived, and parsing peeks the value then returns its default, for `#[derive(Peek)]`
+    pub peek_only: bool,
+    /// `FromStr` is implemented by parsing the whole string
+    pub from_str: bool,
+    /// parsing fails if the input continues after the type
+    pub complete: bool,
+    /// the derives put on the output type that `#[derive(Process)]` generates
+    pub output_derive: Option<Punctuated<syn::Path, Token![,]>>,
+}
+
+/// Settings for types that are parsed into an arena
+#[derive(Clone)]
+pub struct Arena {
+    /// lifetime of the arena
+    pub lifetime: syn::Lifetime,
+    /// whether the lifetime is a parameter of the type being derived
+    pub declared: bool,
 }
 
 fn parse_type(
     span: Span,
-    mut tokens: proc_macro2::token_stream::IntoIter,
+    tokens: proc_macro2::token_stream::IntoIter,
 ) -> syn::Result<syn::Type> {
+    parse_value(span, tokens)
+}
+
+/// Parses a path written as a string, such as `with = "path::to::fn"`
+fn parse_path(span: Span, tokens: proc_macro2::token_stream::IntoIter) -> syn::Result<syn::Path> {
+    parse_value::<syn::LitStr>(span, tokens)?.parse()
+}
+
+/// The `Tag` that matches a literal given as `tag = "while"`
+fn tag_type(tag: &syn::LitStr) -> syn::Type {
+    syn::parse_quote! { _nommy::text::Tag<#tag> }
+}
+
+fn parse_value<T: syn::parse::Parse>(
+    span: Span,
+    mut tokens: proc_macro2::token_stream::IntoIter,
+) -> syn::Result<T> {
     match tokens.next() {
         Some(TokenTree::Punct(p)) => {
             if p.as_char() != '=' {
@@ -31,15 +99,130 @@ fn parse_type(
 
 impl GlobalAttr {
     pub fn extend_with(mut self, extend: &Self) -> Self {
-        self.ignore.extend_from_slice(&extend.ignore);
+        if !self.no_ignore {
+            self.ignore.extend_from_slice(&extend.ignore);
+        }
         self.parse_type = extend.parse_type.clone();
+        self.arena = extend.arena.clone();
+        self.not_parsed = extend.not_parsed.clone();
+        self.bound = extend.bound.clone();
         self
     }
 
+    /// The bounds of the `Parse` impl. These are the `bound` attribute if it's given,
+    /// otherwise the `generated` bounds, which aren't needed if the parse type is fixed
+    pub fn bounds(&self, generated: &TokenStream) -> TokenStream {
+        match (&self.bound, &self.parse_type) {
+            (Some(bound), _) => quote! { #bound, },
+            (None, Some(_)) => quote! {},
+            (None, None) => generated.clone(),
+        }
+    }
+
+    /// The path to the nommy crate, for generated items that can't go in [`GlobalAttr::wrap_crate`]
+    pub fn krate(&self) -> TokenStream {
+        match &self.krate {
+            Some(path) => quote! { #path },
+            None => quote! { ::nommy },
+        }
+    }
+
+    /// Puts the generated impls in an anonymous const, where `_nommy` names the nommy crate.
+    /// The generated code refers to nommy through `_nommy`, so that it works with the `crate` path
+    pub fn wrap_crate(&self, impls: TokenStream) -> TokenStream {
+        let krate = self.krate();
+        quote! {
+            const _: () = {
+                use #krate as _nommy;
+                #impls
+            };
+        }
+    }
+
+    /// Returns the parsed `value` from a parse function, passed through the `map` function if there is one
+    pub fn ok(&self, value: TokenStream) -> TokenStream {
+        match &self.map {
+            Some(map) => quote! { _nommy::map::MapResult::<Self>::into_result(#map(#value)) },
+            None => quote! { Ok(#value) },
+        }
+    }
+
+    /// The `body` of a parse function, with its errors wrapped with the `context` if there is one
+    pub fn with_context(&self, body: TokenStream) -> TokenStream {
+        match &self.context {
+            Some(context) => quote! {
+                let __context_start = _nommy::Buffer::offset(input);
+                let result: _nommy::eyre::Result<Self> = (|| { #body })();
+                _nommy::eyre::WrapErr::wrap_err(result, #context)
+                    .map_err(|err| _nommy::span::locate(err, __context_start))
+            },
+            None => body,
+        }
+    }
+
+    /// Finds the type parameters marked `#[nommy(not_parsed)]`
+    pub fn resolve_params(&mut self, generics: &syn::Generics) -> syn::Result<()> {
+        for param in generics.type_params() {
+            for attr in &param.attrs {
+                if !attr.path.is_ident("nommy") {
+                    continue;
+                }
+                let arg: syn::Ident = attr.parse_args()?;
+                if arg != "not_parsed" {
+                    return Err(syn::Error::new_spanned(arg, "unknown parameter"));
+                }
+                self.not_parsed.push(param.ident.clone());
+            }
+        }
+        Ok(())
+    }
+
+    /// Uses the first lifetime parameter of the type as the arena lifetime, if there is one
+    pub fn resolve_arena(&mut self, generics: &syn::Generics) {
+        if let Some(arena) = &mut self.arena {
+            if let Some(lt) = generics.lifetimes().next() {
+                arena.lifetime = lt.lifetime.clone();
+                arena.declared = true;
+            }
+        }
+    }
+
+    /// Checks the attributes that depend on whether the type derives `Parse` or `Peek`
+    pub fn check_derive(&self, name: &syn::Ident) -> syn::Result<()> {
+        if self.peek_only && self.no_peek {
+            return Err(syn::Error::new_spanned(
+                name,
+                "`no_peek` can't be used with `#[derive(Peek)]`",
+            ));
+        }
+        if self.from_str && self.arena.is_some() {
+            return Err(syn::Error::new_spanned(
+                name,
+                "`from_str` can't be used with `arena`, since parsing needs the arena",
+            ));
+        }
+        if self.peek_only
+            && (self.arena.is_some() || self.map.is_some() || self.context.is_some() || self.from_str)
+        {
+            return Err(syn::Error::new_spanned(
+                name,
+                "`#[derive(Peek)]` can't be used with `arena`, `map`, `context` or `from_str`",
+            ));
+        }
+        Ok(())
+    }
+
     pub fn parse_attrs(attrs: Vec<syn::Attribute>) -> syn::Result<Self> {
         let mut output = GlobalAttr::default();
+        // other attributes, such as doc comments and other derives' attributes, aren't ours to check
         for attr in attrs {
             if attr.path.is_ident("nommy") {
+                if attr.tokens.is_empty() {
+                    return Err(syn::Error::new_spanned(
+                        attr,
+                        "expected parenthesied attribute arguments",
+                    ));
+                }
                 output.parse_attr(attr.tokens)?;
             }
         }
@@ -74,6 +257,7 @@ impl GlobalAttr {
 
     pub fn parse_args(&mut self, mut span: Span, tokens: TokenStream) -> syn::Result<()> {
         let mut stream = TokenStream::new();
+        let mut after_comma = false;
         for tt in tokens {
             match tt {
                 TokenTree::Punct(p) => {
@@ -82,6 +266,7 @@ impl GlobalAttr {
                         std::mem::swap(&mut stream, &mut tmp);
                         self.parse_arg(span, tmp)?;
                         span = p.span();
+                        after_comma = true;
                     } else {
                         stream.extend(vec![TokenTree::Punct(p)])
                     }
@@ -89,6 +274,10 @@ impl GlobalAttr {
                 _ => stream.extend(vec![tt]),
             }
         }
+        // a trailing comma ends the arguments
+        if after_comma && stream.is_empty() {
+            return Ok(());
+        }
         self.parse_arg(span, stream)
     }
 
@@ -102,10 +291,84 @@ impl GlobalAttr {
 
         match ident.to_string().as_ref() {
             "ignore" => self.ignore.push(parse_type(ident.span(), tokens)?),
-            "prefix" => self.prefix = Some(parse_type(ident.span(), tokens)?),
+            "whitespace" => {
+                let mode: syn::LitStr = parse_value(ident.span(), tokens)?;
+                self.ignore.push(match mode.value().as_str() {
+                    "spaces" => syn::parse_quote! { _nommy::text::Space },
+                    "lines" => syn::parse_quote! { _nommy::text::WhiteSpace },
+                    "all" => syn::parse_quote! { _nommy::text::UnicodeWhiteSpace },
+                    _ => {
+                        return Err(syn::Error::new_spanned(
+                            mode,
+                            "expected \"spaces\", \"lines\" or \"all\"",
+                        ))
+                    }
+                });
+            }
+            "ignore_trivia" => {
+                let trivia = parse_type(ident.span(), tokens)?;
+                self.ignore
+                    .push(syn::parse_quote! { _nommy::trivia::Skip<#trivia> });
+            }
+            "prefix" => {
+                self.prefix = Some(parse_type(ident.span(), tokens)?);
+                self.tag = None;
+            }
+            "tag" => {
+                let tag = parse_value(ident.span(), tokens)?;
+                self.prefix = Some(tag_type(&tag));
+                self.tag = Some(tag);
+            }
             "suffix" => self.suffix = Some(parse_type(ident.span(), tokens)?),
             "parse_type" => self.parse_type = Some(parse_type(ident.span(), tokens)?),
             "debug" => self.debug = true,
+            "no_ignore" => self.no_ignore = true,
+            "crate" => self.krate = Some(parse_path(ident.span(), tokens)?),
+            "extensible" => self.extensible = true,
+            "strategy" => {
+                let strategy: syn::LitStr = parse_value(ident.span(), tokens)?;
+                self.longest = match strategy.value().as_str() {
+                    "first" => false,
+                    "longest" => true,
+                    _ => {
+                        return Err(syn::Error::new_spanned(
+                            strategy,
+                            "expected \"first\" or \"longest\"",
+                        ))
+                    }
+                };
+            }
+            "transparent" => self.transparent = true,
+            "no_peek" => self.no_peek = true,
+            "from_str" => self.from_str = true,
+            "complete" => self.complete = true,
+            "default" => self.fallback = true,
+            "cut" => self.cut = true,
+            "map" => self.map = Some(parse_path(ident.span(), tokens)?),
+            "context" => self.context = Some(parse_value(ident.span(), tokens)?),
+            "bound" => {
+                let bound: syn::LitStr = parse_value(ident.span(), tokens)?;
+                self.bound = Some(bound.parse_with(Punctuated::parse_terminated)?)
+            }
+            "output_derive" => {
+                let derives: syn::LitStr = parse_value(ident.span(), tokens)?;
+                self.output_derive = Some(derives.parse_with(Punctuated::parse_terminated)?)
+            }
+            "guard" => self.guard = Some(parse_value(ident.span(), tokens)?),
+            "peek_limit" => {
+                let limit: syn::LitInt = parse_value(ident.span(), tokens)?;
+                self.peek_limit = Some(limit.base10_parse()?)
+            }
+            "priority" => {
+                let priority: syn::LitInt = parse_value(ident.span(), tokens)?;
+                self.priority = Some(priority.base10_parse()?)
+            }
+            "arena" => {
+                self.arena = Some(Arena {
+                    lifetime: syn::Lifetime::new("'__arena", ident.span()),
+                    declared: false,
+                })
+            }
             _ => return Err(syn::Error::new_spanned(ident, "unknown parameter")),
         }
         Ok(())
@@ -117,7 +380,69 @@ pub struct FieldAttr {
     pub suffix: Option<syn::Type>,
     pub parser: Option<syn::Type>,
     pub vec: VecFieldAttr,
+    /// the field isn't parsed, and instead records the input that was ignored
+    pub trivia: bool,
+    /// the field isn't parsed, and is set to this instead
+    pub skip: Option<Skip>,
+    /// the fields of the field's type are parsed as if they were fields of this type
+    pub flatten: bool,
+    /// the function called to parse the field instead of `Parse::parse`
+    pub with: Option<syn::Path>,
+    /// the function given a cursor over the input to parse the field, which returns the value and how much it consumed
+    pub raw: Option<syn::Path>,
+    /// the function called to peek the field. Defaults to parsing it with `with`
+    pub peek_with: Option<syn::Path>,
+    /// an `Option` field is parsed with the `Option`'s own impl, rather than by peeking the inner type
+    pub no_auto_option: bool,
+    /// the ignored input isn't skipped between the field's prefix, value and suffix
+    pub no_ignore: bool,
+    /// the function the field's value is passed through after it's parsed
+    pub map: Option<syn::Path>,
+    /// errors converting the parsed value into the field's type are reported for the field
+    pub try_into: bool,
+    /// the message that errors from parsing the field are wrapped with
+    pub context: Option<syn::LitStr>,
+    /// describes what the field should be, for its error message, in place of its name
+    pub expected: Option<syn::LitStr>,
+    /// how the output of the field's parser becomes the field's value, in place of `TryInto`
+    pub convert: Option<Convert>,
+    /// the field holds the version of the message, which later fields' `since` and `until` are compared with
+    pub version: bool,
+    /// the first version the field is in. It's the default in earlier versions
+    pub since: Option<syn::Expr>,
+    /// the version the field is removed in. It's the default from this version on
+    pub until: Option<syn::Expr>,
+    /// the field is a `nommy::framed::Checksum`, checked against the input read by the fields before it
+    pub checksum: bool,
+    /// once the field is parsed, a failure doesn't let the enum try its other variants
+    pub cut: bool,
+    /// the field fails to parse if this type can be peeked where its value starts
+    pub not: Option<syn::Type>,
+    /// the field isn't parsed, but this type has to be peeked where it is. The field is its default
+    pub peek: Option<syn::Type>,
+    /// the field isn't parsed, but is set to the `nommy::span::Span` of the input the type was parsed from
+    pub span: bool,
 }
+/// What a skipped field is set to
+pub enum Skip {
+    /// `Default::default()`
+    Default,
+    /// the expression given by `skip_with`
+    With(syn::Expr),
+}
+
+/// How the output of a field's parser becomes the field's value, given with `convert`
+pub enum Convert {
+    /// `"into"`, with `Into::into`
+    Into,
+    /// `"from_output"`, with the field type's `From` impl for the parser
+    FromOutput,
+    /// `"none"`, the parser's output is already the field's type
+    None,
+    /// a function from the parser's output to the field's type
+    With(syn::Path),
+}
+
 #[derive(Default)]
 pub struct VecFieldAttr {
     pub count: Option<syn::Expr>,
@@ -126,19 +451,229 @@ pub struct VecFieldAttr {
     pub parser: Option<syn::Type>,
     pub seperated_by: Option<syn::Type>,
     pub trailing: Option<bool>,
+    /// values that fail to parse are collected as errors, skipping to the next separator
+    pub recover: bool,
 }
 
 impl VecFieldAttr {
     pub fn is_some(&self) -> bool {
         self.count.is_some() || self.min.is_some() || self.max.is_some() || self.parser.is_some()
     }
+
+    /// The least and most values to parse. `count` sets both
+    pub fn bounds(&self) -> (TokenStream, TokenStream) {
+        if let Some(count) = &self.count {
+            return (quote! { #count }, quote! { #count });
+        }
+        let min = match &self.min {
+            Some(min) => quote! { #min },
+            None => quote! { 0 },
+        };
+        let max = match &self.max {
+            Some(max) => quote! { #max },
+            None => quote! { usize::MAX },
+        };
+        (min, max)
+    }
 }
 
 impl FieldAttr {
+    /// Whether the field is given a way to be parsed, other than its own `Parse` impl
+    fn has_parser(&self) -> bool {
+        self.parser.is_some()
+            || self.vec.is_some()
+            || self.trivia
+            || self.flatten
+            || self.with.is_some()
+            || self.raw.is_some()
+    }
+
+    /// Whether the field is in the message with the `version`, or `None` if it's in every version
+    pub fn in_version(&self, version: TokenStream) -> Option<TokenStream> {
+        match (&self.since, &self.until) {
+            (None, None) => None,
+            (Some(since), None) => Some(quote! { #version >= #since }),
+            (None, Some(until)) => Some(quote! { #version < #until }),
+            (Some(since), Some(until)) => Some(quote! { #version >= #since && #version < #until }),
+        }
+    }
+
+    /// Skips `PhantomData` fields, unless they're given a parser
+    pub fn default_skip(mut self, ty: &syn::Type) -> Self {
+        if self.skip.is_none()
+            && self.peek.is_none()
+            && !self.has_parser()
+            && crate::ty::is_phantom_data(ty)
+        {
+            self.skip = Some(Skip::Default);
+        }
+        self
+    }
+
+    /// Skips the field if its type mentions one of the `not_parsed` type parameters, unless it's given a parser
+    pub fn skip_not_parsed(&mut self, ty: &syn::Type, not_parsed: &[syn::Ident]) {
+        if self.skip.is_none()
+            && self.peek.is_none()
+            && !self.has_parser()
+            && not_parsed.iter().any(|param| crate::ty::contains(ty, param))
+        {
+            self.skip = Some(Skip::Default);
+        }
+    }
+
+    /// Parses the elements of a `Vec` field with their own `Parse` impl,
+    /// if it's given a separator or a count but no `inner_parser`
+    pub fn default_inner(mut self, ty: &syn::Type) -> Self {
+        let vec = &self.vec;
+        let wants_inner = vec.count.is_some()
+            || vec.min.is_some()
+            || vec.max.is_some()
+            || vec.seperated_by.is_some()
+            || vec.trailing.is_some();
+        if vec.parser.is_none() && wants_inner {
+            let inner = crate::ty::vec_inner(ty);
+            // a recovering field collects `Result`s of the values
+            self.vec.parser = match vec.recover {
+                true => inner.and_then(crate::ty::result_ok).cloned(),
+                false => inner.cloned(),
+            };
+        }
+        self
+    }
+
+    /// Checks that the attributes can be used together
+    pub fn check(&self, ty: &syn::Type) -> syn::Result<()> {
+        if self.flatten && (self.parser.is_some() || self.vec.is_some()) {
+            return Err(syn::Error::new_spanned(
+                ty,
+                "`flatten` can't be used with `parser` or `inner_parser`",
+            ));
+        }
+        if self.with.is_some() && (self.flatten || self.parser.is_some() || self.vec.is_some()) {
+            return Err(syn::Error::new_spanned(
+                ty,
+                "`with` can't be used with `flatten`, `parser` or `inner_parser`",
+            ));
+        }
+        if self.raw.is_some()
+            && (self.with.is_some()
+                || self.flatten
+                || self.parser.is_some()
+                || self.vec.is_some()
+                || self.try_into
+                || self.convert.is_some())
+        {
+            return Err(syn::Error::new_spanned(
+                ty,
+                "`raw` can't be used with `with`, `flatten`, `parser`, `inner_parser`, `try_into` or `convert`",
+            ));
+        }
+        if (self.map.is_some()
+            || self.context.is_some()
+            || self.expected.is_some()
+            || self.checksum
+            || self.not.is_some())
+            && (self.skip.is_some() || self.trivia)
+        {
+            return Err(syn::Error::new_spanned(
+                ty,
+                "`map`, `context`, `expected`, `checksum` and `not` can't be used with `skip`, `skip_with` or `trivia`, since the field isn't parsed",
+            ));
+        }
+        if self.try_into && (self.flatten || self.with.is_some() || self.vec.parser.is_some()) {
+            return Err(syn::Error::new_spanned(
+                ty,
+                "`try_into` can't be used with `flatten`, `with` or `inner_parser`, since their values aren't converted",
+            ));
+        }
+        if self.convert.is_some()
+            && (self.flatten
+                || self.with.is_some()
+                || self.vec.parser.is_some()
+                || self.try_into
+                || self.map.is_some()
+                || self.skip.is_some()
+                || self.trivia)
+        {
+            return Err(syn::Error::new_spanned(
+                ty,
+                "`convert` can't be used with `flatten`, `with`, `inner_parser`, `try_into`, `map`, `skip` or `trivia`",
+            ));
+        }
+        if (self.version || self.since.is_some() || self.until.is_some())
+            && (self.skip.is_some() || self.trivia)
+        {
+            return Err(syn::Error::new_spanned(
+                ty,
+                "`version`, `since` and `until` can't be used with `skip`, `skip_with` or `trivia`, since the field isn't parsed",
+            ));
+        }
+        if self.peek.is_some()
+            && (self.has_parser()
+                || self.skip.is_some()
+                || self.prefix.is_some()
+                || self.suffix.is_some()
+                || self.map.is_some()
+                || self.try_into
+                || self.convert.is_some()
+                || self.not.is_some()
+                || self.checksum)
+        {
+            return Err(syn::Error::new_spanned(
+                ty,
+                "a `peek` field isn't parsed, so it can't be given a parser, prefix, suffix or conversion",
+            ));
+        }
+        if self.span
+            && (self.has_parser()
+                || self.skip.is_some()
+                || self.peek.is_some()
+                || self.prefix.is_some()
+                || self.suffix.is_some()
+                || self.map.is_some()
+                || self.try_into
+                || self.convert.is_some()
+                || self.not.is_some()
+                || self.checksum
+                || self.version
+                || self.since.is_some()
+                || self.until.is_some())
+        {
+            return Err(syn::Error::new_spanned(
+                ty,
+                "a `span` field isn't parsed, so it can't be given a parser, prefix, suffix, conversion or version",
+            ));
+        }
+        if self.peek_with.is_some() && self.with.is_none() {
+            return Err(syn::Error::new_spanned(ty, "`peek_with` needs a `with` function"));
+        }
+        let vec = &self.vec;
+        if vec.recover && (vec.seperated_by.is_none() || vec.trailing.is_some()) {
+            return Err(syn::Error::new_spanned(
+                ty,
+                "`recover` needs a `separator` to skip to, and can't be used with `trailing`",
+            ));
+        }
+        if vec.parser.is_none() && (vec.is_some() || vec.seperated_by.is_some() || vec.trailing.is_some()) {
+            return Err(syn::Error::new_spanned(
+                ty,
+                "`separator`, `trailing`, `allow_trailing`, `count`, `min` and `max` need an `inner_parser`, unless the field is a `Vec`",
+            ));
+        }
+        Ok(())
+    }
+
     pub fn parse_attrs(attrs: Vec<syn::Attribute>) -> syn::Result<Self> {
         let mut output = FieldAttr::default();
+        // other attributes, such as doc comments and other derives' attributes, aren't ours to check
         for attr in attrs {
             if attr.path.is_ident("nommy") {
+                if attr.tokens.is_empty() {
+                    return Err(syn::Error::new_spanned(
+                        attr,
+                        "expected parenthesied attribute arguments",
+                    ));
+                }
                 output.parse_attr(attr.tokens)?;
             }
         }
@@ -173,6 +708,7 @@ impl FieldAttr {
 
     pub fn parse_args(&mut self, mut span: Span, tokens: TokenStream) -> syn::Result<()> {
         let mut stream = TokenStream::new();
+        let mut after_comma = false;
         for tt in tokens {
             match tt {
                 TokenTree::Punct(p) => {
@@ -181,6 +717,7 @@ impl FieldAttr {
                         std::mem::swap(&mut stream, &mut tmp);
                         self.parse_arg(span, tmp)?;
                         span = p.span();
+                        after_comma = true;
                     } else {
                         stream.extend(vec![TokenTree::Punct(p)])
                     }
@@ -188,6 +725,10 @@ impl FieldAttr {
                 _ => stream.extend(vec![tt]),
             }
         }
+        // a trailing comma ends the arguments
+        if after_comma && stream.is_empty() {
+            return Ok(());
+        }
         self.parse_arg(span, stream)
     }
 
@@ -201,11 +742,107 @@ impl FieldAttr {
 
         match ident.to_string().as_ref() {
             "prefix" => self.prefix = Some(parse_type(ident.span(), tokens)?),
+            "tag" => self.prefix = Some(tag_type(&parse_value(ident.span(), tokens)?)),
             "suffix" => self.suffix = Some(parse_type(ident.span(), tokens)?),
             "parser" => self.parser = Some(parse_type(ident.span(), tokens)?),
+            "not" => self.not = Some(parse_type(ident.span(), tokens)?),
+            "peek" => self.peek = Some(parse_type(ident.span(), tokens)?),
+            "count" => self.vec.count = Some(parse_value(ident.span(), tokens)?),
+            "min" => self.vec.min = Some(parse_value(ident.span(), tokens)?),
+            "max" => self.vec.max = Some(parse_value(ident.span(), tokens)?),
             "inner_parser" => self.vec.parser = Some(parse_type(ident.span(), tokens)?),
-            "seperated_by" => self.vec.seperated_by = Some(parse_type(ident.span(), tokens)?),
+            "seperated_by" | "separator" => self.vec.seperated_by = Some(parse_type(ident.span(), tokens)?),
             "trailing" => self.parse_trailing(tokens)?,
+            "trivia" => {
+                if let Some(t) = tokens.next() {
+                    return Err(syn::Error::new_spanned(t, "expected no more tokens"));
+                }
+                self.trivia = true
+            }
+            "skip" => {
+                if let Some(t) = tokens.next() {
+                    return Err(syn::Error::new_spanned(t, "expected no more tokens"));
+                }
+                self.skip = Some(Skip::Default)
+            }
+            "skip_with" => self.skip = Some(Skip::With(parse_value(ident.span(), tokens)?)),
+            "with" => self.with = Some(parse_path(ident.span(), tokens)?),
+            "raw" => self.raw = Some(parse_path(ident.span(), tokens)?),
+            "peek_with" => self.peek_with = Some(parse_path(ident.span(), tokens)?),
+            "map" => self.map = Some(parse_path(ident.span(), tokens)?),
+            "context" => self.context = Some(parse_value(ident.span(), tokens)?),
+            "expected" => self.expected = Some(parse_value(ident.span(), tokens)?),
+            "since" => self.since = Some(parse_value(ident.span(), tokens)?),
+            "until" => self.until = Some(parse_value(ident.span(), tokens)?),
+            "cut" => {
+                if let Some(t) = tokens.next() {
+                    return Err(syn::Error::new_spanned(t, "expected no more tokens"));
+                }
+                self.cut = true
+            }
+            "span" => {
+                if let Some(t) = tokens.next() {
+                    return Err(syn::Error::new_spanned(t, "expected no more tokens"));
+                }
+                self.span = true
+            }
+            "checksum" => {
+                if let Some(t) = tokens.next() {
+                    return Err(syn::Error::new_spanned(t, "expected no more tokens"));
+                }
+                self.checksum = true
+            }
+            "version" => {
+                if let Some(t) = tokens.next() {
+                    return Err(syn::Error::new_spanned(t, "expected no more tokens"));
+                }
+                self.version = true
+            }
+            "flatten" => {
+                if let Some(t) = tokens.next() {
+                    return Err(syn::Error::new_spanned(t, "expected no more tokens"));
+                }
+                self.flatten = true
+            }
+            "no_auto_option" => {
+                if let Some(t) = tokens.next() {
+                    return Err(syn::Error::new_spanned(t, "expected no more tokens"));
+                }
+                self.no_auto_option = true
+            }
+            "no_ignore" => {
+                if let Some(t) = tokens.next() {
+                    return Err(syn::Error::new_spanned(t, "expected no more tokens"));
+                }
+                self.no_ignore = true
+            }
+            "convert" => {
+                let convert: syn::LitStr = parse_value(ident.span(), tokens)?;
+                self.convert = Some(match convert.value().as_str() {
+                    "into" => Convert::Into,
+                    "from_output" => Convert::FromOutput,
+                    "none" => Convert::None,
+                    _ => Convert::With(convert.parse()?),
+                });
+            }
+            "try_into" => {
+                if let Some(t) = tokens.next() {
+                    return Err(syn::Error::new_spanned(t, "expected no more tokens"));
+                }
+                self.try_into = true
+            }
+            "recover" => {
+                if let Some(t) = tokens.next() {
+                    return Err(syn::Error::new_spanned(t, "expected no more tokens"));
+                }
+                self.vec.recover = true
+            }
+            "allow_trailing" => {
+                if let Some(t) = tokens.next() {
+                    return Err(syn::Error::new_spanned(t, "expected no more tokens"));
+                }
+                self.vec.trailing = Some(false)
+            }
             _ => return Err(syn::Error::new_spanned(ident, "unknown parameter")),
         }
         Ok(())
@@ -213,7 +850,10 @@ impl FieldAttr {
 
     pub fn parse_trailing(&mut self, mut tokens: proc_macro2::token_stream::IntoIter) -> syn::Result<()> {
         let span = match tokens.next() {
-            None => return Ok(()),
+            None => {
+                self.vec.trailing = Some(false);
+                return Ok(());
+            }
             Some(TokenTree::Punct(p)) => {
                 if p.as_char() != '=' {
                     return Err(syn::Error::new(p.span(), "expected '=' to follow"))
@@ -0,0 +1,108 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use super::{Enum, EnumVariant};
+
+impl Enum {
+    /// The tags of the variants, if every variant is a unit variant matched by its `tag` alone,
+    /// so the variant to parse can be chosen by reading the input once.
+    /// A variant with a suffix, map or context could still fail after its tag matched,
+    /// where a later variant should have been tried, so it rules the trie out
+    pub fn keywords(&self) -> Option<Vec<syn::LitStr>> {
+        let fallback = self.variants.iter().any(|variant| match variant {
+            EnumVariant::Named(v) => v.attrs.fallback,
+            EnumVariant::Unnamed(v) => v.attrs.fallback,
+            EnumVariant::Unit(v) => v.attrs.fallback,
+        });
+        if self.attrs.extensible || fallback || self.variants.len() < 2 {
+            return None;
+        }
+        self.variants
+            .iter()
+            .map(|variant| match variant {
+                EnumVariant::Unit(v)
+                    if v.attrs.guard.is_none()
+                        && v.attrs.prefix.is_some()
+                        && v.attrs.suffix.is_none()
+                        && v.attrs.map.is_none()
+                        && v.attrs.context.is_none() =>
+                {
+                    v.attrs.tag.clone()
+                }
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+/// Builds an expression that reads the input from `cursor` one character at a time, like walking a trie,
+/// and evaluates to the index of the first keyword that the input starts with, or `None`
+pub fn trie(keywords: &[syn::LitStr]) -> TokenStream {
+    let chars: Vec<Vec<char>> = keywords.iter().map(|k| k.value().chars().collect()).collect();
+    let candidates: Vec<usize> = (0..keywords.len()).collect();
+    node(keywords, &chars, &candidates, 0, None)
+}
+
+/// The keywords in `candidates` all match the first `depth` characters of the input
+fn node(
+    keywords: &[syn::LitStr],
+    chars: &[Vec<char>],
+    candidates: &[usize],
+    depth: usize,
+    matched: Option<usize>,
+) -> TokenStream {
+    // variants are tried in order, so a keyword that ends here beats any later keyword
+    let matched = candidates
+        .iter()
+        .copied()
+        .filter(|&i| chars[i].len() == depth)
+        .chain(matched)
+        .min();
+    let longer: Vec<usize> = candidates
+        .iter()
+        .copied()
+        .filter(|&i| chars[i].len() > depth && !matches!(matched, Some(m) if m < i))
+        .collect();
+
+    let result = match matched {
+        Some(i) => quote! { Some(#i) },
+        None => quote! { None },
+    };
+    if longer.is_empty() {
+        return result;
+    }
+
+    let mut next_chars: Vec<char> = Vec::new();
+    for &i in &longer {
+        if !next_chars.contains(&chars[i][depth]) {
+            next_chars.push(chars[i][depth]);
+        }
+    }
+    let arms = next_chars.iter().map(|&c| {
+        let next: Vec<usize> = longer
+            .iter()
+            .copied()
+            .filter(|&i| chars[i][depth] == c)
+            .collect();
+        let body = node(keywords, chars, &next, depth + 1, matched);
+        quote! { Some(c) if c == #c => #body, }
+    });
+    // the keywords that the input ends part way through could complete it
+    let expected = longer.iter().map(|&i| {
+        let keyword = &keywords[i];
+        quote! {
+            _nommy::complete::expect(_nommy::complete::Expected::Tag { tag: #keyword, typed: #depth });
+        }
+    });
+
+    quote! {
+        match _nommy::Buffer::peek_ahead(&mut cursor, #depth) {
+            #(#arms)*
+            None => {
+                #(#expected)*
+                #result
+            }
+            _ => #result,
+        }
+    }
+}
@@ -1,3 +1,4 @@
+mod keywords;
 mod named;
 mod unit;
 mod unnamed;
@@ -11,16 +12,20 @@ use unit::EnumVariantUnit;
 use unnamed::EnumVariantUnnamed;
 
 use crate::{
-    attr::GlobalAttr,
-    fn_impl::{parse_or, BuildOutput, Builder},
+    args::Args,
+    attr::{Arena, FieldAttr, GlobalAttr},
+    fn_impl::{from_str_impl, impl_tokens, item, parse_or, BuildOutput, Builder},
+    parsers::{check_versions, skip_not_parsed},
 };
 
 pub struct Enum {
     pub attrs: GlobalAttr,
     pub name: syn::Ident,
-    pub args: Vec<syn::Ident>,
+    pub args: Args,
     pub variants: Vec<EnumVariant>,
     generic: syn::Type,
+    /// the prefix that every variant starts with, parsed once before trying the variants
+    shared_prefix: Option<syn::Type>,
 }
 
 macro_rules! map_vars {
@@ -41,19 +46,28 @@ impl ToTokens for Enum {
             args,
             variants: vars,
             generic,
+            shared_prefix,
         } = self;
+        let mut impls = TokenStream::new();
 
-        let mut outer_builder = Builder::new(generic, name, &attrs.parse_type);
+        let mut outer_builder = Builder::new(generic, name, &attrs.parse_type, &attrs.arena);
 
         outer_builder.create_ignore(&attrs.ignore);
-        outer_builder.add_fix(&attrs.prefix, "prefix", format!("enum `{}`", name));
-        outer_builder.start_variants();
+        outer_builder.add_fix(&attrs.prefix, "prefix", item("enum", name));
+        outer_builder.add_fix(shared_prefix, "prefix", item("enum", name));
 
+        let params = args.declare(quote!{});
+        let type_args = args.apply();
+        let where_clause = args.where_clause(quote!{});
+
+        // the name of each variant, its peek and parse functions, and how far it peeks
+        let mut dispatch = Vec::new();
         for v in vars {
             let BuildOutput {
                 peek_impl,
                 parse_impl,
                 wc,
+                max_peek,
             } = map_vars! {v => |n| n.fn_impl(&self).build(&name)};
 
             outer_builder.add_where_raw(wc.clone());
@@ -61,41 +75,103 @@ impl ToTokens for Enum {
             let var_name = map_vars!(v => |n| n.name.to_string().to_lowercase());
             let parse_name = format_ident!("__parse_{}", var_name);
             let peek_name = format_ident!("__peek_{}", var_name);
-            let parse_result = map_vars!(v => |n| n.result(&self));
+            let variant_attrs = map_vars!(v => |n| &n.attrs);
+            let parse_result = variant_attrs.ok(map_vars!(v => |n| n.result(&self)));
+
 
-            outer_builder.add_variant(&peek_name, &parse_name);
 
 
+            // the arena lifetime goes on the impl block if the enum declares it, otherwise on each function
+            let (type_lifetime, fn_lifetime, arena_param) = match &attrs.arena {
+                Some(Arena { lifetime, declared: true }) => (
+                    quote!{ #lifetime, },
+                    quote!{},
+                    quote!{ , __arena: &#lifetime _nommy::arena::Bump },
+                ),
+                Some(Arena { lifetime, declared: false }) => (
+                    quote!{},
+                    quote!{ #lifetime, },
+                    quote!{ , __arena: &#lifetime _nommy::arena::Bump },
+                ),
+                None => (quote!{}, quote!{}, quote!{}),
+            };
+
+            // a guarded variant can only be peeked by parsing it and checking the guard
+            let (guard, peek_impl) = match &variant_attrs.guard {
+                Some(guard) => {
+                    let variant = map_vars!(v => |n| &n.name).to_string();
+                    let arena_arg = attrs.arena.as_ref().map(|_| quote!{ , __arena });
+                    (
+                        quote!{
+                            if !(#guard) {
+                                return Err(_nommy::eyre::Report::new(_nommy::message::Message::Guard { variant: #variant }));
+                            }
+                        },
+                        quote!{
+                            if Self::#parse_name(input #arena_arg).is_err() {
+                                return false;
+                            }
+                        },
+                    )
+                }
+                None => (quote!{}, peek_impl),
+            };
+            // a guarded variant is peeked by parsing it
+            let max_peek = match &variant_attrs.guard {
+                Some(_) => quote!{ None },
+                None => max_peek,
+            };
+            let body = variant_attrs.with_context(quote!{
+                #parse_impl
+                #guard
+                #parse_result
+            });
+            dispatch.push((map_vars!(v => |n| n.name.to_string()), peek_name.clone(), parse_name.clone(), max_peek));
 
+            let mut wc = attrs.bounds(&wc);
+            // recursive fields are parsed with the enum's own impl, whose bounds cover every variant
+            if map_vars!(v => |n| n.fn_impl(self).recursive(name)) {
+                wc.extend(match &attrs.arena {
+                    Some(Arena { lifetime, .. }) => quote!{ Self: _nommy::arena::ParseIn<#lifetime, #generic>, },
+                    None => quote!{ Self: _nommy::Parse<#generic>, },
+                });
+            }
             let (peek_fn, parse_fn) = match attrs.parse_type {
                 Some(_) => (
                     quote!{
-                        fn #peek_name(input: &mut impl ::nommy::Buffer<#generic>) -> bool
+                        fn #peek_name<#fn_lifetime>(input: &mut impl _nommy::Buffer<#generic> #arena_param) -> bool where #wc
                     },
                     quote!{
-                        fn #parse_name(input: &mut impl ::nommy::Buffer<#generic>) -> ::nommy::eyre::Result<Self>
+                        fn #parse_name<#fn_lifetime>(input: &mut impl _nommy::Buffer<#generic> #arena_param) -> _nommy::eyre::Result<Self> where #wc
                     },
                 ),
                 None => (
                     quote!{
-                        fn #peek_name<#generic>(input: &mut impl ::nommy::Buffer<#generic>) -> bool where #wc
+                        fn #peek_name<#fn_lifetime #generic>(input: &mut impl _nommy::Buffer<#generic> #arena_param) -> bool where #wc
                     },
                     quote!{
-                        fn #parse_name<#generic>(input: &mut impl ::nommy::Buffer<#generic>) -> ::nommy::eyre::Result<Self> where #wc
+                        fn #parse_name<#fn_lifetime #generic>(input: &mut impl _nommy::Buffer<#generic> #arena_param) -> _nommy::eyre::Result<Self> where #wc
                     },
                 ),
             };
 
-            tokens.extend(quote!{
-                #[automatically_derived]
-                impl<#(#args),*> #name<#(#args),*>
-                {
+            // a type that's only peeked doesn't need to parse its variants, unless they're peeked by parsing them
+            let parse_fn = match attrs.peek_only && variant_attrs.guard.is_none() {
+                true => quote!{},
+                false => quote!{
                     #parse_fn {
-                        use ::nommy::eyre::WrapErr;
+                        use _nommy::eyre::WrapErr;
                         use ::std::convert::TryInto;
-                        #parse_impl
-                        #parse_result
+                        #body
                     }
+                },
+            };
+
+            impls.extend(quote!{
+                #[automatically_derived]
+                impl<#type_lifetime #params> #name<#type_lifetime #type_args> #where_clause
+                {
+                    #parse_fn
 
                     #peek_fn {
                         #peek_impl
@@ -106,43 +182,64 @@ impl ToTokens for Enum {
             })
         }
 
-        outer_builder.finish_variants(format!("no variants of {} could be parsed", name));
+        // sorted last, and kept out of the others, so it's only tried once they've all failed
+        let fallback = match vars.last() {
+            Some(v) if map_vars!(v => |n| n.attrs.fallback) => dispatch.pop(),
+            _ => None,
+        };
+
+        match self.keywords() {
+            // the keywords are matched in order, not by length
+            _ if attrs.longest => {
+                outer_builder.add_longest_variants(name.to_string(), &dispatch, fallback.as_ref());
+            }
+            Some(keywords) => {
+                outer_builder.add_keywords(name.to_string(), &dispatch, keywords::trie(&keywords));
+            }
+            None => {
+                outer_builder.start_variants(name.to_string());
+                for (variant, peek_name, parse_name, max_peek) in dispatch {
+                    outer_builder.add_variant(variant, &peek_name, &parse_name, &max_peek);
+                }
+                if attrs.extensible {
+                    outer_builder.add_extension();
+                }
+                if let Some((variant, peek_name, parse_name, max_peek)) = fallback {
+                    outer_builder.add_variant(variant, &peek_name, &parse_name, &max_peek);
+                }
+                outer_builder.finish_variants();
+            }
+        }
 
-        outer_builder.add_fix(&attrs.suffix, "suffix", format!("enum `{}`", name));
+        outer_builder.add_fix(&attrs.suffix, "suffix", item("enum", name));
 
         let BuildOutput {
             peek_impl,
             parse_impl,
             wc,
+            max_peek,
         } = outer_builder.build();
 
-        let impl_line = match attrs.parse_type {
-            Some(_) => quote!{
-                impl<#(#args),*> ::nommy::Parse<#generic> for #name<#(#args),*>
-            },
-            None => quote!{
-                impl<#generic, #(#args),*> ::nommy::Parse<#generic> for #name<#(#args),*> where #wc
-            },
-        };
-
-        tokens.extend(quote!{
-            #[automatically_derived]
-            #impl_line {
-                fn parse(input: &mut impl ::nommy::Buffer<#generic>) -> ::nommy::eyre::Result<Self> {
-                    use ::nommy::eyre::WrapErr;
-                    use ::std::convert::TryInto;
-                    #parse_impl
+        let header = impl_tokens(attrs, name, args, generic, &wc, &max_peek);
+        let ok = attrs.ok(quote!{ result });
+        let body = attrs.with_context(quote! { #parse_impl #ok });
 
-                    Ok(result)
-                }
+        impls.extend(header.block(
+            attrs,
+            quote! {
+                use _nommy::eyre::WrapErr;
+                use ::std::convert::TryInto;
+                #body
+            },
+            quote! {
+                #peek_impl
 
-                fn peek(input: &mut impl ::nommy::Buffer<#generic>) -> bool {
-                    #peek_impl
+                true
+            },
+        ));
 
-                    true
-                }
-            }
-        })
+        impls.extend(from_str_impl(attrs, name, args));
+        tokens.extend(attrs.wrap_crate(impls));
     }
 }
 
@@ -152,12 +249,53 @@ impl Enum {
         generics: syn::Generics,
         attrs: Vec<syn::Attribute>,
         enum_data: syn::DataEnum,
+        peek_only: bool,
     ) -> syn::Result<Self> {
-        let args = generics.type_params().cloned().map(|tp| tp.ident).collect();
-        let attrs = GlobalAttr::parse_attrs(attrs)?;
+        let mut attrs = GlobalAttr::parse_attrs(attrs)?;
+        attrs.peek_only = peek_only;
+        attrs.check_derive(&name)?;
+        attrs.resolve_arena(&generics);
+        let args = Args::new(&generics, &attrs.arena);
+        attrs.resolve_params(&generics)?;
+        if attrs.extensible && attrs.arena.is_some() {
+            return Err(syn::Error::new_spanned(
+                name,
+                "`extensible` is not supported with `arena`",
+            ));
+        }
+        if attrs.extensible && attrs.longest {
+            return Err(syn::Error::new_spanned(
+                name,
+                "`extensible` can't be used with `strategy = \"longest\"`",
+            ));
+        }
+        if attrs.transparent {
+            return Err(syn::Error::new_spanned(
+                name,
+                "`transparent` is only supported on structs",
+            ));
+        }
+        if attrs.guard.is_some() || attrs.priority.is_some() || attrs.fallback {
+            return Err(syn::Error::new_spanned(
+                name,
+                "`guard`, `priority` and `default` are only supported on enum variants",
+            ));
+        }
+        if attrs.no_ignore {
+            return Err(syn::Error::new_spanned(
+                name,
+                "`no_ignore` is only supported on enum variants and fields",
+            ));
+        }
+        if attrs.peek_limit.is_some() || attrs.cut {
+            return Err(syn::Error::new_spanned(
+                name,
+                "`peek_limit` and `cut` are only supported on structs and enum variants",
+            ));
+        }
         let generic = parse_or(&attrs.parse_type);
 
-        let variants = enum_data
+        let mut variants: Vec<EnumVariant> = enum_data
             .variants
             .into_iter()
             .map(|v| match v.fields {
@@ -186,12 +324,61 @@ impl Enum {
             })
             .collect::<syn::Result<_>>()?;
 
+        // a stable sort, so variants with the same priority are still tried in the order they're declared.
+        // The `default` variant goes last, since it's only tried once the others have failed
+        variants.sort_by_key(|variant| {
+            map_vars!(variant => |v| {
+                (v.attrs.fallback, std::cmp::Reverse(v.attrs.priority.unwrap_or(0)))
+            })
+        });
+        if let Some(second) = variants
+            .iter()
+            .filter(|variant| map_vars!(variant => |v| v.attrs.fallback))
+            .nth(1)
+        {
+            return Err(syn::Error::new_spanned(
+                map_vars!(second => |v| &v.name),
+                "only one variant can be the `default`",
+            ));
+        }
+
+        for variant in &variants {
+            if map_vars!(variant => |v| v.attrs.no_peek || v.attrs.longest || v.attrs.from_str || v.attrs.complete) {
+                return Err(syn::Error::new_spanned(
+                    map_vars!(variant => |v| &v.name),
+                    "`no_peek`, `strategy`, `from_str` and `complete` are only supported on the type",
+                ));
+            }
+        }
+
+        for variant in &mut variants {
+            match variant {
+                EnumVariant::Named(v) => {
+                    skip_not_parsed(&mut v.fields, &attrs.not_parsed);
+                    check_versions(&v.fields)?;
+                }
+                EnumVariant::Unnamed(v) => {
+                    skip_not_parsed(&mut v.fields, &attrs.not_parsed);
+                    check_versions(&v.fields)?;
+                }
+                EnumVariant::Unit(_) => {}
+            }
+        }
+
+        // extensions are parsed from the start of the enum, so they would miss the shared prefix
+        let shared_prefix = if attrs.extensible {
+            None
+        } else {
+            shared_prefix(&mut variants, &attrs.ignore)
+        };
+
         Ok(Enum {
             name,
             attrs,
             args,
             variants,
             generic,
+            shared_prefix,
         })
     }
 }
@@ -201,3 +388,62 @@ pub enum EnumVariant {
     Unnamed(EnumVariantUnnamed),
     Unit(EnumVariantUnit),
 }
+
+impl EnumVariant {
+    /// The prefix the variant parses first, either its own or its first field's.
+    /// `None` if the input the variant ignores after it isn't `ignore`,
+    /// since the enum would skip `ignore` after the prefix once it's shared
+    fn leading_prefix(&mut self, ignore: &[syn::Type]) -> Option<&mut Option<syn::Type>> {
+        fn leading<'a>(
+            attrs: &'a mut GlobalAttr,
+            field: Option<&'a mut FieldAttr>,
+            ignore: &[syn::Type],
+        ) -> Option<&'a mut Option<syn::Type>> {
+            let key = |types: &[syn::Type]| -> Vec<String> {
+                types.iter().map(|ty| ty.to_token_stream().to_string()).collect()
+            };
+            if key(&attrs.ignore) != key(ignore) {
+                return None;
+            }
+            if attrs.prefix.is_some() {
+                return Some(&mut attrs.prefix);
+            }
+            field
+                .filter(|f| !f.trivia && f.skip.is_none() && !f.no_ignore)
+                .map(|f| &mut f.prefix)
+        }
+
+        match self {
+            EnumVariant::Named(v) => {
+                leading(&mut v.attrs, v.fields.first_mut().map(|f| &mut f.attrs), ignore)
+            }
+            EnumVariant::Unnamed(v) => {
+                leading(&mut v.attrs, v.fields.first_mut().map(|f| &mut f.attrs), ignore)
+            }
+            EnumVariant::Unit(v) => leading(&mut v.attrs, None, ignore),
+        }
+    }
+}
+
+/// If every variant starts with the same prefix, removes it from the variants and returns it,
+/// so it can be parsed once rather than once per variant
+fn shared_prefix(variants: &mut [EnumVariant], ignore: &[syn::Type]) -> Option<syn::Type> {
+    let mut prefixes = variants
+        .iter_mut()
+        .map(|variant| variant.leading_prefix(ignore))
+        .collect::<Option<Vec<_>>>()?;
+    if prefixes.len() < 2 {
+        return None;
+    }
+
+    let key = |prefix: &Option<syn::Type>| {
+        prefix
+            .as_ref()
+            .map(|ty| ty.to_token_stream().to_string())
+    };
+    let first = key(prefixes[0]);
+    if first.is_none() || prefixes.iter().any(|prefix| key(prefix) != first) {
+        return None;
+    }
+    prefixes.iter_mut().map(|prefix| prefix.take()).last().flatten()
+}
@@ -25,7 +25,7 @@ impl EnumVariantUnit {
         let enum_name = &enum_.name;
         let variant_name = &self.name;
         quote! {
-            Ok(#enum_name::#variant_name)
+            #enum_name::#variant_name
         }
     }
 }
@@ -34,9 +34,9 @@ impl EnumVariantUnnamed {
         let enum_name = &enum_.name;
         let variant_name = &self.name;
         quote! {
-            Ok(#enum_name::#variant_name (#(
+            #enum_name::#variant_name (#(
                 #names,
-            )*))
+            )*)
         }
     }
 }
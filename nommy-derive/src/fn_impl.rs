@@ -2,7 +2,8 @@ use proc_macro2::TokenStream;
 use quote::{format_ident, quote, ToTokens};
 
 use crate::{
-    attr::{GlobalAttr, VecFieldAttr},
+    args::Args,
+    attr::{Arena, Convert, FieldAttr, GlobalAttr, Skip, VecFieldAttr},
     parsers::FieldType,
 };
 
@@ -11,17 +12,38 @@ pub struct BuildOutput {
     pub peek_impl: TokenStream,
     pub parse_impl: TokenStream,
     pub wc: TokenStream,
+    /// the value of `Parse::MAX_PEEK`
+    pub max_peek: TokenStream,
 }
 
 pub struct Builder<'a> {
     generic: &'a syn::Type,
     type_name: &'a syn::Ident,
     parse_type: &'a Option<syn::Type>,
+    arena: &'a Option<Arena>,
 
     peek_impl: TokenStream,
     parse_impl: TokenStream,
     wc: TokenStream,
     after_each: TokenStream,
+    /// the field that records the ignored input, if any
+    trivia: Option<syn::Ident>,
+    /// the peek function and its lookahead as they were when the peek limit was reached, if it was
+    limited_peek: Option<(TokenStream, Option<TokenStream>)>,
+    /// how far the peek function reads ahead so far, or `None` if there's no limit
+    max_peek: Option<TokenStream>,
+    /// the most that any of the variants added so far read ahead
+    variant_peek: Option<TokenStream>,
+    /// whether ignored input is skipped between fields, which has no limit
+    ignores: bool,
+    /// whether the field being added is marked `no_ignore`, so nothing is skipped until its suffix
+    suppress_ignore: bool,
+    /// the field marked `version`, once it's been parsed
+    version: Option<syn::Ident>,
+    /// whether the parser has a `cut`, so it declares a `nommy::error::Cut`
+    cut: bool,
+    /// whether a field records the span, so the end of each part is kept
+    span: bool,
 }
 
 impl<'a> Builder<'a> {
@@ -29,42 +51,161 @@ impl<'a> Builder<'a> {
         generic: &'a syn::Type,
         type_name: &'a syn::Ident,
         parse_type: &'a Option<syn::Type>,
+        arena: &'a Option<Arena>,
     ) -> Self {
         Self {
             generic,
             type_name,
             parse_type,
+            arena,
             peek_impl: TokenStream::new(),
             parse_impl: TokenStream::new(),
             wc: TokenStream::new(),
             after_each: TokenStream::new(),
+            trivia: None,
+            limited_peek: None,
+            max_peek: Some(quote! { Some(0) }),
+            variant_peek: None,
+            ignores: false,
+            suppress_ignore: false,
+            version: None,
+            cut: false,
+            span: false,
         }
     }
 
     pub fn build(self) -> BuildOutput {
         let Builder {
             peek_impl,
-            parse_impl,
+            mut parse_impl,
             wc,
+            limited_peek,
+            max_peek,
+            cut,
             ..
         } = self;
+        if cut {
+            parse_impl.extend(quote! { __cut.pass(); });
+        }
+        let (peek_impl, max_peek) = limited_peek.unwrap_or((peek_impl, max_peek));
         BuildOutput {
             peek_impl,
             parse_impl,
             wc,
+            max_peek: max_peek.unwrap_or_else(|| quote! { None }),
         }
     }
 
+    /// Adds the lookahead of peeking something after everything so far
+    fn then_peek(&mut self, peek: Option<TokenStream>) {
+        self.max_peek = match (self.max_peek.take(), peek) {
+            (Some(before), Some(peek)) => Some(quote! { _nommy::lookahead::then(#before, #peek) }),
+            _ => None,
+        };
+    }
+
     pub fn create_ignore(&mut self, ignore: &[syn::Type]) {
+        if !ignore.is_empty() {
+            self.ignores = true;
+            self.max_peek = None;
+        }
         let (ignore_impl, after_each) = self.ignore_impl(ignore);
         self.after_each = after_each;
         self.peek_impl.extend(ignore_impl.clone());
         self.parse_impl.extend(ignore_impl);
     }
 
+    /// Skips the input ignored by the parent type, given as the `__ParseIgnore` parameter of the flat functions
+    pub fn flat_ignore(&mut self) {
+        let generic = self.generic;
+        self.ignores = true;
+        self.after_each = quote! {
+            <::std::vec::Vec<__ParseIgnore> as _nommy::Parse<#generic>>::peek(input);
+        };
+    }
+
+    /// Stops the peek function from checking anything added after this
+    pub fn limit_peek(&mut self) {
+        if self.limited_peek.is_none() {
+            self.limited_peek = Some((self.peek_impl.clone(), self.max_peek.clone()));
+        }
+    }
+
+    /// Declares the `Cut` that the parser's `cut`s arm. It's declared up front,
+    /// since a cut can be inside the block of a versioned field or the closure of a field's context
+    pub fn declare_cut(&mut self) {
+        self.cut = true;
+        self.parse_impl.extend(quote! {
+            let mut __cut = _nommy::error::Cut::new();
+        });
+    }
+
+    /// Commits to the variant being parsed from here on
+    pub fn add_cut(&mut self) {
+        self.parse_impl.extend(quote! { __cut.arm(); });
+    }
+
+    /// Keeps a copy of the input read from here on, for the `checksum` fields to be checked against
+    pub fn record_checksum(&mut self) {
+        let generic = self.generic;
+        self.wc.extend(quote! {#generic: ::std::clone::Clone,});
+        self.parse_impl.extend(quote! {
+            let mut checksum_input = _nommy::framed::Record::new(input);
+            let input = &mut checksum_input;
+        });
+    }
+
+    /// Records where the type starts, and keeps where the last part of it ends
+    /// before the input ignored after it, for the `span` fields
+    pub fn start_span(&mut self) {
+        self.span = true;
+        self.parse_impl.extend(quote! {
+            let __span_start = input.offset();
+            let __span_end = ::std::cell::Cell::new(__span_start);
+        });
+    }
+
+    /// Sets the `span` field `name`, once the whole type is parsed
+    pub fn end_span(&mut self, name: syn::Ident) {
+        self.parse_impl.extend(quote! {
+            let #name = _nommy::span::Span::new(__span_start, __span_end.get());
+        });
+    }
+
+    /// Records the ignored input into the field `name` while parsing
+    pub fn record_trivia(&mut self, name: syn::Ident, ty: &syn::Type) {
+        let generic = self.generic;
+        if !crate::ty::contains(ty, self.type_name) {
+            self.wc.extend(
+                quote! {#ty: _nommy::trivia::Record<#generic> + ::std::default::Default,},
+            );
+        }
+        self.parse_impl.extend(quote! {
+            let mut #name = <#ty as ::std::default::Default>::default();
+        });
+        self.trivia = Some(name);
+    }
+
     pub fn ignore(&mut self) {
+        if self.span {
+            self.parse_impl.extend(quote! { __span_end.set(input.offset()); });
+        }
+        if self.suppress_ignore {
+            return;
+        }
+        if self.ignores {
+            self.max_peek = None;
+        }
         self.peek_impl.extend(self.after_each.clone());
-        self.parse_impl.extend(self.after_each.clone());
+        match &self.trivia {
+            Some(name) => {
+                let generic = self.generic;
+                self.parse_impl.extend(quote! {
+                    _nommy::trivia::record::<__ParseIgnore, #generic, _>(input, &mut #name);
+                });
+            }
+            None => self.parse_impl.extend(self.after_each.clone()),
+        }
     }
 
     pub fn add_where(&mut self, ty: &syn::Type) {
@@ -74,16 +215,18 @@ impl<'a> Builder<'a> {
         self.wc.extend(tokens);
     }
 
-    pub fn add_fix(&mut self, fix: &Option<syn::Type>, fix_type: &'static str, name: String) {
+    /// Parses a prefix or suffix, where `fix_type` is `"prefix"` or `"suffix"`,
+    /// and `of` is the `nommy::message::Item` it belongs to
+    pub fn add_fix(&mut self, fix: &Option<syn::Type>, fix_type: &'static str, of: TokenStream) {
         if let Some(fix) = fix {
             self.add_where(&fix);
-            self.parse_impl.extend(
-                self.parser_peek_tokens(
-                    &fix,
-                    &format!("failed to parse {} for {}", fix_type, name),
-                ),
-            );
+            let error = match fix_type {
+                "prefix" => quote! { _nommy::message::Message::Prefix { of: #of } },
+                _ => quote! { _nommy::message::Message::Suffix { of: #of } },
+            };
+            self.parse_impl.extend(self.parser_fix_tokens(&fix, error));
             self.peek_impl.extend(self.peeker_peek_tokens(&fix));
+            self.then_peek(self.max_peek_of(fix));
             self.ignore();
         }
     }
@@ -93,15 +236,211 @@ impl<'a> Builder<'a> {
         let name = field.name(field_num);
         let attrs = field.attrs();
 
-        self.add_fix(&attrs.prefix, "prefix", format!("field `{}`", name));
+        if attrs.trivia {
+            // declared by `record_trivia`, or left empty if another field records the trivia
+            if self.trivia.as_ref() != Some(&name) {
+                self.parse_impl.extend(quote! {
+                    let #name = <#ty as ::std::default::Default>::default();
+                });
+            }
+            return;
+        }
+
+        // set by `end_span`, after the rest of the type is parsed
+        if attrs.span {
+            return;
+        }
+
+        // the lookahead is only peeked, so the field reads nothing and is its default
+        if let Some(lookahead) = &attrs.peek {
+            self.add_where(lookahead);
+            if !crate::ty::contains(ty, self.type_name) {
+                self.wc.extend(quote! {#ty: ::std::default::Default,});
+            }
+            let peek = self.peek_call(lookahead, quote! { &mut cursor });
+            let field = name.to_string();
+            self.parse_impl.extend(quote! {
+                if !{ let mut cursor = input.cursor(); #peek } {
+                    return Err(_nommy::eyre::Report::new(_nommy::message::Message::Lookahead { field: #field }));
+                }
+                let #name = <#ty as ::std::default::Default>::default();
+            });
+            self.peek_impl.extend(quote! {
+                if !{ let mut cursor = input.cursor(); #peek } { return false }
+            });
+            // an upper bound, since the fields after it read from where the lookahead started
+            self.then_peek(self.max_peek_of(lookahead));
+            return;
+        }
+
+        match &attrs.skip {
+            Some(Skip::Default) => {
+                if !crate::ty::contains(ty, self.type_name) {
+                    self.wc.extend(quote! {#ty: ::std::default::Default,});
+                }
+                self.parse_impl.extend(quote! {
+                    let #name = <#ty as ::std::default::Default>::default();
+                });
+                return;
+            }
+            Some(Skip::With(skip)) => {
+                self.parse_impl.extend(quote! {
+                    let #name = #skip;
+                });
+                return;
+            }
+            None => {}
+        }
+
+        // where the field's frame of any error starts, for `span::parse_at`
+        self.parse_impl.extend(quote! {
+            let __field_start = _nommy::Buffer::offset(input);
+        });
+
+        // a field that isn't in every version is parsed in a block, and is its default in the other versions.
+        // The version isn't known while peeking, so the peek stops before the field
+        let in_version = self
+            .version
+            .as_ref()
+            .and_then(|version| attrs.in_version(version.to_token_stream()));
+        let unversioned_impl = in_version.as_ref().map(|_| {
+            self.limit_peek();
+            std::mem::take(&mut self.parse_impl)
+        });
+
+        // the field is parsed in a closure, so its errors can be wrapped with the context
+        let outer_impl = attrs
+            .context
+            .as_ref()
+            .map(|_| std::mem::take(&mut self.parse_impl));
 
-        if attrs.vec.is_some() {
-            let parser: Option<&syn::Type> = (&attrs.vec.parser).into();
-            let parser = parser.unwrap();
+        // the checksum covers the input before its own prefix
+        if attrs.checksum {
+            self.parse_impl.extend(quote! {
+                let checksum_len = input.recorded().len();
+            });
+        }
+
+        self.suppress_ignore = attrs.no_ignore;
+        self.add_fix(&attrs.prefix, "prefix", item("field", &name));
+        // without a suffix, the input after the value is between this field and the next
+        self.suppress_ignore = attrs.no_ignore && attrs.suffix.is_some();
+
+        // the excluded type is only peeked, so it reads ahead from the start of the value without consuming anything
+        let excluded_peek = attrs.not.as_ref().and_then(|not| {
+            self.add_where(not);
+            let peek = self.peek_call(not, quote! { &mut cursor });
+            let field = name.to_string();
+            self.parse_impl.extend(quote! {
+                if { let mut cursor = input.cursor(); #peek } {
+                    return Err(_nommy::eyre::Report::new(_nommy::message::Message::Excluded { field: #field }));
+                }
+            });
+            self.peek_impl.extend(quote! {
+                if { let mut cursor = input.cursor(); #peek } { return false }
+            });
+            match (&self.max_peek, self.max_peek_of(not)) {
+                (Some(before), Some(not)) => Some(quote! { _nommy::lookahead::then(#before, #not) }),
+                _ => None,
+            }
+        });
+        let excluded = attrs.not.is_some();
+
+        if attrs.flatten {
+            let generic = self.generic;
+            if !crate::ty::contains(ty, self.type_name) {
+                self.wc
+                    .extend(quote! {#ty: _nommy::flatten::Flatten<#generic>,});
+            }
+            let error = field_message(&name, attrs);
+            self.parse_impl.extend(quote! {
+                let #name = <#ty as _nommy::flatten::Flatten<#generic>>::parse_flat::<__ParseIgnore>(input).wrap_err(#error).map_err(|err| _nommy::span::locate(err, __field_start))?;
+            });
+            self.peek_impl.extend(quote! {
+                if !<#ty as _nommy::flatten::Flatten<#generic>>::peek_flat::<__ParseIgnore>(input) { return false }
+            });
+            self.then_peek(None);
+            self.ignore();
+        } else if let Some(with) = &attrs.with {
+            let error = field_message(&name, attrs);
+            self.parse_impl.extend(quote! {
+                let #name = #with(input).wrap_err(#error).map_err(|err| _nommy::span::locate(err, __field_start))?;
+            });
+            self.peek_impl.extend(match &attrs.peek_with {
+                Some(peek_with) => quote! {
+                    if !#peek_with(input) { return false }
+                },
+                None => quote! {
+                    if #with(input).is_err() { return false }
+                },
+            });
+            self.then_peek(None);
+            self.ignore();
+        } else if let Some(raw) = &attrs.raw {
+            let error = field_message(&name, attrs);
+            self.parse_impl.extend(quote! {
+                let #name = {
+                    let (value, consumed) = #raw(&mut input.cursor()).wrap_err(#error).map_err(|err| _nommy::span::locate(err, __field_start))?;
+                    input.fast_forward(consumed);
+                    value
+                };
+            });
+            self.peek_impl.extend(quote! {
+                match #raw(&mut input.cursor()) {
+                    Ok((_, consumed)) => input.fast_forward(consumed),
+                    Err(_) => return false,
+                }
+            });
+            self.then_peek(None);
+            self.ignore();
+        } else if let Some(parser) = &attrs.vec.parser {
             self.add_where(&parser);
-            self.parse_impl
-                .extend(self.parser_parse_vec_tokens(&name, &attrs.vec));
-            self.peek_impl.extend(self.peeker_peek_vec_tokens(&parser));
+            if let Some(sep) = &attrs.vec.seperated_by {
+                self.add_where(sep);
+            }
+            self.parse_impl.extend(self.parser_parse_vec_tokens(
+                &name,
+                parser,
+                &attrs.vec,
+                &attrs.suffix,
+                field_message(&name, attrs),
+            ));
+            // which values fail can't be known without parsing them, so the peek stops before the field
+            if attrs.vec.recover {
+                self.limit_peek();
+            }
+            self.peek_impl
+                .extend(self.peeker_peek_vec_tokens(&parser, &attrs.vec));
+            self.then_peek(self.max_peek_vec(parser, &attrs.vec));
+        } else if let Some(inner) = crate::ty::option_inner(ty)
+            .filter(|_| attrs.parser.is_none() && attrs.convert.is_none() && !attrs.no_auto_option)
+        {
+            // only parsed if the inner type can be peeked, so its errors aren't hidden by `None`
+            self.add_where(inner);
+            let parse = self.parse_call(inner);
+            let peek = self.peek_call(inner, quote! { &mut cursor });
+            let error = field_message(&name, attrs);
+            self.parse_impl.extend(quote! {
+                let #name = {
+                    let mut cursor = input.cursor();
+                    if #peek {
+                        Some(#parse.wrap_err(#error).map_err(|err| _nommy::span::locate(err, __field_start))?)
+                    } else {
+                        None
+                    }
+                };
+            });
+            self.peek_impl.extend(quote! {
+                {
+                    let mut cursor = input.cursor();
+                    if #peek {
+                        let pos = cursor.position();
+                        input.fast_forward(pos);
+                    }
+                }
+            });
+            self.then_peek(self.max_peek_of(inner));
+            self.ignore();
         } else {
             let parser: Option<&syn::Type> = (&attrs.parser).into();
             let parser = parser.unwrap_or(&ty);
@@ -109,36 +448,319 @@ impl<'a> Builder<'a> {
             self.parse_impl.extend(self.parser_parse_tokens(
                 &name,
                 &parser,
-                &format!("failed to parse field `{}`", name),
+                ty,
+                field_message(&name, attrs),
+                attrs,
             ));
             self.peek_impl.extend(self.peeker_peek_tokens(&parser));
+            self.then_peek(self.max_peek_of(parser));
             self.ignore();
         }
+        if excluded {
+            self.max_peek = match (self.max_peek.take(), excluded_peek) {
+                (Some(field), Some(not)) => Some(quote! { _nommy::lookahead::either(#field, #not) }),
+                _ => None,
+            };
+        }
+        if let Some(map) = &attrs.map {
+            let error = field_message(&name, attrs);
+            self.parse_impl.extend(quote! {
+                let #name = _nommy::map::MapResult::<#ty>::into_result(#map(#name)).wrap_err(#error).map_err(|err| _nommy::span::locate(err, __field_start))?;
+            });
+        }
+        // fixes the type of the field, so later `skip_with` expressions can call methods on it
+        self.parse_impl.extend(quote! {
+            let #name: #ty = #name;
+        });
+        if attrs.checksum {
+            let generic = self.generic;
+            let error = field_message(&name, attrs);
+            if !crate::ty::contains(ty, self.type_name) {
+                self.wc
+                    .extend(quote! {#ty: _nommy::framed::Checksum<#generic>,});
+            }
+            self.parse_impl.extend(quote! {
+                if !_nommy::framed::Checksum::<#generic>::matches(&#name, &input.recorded()[..checksum_len]) {
+                    return Err(_nommy::span::locate(_nommy::eyre::Report::new(_nommy::message::Message::Checksum).wrap_err(#error), __field_start));
+                }
+            });
+        }
+        self.suppress_ignore = false;
+
+        self.add_fix(&attrs.suffix, "suffix", item("field", &name));
 
-        self.add_fix(&attrs.suffix, "suffix", format!("field `{}`", name));
+        if let (Some(context), Some(outer_impl)) = (&attrs.context, outer_impl) {
+            let field_impl = std::mem::replace(&mut self.parse_impl, outer_impl);
+            self.parse_impl.extend(quote! {
+                let #name = (|| -> _nommy::eyre::Result<#ty> {
+                    #field_impl
+                    Ok(#name)
+                })().wrap_err(#context).map_err(|err| _nommy::span::locate(err, __field_start))?;
+            });
+        }
+
+        if let (Some(in_version), Some(unversioned_impl)) = (in_version, unversioned_impl) {
+            let field_impl = std::mem::replace(&mut self.parse_impl, unversioned_impl);
+            if !crate::ty::contains(ty, self.type_name) {
+                self.wc.extend(quote! {#ty: ::std::default::Default,});
+            }
+            self.parse_impl.extend(quote! {
+                let #name: #ty = if #in_version {
+                    #field_impl
+                    #name
+                } else {
+                    <#ty as ::std::default::Default>::default()
+                };
+            });
+        }
+        if attrs.version {
+            self.version = Some(name);
+        }
+        if attrs.cut {
+            self.add_cut();
+        }
     }
 
-    pub fn start_variants(&mut self) {
+    pub fn start_variants(&mut self, enum_name: String) {
         self.parse_impl.extend(quote! {
+            let __enum_start = _nommy::Buffer::offset(input);
             let mut cursor = input.cursor();
+            let mut variant_errors = _nommy::error::VariantErrors::new(#enum_name);
             let result =
         });
         self.peek_impl
             .extend(quote! { let mut cursor = input.cursor(); if });
+        self.variant_peek = Some(quote! { Some(0) });
+    }
+    /// Chooses the most that any of the variants read ahead
+    fn either_peek(&mut self, max_peek: &TokenStream) {
+        self.variant_peek = self
+            .variant_peek
+            .take()
+            .map(|before| quote! { _nommy::lookahead::either(#before, #max_peek) });
+    }
+    pub fn add_variant(
+        &mut self,
+        variant: String,
+        peek_name: &syn::Ident,
+        parse_name: &syn::Ident,
+        max_peek: &TokenStream,
+    ) {
+        self.either_peek(max_peek);
+        let arena_arg = self.arena.as_ref().map(|_| quote! {, __arena});
+        self.parse_impl.extend(quote! {
+            if let (true, Some(result)) = (
+                {
+                    _nommy::error::Cut::reset();
+                    cursor.reset_internal()
+                },
+                Self::#parse_name(&mut cursor #arena_arg)
+                    .map_err(|err| variant_errors.push(#variant, cursor.position(), err))
+                    .ok(),
+            ) {
+                result
+            } else if _nommy::error::Cut::committed() {
+                variant_errors.commit();
+                return Err(_nommy::span::locate(variant_errors.into(), __enum_start));
+            } else
+        });
+        self.peek_impl.extend(quote! {
+            !Self::#peek_name(&mut cursor #arena_arg) && cursor.reset_internal() &&
+        });
+    }
+    /// Parses the variant chosen by `trie`, which reads the keywords from the cursor,
+    /// rather than trying each variant in turn
+    pub fn add_keywords(
+        &mut self,
+        enum_name: String,
+        variants: &[(String, syn::Ident, syn::Ident, TokenStream)],
+        trie: TokenStream,
+    ) {
+        let generic = self.generic;
+        self.variant_peek = Some(quote! { Some(0) });
+        for (_, _, _, max_peek) in variants {
+            self.either_peek(max_peek);
+        }
+        let variant_peek = self.variant_peek.take();
+        self.then_peek(variant_peek);
+        let arena_arg = self.arena.as_ref().map(|_| quote! {, __arena});
+        let parse_arms = variants.iter().enumerate().map(|(i, (variant, _, parse_name, _))| {
+            quote! {
+                Some(#i) => match Self::#parse_name(&mut cursor #arena_arg) {
+                    Ok(result) => result,
+                    Err(err) => {
+                        variant_errors.push(#variant, cursor.position(), err);
+                        return Err(_nommy::span::locate(variant_errors.into(), __enum_start));
+                    }
+                },
+            }
+        });
+        let peek_arms = variants.iter().enumerate().map(|(i, (_, peek_name, _, _))| {
+            quote! {
+                Some(#i) => Self::#peek_name(&mut cursor #arena_arg),
+            }
+        });
+
+        self.wc.extend(quote! {#generic: ::std::cmp::PartialEq<char>,});
+        self.parse_impl.extend(quote! {
+            let __enum_start = _nommy::Buffer::offset(input);
+            let mut cursor = input.cursor();
+            let mut variant_errors = _nommy::error::VariantErrors::new(#enum_name);
+            let result = match #trie {
+                #(#parse_arms)*
+                _ => return Err(_nommy::span::locate(variant_errors.into(), __enum_start)),
+            };
+            let pos = cursor.position();
+            input.fast_forward(pos);
+        });
+        self.peek_impl.extend(quote! {
+            let mut cursor = input.cursor();
+            let peeked = match #trie {
+                #(#peek_arms)*
+                _ => false,
+            };
+            if !peeked {
+                return false;
+            }
+            let pos = cursor.position();
+            input.fast_forward(pos);
+        });
+    }
+    /// Parses every variant, and chooses the one that read the most input.
+    /// If variants read the same amount, the first of them is chosen. The `fallback` is only tried if they all fail
+    pub fn add_longest_variants(
+        &mut self,
+        enum_name: String,
+        variants: &[(String, syn::Ident, syn::Ident, TokenStream)],
+        fallback: Option<&(String, syn::Ident, syn::Ident, TokenStream)>,
+    ) {
+        self.variant_peek = Some(quote! { Some(0) });
+        for (_, _, _, max_peek) in variants.iter().chain(fallback) {
+            self.either_peek(max_peek);
+        }
+        let variant_peek = self.variant_peek.take();
+        self.then_peek(variant_peek);
+        let arena_arg = self.arena.as_ref().map(|_| quote! {, __arena});
+        let parse_variants = variants.iter().map(|(variant, _, parse_name, _)| {
+            quote! {
+                let mut cursor = input.cursor();
+                _nommy::error::Cut::reset();
+                match Self::#parse_name(&mut cursor #arena_arg) {
+                    Ok(result) => {
+                        let pos = cursor.position();
+                        let longer = match &longest {
+                            Some((longest, _)) => pos > *longest,
+                            None => true,
+                        };
+                        if longer {
+                            longest = Some((pos, result));
+                        }
+                    }
+                    Err(err) => {
+                        variant_errors.push(#variant, cursor.position(), err);
+                        if _nommy::error::Cut::committed() {
+                            variant_errors.commit();
+                            return Err(_nommy::span::locate(variant_errors.into(), __enum_start));
+                        }
+                    }
+                }
+            }
+        });
+        let peek_variants = variants.iter().map(|(_, peek_name, _, _)| {
+            quote! {
+                let mut cursor = input.cursor();
+                if Self::#peek_name(&mut cursor #arena_arg) {
+                    let pos = cursor.position();
+                    let longer = match longest {
+                        Some(longest) => pos > longest,
+                        None => true,
+                    };
+                    if longer {
+                        longest = Some(pos);
+                    }
+                }
+            }
+        });
+        let (parse_fallback, peek_fallback) = match fallback {
+            Some((variant, peek_name, parse_name, _)) => (
+                quote! {
+                    let mut cursor = input.cursor();
+                    match Self::#parse_name(&mut cursor #arena_arg) {
+                        Ok(result) => {
+                            let pos = cursor.position();
+                            input.fast_forward(pos);
+                            result
+                        }
+                        Err(err) => {
+                            variant_errors.push(#variant, cursor.position(), err);
+                            return Err(_nommy::span::locate(variant_errors.into(), __enum_start));
+                        }
+                    }
+                },
+                quote! {
+                    let mut cursor = input.cursor();
+                    if !Self::#peek_name(&mut cursor #arena_arg) {
+                        return false;
+                    }
+                    let pos = cursor.position();
+                    input.fast_forward(pos);
+                },
+            ),
+            None => (
+                quote! { return Err(_nommy::span::locate(variant_errors.into(), __enum_start)) },
+                quote! { return false; },
+            ),
+        };
+        self.parse_impl.extend(quote! {
+            let __enum_start = _nommy::Buffer::offset(input);
+            let mut variant_errors = _nommy::error::VariantErrors::new(#enum_name);
+            let mut longest: ::std::option::Option<(usize, Self)> = None;
+            #({ #parse_variants })*
+            let result = match longest {
+                Some((pos, result)) => {
+                    input.fast_forward(pos);
+                    result
+                }
+                None => { #parse_fallback }
+            };
+        });
+        self.peek_impl.extend(quote! {
+            let mut longest: ::std::option::Option<usize> = None;
+            #({ #peek_variants })*
+            match longest {
+                Some(pos) => input.fast_forward(pos),
+                None => { #peek_fallback }
+            }
+        });
     }
-    pub fn add_variant(&mut self, peek_name: &syn::Ident, parse_name: &syn::Ident) {
+    pub fn add_extension(&mut self) {
+        let generic = self.generic;
+        self.variant_peek = None;
         self.parse_impl.extend(quote! {
-            if let (true, Ok(result)) = (cursor.reset_internal(), Self::#parse_name(&mut cursor)) {
+            if let (true, Some(result)) = (
+                cursor.reset_internal(),
+                _nommy::extension::parse::<#generic, Self>(&mut cursor)
+                    .map_err(|err| variant_errors.push("extension", cursor.position(), err))
+                    .ok(),
+            ) {
                 result
             } else
         });
         self.peek_impl.extend(quote! {
-            !Self::#peek_name(&mut cursor) && cursor.reset_internal() &&
+            !_nommy::extension::peek::<#generic, Self>(&mut cursor) && cursor.reset_internal() &&
         });
+        if self.parse_type.is_none() {
+            self.wc.extend(quote! {#generic: 'static, Self: 'static,});
+        }
     }
-    pub fn finish_variants(&mut self, error: String) {
+    pub fn finish_variants(&mut self) {
+        let variant_peek = self.variant_peek.take();
+        self.then_peek(variant_peek);
+        // every variant error is kept, see `nommy::error::VariantErrors` for which becomes the source
         self.parse_impl.extend(quote! {
-            { return Err(::nommy::eyre::eyre!(#error)); };
+            {
+                return Err(_nommy::span::locate(variant_errors.into(), __enum_start));
+            };
             let pos = cursor.position();
             input.fast_forward(pos);
         });
@@ -160,29 +782,303 @@ pub struct FnImpl<'a, F> {
 
 impl<'a, F: FieldType> FnImpl<'a, F> {
     pub fn build(&self, type_name: &syn::Ident) -> BuildOutput {
-        let mut builder = Builder::new(self.generic, type_name, &self.attrs.parse_type);
+        self.build_with(type_name, false)
+    }
 
-        builder.create_ignore(&self.attrs.ignore);
-        builder.add_fix(
-            &self.attrs.prefix,
-            "prefix",
-            format!("{} `{}`", self.ty, self.name),
+    /// Whether any of the fields mention the type being derived, so they're parsed with its own impl
+    pub fn recursive(&self, type_name: &syn::Ident) -> bool {
+        self.fields
+            .iter()
+            .any(|field| crate::ty::contains(field.ty(), type_name))
+    }
+
+    /// Builds the functions of `nommy::flatten::Flatten`, which skip the parent's ignored input
+    /// instead of this type's
+    pub fn build_flat(&self, type_name: &syn::Ident) -> BuildOutput {
+        self.build_with(type_name, true)
+    }
+
+    fn build_with(&self, type_name: &syn::Ident, flat: bool) -> BuildOutput {
+        let mut builder = Builder::new(
+            self.generic,
+            type_name,
+            &self.attrs.parse_type,
+            &self.attrs.arena,
         );
 
+        if flat {
+            builder.flat_ignore();
+            // recursive fields are parsed with this type's `Parse` impl, which needs its ignored types
+            if self.recursive(type_name) {
+                for ty in &self.attrs.ignore {
+                    builder.add_where(ty);
+                }
+            }
+        } else {
+            builder.create_ignore(&self.attrs.ignore);
+        }
+        if let Some((field_num, field)) = self
+            .fields
+            .iter()
+            .enumerate()
+            .find(|(_, field)| field.attrs().trivia)
+        {
+            builder.record_trivia(field.name(field_num), field.ty());
+        }
+        if self.fields.iter().any(|field| field.attrs().span) {
+            builder.start_span();
+        }
+        if self.attrs.cut || self.fields.iter().any(|field| field.attrs().cut) {
+            builder.declare_cut();
+        }
+        builder.add_fix(&self.attrs.prefix, "prefix", item(self.ty, self.name));
+        if self.attrs.cut {
+            builder.add_cut();
+        }
+        if self.fields.iter().any(|field| field.attrs().checksum) {
+            builder.record_checksum();
+        }
+
         for (field_num, field) in self.fields.iter().enumerate() {
+            if self.attrs.peek_limit == Some(field_num) {
+                builder.limit_peek();
+            }
             builder.add_field(field, field_num)
         }
 
-        builder.add_fix(
-            &self.attrs.suffix,
-            "suffix",
-            format!("{} `{}`", self.ty, self.name),
-        );
+        builder.add_fix(&self.attrs.suffix, "suffix", item(self.ty, self.name));
+        for (field_num, field) in self.fields.iter().enumerate() {
+            if field.attrs().span {
+                builder.end_span(field.name(field_num));
+            }
+        }
 
         builder.build()
     }
 }
 
+/// The `nommy::message::Message` that the errors of the field `name` are wrapped with
+fn field_message(name: &syn::Ident, attrs: &FieldAttr) -> TokenStream {
+    let field = name.to_string();
+    match &attrs.expected {
+        Some(expected) => {
+            quote! { _nommy::message::Message::Expected { field: #field, expected: #expected } }
+        }
+        None => quote! { _nommy::message::Message::Field { field: #field } },
+    }
+}
+
+/// The `nommy::message::Item` for a `kind` of item, such as `"struct"`, `"enum"`, `"field"` or `"tuple variant"`
+pub fn item(kind: &str, name: &syn::Ident) -> TokenStream {
+    let name = name.to_string();
+    let kind = match kind {
+        "struct" => quote! { Struct },
+        "enum" => quote! { Enum },
+        "field" => quote! { Field },
+        _ => quote! { Variant },
+    };
+    quote! { _nommy::message::Item::#kind(#name) }
+}
+
+/// The header of the derived impl, along with the signatures of its parse and peek functions
+pub struct ImplTokens {
+    pub impl_line: TokenStream,
+    pub parse_fn: TokenStream,
+    pub peek_fn: TokenStream,
+    /// the `MAX_PEEK` constant, which `ParseIn` doesn't have
+    pub max_peek_const: TokenStream,
+    /// the header of the `Peek` impl for `#[derive(Peek)]`, which only has the peek function
+    pub peek_line: TokenStream,
+}
+
+impl ImplTokens {
+    /// The impl block, given the bodies of the parse and peek functions.
+    /// `#[derive(Peek)]` only implements `Peek`, and `no_peek` leaves peeking to the default, which parses
+    pub fn block(self, attrs: &GlobalAttr, parse_body: TokenStream, peek_body: TokenStream) -> TokenStream {
+        let ImplTokens {
+            impl_line,
+            parse_fn,
+            peek_fn,
+            max_peek_const,
+            peek_line,
+        } = self;
+        // the value is parsed in a closure, so the end of the input can be checked after any of its returns
+        let parse = match attrs.complete {
+            true => quote! {
+                #parse_fn {
+                    let result: _nommy::eyre::Result<Self> = (|| { #parse_body })();
+                    let value = result?;
+                    if let ::std::option::Option::Some(found) = input.peek_ahead(0) {
+                        return Err(_nommy::eyre::Report::new(_nommy::message::Message::Trailing {
+                            found: ::std::format!("{:?}", found),
+                        }));
+                    }
+                    Ok(value)
+                }
+            },
+            false => quote! { #parse_fn { #parse_body } },
+        };
+        // like parsing, peeking fails if the input continues after the value
+        let peek_body = match attrs.complete {
+            true => quote! {
+                let matched: bool = (|| { #peek_body })();
+                matched && input.peek_ahead(0).is_none()
+            },
+            false => peek_body,
+        };
+        if attrs.peek_only {
+            return quote! {
+                #[automatically_derived]
+                #peek_line {
+                    #max_peek_const
+                    #peek_fn { #peek_body }
+                }
+            };
+        }
+        let (peek, max_peek_const) = match attrs.no_peek {
+            true => (quote! {}, quote! {}),
+            false => (quote! { #peek_fn { #peek_body } }, max_peek_const),
+        };
+        quote! {
+            #[automatically_derived]
+            #impl_line {
+                #max_peek_const
+                #parse
+                #peek
+            }
+        }
+    }
+}
+
+pub fn impl_tokens(
+    attrs: &GlobalAttr,
+    name: &syn::Ident,
+    args: &Args,
+    generic: &syn::Type,
+    wc: &TokenStream,
+    max_peek: &TokenStream,
+) -> ImplTokens {
+    let generic_param = match attrs.parse_type {
+        Some(_) => quote! {},
+        None => quote! { #generic, },
+    };
+    let mut wc = attrs.bounds(wc);
+    // the trailing token is reported with its `Debug` impl
+    if attrs.complete && attrs.parse_type.is_none() {
+        wc.extend(quote! { #generic: ::std::fmt::Debug, });
+    }
+    let wc = args.where_clause(wc);
+    // the end of the input is checked by peeking one more token
+    let max_peek = match attrs.complete {
+        true => quote! { _nommy::lookahead::then(#max_peek, Some(1)) },
+        false => quote! { #max_peek },
+    };
+
+    let params = args.declare(generic_param);
+    let args = args.apply();
+
+    match &attrs.arena {
+        None => ImplTokens {
+            impl_line: quote! {
+                impl<#params> _nommy::Parse<#generic> for #name<#args> #wc
+            },
+            parse_fn: quote! {
+                fn parse(input: &mut impl _nommy::Buffer<#generic>) -> _nommy::eyre::Result<Self>
+            },
+            peek_fn: quote! {
+                fn peek(input: &mut impl _nommy::Buffer<#generic>) -> bool
+            },
+            max_peek_const: quote! {
+                const MAX_PEEK: ::std::option::Option<usize> = #max_peek;
+            },
+            peek_line: quote! {
+                impl<#params> _nommy::Peek<#generic> for #name<#args> #wc
+            },
+        },
+        Some(Arena { lifetime, declared }) => {
+            let type_lifetime = if *declared {
+                quote! { #lifetime, }
+            } else {
+                quote! {}
+            };
+            ImplTokens {
+                impl_line: quote! {
+                    impl<#lifetime, #params> _nommy::arena::ParseIn<#lifetime, #generic> for #name<#type_lifetime #args> #wc
+                },
+                parse_fn: quote! {
+                    fn parse_in(input: &mut impl _nommy::Buffer<#generic>, __arena: &#lifetime _nommy::arena::Bump) -> _nommy::eyre::Result<Self>
+                },
+                peek_fn: quote! {
+                    fn peek_in(input: &mut impl _nommy::Buffer<#generic>, __arena: &#lifetime _nommy::arena::Bump) -> bool
+                },
+                max_peek_const: quote! {},
+                // `#[derive(Peek)]` can't be used with an arena
+                peek_line: quote! {},
+            }
+        }
+    }
+}
+
+/// Implements `FromStr` by parsing the whole string, if the type asks for it with `from_str`
+pub fn from_str_impl(attrs: &GlobalAttr, name: &syn::Ident, args: &Args) -> TokenStream {
+    if !attrs.from_str {
+        return TokenStream::new();
+    }
+    let type_params = args.declare(quote! {});
+    let type_args = args.apply();
+    let where_clause = args.where_clause(quote! { Self: _nommy::Parse<char>, });
+    quote! {
+        impl<#type_params> ::std::str::FromStr for #name<#type_args> #where_clause {
+            type Err = _nommy::error::ParseError;
+            fn from_str(s: &str) -> ::std::result::Result<Self, Self::Err> {
+                _nommy::parse_terminated(s.chars()).map_err(_nommy::error::ParseError::from)
+            }
+        }
+    }
+}
+
+/// The impl of `nommy::flatten::Flatten` for a struct, which returns `result` after the fields are parsed
+pub fn flat_impl(
+    attrs: &GlobalAttr,
+    name: &syn::Ident,
+    args: &Args,
+    generic: &syn::Type,
+    build: BuildOutput,
+    result: TokenStream,
+) -> TokenStream {
+    let BuildOutput {
+        peek_impl,
+        parse_impl,
+        wc,
+        ..
+    } = build;
+    let generic_param = match attrs.parse_type {
+        Some(_) => quote! {},
+        None => quote! { #generic, },
+    };
+    let wc = args.where_clause(attrs.bounds(&wc));
+    let params = args.declare(generic_param);
+    let args = args.apply();
+    let ok = attrs.ok(result);
+    let body = attrs.with_context(quote! { #parse_impl #ok });
+
+    quote! {
+        #[automatically_derived]
+        impl<#params> _nommy::flatten::Flatten<#generic> for #name<#args> #wc {
+            fn parse_flat<__ParseIgnore: _nommy::Parse<#generic>>(input: &mut impl _nommy::Buffer<#generic>) -> _nommy::eyre::Result<Self> {
+                use _nommy::eyre::WrapErr;
+                use ::std::convert::TryInto;
+                #body
+            }
+
+            fn peek_flat<__ParseIgnore: _nommy::Parse<#generic>>(input: &mut impl _nommy::Buffer<#generic>) -> bool {
+                #peek_impl
+                true
+            }
+        }
+    }
+}
+
 pub fn parse_or(parse_type: &Option<syn::Type>) -> syn::Type {
     match &parse_type {
         Some(pt) => pt.clone(),
@@ -205,100 +1101,273 @@ pub fn parse_or(parse_type: &Option<syn::Type>) -> syn::Type {
 }
 
 impl<'a> Builder<'a> {
+    /// Returns the arena lifetime if `ty` should be parsed into the arena
+    fn arena_lifetime(&self, ty: &syn::Type) -> Option<&syn::Lifetime> {
+        self.arena
+            .as_ref()
+            .map(|arena| &arena.lifetime)
+            .filter(|lt| crate::ty::mentions_lifetime(ty, lt))
+    }
     fn where_tokens(&self, ty: &syn::Type) -> TokenStream {
+        let generic = &self.generic;
         if crate::ty::contains(&ty, &self.type_name) {
             quote! {}
+        } else if let Some(lt) = self.arena_lifetime(ty) {
+            quote! {#ty: _nommy::arena::ParseIn<#lt, #generic>,}
         } else {
-            let generic = &self.generic;
-            quote! {#ty: ::nommy::Parse<#generic>,}
+            quote! {#ty: _nommy::Parse<#generic>,}
         }
     }
-    fn parser_peek_tokens(&self, ty: &syn::Type, error: &str) -> TokenStream {
+    /// The lookahead of peeking `ty`. Types that mention the type being derived, or are parsed into the arena,
+    /// are assumed to have no limit, so the constant doesn't depend on itself
+    fn max_peek_of(&self, ty: &syn::Type) -> Option<TokenStream> {
+        if crate::ty::contains(ty, self.type_name) || self.arena_lifetime(ty).is_some() {
+            return None;
+        }
         let generic = &self.generic;
-        quote! {
-            if !(<#ty as ::nommy::Parse<#generic>>::peek(input)) { return Err(::nommy::eyre::eyre!(#error)) }
+        Some(quote! { <#ty as _nommy::Parse<#generic>>::MAX_PEEK })
+    }
+    /// The lookahead of peeking a collection field, which has no limit unless the number of values does
+    fn max_peek_vec(&self, parser: &syn::Type, attrs: &VecFieldAttr) -> Option<TokenStream> {
+        if attrs.count.is_none() && attrs.max.is_none() {
+            return None;
+        }
+        let (_, max) = attrs.bounds();
+        let value = self.max_peek_of(parser)?;
+        let each = match &attrs.seperated_by {
+            Some(sep) => {
+                let sep = self.max_peek_of(sep)?;
+                quote! { _nommy::lookahead::then(#value, #sep) }
+            }
+            None => value,
+        };
+        Some(quote! { _nommy::lookahead::repeat(#each, #max) })
+    }
+    fn parse_call(&self, ty: &syn::Type) -> TokenStream {
+        let generic = &self.generic;
+        match self.arena_lifetime(ty) {
+            Some(lt) => quote! { <#ty as _nommy::arena::ParseIn<#lt, #generic>>::parse_in(input, __arena) },
+            None => quote! { <#ty as _nommy::Parse<#generic>>::parse(input) },
         }
     }
-    fn parser_parse_tokens(&self, name: &syn::Ident, ty: &syn::Type, error: &str) -> TokenStream {
+    fn peek_call(&self, ty: &syn::Type, input: TokenStream) -> TokenStream {
         let generic = &self.generic;
+        match self.arena_lifetime(ty) {
+            Some(lt) => quote! { <#ty as _nommy::arena::ParseIn<#lt, #generic>>::peek_in(#input, __arena) },
+            None => quote! { <#ty as _nommy::Parse<#generic>>::peek(#input) },
+        }
+    }
+    fn parser_fix_tokens(&self, ty: &syn::Type, error: TokenStream) -> TokenStream {
+        let parse = self.parse_call(ty);
         quote! {
-            let #name = <#ty as ::nommy::Parse<#generic>>::parse(input).wrap_err(#error)?.try_into()?;
+            #parse.wrap_err(#error)?;
+        }
+    }
+    /// Parses the field `name` of type `field_ty` with `parser`, converting the output as the attributes say
+    fn parser_parse_tokens(
+        &self,
+        name: &syn::Ident,
+        parser: &syn::Type,
+        field_ty: &syn::Type,
+        error: TokenStream,
+        attrs: &FieldAttr,
+    ) -> TokenStream {
+        let parse = self.parse_call(parser);
+        match &attrs.convert {
+            Some(Convert::Into) => quote! {
+                let #name: #field_ty = ::std::convert::Into::into(#parse.wrap_err(#error).map_err(|err| _nommy::span::locate(err, __field_start))?);
+            },
+            Some(Convert::FromOutput) => quote! {
+                let #name = <#field_ty as ::std::convert::From<#parser>>::from(#parse.wrap_err(#error).map_err(|err| _nommy::span::locate(err, __field_start))?);
+            },
+            Some(Convert::None) => quote! {
+                let #name: #field_ty = #parse.wrap_err(#error).map_err(|err| _nommy::span::locate(err, __field_start))?;
+            },
+            Some(Convert::With(convert)) => quote! {
+                let #name: #field_ty = #convert(#parse.wrap_err(#error).map_err(|err| _nommy::span::locate(err, __field_start))?);
+            },
+            None if attrs.try_into => {
+                let field = name.to_string();
+                quote! {
+                    let #name = #parse.wrap_err(#error).map_err(|err| _nommy::span::locate(err, __field_start))?.try_into().wrap_err(_nommy::message::Message::Convert { field: #field })?;
+                }
+            }
+            None => quote! {
+                let #name = #parse.wrap_err(#error).map_err(|err| _nommy::span::locate(err, __field_start))?.try_into()?;
+            },
         }
     }
     fn peeker_peek_tokens(&self, ty: &syn::Type) -> TokenStream {
-        let generic = &self.generic;
+        let peek = self.peek_call(ty, quote! { input });
         quote! {
-            if !(<#ty as ::nommy::Parse<#generic>>::peek(input)) { return false }
+            if !(#peek) { return false }
         }
     }
 
-    fn parser_parse_vec_tokens(&self, name: &syn::Ident, attrs: &VecFieldAttr) -> TokenStream {
+    fn parser_parse_vec_tokens(
+        &self,
+        name: &syn::Ident,
+        parser: &syn::Type,
+        attrs: &VecFieldAttr,
+        suffix: &Option<syn::Type>,
+        error: TokenStream,
+    ) -> TokenStream {
         let generic = &self.generic;
 
-        let parser: Option<&syn::Type> = (&attrs.parser).into();
-        let parser = parser.unwrap();
+        let (min, max) = attrs.bounds();
 
-        let (min, max) = match &attrs.count {
-            Some(count) => (quote! { #count }, quote! { #count }),
-            None => {
-                let min = match &attrs.min {
-                    Some(min) => quote! { #min },
-                    None => quote! { 0 },
-                };
-                let max = match &attrs.max {
-                    Some(max) => quote! { #max },
-                    None => quote! { usize::MAX },
-                };
-                (min, max)
+        if let (true, Some(sep)) = (attrs.recover, &attrs.seperated_by) {
+            let until = match suffix {
+                Some(suffix) => quote! { #suffix },
+                None => quote! { _nommy::vec::Never },
+            };
+            quote! {
+                let #name = _nommy::vec::parse_vec_recover::<#parser, _, #sep, #until, __ParseIgnore, #generic, _, _>(#min, #max, input).wrap_err(#error).map_err(|err| _nommy::span::locate(err, __field_start))?;
             }
-        };
-
-        if let Some(sep) = &attrs.seperated_by {
+        } else if let Some(sep) = &attrs.seperated_by {
             match &attrs.trailing {
                 Some(true) => quote! {
-                    let #name = ::nommy::vec::parse_vec_seperated_by_trailing::<#parser, _, #sep, __ParseIgnore, #generic, _>(#max, input)?;
-                    if #name.len() < #min {
-                        return Err(::nommy::eyre::eyre!("could not parse enough for vec"));
-                    }
+                    let #name = _nommy::vec::parse_vec_seperated_by_trailing::<#parser, _, #sep, __ParseIgnore, #generic, _, _>(#min, #max, input).wrap_err(#error).map_err(|err| _nommy::span::locate(err, __field_start))?;
                 },
                 Some(false) => quote! {
-                    let #name = ::nommy::vec::parse_vec_seperated_by_maybe_trailing::<#parser, _, #sep, __ParseIgnore, #generic, _>(#max, input)?;
-                    if #name.len() < #min {
-                        return Err(::nommy::eyre::eyre!("could not parse enough for vec"));
-                    }
+                    let #name = _nommy::vec::parse_vec_seperated_by_maybe_trailing::<#parser, _, #sep, __ParseIgnore, #generic, _, _>(#min, #max, input).wrap_err(#error).map_err(|err| _nommy::span::locate(err, __field_start))?;
                 },
                 None => quote! {
-                    let #name = ::nommy::vec::parse_vec_seperated_by::<#parser, _, #sep, __ParseIgnore, #generic, _>(#max, input)?;
-                    if #name.len() < #min {
-                        return Err(::nommy::eyre::eyre!("could not parse enough for vec"));
-                    }
+                    let #name = _nommy::vec::parse_vec_seperated_by::<#parser, _, #sep, __ParseIgnore, #generic, _, _>(#min, #max, input).wrap_err(#error).map_err(|err| _nommy::span::locate(err, __field_start))?;
                 },
             }
         } else {
             quote! {
-                let #name = ::nommy::vec::parse_vec::<#parser, _, __ParseIgnore, #generic, _>(#max, input)?;
-                if #name.len() < #min {
-                    return Err(::nommy::eyre::eyre!("could not parse enough for vec"));
-                }
+                let #name = _nommy::vec::parse_vec::<#parser, _, __ParseIgnore, #generic, _, _>(#min, #max, input).wrap_err(#error).map_err(|err| _nommy::span::locate(err, __field_start))?;
             }
         }
     }
 
-    pub fn peeker_peek_vec_tokens(&self, ty: &syn::Type) -> TokenStream {
-        let generic = &self.generic;
+    pub fn peeker_peek_vec_tokens(&self, ty: &syn::Type, attrs: &VecFieldAttr) -> TokenStream {
         let after_each = &self.after_each;
+        let peek = self.peek_call(ty, quote! { &mut cursor });
+        let (min, max) = attrs.bounds();
+        let min_check = (attrs.count.is_some() || attrs.min.is_some()).then(|| {
+            quote! {
+                if count < #min {
+                    return false;
+                }
+            }
+        });
 
-        quote! {
-            loop {
+        // the input ignored between the element and its separator is skipped on `cursor`,
+        // so none of it is consumed unless the whole step matches
+        let ignore_cursor = quote! {
+            {
+                let input = &mut cursor;
+                #after_each
+            }
+        };
+
+        // like the functions in `nommy::vec`, the ignored input isn't counted, so only an element
+        // that reads nothing, and would be peeked from the same state again, repeats forever
+        let step = match &attrs.seperated_by {
+            None => quote! {
                 let mut cursor = input.cursor();
-                if !<#ty as ::nommy::Parse<#generic>>::peek(&mut cursor) {
+                if !#peek {
                     break;
                 }
-                let pos = cursor.position();
-                input.fast_forward(pos);
-
+                let read = cursor.position();
+                input.fast_forward(read);
+                count += 1;
                 #after_each
+
+                if _nommy::vec::check_element(count, read == 0 && count > 1).is_err() {
+                    return false;
+                }
+            },
+            // `parse_vec_seperated_by`, where the separator only counts if an element follows it
+            Some(sep) if attrs.trailing.is_none() => {
+                let peek_sep = self.peek_call(sep, quote! { &mut cursor });
+                quote! {
+                    let mut cursor = input.cursor();
+                    if count > 0 {
+                        #ignore_cursor
+                        if !#peek_sep {
+                            break;
+                        }
+                        #ignore_cursor
+                    }
+                    if !#peek {
+                        break;
+                    }
+                    let read = cursor.position();
+                    input.fast_forward(read);
+                    count += 1;
+
+                    if _nommy::vec::check_element(count, read == 0 && count > 1).is_err() {
+                        return false;
+                    }
+                }
+            }
+            // `parse_vec_seperated_by_trailing`, where an element only counts if a separator follows it
+            Some(sep) if attrs.trailing == Some(true) => {
+                let peek_sep = self.peek_call(sep, quote! { &mut cursor });
+                quote! {
+                    let mut cursor = input.cursor();
+                    if !#peek {
+                        break;
+                    }
+                    #ignore_cursor
+                    if !#peek_sep {
+                        break;
+                    }
+                    #ignore_cursor
+                    let read = cursor.position();
+                    input.fast_forward(read);
+                    count += 1;
+
+                    if _nommy::vec::check_element(count, read == 0).is_err() {
+                        return false;
+                    }
+                }
+            }
+            // `parse_vec_seperated_by_maybe_trailing`, where a missing separator ends the collection after the element
+            Some(sep) => {
+                let peek_sep = self.peek_call(sep, quote! { &mut cursor });
+                quote! {
+                    let mut cursor = input.cursor();
+                    if !#peek {
+                        break;
+                    }
+                    let mut read = cursor.position();
+                    input.fast_forward(read);
+                    count += 1;
+                    #after_each
+
+                    let mut cursor = input.cursor();
+                    let seperated = #peek_sep;
+                    if seperated {
+                        let pos = cursor.position();
+                        input.fast_forward(pos);
+                        read += pos;
+                        #after_each
+                    }
+
+                    if _nommy::vec::check_element(count, read == 0 && seperated).is_err() {
+                        return false;
+                    }
+                    if !seperated {
+                        break;
+                    }
+                }
+            }
+        };
+
+        quote! {
+            let mut count: usize = 0;
+            loop {
+                if count >= #max {
+                    break;
+                }
+                #step
             }
+            #min_check
         }
     }
 
@@ -314,7 +1383,7 @@ impl<'a> Builder<'a> {
             ignore_impl.extend(quote! {
             {
                 let mut cursor = input.cursor();
-                if <#ty as ::nommy::Parse<#generic>>::peek(&mut cursor) {
+                if <#ty as _nommy::Parse<#generic>>::peek(&mut cursor) {
                     let pos = cursor.position();
                     if ::std::cfg!(debug_assertions) && pos == 0 {
                         panic!("ignore type `{}` passed but read 0 elements. Please ensure it reads at least 1 element otherwise it will cause an infinite loop", #ty_string);
@@ -328,20 +1397,20 @@ impl<'a> Builder<'a> {
 
         let impl_line = match self.parse_type {
             Some(_) => quote! {
-                impl ::nommy::Parse<#generic> for __ParseIgnore
+                impl _nommy::Parse<#generic> for __ParseIgnore
             },
             None => quote! {
-                impl<#generic> ::nommy::Parse<#generic> for __ParseIgnore where #ignore_wc
+                impl<#generic> _nommy::Parse<#generic> for __ParseIgnore where #ignore_wc
             },
         };
 
         let ignore_impl = quote! {
             struct __ParseIgnore;
             #impl_line {
-                fn parse(_: &mut impl ::nommy::Buffer<#generic>) -> ::nommy::eyre::Result<Self> {
+                fn parse(_: &mut impl _nommy::Buffer<#generic>) -> _nommy::eyre::Result<Self> {
                     unimplemented!()
                 }
-                fn peek(input: &mut impl ::nommy::Buffer<#generic>) -> bool {
+                fn peek(input: &mut impl _nommy::Buffer<#generic>) -> bool {
                     #ignore_impl
 
                     false
@@ -350,7 +1419,7 @@ impl<'a> Builder<'a> {
         };
 
         let after_each = quote! {
-            <::std::vec::Vec<__ParseIgnore> as ::nommy::Parse<#generic>>::peek(input);
+            <::std::vec::Vec<__ParseIgnore> as _nommy::Parse<#generic>>::peek(input);
         };
 
         (ignore_impl, after_each)
@@ -2,16 +2,30 @@ use proc_macro::TokenStream;
 use quote::ToTokens;
 use syn::{parse_macro_input, spanned::Spanned, DeriveInput};
 
+mod args;
 mod attr;
 mod enum_impl;
 mod fn_impl;
 mod parsers;
+mod process_impl;
 mod struct_impl;
 mod ty;
+mod unparse_impl;
 
 #[proc_macro_derive(Parse, attributes(nommy))]
 pub fn derive_parse(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
+    derive(input, false).into()
+}
+
+#[proc_macro_derive(Peek, attributes(nommy))]
+pub fn derive_peek(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    derive(input, true).into()
+}
+
+/// Derives `Parse`, or with `peek_only`, only its peek function
+fn derive(input: DeriveInput, peek_only: bool) -> proc_macro2::TokenStream {
     let span = input.span();
     let DeriveInput {
         attrs,
@@ -24,29 +38,48 @@ pub fn derive_parse(input: TokenStream) -> TokenStream {
     match data {
         syn::Data::Struct(s) => match s.fields {
             syn::Fields::Named(fields) => {
-                match struct_impl::Named::new(ident, generics, attrs, fields) {
+                match struct_impl::Named::new(ident, generics, attrs, fields, peek_only) {
                     Ok(t) => t.into_token_stream(),
                     Err(e) => e.to_compile_error(),
                 }
             }
             syn::Fields::Unnamed(fields) => {
-                match struct_impl::Unnamed::new(ident, generics, attrs, fields) {
+                match struct_impl::Unnamed::new(ident, generics, attrs, fields, peek_only) {
                     Ok(t) => t.into_token_stream(),
                     Err(e) => e.to_compile_error(),
                 }
             }
-            syn::Fields::Unit => match struct_impl::Unit::new(ident, generics, attrs) {
+            syn::Fields::Unit => match struct_impl::Unit::new(ident, generics, attrs, peek_only) {
                 Ok(t) => t.into_token_stream(),
                 Err(e) => e.to_compile_error(),
             },
         },
         syn::Data::Enum(enum_data) => {
-            match enum_impl::Enum::new(ident, generics, attrs, enum_data) {
+            match enum_impl::Enum::new(ident, generics, attrs, enum_data, peek_only) {
                 Ok(t) => t.into_token_stream(),
                 Err(e) => e.to_compile_error(),
             }
         }
         syn::Data::Union(_) => syn::Error::new(span, "unions not supported").into_compile_error(),
     }
+}
+
+#[proc_macro_derive(Unparse, attributes(nommy))]
+pub fn derive_unparse(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match unparse_impl::Unparse::new(input) {
+        Ok(t) => t.into_token_stream(),
+        Err(e) => e.to_compile_error(),
+    }
+    .into()
+}
+
+#[proc_macro_derive(Process, attributes(nommy))]
+pub fn derive_process(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match process_impl::Process::new(input) {
+        Ok(t) => t.into_token_stream(),
+        Err(e) => e.to_compile_error(),
+    }
     .into()
 }
@@ -16,12 +16,10 @@ impl TryFrom<syn::Field> for NamedField {
         let syn::Field {
             ident, attrs, ty, ..
         } = field;
-        let attrs = FieldAttr::parse_attrs(attrs)?;
-        Ok(NamedField {
-            attrs,
-            name: ident.unwrap(),
-            ty,
-        })
+        let attrs = FieldAttr::parse_attrs(attrs)?.default_skip(&ty).default_inner(&ty);
+        attrs.check(&ty)?;
+        let name = ident.ok_or_else(|| syn::Error::new_spanned(&ty, "expected a named field"))?;
+        Ok(NamedField { attrs, name, ty })
     }
 }
 
@@ -29,7 +27,8 @@ impl TryFrom<syn::Field> for UnnamedField {
     type Error = syn::Error;
     fn try_from(field: syn::Field) -> syn::Result<Self> {
         let syn::Field { attrs, ty, .. } = field;
-        let attrs = FieldAttr::parse_attrs(attrs)?;
+        let attrs = FieldAttr::parse_attrs(attrs)?.default_skip(&ty).default_inner(&ty);
+        attrs.check(&ty)?;
         Ok(UnnamedField { attrs, ty })
     }
 }
@@ -43,6 +42,37 @@ pub trait FieldType {
     fn ty(&self) -> &syn::Type;
     fn name(&self, i: usize) -> syn::Ident;
     fn attrs(&self) -> &FieldAttr;
+    fn attrs_mut(&mut self) -> &mut FieldAttr;
+}
+
+/// Skips the fields whose types mention one of the `not_parsed` type parameters
+pub fn skip_not_parsed<F: FieldType>(fields: &mut [F], not_parsed: &[syn::Ident]) {
+    for field in fields {
+        let ty = field.ty().clone();
+        field.attrs_mut().skip_not_parsed(&ty, not_parsed);
+    }
+}
+
+/// Checks that there's at most one `version` field, and that it comes before the fields that use `since` or `until`
+pub fn check_versions<F: FieldType>(fields: &[F]) -> syn::Result<()> {
+    let mut version = false;
+    for field in fields {
+        let attrs = field.attrs();
+        if attrs.version && version {
+            return Err(syn::Error::new_spanned(
+                field.ty(),
+                "only one field can be the `version`",
+            ));
+        }
+        if (attrs.since.is_some() || attrs.until.is_some()) && !version {
+            return Err(syn::Error::new_spanned(
+                field.ty(),
+                "`since` and `until` need an earlier field marked `version`",
+            ));
+        }
+        version |= attrs.version;
+    }
+    Ok(())
 }
 
 impl FieldType for NamedField {
@@ -55,6 +85,9 @@ impl FieldType for NamedField {
     fn attrs(&self) -> &FieldAttr {
         &self.attrs
     }
+    fn attrs_mut(&mut self) -> &mut FieldAttr {
+        &mut self.attrs
+    }
 }
 
 impl FieldType for UnnamedField {
@@ -67,4 +100,7 @@ impl FieldType for UnnamedField {
     fn attrs(&self) -> &FieldAttr {
         &self.attrs
     }
+    fn attrs_mut(&mut self) -> &mut FieldAttr {
+        &mut self.attrs
+    }
 }
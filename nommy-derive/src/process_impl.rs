@@ -0,0 +1,243 @@
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote, ToTokens};
+
+use crate::{args::Args, attr::GlobalAttr};
+
+/// `derive(Process)`, which generates an output type with the same shape as the derived type,
+/// where each field is replaced by its processed output
+pub struct Process {
+    vis: syn::Visibility,
+    name: syn::Ident,
+    output: syn::Ident,
+    args: Args,
+    attrs: GlobalAttr,
+    /// the type parameters, which mean the fields need `Process` bounds
+    type_params: Vec<syn::Ident>,
+    body: Body,
+}
+
+enum Body {
+    Struct(syn::Fields),
+    Enum(Vec<(syn::Ident, syn::Fields)>),
+}
+
+/// The declaration of the fields in the output type, where `process` is the path to the `Process` trait.
+/// A field that's just a type parameter is declared as `T::Output`, which the std derives
+/// know to bound, unlike the fully qualified form
+fn declare_fields(
+    fields: &syn::Fields,
+    type_params: &[syn::Ident],
+    process: &TokenStream,
+) -> TokenStream {
+    let fields = fields.iter().map(|f| {
+        let syn::Field { vis, ident, ty, .. } = f;
+        let colon = ident.as_ref().map(|_| quote! { : });
+        let param = match ty {
+            syn::Type::Path(path) if path.qself.is_none() => path
+                .path
+                .get_ident()
+                .filter(|ident| type_params.contains(ident)),
+            _ => None,
+        };
+        match param {
+            Some(param) => quote! { #vis #ident #colon #param::Output },
+            None => quote! { #vis #ident #colon <#ty as #process>::Output },
+        }
+    });
+    quote! { #(#fields),* }
+}
+
+/// The name that a field is bound to when it's taken out of the value
+fn binding(field: &syn::Field, i: usize) -> syn::Ident {
+    match &field.ident {
+        Some(ident) => ident.clone(),
+        None => format_ident!("elem{}", i),
+    }
+}
+
+/// The pattern that binds each field following `path`, and the expression that builds
+/// the same shape following `output` from the processed fields
+fn convert(
+    fields: &syn::Fields,
+    path: TokenStream,
+    output: TokenStream,
+) -> (TokenStream, TokenStream) {
+    let names: Vec<_> = fields
+        .iter()
+        .enumerate()
+        .map(|(i, f)| binding(f, i))
+        .collect();
+    match fields {
+        syn::Fields::Named(_) => (
+            quote! { #path { #(#names,)* } },
+            quote! { #output { #(#names: _nommy::process::Process::process(#names),)* } },
+        ),
+        syn::Fields::Unnamed(_) => (
+            quote! { #path ( #(#names,)* ) },
+            quote! { #output ( #(_nommy::process::Process::process(#names),)* ) },
+        ),
+        syn::Fields::Unit => (path, output),
+    }
+}
+
+impl Process {
+    /// The `Process` bounds of every field, which are only needed if the type has type parameters
+    fn bounds(&self, process: &TokenStream) -> TokenStream {
+        if self.type_params.is_empty() {
+            return quote! {};
+        }
+        let fields: Vec<&syn::Fields> = match &self.body {
+            Body::Struct(fields) => vec![fields],
+            Body::Enum(variants) => variants.iter().map(|(_, fields)| fields).collect(),
+        };
+        let types = fields
+            .into_iter()
+            .flat_map(|fields| fields.iter().map(|f| &f.ty));
+        quote! { #(#types: #process,)* }
+    }
+
+    /// The output type, declared next to the derived type so that it can be named
+    fn output_type(&self) -> TokenStream {
+        let Process {
+            vis,
+            output,
+            args,
+            attrs,
+            type_params,
+            body,
+            ..
+        } = self;
+        let krate = attrs.krate();
+        let process = quote! { #krate::process::Process };
+        let params = args.declare(quote! {});
+        let wc = args.where_clause(self.bounds(&process));
+        let derive = attrs
+            .output_derive
+            .as_ref()
+            .map(|derives| quote! { #[derive(#derives)] });
+
+        match body {
+            Body::Struct(fields @ syn::Fields::Named(_)) => {
+                let fields = declare_fields(fields, type_params, &process);
+                quote! { #derive #vis struct #output<#params> #wc { #fields } }
+            }
+            Body::Struct(fields @ syn::Fields::Unnamed(_)) => {
+                let fields = declare_fields(fields, type_params, &process);
+                quote! { #derive #vis struct #output<#params>(#fields) #wc; }
+            }
+            Body::Struct(syn::Fields::Unit) => {
+                quote! { #derive #vis struct #output<#params> #wc; }
+            }
+            Body::Enum(variants) => {
+                let variants = variants.iter().map(|(name, fields)| {
+                    let declared = declare_fields(fields, type_params, &process);
+                    match fields {
+                        syn::Fields::Named(_) => quote! { #name { #declared } },
+                        syn::Fields::Unnamed(_) => quote! { #name(#declared) },
+                        syn::Fields::Unit => quote! { #name },
+                    }
+                });
+                quote! { #derive #vis enum #output<#params> #wc { #(#variants,)* } }
+            }
+        }
+    }
+}
+
+impl ToTokens for Process {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let Process {
+            name,
+            output,
+            args,
+            attrs,
+            body,
+            ..
+        } = self;
+
+        let process_impl = match body {
+            Body::Struct(fields) => {
+                let (pattern, build) = convert(fields, quote! { Self }, quote! { #output });
+                quote! {
+                    let #pattern = self;
+                    #build
+                }
+            }
+            Body::Enum(variants) => {
+                let arms = variants.iter().map(|(variant, fields)| {
+                    let (pattern, build) = convert(
+                        fields,
+                        quote! { Self::#variant },
+                        quote! { #output::#variant },
+                    );
+                    quote! { #pattern => #build, }
+                });
+                quote! {
+                    match self {
+                        #(#arms)*
+                    }
+                }
+            }
+        };
+
+        let params = args.declare(quote! {});
+        let wc = args.where_clause(self.bounds(&quote! { _nommy::process::Process }));
+        let args = args.apply();
+
+        let impls = quote! {
+            #[automatically_derived]
+            impl<#params> _nommy::process::Process for #name<#args> #wc {
+                type Output = #output<#args>;
+
+                fn process(self) -> Self::Output {
+                    #process_impl
+                }
+            }
+        };
+
+        tokens.extend(self.output_type());
+        tokens.extend(attrs.wrap_crate(impls));
+    }
+}
+
+impl Process {
+    pub fn new(input: syn::DeriveInput) -> syn::Result<Self> {
+        let syn::DeriveInput {
+            attrs,
+            vis,
+            ident: name,
+            generics,
+            data,
+        } = input;
+
+        let args = Args::new(&generics, &None);
+        let attrs = GlobalAttr::parse_attrs(attrs)?;
+        let type_params = generics
+            .type_params()
+            .map(|param| param.ident.clone())
+            .collect();
+        let output = format_ident!("{}Output", name);
+
+        let body = match data {
+            syn::Data::Struct(s) => Body::Struct(s.fields),
+            syn::Data::Enum(e) => Body::Enum(
+                e.variants
+                    .into_iter()
+                    .map(|v| (v.ident, v.fields))
+                    .collect(),
+            ),
+            syn::Data::Union(_) => {
+                return Err(syn::Error::new_spanned(name, "unions not supported"))
+            }
+        };
+
+        Ok(Process {
+            vis,
+            name,
+            output,
+            args,
+            attrs,
+            type_params,
+            body,
+        })
+    }
+}
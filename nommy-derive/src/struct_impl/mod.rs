@@ -1,4 +1,5 @@
 mod named;
+mod transparent;
 mod unit;
 mod unnamed;
 
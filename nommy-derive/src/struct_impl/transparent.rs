@@ -0,0 +1,90 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+use crate::{
+    args::Args,
+    attr::GlobalAttr,
+    fn_impl::impl_tokens,
+    parsers::FieldType,
+};
+
+/// Checks that a `transparent` struct has a single field, with nothing else to parse around it
+pub fn check<F: FieldType>(name: &syn::Ident, attrs: &GlobalAttr, fields: &[F]) -> syn::Result<()> {
+    if !attrs.transparent {
+        return Ok(());
+    }
+    if fields.len() != 1 {
+        return Err(syn::Error::new_spanned(
+            name,
+            "`transparent` structs must have exactly one field",
+        ));
+    }
+    if attrs.prefix.is_some()
+        || attrs.suffix.is_some()
+        || !attrs.ignore.is_empty()
+        || attrs.peek_limit.is_some()
+    {
+        return Err(syn::Error::new_spanned(
+            name,
+            "`transparent` structs are parsed as their field, so can't have a prefix, suffix, ignore or peek_limit",
+        ));
+    }
+    if attrs.arena.is_some() {
+        return Err(syn::Error::new_spanned(
+            name,
+            "`transparent` can't be used with `arena`",
+        ));
+    }
+    let field = fields[0].attrs();
+    if field.prefix.is_some()
+        || field.suffix.is_some()
+        || field.vec.is_some()
+        || field.trivia
+        || field.skip.is_some()
+        || field.flatten
+        || field.with.is_some()
+        || field.peek_with.is_some()
+        || field.map.is_some()
+        || field.try_into
+        || field.convert.is_some()
+        || field.context.is_some()
+        || field.expected.is_some()
+    {
+        return Err(syn::Error::new_spanned(
+            fields[0].ty(),
+            "the field of a `transparent` struct can only be given a `parser`",
+        ));
+    }
+    Ok(())
+}
+
+/// The impl of `Parse` for a `transparent` struct, which parses and peeks its field in place of the struct,
+/// then returns `result`, built from the parsed `value` and passed through the `map` function if there is one
+pub fn transparent_impl<F: FieldType>(
+    attrs: &GlobalAttr,
+    name: &syn::Ident,
+    args: &Args,
+    generic: &syn::Type,
+    field: &F,
+    result: TokenStream,
+) -> TokenStream {
+    let parser = field.attrs().parser.as_ref().unwrap_or_else(|| field.ty());
+    let wc = quote! { #parser: _nommy::Parse<#generic>, };
+    let max_peek = quote! { <#parser as _nommy::Parse<#generic>>::MAX_PEEK };
+    let ok = attrs.ok(result);
+    let body = attrs.with_context(quote! {
+        let value = <#parser as _nommy::Parse<#generic>>::parse(input)?.try_into()?;
+        #ok
+    });
+
+    impl_tokens(attrs, name, args, generic, &wc, &max_peek).block(
+        attrs,
+        quote! {
+            use ::std::convert::TryInto;
+            #body
+        },
+        quote! {
+            <#parser as _nommy::Parse<#generic>>::peek(input)
+        },
+    )
+}
@@ -2,14 +2,15 @@ use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
 
 use crate::{
+    args::Args,
     attr::GlobalAttr,
-    fn_impl::{parse_or, BuildOutput, FnImpl},
+    fn_impl::{from_str_impl, impl_tokens, parse_or, BuildOutput, FnImpl},
     parsers::NamedField,
 };
 
 pub struct Unit {
     name: syn::Ident,
-    args: Vec<syn::Ident>,
+    args: Args,
     attrs: GlobalAttr,
     generic: syn::Type,
 }
@@ -22,6 +23,7 @@ impl ToTokens for Unit {
             attrs,
             generic,
         } = self;
+        let mut impls = TokenStream::new();
 
         let fields: &[NamedField] = &[];
         let fn_impl = FnImpl {
@@ -36,33 +38,28 @@ impl ToTokens for Unit {
             peek_impl,
             parse_impl,
             wc,
+            max_peek,
         } = fn_impl.build(&name);
 
-        let impl_line = match attrs.parse_type {
-            Some(_) => quote!{
-                impl<#(#args),*> ::nommy::Parse<#generic> for #name<#(#args),*>
+        let header = impl_tokens(attrs, name, args, generic, &wc, &max_peek);
+        let ok = attrs.ok(quote! { #name });
+        let body = attrs.with_context(quote! { #parse_impl #ok });
+
+        impls.extend(header.block(
+            attrs,
+            quote! {
+                use _nommy::eyre::WrapErr;
+                use ::std::convert::TryInto;
+                #body
             },
-            None => quote!{
-                impl<#generic, #(#args),*> ::nommy::Parse<#generic> for #name<#(#args),*> where #wc
+            quote! {
+                #peek_impl
+                true
             },
-        };
+        ));
 
-        tokens.extend(quote!{
-            #[automatically_derived]
-            #impl_line {
-                fn parse(input: &mut impl ::nommy::Buffer<#generic>) -> ::nommy::eyre::Result<Self> {
-                    use ::nommy::eyre::WrapErr;
-                    use ::std::convert::TryInto;
-                    #parse_impl
-                    Ok(#name)
-                }
-
-                fn peek(input: &mut impl ::nommy::Buffer<#generic>) -> bool {
-                    #peek_impl
-                    true
-                }
-            }
-        })
+        impls.extend(from_str_impl(attrs, name, args));
+        tokens.extend(attrs.wrap_crate(impls));
     }
 }
 
@@ -71,9 +68,38 @@ impl Unit {
         name: syn::Ident,
         generics: syn::Generics,
         attrs: Vec<syn::Attribute>,
+        peek_only: bool,
     ) -> syn::Result<Self> {
-        let args = generics.type_params().cloned().map(|tp| tp.ident).collect();
-        let attrs = GlobalAttr::parse_attrs(attrs)?;
+        let mut attrs = GlobalAttr::parse_attrs(attrs)?;
+        attrs.peek_only = peek_only;
+        attrs.check_derive(&name)?;
+        attrs.resolve_arena(&generics);
+        let args = Args::new(&generics, &attrs.arena);
+        attrs.resolve_params(&generics)?;
+        if attrs.extensible || attrs.longest {
+            return Err(syn::Error::new_spanned(
+                name,
+                "`extensible` and `strategy` are only supported on enums",
+            ));
+        }
+        if attrs.transparent {
+            return Err(syn::Error::new_spanned(
+                name,
+                "`transparent` structs must have exactly one field",
+            ));
+        }
+        if attrs.guard.is_some() || attrs.priority.is_some() || attrs.fallback {
+            return Err(syn::Error::new_spanned(
+                name,
+                "`guard`, `priority` and `default` are only supported on enum variants",
+            ));
+        }
+        if attrs.no_ignore {
+            return Err(syn::Error::new_spanned(
+                name,
+                "`no_ignore` is only supported on enum variants and fields",
+            ));
+        }
         let generic = parse_or(&attrs.parse_type);
 
         Ok(Unit {
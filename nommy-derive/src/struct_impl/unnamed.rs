@@ -4,15 +4,18 @@ use proc_macro2::TokenStream;
 use quote::{quote, ToTokens};
 
 use crate::{
+    args::Args,
     attr::GlobalAttr,
-    fn_impl::{parse_or, BuildOutput, FnImpl},
-    parsers::{FieldType, UnnamedField},
+    fn_impl::{flat_impl, from_str_impl, impl_tokens, parse_or, BuildOutput, FnImpl},
+    parsers::{check_versions, skip_not_parsed, FieldType, UnnamedField},
 };
 
+use super::transparent::{self, transparent_impl};
+
 pub struct Unnamed {
     name: syn::Ident,
     fields: Vec<UnnamedField>,
-    args: Vec<syn::Ident>,
+    args: Args,
     attrs: GlobalAttr,
     generic: syn::Type,
 }
@@ -26,6 +29,10 @@ impl ToTokens for Unnamed {
             fields,
             attrs,
         } = self;
+        let mut impls = TokenStream::new();
+
+        let names = self.fields.iter().enumerate().map(|(i, f)| f.name(i));
+        let result = quote! { #name (#(#names,)*) };
 
         let fn_impl = FnImpl {
             ty: "struct",
@@ -35,42 +42,56 @@ impl ToTokens for Unnamed {
             generic,
         };
 
-        let BuildOutput {
-            peek_impl,
-            parse_impl,
-            wc,
-        } = fn_impl.build(&name);
+        if attrs.transparent {
+            let field = &fields[0];
+            impls.extend(transparent_impl(
+                attrs,
+                name,
+                args,
+                generic,
+                field,
+                quote! { #name(value) },
+            ));
+        } else {
+            let BuildOutput {
+                peek_impl,
+                parse_impl,
+                wc,
+                max_peek,
+            } = fn_impl.build(&name);
 
-        let impl_line = match attrs.parse_type {
-            Some(_) => quote!{
-                impl<#(#args),*> ::nommy::Parse<#generic> for #name<#(#args),*>
-            },
-            None => quote!{
-                impl<#generic, #(#args),*> ::nommy::Parse<#generic> for #name<#(#args),*> where #wc
-            },
-        };
-
-        let names = self.fields.iter().enumerate().map(|(i, f)| f.name(i));
+            let header = impl_tokens(attrs, name, args, generic, &wc, &max_peek);
+            let ok = attrs.ok(result.clone());
+            let body = attrs.with_context(quote! { #parse_impl #ok });
 
-        tokens.extend(quote!{
-            #[automatically_derived]
-            #impl_line {
-                fn parse(input: &mut impl ::nommy::Buffer<#generic>) -> ::nommy::eyre::Result<Self> {
-                    use ::nommy::eyre::WrapErr;
+            impls.extend(header.block(
+                attrs,
+                quote! {
+                    use _nommy::eyre::WrapErr;
                     use ::std::convert::TryInto;
-                    #parse_impl
-
-                    Ok(#name (#(
-                        #names,
-                    )*))
-                }
-
-                fn peek(input: &mut impl ::nommy::Buffer<#generic>) -> bool {
+                    #body
+                },
+                quote! {
                     #peek_impl
                     true
-                }
-            }
-        })
+                },
+            ));
+        }
+
+        // the arena isn't passed to flattened fields, and `#[derive(Peek)]` types are parsed whole, as their default
+        if attrs.arena.is_none() && !attrs.peek_only {
+            impls.extend(flat_impl(
+                attrs,
+                name,
+                args,
+                generic,
+                fn_impl.build_flat(name),
+                result,
+            ));
+        }
+
+        impls.extend(from_str_impl(attrs, name, args));
+        tokens.extend(attrs.wrap_crate(impls));
     }
 }
 
@@ -80,14 +101,40 @@ impl Unnamed {
         generics: syn::Generics,
         attrs: Vec<syn::Attribute>,
         fields: syn::FieldsUnnamed,
+        peek_only: bool,
     ) -> syn::Result<Self> {
-        let args = generics.type_params().cloned().map(|tp| tp.ident).collect();
-        let fields = fields
+        let mut fields: Vec<UnnamedField> = fields
             .unnamed
             .into_iter()
             .map(|f| f.try_into())
             .collect::<syn::Result<_>>()?;
-        let attrs = GlobalAttr::parse_attrs(attrs)?;
+        let mut attrs = GlobalAttr::parse_attrs(attrs)?;
+        attrs.peek_only = peek_only;
+        attrs.check_derive(&name)?;
+        attrs.resolve_arena(&generics);
+        let args = Args::new(&generics, &attrs.arena);
+        attrs.resolve_params(&generics)?;
+        skip_not_parsed(&mut fields, &attrs.not_parsed);
+        check_versions(&fields)?;
+        transparent::check(&name, &attrs, &fields)?;
+        if attrs.extensible || attrs.longest {
+            return Err(syn::Error::new_spanned(
+                name,
+                "`extensible` and `strategy` are only supported on enums",
+            ));
+        }
+        if attrs.guard.is_some() || attrs.priority.is_some() || attrs.fallback {
+            return Err(syn::Error::new_spanned(
+                name,
+                "`guard`, `priority` and `default` are only supported on enum variants",
+            ));
+        }
+        if attrs.no_ignore {
+            return Err(syn::Error::new_spanned(
+                name,
+                "`no_ignore` is only supported on enum variants and fields",
+            ));
+        }
         let generic = parse_or(&attrs.parse_type);
 
         Ok(Unnamed {
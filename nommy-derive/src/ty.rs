@@ -1,32 +1,22 @@
+/// Returns true if `ty` mentions `type_name` or `Self`, so bounds on it would be recursive.
+/// Types that can't be searched are assumed to mention it
 pub fn contains(ty: &syn::Type, type_name: &syn::Ident) -> bool {
     match ty {
         syn::Type::Array(ty) => contains(&ty.elem, type_name),
         syn::Type::Group(ty) => contains(&ty.elem, type_name),
         syn::Type::Paren(ty) => contains(&ty.elem, type_name),
-        syn::Type::Path(path) => match path.path.get_ident() {
-            Some(ident) => *ident == *type_name,
-            None => {
-                for segment in &path.path.segments {
-                    match &segment.arguments {
-                        syn::PathArguments::AngleBracketed(generic_args) => {
-                            for arg in &generic_args.args {
-                                match &arg {
-                                    syn::GenericArgument::Type(t) => {
-                                        if contains(t, type_name) {
-                                            return true;
-                                        }
-                                    }
-                                    _ => continue,
-                                }
-                            }
-                            continue;
-                        }
-                        _ => continue,
-                    };
+        syn::Type::Path(path) => path.path.segments.iter().any(|segment| {
+            segment.ident == *type_name
+                || segment.ident == "Self"
+                || match &segment.arguments {
+                    syn::PathArguments::AngleBracketed(generic_args) => {
+                        generic_args.args.iter().any(|arg| {
+                            matches!(arg, syn::GenericArgument::Type(t) if contains(t, type_name))
+                        })
+                    }
+                    _ => false,
                 }
-                false
-            }
-        },
+        }),
         syn::Type::Ptr(ty) => contains(&ty.elem, type_name),
         syn::Type::Reference(ty) => contains(&ty.elem, type_name),
         syn::Type::Slice(ty) => contains(&ty.elem, type_name),
@@ -34,3 +24,81 @@ pub fn contains(ty: &syn::Type, type_name: &syn::Ident) -> bool {
         _ => true,
     }
 }
+
+/// Returns true if the lifetime `lt` appears anywhere in `ty`
+pub fn mentions_lifetime(ty: &syn::Type, lt: &syn::Lifetime) -> bool {
+    fn search(tokens: proc_macro2::TokenStream, lt: &syn::Lifetime) -> bool {
+        let mut tokens = tokens.into_iter().peekable();
+        while let Some(tt) = tokens.next() {
+            match tt {
+                proc_macro2::TokenTree::Punct(p) if p.as_char() == '\'' => {
+                    if let Some(proc_macro2::TokenTree::Ident(ident)) = tokens.peek() {
+                        if *ident == lt.ident {
+                            return true;
+                        }
+                    }
+                }
+                proc_macro2::TokenTree::Group(g) if search(g.stream(), lt) => return true,
+                _ => {}
+            }
+        }
+        false
+    }
+    search(quote::ToTokens::to_token_stream(ty), lt)
+}
+
+/// Returns true if `ty` is a `PhantomData`, which has nothing to parse
+pub fn is_phantom_data(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Path(path) => {
+            matches!(path.path.segments.last(), Some(segment) if segment.ident == "PhantomData")
+        }
+        _ => false,
+    }
+}
+
+/// Returns the type inside `ty` if it's an `Option`
+pub fn option_inner(ty: &syn::Type) -> Option<&syn::Type> {
+    wrapped(ty, "Option")
+}
+
+/// Returns the element type of `ty` if it's a `Vec`
+pub fn vec_inner(ty: &syn::Type) -> Option<&syn::Type> {
+    wrapped(ty, "Vec")
+}
+
+/// Returns `T` if `ty` is a `Result<T, E>`
+pub fn result_ok(ty: &syn::Type) -> Option<&syn::Type> {
+    let segment = match ty {
+        syn::Type::Path(path) if path.qself.is_none() => path.path.segments.last()?,
+        _ => return None,
+    };
+    if segment.ident != "Result" {
+        return None;
+    }
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) => match args.args.first()? {
+            syn::GenericArgument::Type(ok) => Some(ok),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Returns `T` if `ty` is `wrapper<T>`
+fn wrapped<'a>(ty: &'a syn::Type, wrapper: &str) -> Option<&'a syn::Type> {
+    let segment = match ty {
+        syn::Type::Path(path) if path.qself.is_none() => path.path.segments.last()?,
+        _ => return None,
+    };
+    if segment.ident != wrapper {
+        return None;
+    }
+    match &segment.arguments {
+        syn::PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            syn::GenericArgument::Type(inner) => Some(inner),
+            _ => None,
+        },
+        _ => None,
+    }
+}
@@ -0,0 +1,448 @@
+use std::convert::TryInto;
+
+use proc_macro2::TokenStream;
+use quote::{quote, ToTokens};
+
+use crate::{
+    args::Args,
+    attr::GlobalAttr,
+    fn_impl::parse_or,
+    parsers::{check_versions, skip_not_parsed, FieldType, NamedField, UnnamedField},
+};
+
+/// `derive(Unparse)`, generated from the same attributes as `derive(Parse)`
+pub struct Unparse {
+    name: syn::Ident,
+    args: Args,
+    attrs: GlobalAttr,
+    generic: syn::Type,
+    body: Body,
+}
+
+enum Body {
+    Struct(Fields),
+    Enum(Vec<Variant>),
+}
+
+struct Variant {
+    name: syn::Ident,
+    attrs: GlobalAttr,
+    fields: Fields,
+}
+
+enum Fields {
+    Named(Vec<NamedField>),
+    Unnamed(Vec<UnnamedField>),
+    Unit,
+}
+
+impl Fields {
+    fn new(fields: syn::Fields, not_parsed: &[syn::Ident]) -> syn::Result<Self> {
+        match fields {
+            syn::Fields::Named(named) => {
+                let mut fields: Vec<NamedField> = named
+                    .named
+                    .into_iter()
+                    .map(|f| f.try_into())
+                    .collect::<syn::Result<_>>()?;
+                skip_not_parsed(&mut fields, not_parsed);
+                check_versions(&fields)?;
+                Ok(Fields::Named(fields))
+            }
+            syn::Fields::Unnamed(unnamed) => {
+                let mut fields: Vec<UnnamedField> = unnamed
+                    .unnamed
+                    .into_iter()
+                    .map(|f| f.try_into())
+                    .collect::<syn::Result<_>>()?;
+                skip_not_parsed(&mut fields, not_parsed);
+                check_versions(&fields)?;
+                Ok(Fields::Unnamed(fields))
+            }
+            syn::Fields::Unit => Ok(Fields::Unit),
+        }
+    }
+
+    /// The pattern that binds each field by name, following `path`
+    fn pattern(&self, path: TokenStream) -> TokenStream {
+        match self {
+            Fields::Named(fields) => {
+                let names = fields.iter().map(|f| {
+                    let name = &f.name;
+                    if f.attrs.trivia || f.attrs.skip.is_some() || f.attrs.peek.is_some() || f.attrs.span {
+                        quote! { #name: _ }
+                    } else {
+                        quote! { #name }
+                    }
+                });
+                quote! { #path { #(#names,)* } }
+            }
+            Fields::Unnamed(fields) => {
+                let names = fields.iter().enumerate().map(|(i, f)| {
+                    if f.attrs.trivia || f.attrs.skip.is_some() || f.attrs.peek.is_some() || f.attrs.span {
+                        quote! { _ }
+                    } else {
+                        f.name(i).into_token_stream()
+                    }
+                });
+                quote! { #path ( #(#names,)* ) }
+            }
+            Fields::Unit => path,
+        }
+    }
+
+    fn add_to(&self, builder: &mut Builder) {
+        match self {
+            Fields::Named(fields) => {
+                for (i, field) in fields.iter().enumerate() {
+                    builder.add_field(field, i)
+                }
+            }
+            Fields::Unnamed(fields) => {
+                for (i, field) in fields.iter().enumerate() {
+                    builder.add_field(field, i)
+                }
+            }
+            Fields::Unit => {}
+        }
+    }
+}
+
+/// Builds up the statements that write out a type, one element at a time
+struct Builder<'a> {
+    generic: &'a syn::Type,
+    type_name: &'a syn::Ident,
+    /// the first ignore type, written out between each element
+    ignore: Option<&'a syn::Type>,
+
+    wc: &'a mut TokenStream,
+    elements: Vec<Element>,
+    /// the field marked `version`, once it's been written
+    version: Option<syn::Ident>,
+}
+
+enum Element {
+    /// Statements that always write something
+    Single(TokenStream),
+    /// Each item in `name`, which writes nothing if there are no items.
+    /// The ignore before the list is only written with the first item, so
+    /// empty lists don't leave two ignores next to each other
+    Each {
+        name: syn::Ident,
+        between: Option<TokenStream>,
+        trailing: TokenStream,
+        /// whether the parser ignores input after the list
+        ignore_after: bool,
+    },
+}
+
+impl<'a> Builder<'a> {
+    fn new(
+        generic: &'a syn::Type,
+        type_name: &'a syn::Ident,
+        attrs: &'a GlobalAttr,
+        wc: &'a mut TokenStream,
+    ) -> Self {
+        let mut builder = Self {
+            generic,
+            type_name,
+            ignore: attrs.ignore.first(),
+            wc,
+            elements: Vec::new(),
+            version: None,
+        };
+        if let Some(ignore) = builder.ignore {
+            builder.add_where_default(ignore);
+        }
+        builder
+    }
+
+    fn build(self) -> TokenStream {
+        let generic = self.generic;
+        let ignore = self.ignore.map(|ty| self.default_tokens(ty));
+        let mut output = TokenStream::new();
+        let mut ignore_next = false;
+        for element in self.elements {
+            let leading = if ignore_next { ignore.clone() } else { None };
+            match element {
+                Element::Single(tokens) => {
+                    output.extend(leading);
+                    output.extend(tokens);
+                    ignore_next = true;
+                }
+                Element::Each {
+                    name,
+                    between,
+                    trailing,
+                    ignore_after,
+                } => {
+                    ignore_next = ignore_after;
+                    let unparse = quote! {
+                        _nommy::unparse::Unparse::<#generic>::unparse(&__item, output);
+                    };
+                    output.extend(if leading.is_none() && between.is_none() {
+                        quote! {
+                            for __item in #name {
+                                #unparse
+                            }
+                        }
+                    } else {
+                        quote! {
+                            {
+                                let mut __first = true;
+                                for __item in #name {
+                                    if __first { #leading } else { #between }
+                                    __first = false;
+                                    #unparse
+                                }
+                                #trailing
+                            }
+                        }
+                    });
+                }
+            }
+        }
+        output
+    }
+
+    fn add_where(&mut self, ty: &syn::Type) {
+        let generic = self.generic;
+        if !crate::ty::contains(ty, self.type_name) {
+            self.wc
+                .extend(quote! {#ty: _nommy::unparse::Unparse<#generic>,});
+        }
+    }
+
+    fn add_where_default(&mut self, ty: &syn::Type) {
+        let generic = self.generic;
+        self.wc
+            .extend(quote! {#ty: _nommy::unparse::Unparse<#generic> + ::std::default::Default,});
+    }
+
+    /// Writes out the default value of `ty`, for types whose value isn't stored
+    fn default_tokens(&self, ty: &syn::Type) -> TokenStream {
+        let generic = self.generic;
+        quote! {
+            <#ty as _nommy::unparse::Unparse<#generic>>::unparse(&<#ty as ::std::default::Default>::default(), output);
+        }
+    }
+
+    fn add_fix(&mut self, fix: &Option<syn::Type>) {
+        if let Some(fix) = fix {
+            self.add_where_default(fix);
+            self.elements
+                .push(Element::Single(self.default_tokens(fix)));
+        }
+    }
+
+    fn add_field<F: FieldType>(&mut self, field: &F, field_num: usize) {
+        let ty = field.ty();
+        let name = field.name(field_num);
+        let attrs = field.attrs();
+        let generic = self.generic;
+
+        // the recorded trivia isn't written back out, the ignore type is written instead.
+        // skipped and `peek` fields weren't parsed, so they aren't written either
+        if attrs.trivia || attrs.skip.is_some() || attrs.peek.is_some() || attrs.span {
+            return;
+        }
+        if attrs.version {
+            self.version = Some(name.clone());
+        }
+
+        // a field that isn't in every version is only written in the versions it was parsed in
+        let in_version = self
+            .version
+            .as_ref()
+            .and_then(|version| attrs.in_version(quote! { (*#version) }));
+        if let Some(in_version) = in_version {
+            let mut field_builder = Builder {
+                generic,
+                type_name: self.type_name,
+                ignore: self.ignore,
+                wc: &mut *self.wc,
+                elements: Vec::new(),
+                version: None,
+            };
+            field_builder.add_field(field, field_num);
+            let field_impl = field_builder.build();
+            self.elements.push(Element::Single(quote! {
+                if #in_version {
+                    #field_impl
+                }
+            }));
+            return;
+        }
+
+        // the prefix, value and suffix are written together, without ignored input between them
+        if attrs.no_ignore && !attrs.vec.is_some() {
+            self.add_where(ty);
+            let mut tokens = TokenStream::new();
+            if let Some(prefix) = &attrs.prefix {
+                self.add_where_default(prefix);
+                tokens.extend(self.default_tokens(prefix));
+            }
+            tokens.extend(quote! {
+                <#ty as _nommy::unparse::Unparse<#generic>>::unparse(#name, output);
+            });
+            if let Some(suffix) = &attrs.suffix {
+                self.add_where_default(suffix);
+                tokens.extend(self.default_tokens(suffix));
+            }
+            self.elements.push(Element::Single(tokens));
+            return;
+        }
+
+        self.add_fix(&attrs.prefix);
+
+        if attrs.vec.is_some() {
+            if !crate::ty::contains(ty, self.type_name) {
+                self.wc.extend(quote! {
+                    for<'__unparse> &'__unparse #ty: ::std::iter::IntoIterator,
+                    for<'__unparse> <&'__unparse #ty as ::std::iter::IntoIterator>::Item: _nommy::unparse::Unparse<#generic>,
+                });
+            }
+
+            let mut between = self.ignore.map(|ty| self.default_tokens(ty));
+            let mut trailing = TokenStream::new();
+            if let Some(sep) = &attrs.vec.seperated_by {
+                self.add_where_default(sep);
+                let sep = self.default_tokens(sep);
+                let ignore = between.clone();
+                if attrs.vec.trailing == Some(true) {
+                    trailing = quote! { if !__first { #ignore #sep } };
+                }
+                between = Some(quote! { #ignore #sep #ignore });
+            }
+
+            self.elements.push(Element::Each {
+                name,
+                between,
+                trailing,
+                ignore_after: attrs.vec.seperated_by.is_some() && attrs.vec.trailing.is_some(),
+            });
+        } else {
+            self.add_where(ty);
+            self.elements.push(Element::Single(quote! {
+                <#ty as _nommy::unparse::Unparse<#generic>>::unparse(#name, output);
+            }));
+        }
+
+        self.add_fix(&attrs.suffix);
+    }
+}
+
+impl ToTokens for Unparse {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let Unparse {
+            name,
+            args,
+            attrs,
+            generic,
+            body,
+        } = self;
+        let mut impls = TokenStream::new();
+
+        let mut wc = TokenStream::new();
+        let mut builder = Builder::new(generic, name, attrs, &mut wc);
+        builder.add_fix(&attrs.prefix);
+
+        // struct fields are bound up front so that each element can refer to them
+        let bindings = match body {
+            Body::Struct(fields) => {
+                fields.add_to(&mut builder);
+                let pattern = fields.pattern(quote! { Self });
+                quote! { let #pattern = self; }
+            }
+            Body::Enum(variants) => {
+                let mut arms = Vec::new();
+                for v in variants {
+                    let mut variant_wc = TokenStream::new();
+                    let mut variant_builder =
+                        Builder::new(generic, name, &v.attrs, &mut variant_wc);
+                    variant_builder.add_fix(&v.attrs.prefix);
+                    v.fields.add_to(&mut variant_builder);
+                    variant_builder.add_fix(&v.attrs.suffix);
+                    let variant_impl = variant_builder.build();
+
+                    builder.wc.extend(variant_wc);
+                    let var_name = &v.name;
+                    let pattern = v.fields.pattern(quote! { Self::#var_name });
+                    arms.push(quote! { #pattern => { #variant_impl } });
+                }
+                builder.elements.push(Element::Single(quote! {
+                    match self {
+                        #(#arms)*
+                    }
+                }));
+                quote! {}
+            }
+        };
+
+        builder.add_fix(&attrs.suffix);
+        let unparse_impl = builder.build();
+
+        let (generic_param, wc) = match attrs.parse_type {
+            Some(_) => (quote! {}, args.where_clause(quote! {})),
+            None => (quote! { #generic, }, args.where_clause(wc.clone())),
+        };
+        let params = args.declare(generic_param);
+        let args = args.apply();
+
+        impls.extend(quote! {
+            #[automatically_derived]
+            impl<#params> _nommy::unparse::Unparse<#generic> for #name<#args> #wc {
+                fn unparse(&self, output: &mut impl _nommy::unparse::Emit<#generic>) {
+                    #bindings
+                    #unparse_impl
+                }
+            }
+        });
+
+        tokens.extend(attrs.wrap_crate(impls));
+    }
+}
+
+impl Unparse {
+    pub fn new(input: syn::DeriveInput) -> syn::Result<Self> {
+        let syn::DeriveInput {
+            attrs,
+            ident: name,
+            generics,
+            data,
+            ..
+        } = input;
+
+        let args = Args::new(&generics, &None);
+        let mut attrs = GlobalAttr::parse_attrs(attrs)?;
+        attrs.resolve_params(&generics)?;
+        let generic = parse_or(&attrs.parse_type);
+
+        let body = match data {
+            syn::Data::Struct(s) => Body::Struct(Fields::new(s.fields, &attrs.not_parsed)?),
+            syn::Data::Enum(e) => Body::Enum(
+                e.variants
+                    .into_iter()
+                    .map(|v| {
+                        Ok(Variant {
+                            name: v.ident,
+                            attrs: GlobalAttr::parse_attrs(v.attrs)?.extend_with(&attrs),
+                            fields: Fields::new(v.fields, &attrs.not_parsed)?,
+                        })
+                    })
+                    .collect::<syn::Result<_>>()?,
+            ),
+            syn::Data::Union(_) => {
+                return Err(syn::Error::new_spanned(name, "unions not supported"))
+            }
+        };
+
+        Ok(Unparse {
+            name,
+            args,
+            attrs,
+            generic,
+            body,
+        })
+    }
+}
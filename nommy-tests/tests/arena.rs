@@ -0,0 +1,41 @@
+use nommy::{
+    arena::{self, Bump, ParseIn, Vec},
+    text::{AnyOf1, Tag, WhiteSpace},
+};
+
+/// S-expressions with every list allocated into the arena
+#[derive(Debug, nommy::Parse)]
+#[nommy(arena, ignore = WhiteSpace)]
+#[nommy(parse_type = char)]
+enum SExpr<'a> {
+    List(
+        #[nommy(prefix = Tag<"(">, suffix = Tag<")">)]
+        Vec<'a, &'a SExpr<'a>>,
+    ),
+    Atom(#[nommy(parser = AnyOf1<"abcdefghijklmnopqrstuvwxyz">)] String),
+}
+
+#[derive(Debug, nommy::Parse)]
+#[nommy(arena, ignore = WhiteSpace)]
+struct Program<'a> {
+    #[nommy(prefix = Tag<"program">)]
+    name: Option<arena::Text<'a, AnyOf1<"abcdefghijklmnopqrstuvwxyz">>>,
+    body: &'a SExpr<'a>,
+}
+
+fn atoms(expr: &SExpr) -> std::vec::Vec<String> {
+    match expr {
+        SExpr::Atom(a) => vec![a.clone()],
+        SExpr::List(list) => list.iter().flat_map(|e| atoms(e)).collect(),
+    }
+}
+
+fn main() {
+    let bump = Bump::new();
+    let program: Program = arena::parse_in("program demo (add (mul a b) c)".chars(), &bump).unwrap();
+    assert_eq!(program.name.unwrap().as_str(), "demo");
+    assert_eq!(atoms(program.body), vec!["add", "mul", "a", "b", "c"]);
+
+    let mut input = nommy::IntoBuf::into_buf("(a b".chars());
+    assert!(!<SExpr as ParseIn<char>>::peek_in(&mut nommy::Buffer::cursor(&mut input), &bump));
+}
@@ -0,0 +1,46 @@
+use nommy::{
+    parse,
+    text::{AnyOf1, Tag, WhiteSpace},
+    Parse,
+};
+
+type Word = AnyOf1<"abcdefghijklmnopqrstuvwxyz">;
+
+/// Attributes that belong to other derives and lints sit alongside nommy's
+#[derive(Debug, Clone, PartialEq, Parse)]
+#[allow(dead_code)]
+#[nommy(ignore = WhiteSpace,)]
+struct Assign {
+    /// the name being assigned to
+    #[nommy(parser = Word, suffix = Tag<"=">,)]
+    #[allow(clippy::redundant_field_names)]
+    name: String,
+
+    #[doc = "the value"]
+    #[nommy(parser = Word)]
+    value: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Parse)]
+#[nommy(ignore = WhiteSpace)]
+enum Statement {
+    /// `let name = value`
+    #[nommy(prefix = Tag<"let">)]
+    Let(Assign),
+    /// an empty statement
+    #[allow(unused)]
+    #[nommy(tag = ";",)]
+    Empty,
+}
+
+fn main() {
+    let statement: Statement = parse("let x = y".chars()).unwrap();
+    assert_eq!(
+        statement,
+        Statement::Let(Assign {
+            name: "x".to_owned(),
+            value: "y".to_owned(),
+        })
+    );
+    assert_eq!(parse::<Statement, _>(";".chars()).unwrap(), Statement::Empty);
+}
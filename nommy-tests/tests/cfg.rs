@@ -0,0 +1,32 @@
+use nommy::{parse, text::Tag, Parse};
+
+// `any()` is never enabled, so `not(any())` always is
+#[cfg(any())]
+struct Missing;
+
+#[derive(Debug, PartialEq, Parse)]
+enum Letter {
+    #[nommy(prefix = Tag<"a">)]
+    A,
+    #[cfg(any())]
+    #[nommy(prefix = Tag<"b">)]
+    B(Missing),
+    #[cfg_attr(not(any()), nommy(prefix = Tag<"c">))]
+    C,
+}
+
+#[derive(Debug, PartialEq, Parse)]
+struct Pair {
+    first: Tag<"x">,
+    #[cfg(any())]
+    missing: Missing,
+    #[cfg(not(any()))]
+    #[nommy(prefix = Tag<",">)]
+    second: Tag<"y">,
+}
+
+fn main() {
+    assert_eq!(parse::<Letter, _>("c".chars()).unwrap(), Letter::C);
+    assert!(parse::<Letter, _>("b".chars()).is_err());
+    parse::<Pair, _>("x,y".chars()).unwrap();
+}
@@ -0,0 +1,48 @@
+use nommy::{
+    bytes::{AnyOf1, OneOf, Tag},
+    framed::{Crc32, Xor8},
+    message::Message,
+    parse, unparse, Parse, Unparse,
+};
+
+/// the sync bytes are the struct's prefix, so they aren't covered by the checksum
+#[derive(Debug, PartialEq, Parse, Unparse)]
+#[nommy(prefix = Tag<b"\xaa\x55">)]
+struct Frame {
+    #[nommy(parser = OneOf<b"\x01\x02">)]
+    kind: u8,
+    #[nommy(parser = AnyOf1<b"abcdefghijklmnopqrstuvwxyz">)]
+    payload: Vec<u8>,
+    #[nommy(checksum, prefix = Tag<b";">)]
+    crc: Crc32,
+}
+
+#[derive(Debug, PartialEq, Parse)]
+enum Record {
+    Line(
+        #[nommy(prefix = Tag<b"L">, parser = AnyOf1<b"0123456789">)] Vec<u8>,
+        #[nommy(checksum)] Xor8,
+    ),
+}
+
+fn main() {
+    let mut input = b"\xaa\x55\x01hello;".to_vec();
+    input.extend(&Crc32::of(b"\x01hello").0.to_be_bytes());
+
+    let frame: Frame = parse(input.iter().copied()).unwrap();
+    assert_eq!(frame.kind, 1);
+    assert_eq!(frame.payload, b"hello");
+    assert_eq!(unparse::to_vec(&frame), input);
+
+    input[4] = b'j';
+    let err = parse::<Frame, _>(input.iter().copied()).unwrap_err();
+    assert_eq!(err.to_string(), "failed to parse field `crc`");
+    assert_eq!(err.root_cause().downcast_ref(), Some(&Message::Checksum));
+
+    // the prefixes of the fields are covered, 'L' ^ '1' ^ '2' == 0x4f
+    assert_eq!(
+        parse::<Record, _>(b"L12\x4f".iter().copied()).unwrap(),
+        Record::Line(b"12".to_vec(), Xor8(0x4f))
+    );
+    assert!(parse::<Record, _>(b"L12\x03".iter().copied()).is_err());
+}
@@ -0,0 +1,49 @@
+use std::collections::BTreeSet;
+
+use nommy::{
+    parse,
+    smallvec::SmallVec,
+    smartstring::alias::String as SmartString,
+    text::{AnyOf1, Tag, WhiteSpace},
+    Parse,
+};
+
+type Ident = AnyOf1<"abcdefghijklmnopqrstuvwxyz_">;
+
+#[derive(Debug, Parse)]
+#[nommy(ignore = WhiteSpace)]
+struct Attr {
+    #[nommy(prefix = Tag<"#">, parser = Ident)]
+    name: SmartString,
+}
+
+/// `use path::to::{a, b, c};` with the short paths and names stored inline
+#[derive(Debug, Parse)]
+#[nommy(ignore = WhiteSpace)]
+#[nommy(suffix = Tag<";">)]
+#[nommy(parse_type = char)]
+struct Use {
+    attrs: SmallVec<[Attr; 2]>,
+
+    #[nommy(prefix = Tag<"use">)]
+    #[nommy(inner_parser = Ident, seperated_by = Tag<"::">)]
+    path: SmallVec<[SmartString; 4]>,
+
+    #[nommy(prefix = Tag<"::{">, suffix = Tag<"}">)]
+    #[nommy(inner_parser = Ident, seperated_by = Tag<",">, trailing = "maybe")]
+    items: BTreeSet<SmartString>,
+}
+
+fn main() {
+    let u: Use = parse("#inline use std::collections::{map, b, a, b,};".chars()).unwrap();
+
+    assert_eq!(u.attrs.len(), 1);
+    assert_eq!(u.attrs[0].name, "inline");
+    assert!(u.attrs[0].name.is_inline());
+
+    assert_eq!(u.path.as_slice(), ["std", "collections"]);
+    assert!(!u.path.spilled());
+
+    let items: Vec<&str> = u.items.iter().map(|s| s.as_str()).collect();
+    assert_eq!(items, ["a", "b", "map"]);
+}
@@ -0,0 +1,60 @@
+use nommy::{
+    bytes,
+    message::Message,
+    parse,
+    text::{AnyOf1, Tag, WhiteSpace},
+    IntoBuf, Parse,
+};
+
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(complete, ignore = WhiteSpace)]
+struct Call {
+    #[nommy(parser = AnyOf1<"abcdefghijklmnopqrstuvwxyz">)]
+    name: String,
+    #[nommy(prefix = Tag<"(">, suffix = Tag<")">)]
+    arg: Option<Arg>,
+}
+
+#[derive(Debug, PartialEq, Parse)]
+struct Arg(#[nommy(parser = AnyOf1<"0123456789">)] String);
+
+/// generic over the input, so the tokens need `Debug`
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(complete, context = "parsing a flag")]
+enum Flag {
+    On(bytes::Tag<b"1">),
+    Off(bytes::Tag<b"0">),
+}
+
+fn main() {
+    // the ignored input after the value is consumed, so it's not left over
+    assert_eq!(
+        parse::<Call, _>("f( 1 )  ".chars()).unwrap(),
+        Call {
+            name: "f".to_owned(),
+            arg: Some(Arg("1".to_owned())),
+        }
+    );
+
+    let err = parse::<Call, _>("f(1) g".chars()).unwrap_err();
+    assert_eq!(err.to_string(), "expected the end of the input, found 'g'");
+    assert_eq!(
+        err.downcast_ref::<Message>(),
+        Some(&Message::Trailing {
+            found: "'g'".to_owned()
+        })
+    );
+    // errors from the value itself are still reported
+    let err = parse::<Call, _>("f(1".chars()).unwrap_err();
+    assert!(err.to_string().starts_with("failed to parse"));
+
+    // peeking agrees with parsing about the input left over
+    assert!(Call::peek(&mut "f(1) ".chars().into_buf()));
+    assert!(!Call::peek(&mut "f(1) g".chars().into_buf()));
+    assert!(!Flag::peek(&mut b"01".iter().copied().into_buf()));
+    assert_eq!(<Flag as Parse<u8>>::MAX_PEEK, Some(2));
+
+    assert!(parse::<Flag, _>(b"1".iter().copied()).is_ok());
+    let err = parse::<Flag, _>(b"01".iter().copied()).unwrap_err();
+    assert_eq!(err.to_string(), "expected the end of the input, found 49");
+}
@@ -0,0 +1,75 @@
+use nommy::{
+    message::Message,
+    parse, span,
+    text::{AnyOf1, Tag, WhiteSpace},
+    Parse,
+};
+
+type Ident = AnyOf1<"abcdefghijklmnopqrstuvwxyz">;
+
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(ignore = WhiteSpace, context = "while parsing function signature")]
+struct Signature {
+    #[nommy(prefix = Tag<"fn">, parser = Ident)]
+    name: String,
+    #[nommy(prefix = Tag<"(">, suffix = Tag<")">, context = "while parsing the parameter")]
+    param: Param,
+}
+
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(ignore = WhiteSpace)]
+struct Param {
+    #[nommy(parser = Ident)]
+    name: String,
+    #[nommy(prefix = Tag<":">, parser = Ident)]
+    ty: String,
+}
+
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(context = "while parsing a literal")]
+enum Literal {
+    #[nommy(context = "while parsing a string")]
+    String(#[nommy(prefix = Tag<"\"">, suffix = Tag<"\"">, parser = Ident)] String),
+    #[nommy(tag = "true")]
+    True,
+}
+
+fn main() {
+    let sig: Signature = parse("fn foo(x: int)".chars()).unwrap();
+    assert_eq!(sig.param.ty, "int");
+
+    let err = parse::<Signature, _>("fn foo(x int)".chars()).unwrap_err();
+    let chain: Vec<String> = err.chain().map(|e| e.to_string()).collect();
+    assert_eq!(
+        chain,
+        vec![
+            "while parsing function signature",
+            "while parsing the parameter",
+            "failed to parse field `param`",
+            "failed to parse prefix for field `ty`",
+            "failed to parse tag \":\", found \"i\"",
+        ]
+    );
+    // the context is a layer of the error, so the messages underneath can still be found
+    assert_eq!(
+        err.downcast_ref::<Message>(),
+        Some(&Message::Field { field: "param" })
+    );
+
+    // each frame spans from where the type or field it names started,
+    // except the prefix's, which only knows where the error is
+    let err = span::parse_at::<Signature>("fn foo(x int)", 0).unwrap_err();
+    assert_eq!(err.span, 9..10);
+    assert_eq!(err.frames, vec![0..10, 6..10, 6..10, 9..10]);
+
+    // the prefix of the field is covered by its context too
+    let err = parse::<Signature, _>("fn foo x: int)".chars()).unwrap_err();
+    let chain: Vec<String> = err.chain().map(|e| e.to_string()).collect();
+    assert_eq!(chain[1], "while parsing the parameter");
+
+    assert_eq!(parse::<Literal, _>("true".chars()).unwrap(), Literal::True);
+    let err = parse::<Literal, _>("\"abc".chars()).unwrap_err();
+    let chain: Vec<String> = err.chain().map(|e| e.to_string()).collect();
+    assert_eq!(chain[0], "while parsing a literal");
+    assert!(chain.contains(&"while parsing a string".to_owned()));
+}
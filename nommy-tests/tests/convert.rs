@@ -0,0 +1,51 @@
+use nommy::{
+    parse,
+    text::{AnyOf1, Tag, WhiteSpace},
+    Parse,
+};
+
+type Letters = AnyOf1<"abcdefghijklmnopqrstuvwxyz">;
+type Digits = AnyOf1<"0123456789">;
+
+#[derive(Debug, PartialEq)]
+struct Ident(String);
+
+impl From<Letters> for Ident {
+    fn from(letters: Letters) -> Self {
+        Ident(letters.into())
+    }
+}
+
+impl From<Digits> for Ident {
+    fn from(digits: Digits) -> Self {
+        Ident(format!("_{}", String::from(digits)))
+    }
+}
+
+fn count(digits: Digits) -> usize {
+    String::from(digits).len()
+}
+
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(ignore = WhiteSpace)]
+struct Decl {
+    #[nommy(parser = Letters, convert = "from_output")]
+    name: Ident,
+    #[nommy(prefix = Tag<":">, parser = Digits, convert = "into")]
+    alias: Ident,
+    #[nommy(prefix = Tag<"=">, parser = Letters, convert = "none")]
+    value: Letters,
+    #[nommy(prefix = Tag<"*">, parser = Digits, convert = "count")]
+    width: usize,
+}
+
+fn main() {
+    let decl: Decl = parse("foo: 12 = bar * 100".chars()).unwrap();
+    assert_eq!(decl.name, Ident("foo".to_owned()));
+    assert_eq!(decl.alias, Ident("_12".to_owned()));
+    assert_eq!(String::from(decl.value), "bar");
+    assert_eq!(decl.width, 3);
+
+    let err = parse::<Decl, _>("foo: x".chars()).unwrap_err();
+    assert_eq!(err.to_string(), "failed to parse field `alias`");
+}
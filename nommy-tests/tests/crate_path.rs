@@ -0,0 +1,52 @@
+/// nommy re-exported from another module, as a crate built on top of it would
+mod parsing {
+    pub use nommy as reexported;
+    pub use nommy::{parse, unparse, Parse, Unparse};
+}
+
+use parsing::{
+    parse,
+    reexported::text::{AnyOf1, Tag, WhiteSpace},
+    unparse, Parse, Unparse,
+};
+
+#[derive(Debug, PartialEq, Parse, Unparse)]
+#[nommy(crate = "parsing::reexported")]
+#[nommy(ignore = WhiteSpace)]
+struct Pair {
+    #[nommy(parser = AnyOf1<"xyz">, suffix = Tag<"=">)]
+    name: String,
+    #[nommy(parser = AnyOf1<"0123456789">)]
+    value: String,
+}
+
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(crate = "crate::parsing::reexported")]
+#[nommy(ignore = WhiteSpace)]
+enum Statement {
+    #[nommy(tag = "set")]
+    Set(Pair),
+    #[nommy(tag = "reset")]
+    Reset,
+}
+
+fn main() {
+    let statement: Statement = parse("set x = 1".chars()).unwrap();
+    assert_eq!(
+        statement,
+        Statement::Set(Pair {
+            name: "x".to_owned(),
+            value: "1".to_owned(),
+        })
+    );
+    assert_eq!(
+        parse::<Statement, _>("reset".chars()).unwrap(),
+        Statement::Reset
+    );
+
+    let pair = Pair {
+        name: "y".to_owned(),
+        value: "2".to_owned(),
+    };
+    assert_eq!(unparse::to_string(&pair), "y = 2");
+}
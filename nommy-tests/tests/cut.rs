@@ -0,0 +1,77 @@
+use nommy::{
+    error::VariantErrors,
+    parse,
+    text::{AnyOf1, Tag, WhiteSpace},
+    Parse,
+};
+
+type Ident = AnyOf1<"abcdefghijklmnopqrstuvwxyz">;
+
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(ignore = WhiteSpace)]
+enum Item {
+    /// once `fn` is parsed, this has to be a function
+    #[nommy(prefix = Tag<"fn">, cut)]
+    Function {
+        #[nommy(parser = Ident)]
+        name: String,
+        #[nommy(prefix = Tag<"(">, suffix = Tag<")">, parser = Ident)]
+        param: String,
+    },
+    /// the cut is after the name, so a missing name still falls through
+    Call(
+        #[nommy(parser = Ident, cut)] String,
+        #[nommy(prefix = Tag<"(">, suffix = Tag<")">)] Tag<"x">,
+    ),
+    Words(#[nommy(parser = AnyOf1<"abcdefghijklmnopqrstuvwxyz() ">)] String),
+}
+
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(ignore = WhiteSpace)]
+enum Items {
+    /// the inner enum's cut doesn't commit this one
+    One(Item),
+    Numbers(#[nommy(parser = AnyOf1<"0123456789fn ">)] String),
+}
+
+fn main() {
+    assert_eq!(
+        parse::<Item, _>("fn foo(x)".chars()).unwrap(),
+        Item::Function {
+            name: "foo".to_owned(),
+            param: "x".to_owned()
+        }
+    );
+
+    let err = parse::<Item, _>("fn foo(".chars()).unwrap_err();
+    assert_eq!(err.to_string(), "failed to parse Item as variant `Function`");
+    assert_eq!(
+        err.downcast_ref::<VariantErrors>().unwrap().errors().len(),
+        1
+    );
+
+    // `fn` followed by something else still fails with the function's error
+    assert!(parse::<Item, _>("fn 1".chars()).is_err());
+
+    assert_eq!(
+        parse::<Item, _>("f(x)".chars()).unwrap(),
+        Item::Call("f".to_owned(), Tag)
+    );
+    let err = parse::<Item, _>("f(y)".chars()).unwrap_err();
+    assert_eq!(err.to_string(), "failed to parse Item as variant `Call`");
+    assert_eq!(
+        err.downcast_ref::<VariantErrors>().unwrap().errors().len(),
+        2
+    );
+
+    // without reaching a cut, the next variants are tried
+    assert_eq!(
+        parse::<Item, _>("(abc)".chars()).unwrap(),
+        Item::Words("(abc)".to_owned())
+    );
+
+    assert_eq!(
+        parse::<Items, _>("fn 12".chars()).unwrap(),
+        Items::Numbers("fn 12".to_owned())
+    );
+}
@@ -1,4 +1,6 @@
-use nommy::{IntoBuf, Parse, text::Tag};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use nommy::{error::VariantErrors, eyre, text::{AnyOf1, Tag, WhiteSpace}, Buffer, IntoBuf, Parse};
 
 #[derive(Debug, Parse, PartialEq)]
 enum Enum {
@@ -11,6 +13,85 @@ enum Enum {
     Close(Tag<")">),
 }
 
+#[derive(Debug, Parse, PartialEq)]
+enum Keyword {
+    #[nommy(prefix = Tag<"if">)]
+    If,
+    #[nommy(prefix = Tag<"else">, suffix = Tag<";">)]
+    Else,
+    #[nommy(tag = "while")]
+    While,
+    Loop(#[nommy(tag = "loop", parser = AnyOf1<"0123456789">)] String),
+}
+
+/// Every variant is a keyword, so the variant is chosen by reading the input once
+#[derive(Debug, Parse, PartialEq)]
+enum Type {
+    #[nommy(tag = "int")]
+    Int,
+    #[nommy(tag = "in")]
+    In,
+    #[nommy(tag = "i8")]
+    I8,
+    #[nommy(tag = "float")]
+    Float,
+}
+
+/// `A` could fail after its tag, so `AB` must still be tried
+#[derive(Debug, Parse, PartialEq)]
+enum Bang {
+    #[nommy(tag = "a", suffix = Tag<"!">)]
+    A,
+    #[nommy(tag = "ab")]
+    AB,
+}
+
+#[derive(Debug, Parse, PartialEq)]
+enum Short {
+    #[nommy(tag = "in")]
+    In,
+    #[nommy(tag = "int")]
+    Int,
+}
+
+static LETS: AtomicUsize = AtomicUsize::new(0);
+
+/// Counts how many times `let` is parsed
+struct Let;
+impl Parse<char> for Let {
+    fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
+        LETS.fetch_add(1, Ordering::SeqCst);
+        Tag::<"let">::parse(input)?;
+        Ok(Let)
+    }
+}
+
+type Ident = AnyOf1<"abcdefghijklmnopqrstuvwxyz">;
+
+#[derive(Debug, Parse, PartialEq)]
+#[nommy(ignore = WhiteSpace)]
+enum Binding {
+    Typed(
+        #[nommy(prefix = Let, parser = Ident)] String,
+        #[nommy(prefix = Tag<":">, parser = Ident)] String,
+    ),
+    Untyped(#[nommy(prefix = Let, parser = Ident)] String),
+}
+
+#[derive(Debug, Parse, PartialEq)]
+enum Operand {
+    #[nommy(guard = elem0 == "ax" || elem0 == "bx")]
+    Register(#[nommy(parser = Ident)] String),
+    Label(#[nommy(parser = Ident)] String),
+}
+
+#[derive(Debug, Parse, PartialEq)]
+#[nommy(ignore = WhiteSpace)]
+enum Statement {
+    #[nommy(peek_limit = 1, suffix = Tag<";">)]
+    Call(#[nommy(prefix = Tag<"call">, parser = Ident)] String, Tag<"()">),
+}
+
 fn main() {
     let mut input = "(...)".chars().into_buf();
 
@@ -25,4 +106,81 @@ fn main() {
     assert_eq!(Enum::parse(&mut input).unwrap(), Enum::Close(Tag::<")">));
 
     assert_eq!(input.next(), None);
+
+    let mut input = ".x".chars().into_buf();
+    let err = Enum::parse(&mut input).unwrap_err();
+    // `Dot` read the most input before failing, so its error is the source
+    let chain: Vec<String> = err.chain().map(|e| e.to_string()).collect();
+    assert_eq!(
+        chain,
+        vec![
+            "failed to parse Enum as variant `Dot`",
+            "failed to parse field `dot2`",
+            "failed to parse tag \".\", found \"x\"",
+        ]
+    );
+
+    // the errors from the other variants are still available
+    let errors = err.downcast_ref::<VariantErrors>().unwrap();
+    let variants: Vec<_> = errors.errors().iter().map(|e| (e.variant, e.position)).collect();
+    assert_eq!(variants, vec![("Open", 0), ("Dot", 1), ("Close", 0)]);
+
+    // no variant got further than the others
+    let mut input = "x".chars().into_buf();
+    let err = Enum::parse(&mut input).unwrap_err();
+    assert_eq!(err.to_string(), "no variants of Enum could be parsed");
+
+    // unit variants are matched by their prefix and suffix alone
+    let mut input = "ifelse;whileloop3".chars().into_buf();
+    assert_eq!(Keyword::parse(&mut input).unwrap(), Keyword::If);
+    assert_eq!(Keyword::parse(&mut input).unwrap(), Keyword::Else);
+    assert_eq!(Keyword::parse(&mut input).unwrap(), Keyword::While);
+    assert_eq!(Keyword::parse(&mut input).unwrap(), Keyword::Loop("3".to_owned()));
+    assert_eq!(input.next(), None);
+
+    let mut input = "else".chars().into_buf();
+    assert!(Keyword::parse(&mut input).is_err());
+
+    let mut input = "intini8float".chars().into_buf();
+    assert_eq!(Type::parse(&mut input).unwrap(), Type::Int);
+    assert_eq!(Type::parse(&mut input).unwrap(), Type::In);
+    assert_eq!(Type::parse(&mut input).unwrap(), Type::I8);
+    assert!(Type::peek(&mut input.cursor()));
+    assert_eq!(Type::parse(&mut input).unwrap(), Type::Float);
+    assert_eq!(input.next(), None);
+
+    let mut input = "ix".chars().into_buf();
+    let err = Type::parse(&mut input).unwrap_err();
+    assert!(err.downcast_ref::<VariantErrors>().unwrap().errors().is_empty());
+    assert!(Type::parse(&mut "fl".chars().into_buf()).is_err());
+    let next = nommy::complete::completions::<Type>("i");
+    assert_eq!(next.len(), 3);
+
+    let mut input = "aba!".chars().into_buf();
+    assert_eq!(Bang::parse(&mut input).unwrap(), Bang::AB);
+    assert!(Bang::peek(&mut input.cursor()));
+    assert_eq!(Bang::parse(&mut input).unwrap(), Bang::A);
+    assert_eq!(input.next(), None);
+
+    // the first variant that matches is used, even if a later one is longer
+    let mut input = "int".chars().into_buf();
+    assert_eq!(Short::parse(&mut input).unwrap(), Short::In);
+    assert_eq!(input.next(), Some('t'));
+
+    // the prefix shared by every variant is only parsed once
+    let mut input = "let x".chars().into_buf();
+    assert_eq!(Binding::parse(&mut input).unwrap(), Binding::Untyped("x".to_owned()));
+    assert_eq!(LETS.load(Ordering::SeqCst), 1);
+
+    // variants rejected by their guard fall through to the next one
+    let operand: Operand = nommy::parse("ax".chars()).unwrap();
+    assert_eq!(operand, Operand::Register("ax".to_owned()));
+    let operand: Operand = nommy::parse("cx".chars()).unwrap();
+    assert_eq!(operand, Operand::Label("cx".to_owned()));
+
+    // only the first field of a variant with a peek limit of 1 is peeked
+    assert!(Statement::peek(&mut "call f();".chars().into_buf()));
+    assert!(Statement::peek(&mut "call f".chars().into_buf()));
+    assert!(!Statement::peek(&mut "call".chars().into_buf()));
+    assert!(nommy::parse::<Statement, _>("call f".chars()).is_err());
 }
@@ -0,0 +1,35 @@
+use nommy::{
+    message::Message,
+    parse,
+    text::{AnyOf1, Tag, WhiteSpace},
+    Parse,
+};
+
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(ignore = WhiteSpace)]
+struct Let {
+    #[nommy(prefix = Tag<"let">, parser = AnyOf1<"abcdefghijklmnopqrstuvwxyz">, expected = "an identifier")]
+    name: String,
+    #[nommy(prefix = Tag<"=">, inner_parser = AnyOf1<"0123456789">, separator = Tag<",">, min = 1, expected = "a list of numbers")]
+    values: Vec<String>,
+}
+
+fn main() {
+    let value: Let = parse("let x = 1, 2".chars()).unwrap();
+    assert_eq!(value.name, "x");
+
+    let err = parse::<Let, _>("let 1 = 2".chars()).unwrap_err();
+    assert_eq!(err.to_string(), "expected an identifier");
+    assert_eq!(
+        err.downcast_ref::<Message>(),
+        Some(&Message::Expected {
+            field: "name",
+            expected: "an identifier"
+        })
+    );
+    // the parser's own error is still the source
+    assert_eq!(err.root_cause().to_string(), "no characters found");
+
+    let err = parse::<Let, _>("let x = y".chars()).unwrap_err();
+    assert_eq!(err.to_string(), "expected a list of numbers");
+}
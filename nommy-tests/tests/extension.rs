@@ -0,0 +1,61 @@
+use nommy::{extension::{DynBuffer, Grammar}, text::Tag, Buffer, IntoBuf, Parse};
+
+#[derive(Debug, Parse, PartialEq)]
+#[nommy(extensible)]
+enum Bool {
+    True(Tag<"true">),
+    False(Tag<"false">),
+}
+
+#[derive(Debug, Parse, PartialEq)]
+#[nommy(extensible)]
+#[nommy(parse_type = char)]
+enum Value {
+    Bool(Bool),
+    Not(#[nommy(prefix = Tag<"not">)] Box<Value>),
+}
+
+fn main() {
+    let mut grammar = Grammar::new();
+    let res: Result<Bool, _> = grammar.parse("yes".chars());
+    assert!(res.is_err());
+
+    grammar.register(|input: &mut DynBuffer<char>| {
+        Tag::<"yes">::parse(input)?;
+        Ok(Bool::True(Tag))
+    });
+
+    let mut input = "yestrue".chars().into_buf();
+    grammar.scope(|| {
+        assert!(Bool::peek(&mut input.cursor()));
+        assert_eq!(Bool::parse(&mut input).unwrap(), Bool::True(Tag));
+        assert_eq!(Bool::parse(&mut input).unwrap(), Bool::True(Tag));
+    });
+
+    // the error from the extension rule is kept when it read the most input
+    let mut polite = Grammar::new();
+    polite.register(|input: &mut DynBuffer<char>| {
+        Tag::<"yes">::parse(input)?;
+        Tag::<"sir">::parse(input)?;
+        Ok(Bool::True(Tag))
+    });
+    let err = polite.parse::<Bool, _>("yessur".chars()).unwrap_err();
+    assert_eq!(
+        format!("{}", err.root_cause()),
+        "failed to parse tag \"sir\", found \"su\""
+    );
+
+    // rules can parse the extensible type recursively
+    grammar.register(|input: &mut DynBuffer<char>| {
+        Tag::<"!">::parse(input)?;
+        Ok(Value::Not(Box::new(Value::parse(input)?)))
+    });
+
+    let value: Value = grammar.parse("!not!yes".chars()).unwrap();
+    assert_eq!(
+        value,
+        Value::Not(Box::new(Value::Not(Box::new(Value::Not(Box::new(
+            Value::Bool(Bool::True(Tag))
+        ))))))
+    );
+}
@@ -0,0 +1,52 @@
+use nommy::{
+    parse,
+    text::{AnyOf1, Tag, WhileNot1, WhiteSpace},
+    IntoBuf, Parse,
+};
+
+#[derive(Debug, PartialEq, Parse)]
+enum Directive {
+    /// declared first, but only tried once the others have failed
+    #[nommy(default)]
+    Unknown(#[nommy(parser = WhileNot1<";">)] String),
+    #[nommy(prefix = Tag<"include ">)]
+    Include(#[nommy(parser = AnyOf1<"abcdefghijklmnopqrstuvwxyz.">)] String),
+    #[nommy(tag = "pragma")]
+    Pragma,
+}
+
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(strategy = "longest", ignore = WhiteSpace)]
+enum Op {
+    #[nommy(tag = "=")]
+    Assign,
+    #[nommy(tag = "==")]
+    Equal,
+    #[nommy(default)]
+    Unknown,
+}
+
+fn main() {
+    assert_eq!(
+        parse::<Directive, _>("include foo.h".chars()).unwrap(),
+        Directive::Include("foo.h".to_owned())
+    );
+    assert_eq!(
+        parse::<Directive, _>("pragma".chars()).unwrap(),
+        Directive::Pragma
+    );
+    assert_eq!(
+        parse::<Directive, _>("define x".chars()).unwrap(),
+        Directive::Unknown("define x".to_owned())
+    );
+    assert!(Directive::peek(&mut "define".chars().into_buf()));
+
+    // the default can still fail, and then its error is reported with the others
+    let err = parse::<Directive, _>(";".chars()).unwrap_err();
+    assert_eq!(err.to_string(), "no variants of Directive could be parsed");
+
+    assert_eq!(parse::<Op, _>("==".chars()).unwrap(), Op::Equal);
+    let mut input = "!".chars().into_buf();
+    assert_eq!(Op::parse(&mut input).unwrap(), Op::Unknown);
+    assert_eq!(input.next(), Some('!'));
+}
@@ -0,0 +1,52 @@
+use nommy::{
+    parse,
+    text::{AnyOf1, Space, Tag, WhiteSpace},
+    IntoBuf, Parse,
+};
+
+type Ident = AnyOf1<"abcdefghijklmnopqrstuvwxyz">;
+
+/// Only ignores spaces when parsed on its own
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(ignore = Space)]
+#[nommy(prefix = Tag<"(">, suffix = Tag<")">)]
+struct Span(
+    #[nommy(parser = Ident)] String,
+    #[nommy(prefix = Tag<",">, parser = Ident)] String,
+);
+
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(ignore = WhiteSpace)]
+struct Node {
+    #[nommy(parser = Ident)]
+    kind: String,
+    #[nommy(flatten)]
+    span: Span,
+}
+
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(ignore = WhiteSpace)]
+enum Item {
+    Node(#[nommy(prefix = Tag<"node">)] Node),
+    Span(#[nommy(prefix = Tag<"span">, flatten)] Span),
+}
+
+fn main() {
+    let node: Node = parse("ident\n(\n  a ,\n  b\n)".chars()).unwrap();
+    assert_eq!(
+        node,
+        Node {
+            kind: "ident".to_owned(),
+            span: Span("a".to_owned(), "b".to_owned()),
+        }
+    );
+
+    // on its own, `Span` doesn't ignore new lines
+    assert!(parse::<Span, _>("(a,\nb)".chars()).is_err());
+
+    let item: Item = parse("span\n(a,\nb)".chars()).unwrap();
+    assert_eq!(item, Item::Span(Span("a".to_owned(), "b".to_owned())));
+
+    assert!(Node::peek(&mut "x (a,\nb)".chars().into_buf()));
+    assert!(!Node::peek(&mut "x (a,\nb".chars().into_buf()));
+}
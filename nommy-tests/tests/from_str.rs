@@ -0,0 +1,54 @@
+use std::error::Error;
+
+use nommy::{
+    text::{AnyOf1, Tag, WhiteSpace},
+    Parse,
+};
+
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(from_str)]
+enum Color {
+    #[nommy(tag = "red")]
+    Red,
+    #[nommy(tag = "green")]
+    Green,
+    Rgb(
+        #[nommy(prefix = Tag<"#">, parser = AnyOf1<"0123456789abcdef">)] String,
+    ),
+}
+
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(from_str, ignore = WhiteSpace, prefix = Tag<"(">, suffix = Tag<")">)]
+struct Pair<T> {
+    first: T,
+    #[nommy(prefix = Tag<",">)]
+    second: T,
+}
+
+fn pair() -> Result<Pair<Color>, Box<dyn Error>> {
+    Ok("( red , #0f0 )".parse()?)
+}
+
+fn main() {
+    assert_eq!("green".parse::<Color>().unwrap(), Color::Green);
+    assert_eq!(
+        "#abc".parse::<Color>().unwrap(),
+        Color::Rgb("abc".to_owned())
+    );
+    assert_eq!(
+        pair().unwrap(),
+        Pair {
+            first: Color::Red,
+            second: Color::Rgb("0f0".to_owned())
+        }
+    );
+
+    // the whole string has to be parsed
+    let err = "redd".parse::<Color>().unwrap_err();
+    assert_eq!(err.to_string(), "input was not parsed completely");
+    assert!(err.source().is_none());
+
+    let err = "(red)".parse::<Pair<Color>>().unwrap_err();
+    assert!(err.to_string().starts_with("failed to parse prefix for field `second`"));
+    assert!(err.source().is_some());
+}
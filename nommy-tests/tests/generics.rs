@@ -0,0 +1,91 @@
+use std::marker::PhantomData;
+
+use nommy::{
+    parse,
+    text::{AnyOf1, Tag, WhiteSpace},
+    unparse, Parse, Unparse,
+};
+
+type Ident = AnyOf1<"abcdefghijklmnopqrstuvwxyz">;
+
+#[derive(Debug, Clone, PartialEq, Parse, Unparse)]
+struct Word<'a> {
+    #[nommy(parser = Ident)]
+    text: String,
+    source: PhantomData<&'a str>,
+}
+
+#[derive(Debug, PartialEq, Parse, Unparse)]
+#[nommy(ignore = WhiteSpace)]
+struct Sized<'a, T, const N: usize> {
+    word: Word<'a>,
+    #[nommy(prefix = Tag<":">)]
+    value: T,
+    #[nommy(skip_with = N)]
+    size: usize,
+}
+
+#[derive(Debug, PartialEq, Parse)]
+enum Either<'a, const N: usize> {
+    Sized(#[nommy(prefix = Tag<"!">)] Sized<'a, Word<'a>, N>),
+    Word(Word<'a>),
+}
+
+/// The bounds and where clause are kept on the derived impls
+#[derive(Debug, PartialEq, Parse, Unparse)]
+#[nommy(ignore = WhiteSpace)]
+struct Bounded<T: Clone, U>
+where
+    U: Copy + Default,
+{
+    #[nommy(prefix = Tag<"(">, suffix = Tag<")">)]
+    value: T,
+    #[nommy(skip)]
+    count: U,
+}
+
+#[derive(Debug, PartialEq, Parse)]
+enum Many<'a, 'b: 'a, T>
+where
+    T: Clone,
+{
+    One(Bounded<T, u8>),
+    Words(
+        Word<'a>,
+        #[nommy(prefix = Tag<",">)] Word<'a>,
+        PhantomData<&'b str>,
+    ),
+}
+
+/// The generated bounds are replaced by the given ones
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(parse_type = char, bound = "T: Parse<char>")]
+enum Nested<T> {
+    Inner(#[nommy(prefix = Tag<"(">, suffix = Tag<")">)] Box<Self>),
+    Leaf(T),
+}
+
+fn main() {
+    let sized: Sized<Word, 4> = parse("a : b".chars()).unwrap();
+    assert_eq!(sized.word.text, "a");
+    assert_eq!(sized.value.text, "b");
+    assert_eq!(sized.size, 4);
+    assert_eq!(unparse::to_string(&sized), "a : b");
+
+    let either: Either<2> = parse("!a:b".chars()).unwrap();
+    assert!(matches!(either, Either::Sized(Sized { size: 2, .. })));
+    let either: Either<2> = parse("c".chars()).unwrap();
+    assert_eq!(either, Either::Word(Word { text: "c".to_owned(), source: PhantomData }));
+
+    let bounded: Bounded<Word, u8> = parse("( a )".chars()).unwrap();
+    assert_eq!(bounded.value.text, "a");
+    assert_eq!(unparse::to_string(&bounded), "( a )");
+
+    let many: Many<Word> = parse("a,b".chars()).unwrap();
+    assert!(matches!(many, Many::Words(..)));
+
+    let nested: Nested<Word> = parse("((a))".chars()).unwrap();
+    let word = Word { text: "a".to_owned(), source: PhantomData };
+    let leaf = Nested::Leaf(word);
+    assert_eq!(nested, Nested::Inner(Box::new(Nested::Inner(Box::new(leaf)))));
+}
@@ -0,0 +1,77 @@
+use nommy::{
+    parse,
+    text::{AnyOf1, LineEnding, Tag, WhileNot1, WhiteSpace},
+    unparse, Parse, Unparse,
+};
+
+type Ident = AnyOf1<"abcdefghijklmnopqrstuvwxyz">;
+
+/// `name = "text"`, where the whitespace inside the quotes is kept
+#[derive(Debug, PartialEq, Parse, Unparse)]
+#[nommy(ignore = WhiteSpace)]
+#[nommy(parse_type = char, suffix = Tag<";">)]
+struct Assign {
+    #[nommy(parser = Ident, suffix = Tag<"=">)]
+    name: String,
+    #[nommy(prefix = Tag<"\"">, suffix = Tag<"\"">)]
+    #[nommy(parser = WhileNot1<"\"">, no_ignore)]
+    text: String,
+}
+
+/// `code { x }`, or `<< text >>` with the whitespace inside kept
+#[derive(Debug, PartialEq, Parse, Unparse)]
+#[nommy(ignore = WhiteSpace)]
+#[nommy(parse_type = char)]
+enum Block {
+    #[nommy(prefix = Tag<"code">)]
+    Code(#[nommy(prefix = Tag<"{">, suffix = Tag<"}">, parser = Ident)] String),
+    #[nommy(no_ignore, prefix = Tag<"<<">, suffix = Tag<">>">)]
+    Text(#[nommy(parser = WhileNot1<">">)] String),
+}
+
+/// Both variants start with a quote, but the whitespace after it is only skipped in `Word`
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(ignore = WhiteSpace)]
+#[nommy(parse_type = char)]
+enum Quoted {
+    #[nommy(no_ignore, prefix = Tag<"'">, suffix = Tag<"'">)]
+    Raw(#[nommy(parser = WhileNot1<"'">)] String),
+    #[nommy(prefix = Tag<"'">)]
+    Word(#[nommy(parser = Ident, suffix = Tag<"!">)] String),
+}
+
+/// `key value` on each line, where only spaces and tabs are skipped
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(whitespace = "spaces")]
+#[nommy(parse_type = char)]
+struct Line {
+    #[nommy(parser = Ident)]
+    key: String,
+    #[nommy(parser = Ident, suffix = LineEnding)]
+    value: String,
+}
+
+fn main() {
+    let assign: Assign = parse("x = \"  hello \" ;".chars()).unwrap();
+    assert_eq!(assign.text, "  hello ");
+
+    let output = unparse::to_string(&assign);
+    assert_eq!(output, "x = \"  hello \" ;");
+    assert_eq!(parse::<Assign, _>(output.chars()).unwrap(), assign);
+
+    let block: Block = parse("code { x }".chars()).unwrap();
+    assert_eq!(block, Block::Code("x".to_owned()));
+    let block: Block = parse("<< hi >>".chars()).unwrap();
+    assert_eq!(block, Block::Text(" hi ".to_owned()));
+    assert_eq!(unparse::to_string(&block), "<< hi >>");
+
+    let quoted: Quoted = parse("' hi '".chars()).unwrap();
+    assert_eq!(quoted, Quoted::Raw(" hi ".to_owned()));
+    let quoted: Quoted = parse("' hi !".chars()).unwrap();
+    assert_eq!(quoted, Quoted::Word("hi".to_owned()));
+
+    let lines: Vec<Line> = parse("a \t b\nc d\n".chars()).unwrap();
+    assert_eq!(lines.len(), 2);
+    // the line ending isn't skipped, so the value can't be on the next line
+    assert!(parse::<Line, _>("a\nb\n".chars()).is_err());
+}
@@ -0,0 +1,29 @@
+use lasso::Rodeo;
+use nommy::{
+    intern::{self, Interned},
+    parse,
+    text::{AnyOf1, Tag, WhiteSpace},
+    Parse,
+};
+
+type Ident = Interned<AnyOf1<"abcdefghijklmnopqrstuvwxyz">, Rodeo>;
+
+#[derive(Debug, Parse)]
+#[nommy(ignore = WhiteSpace)]
+#[nommy(parse_type = char)]
+struct Call {
+    func: Ident,
+    #[nommy(prefix = Tag<"(">, suffix = Tag<")">)]
+    #[nommy(inner_parser = Ident, seperated_by = Tag<",">)]
+    args: Vec<Ident>,
+}
+
+fn main() {
+    let mut rodeo = Rodeo::default();
+    let call: Call = intern::scope(&mut rodeo, || parse("max(x, y, x)".chars())).unwrap();
+
+    assert_eq!(rodeo.resolve(&call.func.symbol()), "max");
+    assert_eq!(call.args[0], call.args[2]);
+    assert_ne!(call.args[0], call.args[1]);
+    assert_eq!(rodeo.len(), 3);
+}
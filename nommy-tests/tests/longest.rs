@@ -0,0 +1,53 @@
+use nommy::{
+    parse,
+    text::{AnyOf1, Tag, WhiteSpace},
+    IntoBuf, Parse,
+};
+
+/// declared shortest first, but the longest operator that matches is chosen
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(strategy = "longest")]
+enum Comparison {
+    #[nommy(tag = ">")]
+    Greater,
+    #[nommy(tag = ">=")]
+    GreaterEqual,
+    #[nommy(tag = "<")]
+    Less,
+    #[nommy(tag = "<=")]
+    LessEqual,
+}
+
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(strategy = "longest", ignore = WhiteSpace)]
+enum Expr {
+    Number(#[nommy(parser = AnyOf1<"0123456789">)] String),
+    Range(
+        #[nommy(parser = AnyOf1<"0123456789">)] String,
+        #[nommy(prefix = Tag<"..">, parser = AnyOf1<"0123456789">)] String,
+    ),
+    /// reads as much as `Number`, so `Number` is chosen for being first
+    Digits(#[nommy(parser = AnyOf1<"0123456789">)] String),
+}
+
+fn main() {
+    assert_eq!(parse::<Comparison, _>(">=".chars()).unwrap(), Comparison::GreaterEqual);
+    assert_eq!(parse::<Comparison, _>(">".chars()).unwrap(), Comparison::Greater);
+    assert_eq!(parse::<Comparison, _>("<=".chars()).unwrap(), Comparison::LessEqual);
+
+    let mut input = ">= 1".chars().into_buf();
+    assert!(Comparison::peek(&mut input));
+    assert_eq!(input.next(), Some(' '));
+
+    assert_eq!(
+        parse::<Expr, _>("1 .. 10".chars()).unwrap(),
+        Expr::Range("1".to_owned(), "10".to_owned())
+    );
+    assert_eq!(
+        parse::<Expr, _>("12".chars()).unwrap(),
+        Expr::Number("12".to_owned())
+    );
+
+    let err = parse::<Comparison, _>("=".chars()).unwrap_err();
+    assert_eq!(err.to_string(), "no variants of Comparison could be parsed");
+}
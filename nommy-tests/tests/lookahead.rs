@@ -0,0 +1,77 @@
+// the types are only declared to check their lookahead
+#![allow(dead_code)]
+
+use nommy::{
+    text::{AnyOf1, OneOf, Tag, WhiteSpace},
+    Parse,
+};
+
+type Digit = OneOf<"0123456789">;
+
+/// `0x` followed by two digits
+#[derive(Parse)]
+#[nommy(prefix = Tag<"0x">)]
+struct Byte {
+    high: Digit,
+    low: Digit,
+}
+
+/// only the first two fields are peeked
+#[derive(Parse)]
+#[nommy(peek_limit = 2)]
+struct Triple(Digit, Digit, Digit);
+
+#[derive(Parse)]
+enum Keyword {
+    #[nommy(tag = "if")]
+    If,
+    #[nommy(tag = "else")]
+    Else,
+}
+
+#[derive(Parse)]
+enum Literal {
+    Byte(Byte),
+    Digit(Digit),
+}
+
+#[derive(Parse)]
+struct Date {
+    #[nommy(count = 3, separator = Tag<"-">)]
+    parts: Vec<Digit>,
+}
+
+#[derive(Parse)]
+#[nommy(ignore = WhiteSpace)]
+struct Spaced {
+    a: Tag<"a">,
+    b: Tag<"b">,
+}
+
+#[derive(Parse)]
+struct Word(#[nommy(parser = AnyOf1<"abc">)] String);
+
+#[derive(Parse)]
+enum Nested {
+    #[nommy(prefix = Tag<"(">, suffix = Tag<")">)]
+    Group(Box<Nested>),
+    Digit(Digit),
+}
+
+fn max_peek<P: Parse<char>>() -> Option<usize> {
+    P::MAX_PEEK
+}
+
+fn main() {
+    assert_eq!(max_peek::<Byte>(), Some(4));
+    assert_eq!(max_peek::<Triple>(), Some(2));
+    assert_eq!(max_peek::<Keyword>(), Some(4));
+    assert_eq!(max_peek::<Literal>(), Some(4));
+    assert_eq!(max_peek::<Date>(), Some(6));
+    assert_eq!(max_peek::<Option<Byte>>(), Some(4));
+
+    // unbounded
+    assert_eq!(max_peek::<Spaced>(), None);
+    assert_eq!(max_peek::<Word>(), None);
+    assert_eq!(max_peek::<Nested>(), None);
+}
@@ -0,0 +1,58 @@
+use std::marker::PhantomData;
+
+use nommy::{
+    message::Message,
+    parse,
+    text::{AnyOf1, Tag},
+    Buffer, IntoBuf, Parse, Unparse,
+};
+
+type Letters = AnyOf1<"abcdefghijklmnopqrstuvwxyz">;
+
+#[derive(Debug, PartialEq, Parse, Unparse)]
+#[nommy(parse_type = char)]
+enum Word {
+    /// only chosen when a `;` follows, which is left for the parent to parse
+    Last(
+        #[nommy(parser = Letters)] String,
+        #[nommy(peek = Tag<";">)] PhantomData<()>,
+    ),
+    Other(#[nommy(parser = Letters)] String),
+}
+
+#[derive(Debug, PartialEq, Parse)]
+struct Marker {
+    #[nommy(peek = Tag<"!">)]
+    bang: (),
+}
+
+fn main() {
+    let mut input = "abc;".chars().into_buf();
+    assert_eq!(
+        Word::parse(&mut input).unwrap(),
+        Word::Last("abc".to_owned(), PhantomData)
+    );
+    assert_eq!(input.next(), Some(';'));
+
+    assert_eq!(
+        parse::<Word, _>("abc".chars()).unwrap(),
+        Word::Other("abc".to_owned())
+    );
+    assert_eq!(
+        nommy::unparse::to_string(&Word::Last("abc".to_owned(), PhantomData)),
+        "abc"
+    );
+
+    let mut input = "!".chars().into_buf();
+    let mut cursor = input.cursor();
+    assert!(Marker::peek(&mut cursor));
+    assert_eq!(cursor.position(), 0);
+    Marker::parse(&mut input).unwrap();
+    assert_eq!(input.next(), Some('!'));
+
+    let err = parse::<Marker, _>("?".chars()).unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<Message>(),
+        Some(&Message::Lookahead { field: "bang" })
+    );
+}
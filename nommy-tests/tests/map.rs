@@ -0,0 +1,98 @@
+use nommy::{
+    eyre, parse,
+    text::{AnyOf1, Tag, WhiteSpace},
+    Parse,
+};
+
+type Letters = AnyOf1<"abcdefghijklmnopqrstuvwxyz">;
+type Digits = AnyOf1<"0123456789">;
+
+fn uppercase(name: String) -> String {
+    name.to_uppercase()
+}
+
+fn parse_number(digits: String) -> Result<u32, std::num::ParseIntError> {
+    digits.parse()
+}
+
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(ignore = WhiteSpace)]
+struct Assign {
+    #[nommy(parser = Letters, map = "uppercase")]
+    name: String,
+    #[nommy(prefix = Tag<"=">, parser = Digits, map = "parse_number")]
+    value: u32,
+}
+
+/// a struct's map is called with the whole value
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(map = "Scaled::check")]
+struct Scaled(#[nommy(parser = Digits)] String, Tag<"x">);
+
+impl Scaled {
+    fn check(self) -> eyre::Result<Self> {
+        if self.0.starts_with('0') {
+            eyre::bail!("scale has a leading zero")
+        }
+        Ok(self)
+    }
+}
+
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(map = "Value::simplify")]
+enum Value {
+    #[nommy(map = "Value::double")]
+    Double(#[nommy(prefix = Tag<"2*">, parser = Digits)] String),
+    Number(#[nommy(parser = Digits)] String),
+}
+
+impl Value {
+    fn double(self) -> Self {
+        match self {
+            Value::Double(n) => Value::Double(n.repeat(2)),
+            other => other,
+        }
+    }
+
+    fn simplify(self) -> Self {
+        match self {
+            Value::Double(n) if n.len() > 4 => Value::Number(n),
+            other => other,
+        }
+    }
+}
+
+fn main() {
+    let assign: Assign = parse("x = 12".chars()).unwrap();
+    assert_eq!(assign.name, "X");
+    assert_eq!(assign.value, 12);
+
+    // field errors from the map function are reported for the field
+    let err = parse::<Assign, _>("x = 99999999999".chars()).unwrap_err();
+    assert_eq!(err.to_string(), "failed to parse field `value`");
+    assert_eq!(
+        err.root_cause().to_string(),
+        "number too large to fit in target type"
+    );
+
+    assert_eq!(
+        parse::<Scaled, _>("10x".chars()).unwrap(),
+        Scaled("10".to_owned(), Tag)
+    );
+    let err = parse::<Scaled, _>("01x".chars()).unwrap_err();
+    assert_eq!(err.to_string(), "scale has a leading zero");
+
+    // the variant's map is called before the enum's
+    assert_eq!(
+        parse::<Value, _>("2*12".chars()).unwrap(),
+        Value::Double("1212".to_owned())
+    );
+    assert_eq!(
+        parse::<Value, _>("2*123".chars()).unwrap(),
+        Value::Number("123123".to_owned())
+    );
+    assert_eq!(
+        parse::<Value, _>("7".chars()).unwrap(),
+        Value::Number("7".to_owned())
+    );
+}
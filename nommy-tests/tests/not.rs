@@ -0,0 +1,52 @@
+use nommy::{
+    message::Message,
+    parse,
+    text::{AnyOf1, OneOf, Tag, WhiteSpace},
+    IntoBuf, Parse,
+};
+
+type Letters = AnyOf1<"abcdefghijklmnopqrstuvwxyz">;
+
+#[derive(Parse)]
+enum Keyword {
+    #[nommy(tag = "let")]
+    Let,
+    #[nommy(tag = "if")]
+    If,
+}
+
+/// parses nothing, so it can be given a `not` of its own
+#[derive(Parse)]
+struct End;
+
+/// a keyword that isn't the start of a longer word
+#[allow(dead_code)]
+#[derive(Parse)]
+struct Reserved(Keyword, #[nommy(not = OneOf<"abcdefghijklmnopqrstuvwxyz">)] End);
+
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(ignore = WhiteSpace)]
+struct Let {
+    #[nommy(prefix = Tag<"let">, not = Reserved, parser = Letters)]
+    name: String,
+    #[nommy(prefix = Tag<"=">, parser = AnyOf1<"0123456789">)]
+    value: String,
+}
+
+fn main() {
+    let binding: Let = parse("let x = 1".chars()).unwrap();
+    assert_eq!(binding.name, "x");
+
+    let err = parse::<Let, _>("let if = 1".chars()).unwrap_err();
+    assert_eq!(
+        err.downcast_ref::<Message>(),
+        Some(&Message::Excluded { field: "name" })
+    );
+    assert_eq!(err.to_string(), "field `name` found the type it excludes");
+    assert!(!Let::peek(&mut "let if = 1".chars().into_buf()));
+
+    // words that only start with a keyword are still names
+    let binding: Let = parse("let iffy = 1".chars()).unwrap();
+    assert_eq!(binding.name, "iffy");
+    assert!(Let::peek(&mut "let iffy = 1".chars().into_buf()));
+}
@@ -0,0 +1,50 @@
+use nommy::{
+    parse,
+    text::{AnyOf1, Tag, WhiteSpace},
+    Parse,
+};
+
+type Ident = AnyOf1<"abcdefghijklmnopqrstuvwxyz">;
+
+/// Once the `:` is peeked, the type must follow
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(ignore = WhiteSpace, peek_limit = 1)]
+struct Annotation {
+    colon: Tag<":">,
+    #[nommy(parser = Ident)]
+    ty: String,
+}
+
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(ignore = WhiteSpace)]
+struct Param {
+    #[nommy(parser = Ident)]
+    name: String,
+    annotation: Option<Annotation>,
+}
+
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(ignore = WhiteSpace)]
+struct LooseParam {
+    #[nommy(parser = Ident)]
+    name: String,
+    #[nommy(no_auto_option)]
+    annotation: Option<Annotation>,
+}
+
+fn main() {
+    let param: Param = parse("x : int".chars()).unwrap();
+    assert_eq!(param.annotation.unwrap().ty, "int");
+    let param: Param = parse("x".chars()).unwrap();
+    assert_eq!(param.annotation, None);
+
+    // the annotation was peeked, so its error is reported rather than skipping it
+    let err = parse::<Param, _>("x : 1".chars()).unwrap_err();
+    assert_eq!(err.to_string(), "failed to parse field `annotation`");
+
+    // `Option`'s own impl doesn't backtrack to `None` either
+    let err = parse::<LooseParam, _>("x : 1".chars()).unwrap_err();
+    assert_eq!(err.to_string(), "failed to parse field `annotation`");
+    let param: LooseParam = parse("x".chars()).unwrap();
+    assert_eq!(param.annotation, None);
+}
@@ -0,0 +1,78 @@
+use std::marker::PhantomData;
+
+use nommy::{
+    message::Message,
+    parse,
+    text::{AnyOf1, Tag, WhiteSpace},
+    IntoBuf, Parse, Peek, Peeked,
+};
+
+type Digits = AnyOf1<"0123456789">;
+
+/// only ever peeked, so the fields don't have to be built from their parsers' output
+#[derive(Debug, PartialEq, nommy::Peek)]
+#[nommy(ignore = WhiteSpace)]
+struct Terminator {
+    #[nommy(parser = Tag<";">)]
+    semi: PhantomData<()>,
+    #[nommy(parser = Tag<"end">)]
+    end: PhantomData<()>,
+}
+
+#[allow(dead_code)]
+#[derive(Debug, PartialEq, nommy::Peek)]
+enum Sign {
+    Plus(#[nommy(parser = Tag<"+">)] PhantomData<()>),
+    #[nommy(tag = "-")]
+    Minus,
+}
+
+/// a sentinel can be parsed over anywhere a parser can be used
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(ignore = WhiteSpace)]
+struct Block {
+    #[nommy(inner_parser = Digits, seperated_by = Tag<",">)]
+    values: Vec<String>,
+    end: Peeked<Terminator>,
+}
+
+/// parsed with the derive, but peeked by parsing
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(no_peek)]
+struct Number {
+    #[nommy(parser = Digits)]
+    digits: String,
+}
+
+fn main() {
+    assert!(Terminator::peek(&mut "; end".chars().into_buf()));
+    assert!(!Terminator::peek(&mut "; and".chars().into_buf()));
+    assert_eq!(<Terminator as Peek<char>>::MAX_PEEK, None);
+    assert_eq!(<Peeked<Terminator> as Parse<char>>::MAX_PEEK, None);
+
+    let err = parse::<Peeked<Terminator>, _>("end".chars()).unwrap_err();
+    assert!(matches!(
+        err.downcast_ref::<Message>(),
+        Some(Message::NotFound { name }) if name.ends_with("Terminator")
+    ));
+    assert!(err.to_string().ends_with("Terminator was not found"));
+
+    // only peeking is derived, so no value is ever made up
+    assert!(parse::<Peeked<Sign>, _>("+".chars()).is_ok());
+    assert_eq!(<Sign as Peek<char>>::MAX_PEEK, Some(1));
+    assert!(Sign::peek(&mut "-1".chars().into_buf()));
+    assert!(!Sign::peek(&mut "1".chars().into_buf()));
+
+    let block: Block = parse("1, 2; end".chars()).unwrap();
+    assert_eq!(block.values, vec!["1", "2"]);
+
+    assert!(Number::peek(&mut "12".chars().into_buf()));
+    assert!(!Number::peek(&mut "x".chars().into_buf()));
+    assert_eq!(<Number as Parse<char>>::MAX_PEEK, None);
+    assert_eq!(
+        parse::<Number, _>("12".chars()).unwrap(),
+        Number {
+            digits: "12".to_owned()
+        }
+    );
+}
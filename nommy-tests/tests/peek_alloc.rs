@@ -0,0 +1,85 @@
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use nommy::{
+    bytes,
+    text::{
+        AnyOf, AnyOf1, ITag, LineEnding, OneOf, Space, Tag, TagOneOf, UnicodeWhiteSpace, WhileNot1,
+        WhiteSpace,
+    },
+    Buffer, Parse, SliceBuf, Vec1,
+};
+
+/// Counts every allocation, so peeks can be checked to never allocate
+struct Counting;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+type Ident = AnyOf1<"abcdefghijklmnopqrstuvwxyz">;
+
+// only ever peeked
+#[allow(dead_code)]
+#[derive(Parse)]
+#[nommy(ignore = WhiteSpace)]
+struct Call {
+    #[nommy(parser = Ident)]
+    name: String,
+    #[nommy(prefix = Tag<"(">, suffix = Tag<")">, inner_parser = Ident)]
+    args: Vec<String>,
+}
+
+#[allow(dead_code)]
+#[derive(Parse)]
+enum Item {
+    Call(Call),
+    Word(#[nommy(parser = Ident)] String),
+}
+
+/// Peeks `P` on `input`, returning whether it matched and how many allocations it made
+fn peek<P: Parse<T>, T: Clone>(input: &[T]) -> (bool, usize) {
+    let mut buffer = SliceBuf::new(input);
+    let before = ALLOCATIONS.load(Ordering::SeqCst);
+    let found = P::peek(&mut buffer.cursor());
+    (found, ALLOCATIONS.load(Ordering::SeqCst) - before)
+}
+
+fn main() {
+    let text: Vec<char> = "foo ( a b ) bar".chars().collect();
+    assert_eq!(peek::<Tag<"foo">, _>(&text), (true, 0));
+    assert_eq!(peek::<OneOf<"xyz">, _>(&text), (false, 0));
+    assert_eq!(peek::<Ident, _>(&text), (true, 0));
+    assert_eq!(peek::<Vec<OneOf<"fo">>, _>(&text), (true, 0));
+    assert_eq!(peek::<Vec1<WhiteSpace>, _>(&text), (false, 0));
+    assert_eq!(peek::<Option<WhiteSpace>, _>(&text), (true, 0));
+    assert_eq!(peek::<Call, _>(&text), (true, 0));
+    assert_eq!(peek::<Item, _>(&text), (true, 0));
+    assert_eq!(peek::<ITag<"FOO">, _>(&text), (true, 0));
+    assert_eq!(peek::<TagOneOf<{ &["bar", "foo"] }>, _>(&text), (true, 0));
+    assert_eq!(peek::<AnyOf<"xyz">, _>(&text), (true, 0));
+    assert_eq!(peek::<WhileNot1<"(">, _>(&text), (true, 0));
+    assert_eq!(peek::<LineEnding, _>(&text), (false, 0));
+    assert_eq!(peek::<Space, _>(&text), (false, 0));
+    assert_eq!(peek::<UnicodeWhiteSpace, _>(&text), (false, 0));
+
+    let data = b"GET /".to_vec();
+    assert_eq!(peek::<bytes::Tag<b"GET">, _>(&data), (true, 0));
+    assert_eq!(peek::<bytes::Tag<b"PUT">, _>(&data), (false, 0));
+    assert_eq!(peek::<bytes::OneOf<b"G">, _>(&data), (true, 0));
+    assert_eq!(peek::<bytes::AnyOf1<b"EGT">, _>(&data), (true, 0));
+}
@@ -0,0 +1,40 @@
+use nommy::{
+    parse,
+    text::{AnyOf1, Tag},
+    Parse,
+};
+
+/// the keywords are kept in alphabetical order, but `inline` has to be tried before `in`
+#[derive(Debug, PartialEq, Parse)]
+enum Keyword {
+    #[nommy(tag = "if")]
+    If,
+    #[nommy(tag = "in")]
+    In,
+    #[nommy(tag = "inline", priority = 1)]
+    Inline,
+}
+
+#[derive(Debug, PartialEq, Parse)]
+enum Token {
+    /// any word, only if nothing else matches
+    #[nommy(priority = -1)]
+    Word(#[nommy(parser = AnyOf1<"abcdefghijklmnopqrstuvwxyz">)] String),
+    Let(Tag<"let">),
+    Number(#[nommy(parser = AnyOf1<"0123456789">)] String),
+}
+
+fn main() {
+    assert_eq!(parse::<Keyword, _>("inline".chars()).unwrap(), Keyword::Inline);
+    assert_eq!(parse::<Keyword, _>("if".chars()).unwrap(), Keyword::If);
+
+    assert_eq!(parse::<Token, _>("let".chars()).unwrap(), Token::Let(Tag));
+    assert_eq!(
+        parse::<Token, _>("x".chars()).unwrap(),
+        Token::Word("x".to_owned())
+    );
+    assert_eq!(
+        parse::<Token, _>("12".chars()).unwrap(),
+        Token::Number("12".to_owned())
+    );
+}
@@ -0,0 +1,72 @@
+use nommy::{
+    parse,
+    text::{AnyOf1, OneOf, Tag, WhiteSpace},
+    Parse, Process, Vec1,
+};
+
+type Ident = AnyOf1<"abcdefghijklmnopqrstuvwxyz">;
+
+#[derive(Debug, PartialEq, Parse, Process)]
+#[nommy(ignore = WhiteSpace)]
+#[nommy(output_derive = "Debug, PartialEq")]
+enum Value {
+    #[nommy(tag = "null")]
+    Null,
+    Number(Vec1<OneOf<"0123456789">>),
+    Call {
+        name: Ident,
+        #[nommy(prefix = Tag<"(">, suffix = Tag<")">, seperated_by = Tag<",">)]
+        args: Vec<Value>,
+    },
+}
+
+/// The std derives on the output type bound `T`, as well as `T::Output`,
+/// so `Value` implements them too
+#[derive(Parse, Process)]
+#[nommy(ignore = WhiteSpace)]
+#[nommy(output_derive = "Debug, PartialEq")]
+pub struct Assign<T> {
+    pub name: Ident,
+    eq: Tag<"=">,
+    pub value: T,
+    semi: Option<Tag<";">>,
+}
+
+#[derive(Parse, Process)]
+#[nommy(output_derive = "Debug, Clone, PartialEq")]
+struct Pair(OneOf<"ab">, OneOf<"ab">);
+
+#[derive(Parse, Process)]
+#[nommy(output_derive = "Debug, PartialEq")]
+#[nommy(prefix = Tag<"!">)]
+struct Bang;
+
+fn main() {
+    let assign: Assign<Value> = parse("x = max(1, null, f());".chars()).unwrap();
+    let assign: AssignOutput<Value> = assign.process();
+    assert_eq!(
+        assign,
+        AssignOutput {
+            name: "x".to_owned(),
+            eq: (),
+            value: ValueOutput::Call {
+                name: "max".to_owned(),
+                args: vec![
+                    ValueOutput::Number(vec!['1']),
+                    ValueOutput::Null,
+                    ValueOutput::Call {
+                        name: "f".to_owned(),
+                        args: vec![],
+                    },
+                ],
+            },
+            semi: Some(()),
+        }
+    );
+
+    let pair: Pair = parse("ba".chars()).unwrap();
+    assert_eq!(pair.process(), PairOutput('b', 'a'));
+
+    let bang: Bang = parse("!".chars()).unwrap();
+    assert_eq!(bang.process(), BangOutput);
+}
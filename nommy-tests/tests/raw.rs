@@ -0,0 +1,61 @@
+use nommy::{
+    eyre, parse,
+    text::{AnyOf1, Tag, WhiteSpace},
+    Buffer, Parse,
+};
+
+/// Reads a regex literal's pattern up to the closing `/`, skipping escaped slashes
+fn pattern(input: &mut impl Buffer<char>) -> eyre::Result<(String, usize)> {
+    let mut pattern = String::new();
+    let mut consumed = 0;
+    while let Some(c) = input.next() {
+        match c {
+            '/' => return Ok((pattern, consumed)),
+            '\\' => {
+                let escaped = input.next().ok_or_else(|| eyre::eyre!("unfinished escape"))?;
+                pattern.push(c);
+                pattern.push(escaped);
+                consumed += 2;
+            }
+            _ => {
+                pattern.push(c);
+                consumed += 1;
+            }
+        }
+    }
+    Err(eyre::eyre!("unclosed regex literal"))
+}
+
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(parse_type = char, ignore = WhiteSpace)]
+enum Value {
+    Regex(#[nommy(prefix = Tag<"/">, suffix = Tag<"/">, raw = "pattern")] String),
+    Name(#[nommy(parser = AnyOf1<"abcdefghijklmnopqrstuvwxyz">)] String),
+}
+
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(parse_type = char, ignore = WhiteSpace)]
+struct Assign {
+    #[nommy(parser = AnyOf1<"abcdefghijklmnopqrstuvwxyz">)]
+    name: String,
+    #[nommy(prefix = Tag<"=">)]
+    value: Value,
+}
+
+fn main() {
+    assert_eq!(
+        parse::<Assign, _>(r"re = /a\/b+/".chars()).unwrap(),
+        Assign {
+            name: "re".to_owned(),
+            value: Value::Regex(r"a\/b+".to_owned()),
+        }
+    );
+    assert_eq!(
+        parse::<Assign, _>("x = y".chars()).unwrap().value,
+        Value::Name("y".to_owned())
+    );
+
+    let err = parse::<Value, _>("/abc".chars()).unwrap_err();
+    assert!(format!("{:?}", err).contains("unclosed regex literal"));
+    assert!(!Value::peek(&mut nommy::IntoBuf::into_buf("/abc".chars())));
+}
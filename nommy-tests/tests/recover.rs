@@ -0,0 +1,51 @@
+use nommy::{
+    eyre, parse,
+    text::{AnyOf1, Tag, WhiteSpace},
+    Parse,
+};
+
+type Digits = AnyOf1<"0123456789">;
+
+#[derive(Debug, Parse)]
+#[nommy(ignore = WhiteSpace)]
+struct List {
+    #[nommy(prefix = Tag<"[">, suffix = Tag<"]">)]
+    #[nommy(seperated_by = Tag<",">, recover, inner_parser = Digits)]
+    values: Vec<eyre::Result<String>>,
+}
+
+/// without a suffix, values are collected until the input ends
+#[derive(Debug, Parse)]
+struct Numbers {
+    #[nommy(seperated_by = Tag<",">, recover, min = 2)]
+    values: Vec<Result<Digits, eyre::Report>>,
+}
+
+fn main() {
+    let list: List = parse("[1, 2x, 3]".chars()).unwrap();
+    assert_eq!(list.values.len(), 3);
+    assert_eq!(list.values[0].as_ref().unwrap(), "1");
+    assert!(list.values[1].is_err());
+    assert_eq!(list.values[2].as_ref().unwrap(), "3");
+
+    // every value failing is still a successful parse
+    let list: List = parse("[a, b]".chars()).unwrap();
+    assert!(list.values.iter().all(Result::is_err));
+
+    let list: List = parse("[]".chars()).unwrap();
+    assert!(list.values.is_empty());
+
+    // the structure around the values still has to parse
+    assert!(parse::<List, _>("[1, 2".chars()).is_err());
+
+    let numbers: Numbers = parse("1,x,3".chars()).unwrap();
+    let values: Vec<_> = numbers
+        .values
+        .into_iter()
+        .map(|value| value.ok().map(String::from))
+        .collect();
+    assert_eq!(values, vec![Some("1".to_owned()), None, Some("3".to_owned())]);
+
+    let err = parse::<Numbers, _>("1".chars()).unwrap_err();
+    assert_eq!(err.to_string(), "failed to parse field `values`");
+}
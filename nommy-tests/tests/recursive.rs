@@ -0,0 +1,40 @@
+use nommy::{
+    parse,
+    text::{Tag, WhiteSpace},
+    Parse,
+};
+
+/// Fields that mention `Self` get no bounds, which would be recursive
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(ignore = WhiteSpace)]
+#[nommy(prefix = Tag<"(">, suffix = Tag<")">)]
+struct Group {
+    groups: Vec<Self>,
+}
+
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(ignore = WhiteSpace)]
+enum List<T> {
+    #[nommy(prefix = Tag<"[">, suffix = Tag<"]">)]
+    Nested(Vec<Self>),
+    #[nommy(prefix = Tag<"!">)]
+    Not(Box<List<T>>),
+    Item(T),
+}
+
+fn main() {
+    let group: Group = parse("(() ( () ))".chars()).unwrap();
+    let empty = || Group { groups: vec![] };
+    let nested = Group { groups: vec![empty()] };
+    assert_eq!(group, Group { groups: vec![empty(), nested] });
+
+    let list: List<Tag<"a">> = parse("[a [!a] []]".chars()).unwrap();
+    assert_eq!(
+        list,
+        List::Nested(vec![
+            List::Item(Tag),
+            List::Nested(vec![List::Not(Box::new(List::Item(Tag)))]),
+            List::Nested(vec![]),
+        ])
+    );
+}
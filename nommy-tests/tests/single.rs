@@ -1,4 +1,4 @@
-use nommy::{parse, text::Tag, Parse};
+use nommy::{message::Message, parse, text::Tag, Parse};
 
 #[derive(Debug, Parse, PartialEq)]
 struct Single {
@@ -10,8 +10,23 @@ fn main() {
     assert_eq!(output, Single { only: Tag::<"("> });
 
     let res: Result<Single, _> = parse(".".chars());
+    let err = res.unwrap_err();
+    assert_eq!(format!("{}", err), "failed to parse field `only`");
     assert_eq!(
-        format!("{}", res.unwrap_err()),
-        "failed to parse field `only`"
+        format!("{}", err.root_cause()),
+        "failed to parse tag \"(\", found \".\""
+    );
+
+    // the errors can be checked without relying on their text
+    assert_eq!(
+        err.downcast_ref::<Message>(),
+        Some(&Message::Field { field: "only" })
+    );
+    assert_eq!(
+        err.root_cause().downcast_ref::<Message>(),
+        Some(&Message::Tag {
+            tag: "(",
+            found: ".".to_owned()
+        })
     );
 }
@@ -0,0 +1,76 @@
+use std::marker::PhantomData;
+
+use nommy::{
+    parse,
+    text::{AnyOf1, Tag, WhiteSpace},
+    unparse, IntoBuf, Parse, Unparse,
+};
+
+type Ident = AnyOf1<"abcdefghijklmnopqrstuvwxyz">;
+
+#[derive(Debug, PartialEq, Parse, Unparse)]
+#[nommy(ignore = WhiteSpace)]
+struct Assign<T> {
+    #[nommy(parser = Ident)]
+    name: String,
+    marker: PhantomData<T>,
+    #[nommy(prefix = Tag<"=">, parser = Ident)]
+    value: String,
+    #[nommy(skip_with = name == value)]
+    trivial: bool,
+}
+
+#[derive(Debug, PartialEq, Parse)]
+struct Pair(
+    #[nommy(parser = Ident)] String,
+    #[nommy(skip_with = elem0.len() as u32)] u32,
+    #[nommy(prefix = Tag<":">, parser = Ident)] String,
+);
+
+#[derive(Debug, PartialEq, Parse)]
+enum Marked<T> {
+    Name(#[nommy(parser = Ident)] String, PhantomData<T>),
+    Unit(#[nommy(skip)] u8, Tag<"()">),
+}
+
+/// Generic over its span, which is filled in after parsing
+#[derive(Debug, PartialEq, Parse, Unparse)]
+#[nommy(ignore = WhiteSpace)]
+struct Node<#[nommy(not_parsed)] S> {
+    #[nommy(parser = Ident)]
+    name: String,
+    span: Option<S>,
+    #[nommy(prefix = Tag<":">, parser = Ident)]
+    kind: String,
+}
+
+fn main() {
+    let assign: Assign<u8> = parse("x = x".chars()).unwrap();
+    assert_eq!(
+        assign,
+        Assign {
+            name: "x".to_owned(),
+            marker: PhantomData,
+            value: "x".to_owned(),
+            trivial: true,
+        }
+    );
+    assert_eq!(unparse::to_string(&assign), "x = x");
+
+    let pair: Pair = parse("a:b".chars()).unwrap();
+    assert_eq!(pair, Pair("a".to_owned(), 1, "b".to_owned()));
+
+    let marked: Marked<String> = parse("()".chars()).unwrap();
+    assert_eq!(marked, Marked::Unit(0, Tag));
+    let marked: Marked<String> = parse("a".chars()).unwrap();
+    assert_eq!(marked, Marked::Name("a".to_owned(), PhantomData));
+
+    // `std::ops::Range` doesn't implement `Parse`
+    let node: Node<std::ops::Range<usize>> = parse("a : b".chars()).unwrap();
+    assert_eq!(node.span, None);
+    assert_eq!(unparse::to_string(&node), "a : b");
+
+    // skipped fields don't affect peeking
+    assert!(Pair::peek(&mut "a:b".chars().into_buf()));
+    assert!(!Pair::peek(&mut "a".chars().into_buf()));
+}
@@ -0,0 +1,58 @@
+use nommy::{
+    parse,
+    span::Span,
+    text::{AnyOf1, Tag, WhiteSpace},
+    Buffer, IntoBuf, Parse,
+};
+
+type Ident = AnyOf1<"abcdefghijklmnopqrstuvwxyz">;
+
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(ignore = WhiteSpace, suffix = Tag<";">)]
+struct Let {
+    /// the span can come before the fields it covers
+    #[nommy(span)]
+    span: Span,
+    #[nommy(prefix = Tag<"let">, parser = Ident)]
+    name: String,
+    #[nommy(prefix = Tag<"=">)]
+    value: Expr,
+}
+
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(ignore = WhiteSpace)]
+enum Expr {
+    Call(
+        #[nommy(parser = Ident)] String,
+        #[nommy(prefix = Tag<"(">, suffix = Tag<")">)] Box<Expr>,
+        #[nommy(span)] Span,
+    ),
+    Name(#[nommy(parser = Ident)] String, #[nommy(span)] Span),
+}
+
+fn main() {
+    let source = "let x = f( g ) ;  ";
+    let mut input = source.chars().into_buf();
+    let stmt = Let::parse(&mut input).unwrap();
+    assert_eq!(stmt.span, Span::new(0, 16));
+    let range: std::ops::Range<usize> = stmt.span.into();
+    assert_eq!(&source[range], "let x = f( g ) ;");
+    assert_eq!(
+        stmt.value,
+        Expr::Call(
+            "f".to_owned(),
+            Box::new(Expr::Name("g".to_owned(), Span::new(11, 12))),
+            Span::new(8, 14),
+        )
+    );
+    // the whitespace after the statement is still consumed
+    assert_eq!(input.offset(), source.len());
+
+    // the spans are offsets into the whole input, not just the part being parsed
+    let mut input = "  y".chars().into_buf();
+    input.fast_forward(2);
+    let expr = Expr::parse(&mut input).unwrap();
+    assert_eq!(expr, Expr::Name("y".to_owned(), Span::new(2, 3)));
+
+    assert!(parse::<Let, _>("let x = ;".chars()).is_err());
+}
@@ -1,6 +1,8 @@
+use nommy::derive_test::DeriveTests;
+
 #[test]
 fn tests() {
-    let t = trybuild::TestCases::new();
+    let t = DeriveTests::new();
     t.pass("tests/single.rs");
     t.pass("tests/multiple.rs");
     t.pass("tests/enum.rs");
@@ -9,4 +11,45 @@ fn tests() {
     t.pass("tests/json.rs");
     t.pass("tests/lisp.rs");
     t.pass("tests/xml.rs");
+    t.pass("tests/extension.rs");
+    t.pass("tests/arena.rs");
+    t.pass("tests/compact.rs");
+    t.pass("tests/intern.rs");
+    t.pass("tests/trailing.rs");
+    t.pass("tests/trivia.rs");
+    t.pass("tests/skip.rs");
+    t.pass("tests/flatten.rs");
+    t.pass("tests/with.rs");
+    t.pass("tests/generics.rs");
+    t.pass("tests/recursive.rs");
+    t.pass("tests/option.rs");
+    t.pass("tests/cfg.rs");
+    t.pass("tests/attrs.rs");
+    t.pass("tests/lookahead.rs");
+    t.pass("tests/ignore.rs");
+    t.pass("tests/peek_alloc.rs");
+    t.pass("tests/unparse.rs");
+    t.pass("tests/crate_path.rs");
+    t.pass("tests/transparent.rs");
+    t.pass("tests/map.rs");
+    t.pass("tests/try_into.rs");
+    t.pass("tests/context.rs");
+    t.pass("tests/expected.rs");
+    t.pass("tests/recover.rs");
+    t.pass("tests/peek.rs");
+    t.pass("tests/convert.rs");
+    t.pass("tests/priority.rs");
+    t.pass("tests/longest.rs");
+    t.pass("tests/fallback.rs");
+    t.pass("tests/version.rs");
+    t.pass("tests/checksum.rs");
+    t.pass("tests/not.rs");
+    t.pass("tests/lookahead_field.rs");
+    t.pass("tests/cut.rs");
+    t.pass("tests/raw.rs");
+    t.pass("tests/span_field.rs");
+    t.pass("tests/from_str.rs");
+    t.pass("tests/complete.rs");
+    t.pass("tests/process.rs");
+    t.compile_fail("tests/ui/*.rs");
 }
@@ -0,0 +1,138 @@
+use nommy::{
+    parse,
+    text::{AnyOf1, Tag, WhiteSpace},
+    Buffer, IntoBuf, Parse,
+};
+
+type Word = AnyOf1<"abcdefghijklmnopqrstuvwxyz">;
+
+/// `key = [a, b, c]`, where the list may have a trailing comma
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(ignore = WhiteSpace)]
+#[nommy(parse_type = char)]
+struct Entry {
+    #[nommy(parser = Word, suffix = Tag<"=">)]
+    key: String,
+
+    #[nommy(prefix = Tag<"[">, suffix = Tag<"]">)]
+    #[nommy(inner_parser = Word, seperated_by = Tag<",">, allow_trailing)]
+    values: Vec<String>,
+}
+
+#[derive(Debug, PartialEq, Parse)]
+struct Arg(#[nommy(parser = Word)] String);
+
+/// `f(a, b)`, where the arguments are parsed with their own impl
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(ignore = WhiteSpace)]
+#[nommy(parse_type = char)]
+struct Call {
+    #[nommy(parser = Word)]
+    name: String,
+
+    #[nommy(prefix = Tag<"(">, suffix = Tag<")">)]
+    #[nommy(separator = Tag<",">, trailing)]
+    args: Vec<Arg>,
+}
+
+/// between one and three words, like `a.b.c`
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(parse_type = char)]
+struct Path {
+    #[nommy(min = 1, max = 3, separator = Tag<".">)]
+    segments: Vec<Arg>,
+}
+
+/// `a,b`, without a trailing comma
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(parse_type = char)]
+struct NoTrailing(#[nommy(separator = Tag<",">, trailing = "no")] Vec<Arg>);
+
+/// `a,b`, with or without a trailing comma
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(parse_type = char)]
+struct MaybeTrailing(#[nommy(separator = Tag<",">, trailing = "maybe")] Vec<Arg>);
+
+/// `a,b,`, where every element is followed by a comma
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(parse_type = char)]
+struct YesTrailing(#[nommy(separator = Tag<",">, trailing = "yes")] Vec<Arg>);
+
+/// Peeks and parses `P` from `input`, checking that they read as far as each other,
+/// and returns the elements parsed and the input left over
+fn agree<P: Parse<char> + Into<Vec<Arg>>>(input: &str) -> (usize, String) {
+    let mut buffer = input.chars().into_buf();
+    let mut cursor = buffer.cursor();
+    assert!(P::peek(&mut cursor));
+    let peeked = cursor.position();
+
+    let args: Vec<Arg> = P::parse(&mut buffer).unwrap().into();
+    let rest: String = buffer.collect();
+    assert_eq!(peeked, input.len() - rest.len(), "peek and parse disagree on {:?}", input);
+    (args.len(), rest)
+}
+
+impl From<NoTrailing> for Vec<Arg> {
+    fn from(list: NoTrailing) -> Self {
+        list.0
+    }
+}
+
+impl From<MaybeTrailing> for Vec<Arg> {
+    fn from(list: MaybeTrailing) -> Self {
+        list.0
+    }
+}
+
+impl From<YesTrailing> for Vec<Arg> {
+    fn from(list: YesTrailing) -> Self {
+        list.0
+    }
+}
+
+fn main() {
+    let expected = Entry {
+        key: "key".to_owned(),
+        values: vec!["a".to_owned(), "b".to_owned(), "c".to_owned()],
+    };
+
+    let entry: Entry = parse("key = [a, b, c]".chars()).unwrap();
+    assert_eq!(entry, expected);
+
+    let entry: Entry = parse("key = [a, b, c,]".chars()).unwrap();
+    assert_eq!(entry, expected);
+
+    let entry: Entry = parse("key = [ a ,\n b ,\n c ,\n ]".chars()).unwrap();
+    assert_eq!(entry, expected);
+
+    let entry: Entry = parse("key = []".chars()).unwrap();
+    assert!(entry.values.is_empty());
+
+    // only one trailing separator is allowed
+    assert!(parse::<Entry, _>("key = [a,,]".chars()).is_err());
+
+    let call: Call = parse("f(a, b,)".chars()).unwrap();
+    assert_eq!(call.args, vec![Arg("a".to_owned()), Arg("b".to_owned())]);
+    assert!(parse::<Call, _>("f(a b)".chars()).is_err());
+
+    let path: Path = parse("a.b.c".chars()).unwrap();
+    assert_eq!(path.segments.len(), 3);
+    assert!(parse::<Path, _>("".chars()).is_err());
+
+    // the fourth word is left unparsed
+    let mut input = "a.b.c.d".chars().into_buf();
+    assert!(Path::peek(&mut input.cursor()));
+    assert_eq!(Path::parse(&mut input).unwrap().segments.len(), 3);
+    assert_eq!(input.collect::<String>(), ".d");
+    assert!(!Path::peek(&mut "".chars().into_buf()));
+
+    // the trailing comma is only read if the separator allows it
+    assert_eq!(agree::<NoTrailing>("a,b,"), (2, ",".to_owned()));
+    assert_eq!(agree::<MaybeTrailing>("a,b,"), (2, "".to_owned()));
+    assert_eq!(agree::<YesTrailing>("a,b,"), (2, "".to_owned()));
+
+    // without the trailing comma, the last element doesn't count when one is required
+    assert_eq!(agree::<NoTrailing>("a,b"), (2, "".to_owned()));
+    assert_eq!(agree::<MaybeTrailing>("a,b"), (2, "".to_owned()));
+    assert_eq!(agree::<YesTrailing>("a,b"), (1, "b".to_owned()));
+}
@@ -0,0 +1,41 @@
+use nommy::{
+    parse,
+    text::{AnyOf1, Tag, WhiteSpace},
+    IntoBuf, Parse,
+};
+
+type Letters = AnyOf1<"abcdefghijklmnopqrstuvwxyz">;
+
+/// parsed exactly as `Letters`, converted into the `String`
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(transparent)]
+struct Ident(#[nommy(parser = Letters)] String);
+
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(transparent)]
+struct Keyword {
+    tag: Tag<"let">,
+}
+
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(ignore = WhiteSpace)]
+struct Let {
+    keyword: Keyword,
+    name: Ident,
+}
+
+fn main() {
+    let ident: Ident = parse("foo".chars()).unwrap();
+    assert_eq!(ident, Ident("foo".to_owned()));
+
+    // errors aren't wrapped with the struct's context
+    let err = parse::<Ident, _>("123".chars()).unwrap_err();
+    assert_eq!(err.to_string(), "no characters found");
+    assert_eq!(err.chain().count(), 1);
+
+    assert_eq!(Keyword::MAX_PEEK, Some(3));
+    assert!(Keyword::peek(&mut "let".chars().into_buf()));
+
+    let stmt: Let = parse("let x".chars()).unwrap();
+    assert_eq!(stmt.name, Ident("x".to_owned()));
+}
@@ -0,0 +1,70 @@
+use nommy::{
+    parse,
+    text::{AnyOf1, Tag, WhileNot1, WhiteSpace},
+    trivia::{self, Trivia},
+    Buffer, Parse,
+};
+
+#[derive(Parse)]
+#[nommy(prefix = Tag<"//">)]
+struct LineComment {
+    _text: Option<WhileNot1<"\n">>,
+}
+
+/// Whitespace and line comments, shared by every type in the grammar
+struct Lang;
+impl Trivia<char> for Lang {
+    fn skip(input: &mut impl Buffer<char>) -> bool {
+        trivia::skip::<WhiteSpace, _>(input) || trivia::skip::<LineComment, _>(input)
+    }
+}
+
+type Ident = AnyOf1<"abcdefghijklmnopqrstuvwxyz">;
+
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(ignore_trivia = Lang)]
+enum Stmt {
+    Let(
+        #[nommy(prefix = Tag<"let">, parser = Ident)] String,
+        #[nommy(prefix = Tag<"=">, parser = Ident, suffix = Tag<";">)] String,
+    ),
+    Expr(#[nommy(parser = Ident, suffix = Tag<";">)] String),
+}
+
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(ignore_trivia = Lang)]
+#[nommy(parse_type = char)]
+struct Block {
+    #[nommy(prefix = Tag<"{">, suffix = Tag<"}">)]
+    #[nommy(inner_parser = Stmt)]
+    stmts: Vec<Stmt>,
+}
+
+/// Keeps the comments between its fields
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(ignore_trivia = Lang)]
+struct Assign(
+    #[nommy(parser = Ident)] String,
+    #[nommy(trivia)] Vec<String>,
+    #[nommy(prefix = Tag<"=">, parser = Ident)] String,
+);
+
+fn main() {
+    let input = "{
+        // bind x
+        let x = y; // trailing comment
+        x;
+    }";
+    let block: Block = parse(input.chars()).unwrap();
+    assert_eq!(
+        block.stmts,
+        vec![
+            Stmt::Let("x".to_owned(), "y".to_owned()),
+            Stmt::Expr("x".to_owned()),
+        ]
+    );
+
+    let assign: Assign = parse("x // the name\n = y".chars()).unwrap();
+    assert_eq!(assign.1, vec![" // the name\n ", " "]);
+    assert_eq!(assign.2, "y");
+}
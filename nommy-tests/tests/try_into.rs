@@ -0,0 +1,73 @@
+use std::{convert::TryFrom, fmt};
+
+use nommy::{
+    message::Message,
+    parse,
+    text::{AnyOf1, Tag, WhiteSpace},
+    Parse,
+};
+
+type Digits = AnyOf1<"0123456789">;
+
+/// a port number, which has to fit in a `u16` and can't be zero
+#[derive(Debug, PartialEq)]
+struct Port(u16);
+
+#[derive(Debug)]
+struct InvalidPort(String);
+
+impl fmt::Display for InvalidPort {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} is not a valid port", self.0)
+    }
+}
+
+impl std::error::Error for InvalidPort {}
+
+impl TryFrom<Digits> for Port {
+    type Error = InvalidPort;
+    fn try_from(digits: Digits) -> Result<Self, Self::Error> {
+        let digits = String::from(digits);
+        match digits.parse() {
+            Ok(0) | Err(_) => Err(InvalidPort(digits)),
+            Ok(port) => Ok(Port(port)),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(ignore = WhiteSpace)]
+struct Address {
+    #[nommy(parser = AnyOf1<"abcdefghijklmnopqrstuvwxyz.">)]
+    host: String,
+    #[nommy(prefix = Tag<":">, parser = Digits, try_into)]
+    port: Port,
+}
+
+/// tuple fields are named `elem0`, `elem1`, ...
+#[derive(Debug, PartialEq, Parse)]
+struct Listen(#[nommy(parser = Digits, try_into)] Port);
+
+fn main() {
+    let address: Address = parse("localhost:8080".chars()).unwrap();
+    assert_eq!(address.host, "localhost");
+    assert_eq!(address.port, Port(8080));
+
+    let err = parse::<Address, _>("localhost:99999".chars()).unwrap_err();
+    assert_eq!(err.to_string(), "failed to convert field `port`");
+    assert_eq!(
+        err.downcast_ref::<Message>(),
+        Some(&Message::Convert { field: "port" })
+    );
+    assert_eq!(
+        err.root_cause().to_string(),
+        "\"99999\" is not a valid port"
+    );
+
+    // errors parsing the field are still reported as parse errors
+    let err = parse::<Address, _>("localhost:http".chars()).unwrap_err();
+    assert_eq!(err.to_string(), "failed to parse field `port`");
+
+    let err = parse::<Listen, _>("0".chars()).unwrap_err();
+    assert_eq!(err.to_string(), "failed to convert field `elem0`");
+}
@@ -0,0 +1,13 @@
+use nommy::Parse;
+
+fn double(s: String) -> String {
+    s.repeat(2)
+}
+
+#[derive(Parse)]
+struct Word {
+    #[nommy(parser = nommy::text::AnyOf1<"ab">, convert = "into", map = "double")]
+    word: String,
+}
+
+fn main() {}
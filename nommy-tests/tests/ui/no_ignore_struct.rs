@@ -0,0 +1,8 @@
+use nommy::Parse;
+
+// `no_ignore` belongs on variants and fields, not on the type
+#[derive(Parse)]
+#[nommy(ignore = nommy::text::WhiteSpace, no_ignore)]
+struct Pair(char, char);
+
+fn main() {}
@@ -0,0 +1,5 @@
+#[derive(Default, nommy::Peek)]
+#[nommy(no_peek)]
+struct Sentinel;
+
+fn main() {}
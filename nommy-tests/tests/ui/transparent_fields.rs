@@ -0,0 +1,7 @@
+use nommy::{text::Tag, Parse};
+
+#[derive(Parse)]
+#[nommy(transparent)]
+struct Pair(Tag<"a">, Tag<"b">);
+
+fn main() {}
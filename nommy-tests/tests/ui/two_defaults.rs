@@ -0,0 +1,11 @@
+use nommy::Parse;
+
+#[derive(Parse)]
+enum Value {
+    #[nommy(default)]
+    Empty,
+    #[nommy(default)]
+    Missing,
+}
+
+fn main() {}
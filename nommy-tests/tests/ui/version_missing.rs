@@ -0,0 +1,11 @@
+use nommy::Parse;
+
+#[derive(Parse)]
+struct Message {
+    #[nommy(parser = nommy::bytes::OneOf<b"\x01\x02">)]
+    version: u8,
+    #[nommy(since = 2, parser = nommy::bytes::OneOf<b"0123456789">)]
+    priority: u8,
+}
+
+fn main() {}
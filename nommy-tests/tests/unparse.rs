@@ -0,0 +1,54 @@
+use nommy::{
+    parse,
+    text::{AnyOf1, Tag, WhiteSpace},
+    unparse, Parse, Unparse,
+};
+
+type Letters = AnyOf1<"abcdefghijklmnopqrstuvwxyz">;
+type Digits = AnyOf1<"0123456789">;
+
+#[derive(Debug, PartialEq, Parse, Unparse)]
+#[nommy(ignore = WhiteSpace)]
+#[nommy(parse_type = char)]
+enum Value {
+    #[nommy(prefix = Tag<"null">)]
+    Null,
+    Number(#[nommy(parser = Digits)] String),
+    List(
+        #[nommy(prefix = Tag<"[">, suffix = Tag<"]">)]
+        #[nommy(inner_parser = Value, seperated_by = Tag<",">, trailing = "maybe")]
+        Vec<Value>,
+    ),
+    Object {
+        #[nommy(prefix = Tag<"{">, suffix = Tag<"}">)]
+        #[nommy(inner_parser = Field, seperated_by = Tag<",">, trailing = "yes")]
+        fields: Vec<Field>,
+    },
+}
+
+#[derive(Debug, PartialEq, Parse, Unparse)]
+#[nommy(ignore = WhiteSpace)]
+#[nommy(parse_type = char)]
+struct Field {
+    #[nommy(parser = Letters)]
+    key: String,
+    #[nommy(prefix = Tag<":">)]
+    value: Value,
+}
+
+/// Generic over both the input type and the inner value
+#[derive(Debug, PartialEq, Parse, Unparse)]
+#[nommy(prefix = Tag<"(">, suffix = Tag<")">)]
+struct Parens<T>(T);
+
+fn main() {
+    let input = "[1,null,{a:2,b:[],},]";
+    let value: Value = parse(input.chars()).unwrap();
+
+    let output = unparse::to_string(&value);
+    assert_eq!(output, "[ 1 , null , { a : 2 , b : [ ] , } ]");
+    assert_eq!(parse::<Value, _>(output.chars()).unwrap(), value);
+
+    let parens: Parens<Parens<Letters>> = parse("((abc))".chars()).unwrap();
+    assert_eq!(unparse::to_string(&parens), "((abc))");
+}
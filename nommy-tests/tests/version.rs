@@ -0,0 +1,73 @@
+use nommy::{
+    bytes::{AnyOf1, OneOf, Tag},
+    parse, unparse, IntoBuf, Parse, Unparse,
+};
+
+type Bytes = AnyOf1<b"abcdefghijklmnopqrstuvwxyz">;
+
+/// a message whose layout has changed over three versions of the protocol
+#[derive(Debug, PartialEq, Parse, Unparse)]
+#[nommy(prefix = Tag<b"MSG">)]
+struct Message {
+    #[nommy(version, parser = OneOf<b"\x01\x02\x03">)]
+    version: u8,
+    #[nommy(parser = Bytes)]
+    body: Vec<u8>,
+    /// added in version 2
+    #[nommy(since = 2, prefix = Tag<b"#">, parser = OneOf<b"0123456789">)]
+    priority: u8,
+    /// removed in version 3
+    #[nommy(until = 3, prefix = Tag<b"@">, parser = Bytes)]
+    sender: Vec<u8>,
+}
+
+/// versions can be given to tuple fields and variants too
+#[derive(Debug, PartialEq, Parse)]
+enum Packet {
+    Data(
+        #[nommy(prefix = Tag<b"D">, version, parser = OneOf<b"\x01\x02">)] u8,
+        #[nommy(since = 2, parser = OneOf<b"0123456789">)] u8,
+    ),
+}
+
+fn main() {
+    let v1: Message = parse(b"MSG\x01hi@bob".iter().copied()).unwrap();
+    assert_eq!(v1.body, b"hi");
+    assert_eq!(v1.priority, 0);
+    assert_eq!(v1.sender, b"bob");
+
+    let v2: Message = parse(b"MSG\x02hi#7@bob".iter().copied()).unwrap();
+    assert_eq!(v2.priority, b'7');
+    assert_eq!(v2.sender, b"bob");
+
+    let v3: Message = parse(b"MSG\x03hi#7".iter().copied()).unwrap();
+    assert_eq!(v3.priority, b'7');
+    assert!(v3.sender.is_empty());
+
+    // a field from another version is left in the input
+    let mut input = b"MSG\x01hi@bob#7".iter().copied().into_buf();
+    assert_eq!(Message::parse(&mut input).unwrap(), v1);
+    assert_eq!(input.next(), Some(b'#'));
+
+    let err = parse::<Message, _>(b"MSG\x02hi@bob".iter().copied()).unwrap_err();
+    assert_eq!(err.to_string(), "failed to parse prefix for field `priority`");
+
+    // the version can't be known while peeking, so the peek stops before the versioned fields
+    assert!(Message::peek(&mut b"MSG\x02hi".iter().copied().into_buf()));
+
+    // only the fields in the message's version are written
+    assert_eq!(unparse::to_vec(&v3), b"MSG\x03hi#7");
+    for message in [v1, v2, v3] {
+        let bytes = unparse::to_vec(&message);
+        assert_eq!(parse::<Message, _>(bytes.into_iter()).unwrap(), message);
+    }
+
+    assert_eq!(
+        parse::<Packet, _>(b"D\x01".iter().copied()).unwrap(),
+        Packet::Data(1, 0)
+    );
+    assert_eq!(
+        parse::<Packet, _>(b"D\x025".iter().copied()).unwrap(),
+        Packet::Data(2, b'5')
+    );
+}
@@ -0,0 +1,54 @@
+use nommy::{
+    eyre, parse,
+    text::{Tag, WhiteSpace},
+    Buffer, IntoBuf, Parse,
+};
+
+/// Parses a run of digits into a number
+fn number(input: &mut impl Buffer<char>) -> eyre::Result<u32> {
+    let mut value = 0_u32;
+    let mut digits = 0;
+    while let Some(digit) = input.peek_ahead(digits).and_then(|c| c.to_digit(10)) {
+        value = value
+            .checked_mul(10)
+            .and_then(|v| v.checked_add(digit))
+            .ok_or_else(|| eyre::eyre!("number is too large"))?;
+        digits += 1;
+    }
+    if digits == 0 {
+        return Err(eyre::eyre!("expected a number"));
+    }
+    input.fast_forward(digits);
+    Ok(value)
+}
+
+fn peek_number(input: &mut impl Buffer<char>) -> bool {
+    let mut digits = 0;
+    while matches!(input.peek_ahead(digits), Some('0'..='9')) {
+        digits += 1;
+    }
+    input.fast_forward(digits);
+    digits > 0
+}
+
+#[derive(Debug, PartialEq, Parse)]
+#[nommy(parse_type = char, ignore = WhiteSpace)]
+struct Range {
+    #[nommy(with = "number", peek_with = "peek_number")]
+    start: u32,
+    #[nommy(prefix = Tag<"..">, with = "number")]
+    end: u32,
+}
+
+fn main() {
+    let range: Range = parse("1 .. 20".chars()).unwrap();
+    assert_eq!(range, Range { start: 1, end: 20 });
+
+    let err = parse::<Range, _>("1..99999999999".chars()).unwrap_err();
+    assert_eq!(err.to_string(), "failed to parse field `end`");
+    assert_eq!(err.root_cause().to_string(), "number is too large");
+
+    assert!(Range::peek(&mut "1..2".chars().into_buf()));
+    assert!(!Range::peek(&mut "1..".chars().into_buf()));
+    assert!(!Range::peek(&mut "..2".chars().into_buf()));
+}
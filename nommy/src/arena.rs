@@ -0,0 +1,206 @@
+//! Parsing into a [`Bump`] arena, for large trees that would otherwise need many small allocations.
+//! Requires the `arena` feature
+//!
+//! Types deriving [`Parse`] with `#[nommy(arena)]` implement [`ParseIn`] instead. Fields whose type
+//! mentions the type's lifetime (eg `&'a Expr<'a>` or `Vec<'a, Stmt<'a>>`) are parsed into the arena,
+//! and all other fields are parsed with [`Parse`] as usual.
+//!
+//! ```
+//! use nommy::{arena::{self, Bump}, text::{AnyOf1, Tag}, Parse};
+//!
+//! #[derive(Debug, Parse)]
+//! #[nommy(arena)]
+//! #[nommy(parse_type = char)]
+//! enum Expr<'a> {
+//!     Neg(#[nommy(prefix = Tag<"-">)] &'a Expr<'a>),
+//!     Num(#[nommy(parser = AnyOf1<"0123456789">)] String),
+//! }
+//!
+//! let bump = Bump::new();
+//! let expr: Expr = arena::parse_in("--12".chars(), &bump).unwrap();
+//! match expr {
+//!     Expr::Neg(Expr::Neg(Expr::Num(n))) => assert_eq!(n, "12"),
+//!     _ => panic!("unexpected expression {:?}", expr),
+//! }
+//! ```
+
+pub use bumpalo::{collections::Vec, Bump};
+
+use crate::{eyre, Buffer, IntoBuf, Parse};
+
+/// `ParseIn` is like [`Parse`], but allocates the parsed value into an arena
+pub trait ParseIn<'arena, T>: Sized {
+    /// Parse the input buffer, allocating into `arena`
+    ///
+    /// # Errors
+    /// If the input could not be parsed
+    fn parse_in(input: &mut impl Buffer<T>, arena: &'arena Bump) -> eyre::Result<Self>;
+
+    /// Peek reports whether the next section of the input buffer can be parsed
+    /// into this type. By default, this parses the input into the arena and discards the result
+    fn peek_in(input: &mut impl Buffer<T>, arena: &'arena Bump) -> bool {
+        Self::parse_in(input, arena).is_ok()
+    }
+}
+
+/// Parses `P` and moves it into the arena
+impl<'arena, P: ParseIn<'arena, T>, T> ParseIn<'arena, T> for &'arena P {
+    fn parse_in(input: &mut impl Buffer<T>, arena: &'arena Bump) -> eyre::Result<Self> {
+        let p = P::parse_in(input, arena)?;
+        Ok(arena.alloc(p))
+    }
+
+    fn peek_in(input: &mut impl Buffer<T>, arena: &'arena Bump) -> bool {
+        P::peek_in(input, arena)
+    }
+}
+
+/// Repeatedly attempts to parse `P` into an arena allocated vec, Result is all successful attempts
+impl<'arena, P: ParseIn<'arena, T>, T> ParseIn<'arena, T> for Vec<'arena, P> {
+    fn parse_in(input: &mut impl Buffer<T>, arena: &'arena Bump) -> eyre::Result<Self> {
+        let mut output = Vec::new_in(arena);
+        loop {
+            let mut cursor = input.cursor();
+            match P::parse_in(&mut cursor, arena) {
+                Ok(p) => output.push(p),
+                Err(_) => break,
+            }
+            let pos = cursor.position();
+            crate::vec::check_element(output.len(), pos == 0)?;
+            input.fast_forward(pos);
+        }
+
+        Ok(output)
+    }
+
+    fn peek_in(input: &mut impl Buffer<T>, arena: &'arena Bump) -> bool {
+        let mut count = 0;
+        loop {
+            let mut cursor = input.cursor();
+            if !P::peek_in(&mut cursor, arena) {
+                break;
+            }
+            let pos = cursor.position();
+            count += 1;
+            if crate::vec::check_element(count, pos == 0).is_err() {
+                return false;
+            }
+            input.fast_forward(pos);
+        }
+        true
+    }
+}
+
+/// Result is `None` if parsing `P` fails, otherwise, result is `Some(p)`
+impl<'arena, P: ParseIn<'arena, T>, T> ParseIn<'arena, T> for Option<P> {
+    fn parse_in(input: &mut impl Buffer<T>, arena: &'arena Bump) -> eyre::Result<Self> {
+        let mut cursor = input.cursor();
+        match P::parse_in(&mut cursor, arena) {
+            Ok(p) => {
+                let pos = cursor.position();
+                input.fast_forward(pos);
+                Ok(Some(p))
+            }
+            Err(_) => Ok(None),
+        }
+    }
+
+    fn peek_in(input: &mut impl Buffer<T>, arena: &'arena Bump) -> bool {
+        let mut cursor = input.cursor();
+        if P::peek_in(&mut cursor, arena) {
+            let pos = cursor.position();
+            input.fast_forward(pos);
+        }
+        true
+    }
+}
+
+/// Parses the given iterator into `P`, allocating into `arena`. See [`parse`](crate::parse)
+///
+/// # Errors
+/// If `P` failed to parse the input
+pub fn parse_in<'arena, P, I>(iter: I, arena: &'arena Bump) -> eyre::Result<P>
+where
+    P: ParseIn<'arena, <I::Iter as Iterator>::Item>,
+    I: IntoBuf,
+    <I::Iter as Iterator>::Item: Clone,
+{
+    let mut buffer = iter.into_buf();
+    P::parse_in(&mut buffer, arena)
+}
+
+/// Arena allocated, owned copy of the text parsed by `P`
+///
+/// ```
+/// use nommy::{arena::{self, Bump, Text}, text::AnyOf1};
+///
+/// let bump = Bump::new();
+/// let word: Text<AnyOf1<"abc">> = arena::parse_in("abcd".chars(), &bump).unwrap();
+/// assert_eq!(word.as_str(), "abc");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Text<'arena, P> {
+    text: &'arena str,
+    _p: std::marker::PhantomData<P>,
+}
+
+impl<'arena, P> Text<'arena, P> {
+    /// The parsed text
+    #[must_use]
+    pub const fn as_str(&self) -> &'arena str {
+        self.text
+    }
+}
+
+impl<'arena, P: Parse<char> + Into<String>> ParseIn<'arena, char> for Text<'arena, P> {
+    fn parse_in(input: &mut impl Buffer<char>, arena: &'arena Bump) -> eyre::Result<Self> {
+        let text: String = P::parse(input)?.into();
+        Ok(Self {
+            text: arena.alloc_str(&text),
+            _p: std::marker::PhantomData,
+        })
+    }
+
+    fn peek_in(input: &mut impl Buffer<char>, _: &'arena Bump) -> bool {
+        P::peek(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::{AnyOf1, Tag};
+
+    /// `(` Tree* `)` with the children allocated in the arena
+    struct Tree<'a>(Vec<'a, &'a Tree<'a>>);
+
+    impl<'a> ParseIn<'a, char> for Tree<'a> {
+        fn parse_in(input: &mut impl Buffer<char>, arena: &'a Bump) -> eyre::Result<Self> {
+            Tag::<"(">::parse(input)?;
+            let children = ParseIn::parse_in(input, arena)?;
+            Tag::<")">::parse(input)?;
+            Ok(Self(children))
+        }
+    }
+
+    fn count(tree: &Tree) -> usize {
+        1 + tree.0.iter().map(|t| count(t)).sum::<usize>()
+    }
+
+    #[test]
+    fn tree() {
+        let bump = Bump::new();
+        let tree: Tree = parse_in("(()(()()))".chars(), &bump).unwrap();
+        assert_eq!(tree.0.len(), 2);
+        assert_eq!(count(&tree), 5);
+    }
+
+    #[test]
+    fn option_text() {
+        let bump = Bump::new();
+        let res: Option<Text<AnyOf1<"abc">>> = parse_in("def".chars(), &bump).unwrap();
+        assert!(res.is_none());
+        let res: Option<Text<AnyOf1<"abc">>> = parse_in("cab".chars(), &bump).unwrap();
+        assert_eq!(res.unwrap().as_str(), "cab");
+    }
+}
@@ -37,6 +37,106 @@ pub trait Buffer<T>: Iterator<Item = T> + Sized {
 
     /// Peek ahead by i spaces
     fn peek_ahead(&mut self, i: usize) -> Option<T>;
+
+    /// How many values have been consumed since the start of the input.
+    /// A cursor's offset includes the values it has read past its parent
+    ///
+    /// ```
+    /// use nommy::{Buffer, IntoBuf};
+    /// let mut input = "foobar".chars().into_buf();
+    /// input.fast_forward(2);
+    /// assert_eq!(input.offset(), 2);
+    ///
+    /// let mut cursor = input.cursor();
+    /// cursor.next();
+    /// assert_eq!(cursor.offset(), 3);
+    /// assert_eq!(input.offset(), 2);
+    /// ```
+    fn offset(&self) -> usize;
+
+    /// Returns the unread input as a slice, if the buffer holds it contiguously,
+    /// so that peeks can read it in place. Returns None for buffers that read from an iterator
+    fn probe(&self) -> Option<&[T]> {
+        None
+    }
+
+    /// Reads up to `n` values into a new collection, such as a [`String`] or [`Vec`].
+    /// If the buffer holds its input contiguously, the values are copied from the slice,
+    /// so the collection only has to allocate once
+    ///
+    /// ```
+    /// use nommy::{Buffer, IntoBuf, SliceBuf};
+    /// let mut buffer = "foobar".chars().into_buf();
+    /// let foo: String = buffer.take_collect(3);
+    /// assert_eq!(foo, "foo");
+    ///
+    /// let mut buffer = SliceBuf::new(b"foobar");
+    /// let bar: Vec<u8> = buffer.cursor().take_collect(10);
+    /// assert_eq!(bar, b"foobar");
+    /// ```
+    fn take_collect<C>(&mut self, n: usize) -> C
+    where
+        T: Clone,
+        C: Default + Extend<T>,
+    {
+        let mut output = C::default();
+        if let Some(data) = self.probe() {
+            let n = n.min(data.len());
+            output.extend(data[..n].iter().cloned());
+            self.fast_forward(n);
+        } else {
+            output.extend(self.take(n));
+        }
+        output
+    }
+
+    /// Reads values into a new collection for as long as `pred` returns true,
+    /// leaving the first value that doesn't match unread
+    ///
+    /// ```
+    /// use nommy::{Buffer, IntoBuf};
+    /// let mut buffer = "123abc".chars().into_buf();
+    /// let digits: String = buffer.take_while_collect(char::is_ascii_digit);
+    /// assert_eq!(digits, "123");
+    /// assert_eq!(buffer.next(), Some('a'));
+    /// ```
+    fn take_while_collect<C>(&mut self, mut pred: impl FnMut(&T) -> bool) -> C
+    where
+        T: Clone,
+        C: Default + Extend<T>,
+    {
+        let mut output = C::default();
+        if let Some(data) = self.probe() {
+            let n = data.iter().position(|x| !pred(x)).unwrap_or(data.len());
+            output.extend(data[..n].iter().cloned());
+            self.fast_forward(n);
+        } else {
+            while let Some(x) = self.peek_ahead(0).filter(&mut pred) {
+                output.extend(Some(x));
+                self.fast_forward(1);
+            }
+        }
+        output
+    }
+
+    /// Returns a view over the next `n` values. The view ends after them, even if the input continues,
+    /// so a parser can be handed exactly one region of the input, such as a length delimited frame.
+    /// Reading from the view reads from this buffer
+    ///
+    /// ```
+    /// use nommy::{Buffer, IntoBuf, Parse, text::AnyOf1};
+    /// let mut input = "abcdef".chars().into_buf();
+    ///
+    /// let mut region = input.limit(3);
+    /// let word: String = AnyOf1::<"abcdef">::parse(&mut region).unwrap().into();
+    /// assert_eq!(word, "abc");
+    /// assert_eq!(region.remaining(), 0);
+    ///
+    /// assert_eq!(input.next(), Some('d'));
+    /// ```
+    fn limit(&mut self, n: usize) -> Limit<T, Self> {
+        Limit::new(self, n)
+    }
 }
 
 /// `IntoBuf` is the equivalent of [`IntoIterator`] for a basic implementation of [`Buffer`]
@@ -61,15 +161,21 @@ impl<I: IntoIterator> IntoBuf for I {
 pub struct Buf<I: Iterator> {
     iter: I,
     buffer: VecDeque<I::Item>,
+    offset: usize,
 }
 
 impl<I: Iterator> Iterator for Buf<I> {
     type Item = I::Item;
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.buffer
+        let output = self
+            .buffer
             .pop_front()
-            .map_or_else(|| self.iter.next(), Some)
+            .map_or_else(|| self.iter.next(), Some);
+        if output.is_some() {
+            self.offset += 1;
+        }
+        output
     }
 }
 
@@ -79,8 +185,14 @@ impl<I: Iterator> Buf<I> {
         Self {
             iter: iter.into_iter(),
             buffer: VecDeque::new(),
+            offset: 0,
         }
     }
+
+    /// The iterator that the buffer reads from
+    pub const fn get_ref(&self) -> &I {
+        &self.iter
+    }
 }
 
 impl<I: Iterator> Buffer<I::Item> for Buf<I>
@@ -96,14 +208,17 @@ where
         let len = self.buffer.len();
         if len <= n {
             self.buffer.clear();
+            self.offset += len;
             for _ in 0..(n - len) {
                 if self.iter.next().is_none() {
                     break;
                 }
+                self.offset += 1;
             }
         } else {
             self.buffer.rotate_left(n);
             self.buffer.truncate(len - n);
+            self.offset += n;
         }
     }
 
@@ -121,6 +236,193 @@ where
             Some(output)
         }
     }
+
+    fn offset(&self) -> usize {
+        self.offset
+    }
+}
+
+/// `VecBuf` is a [`Buffer`] that owns all of its input up front.
+/// Reads are plain indexing into the [`Vec`], and the position can be rewound to any earlier point
+///
+/// ```
+/// use nommy::{Buffer, Parse, VecBuf, text::Tag};
+/// let mut input = VecBuf::new("foobar".chars().collect());
+///
+/// Tag::<"foo">::parse(&mut input).unwrap();
+/// let pos = input.position();
+/// Tag::<"bar">::parse(&mut input).unwrap();
+/// assert_eq!(input.next(), None);
+///
+/// input.rewind(pos);
+/// assert_eq!(input.next(), Some('b'));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct VecBuf<T> {
+    data: Vec<T>,
+    index: usize,
+}
+
+impl<T> VecBuf<T> {
+    /// Create a new `VecBuf` that reads from the start of `data`
+    #[must_use]
+    pub const fn new(data: Vec<T>) -> Self {
+        Self { data, index: 0 }
+    }
+
+    /// Returns how many values have been read
+    #[must_use]
+    pub const fn position(&self) -> usize {
+        self.index
+    }
+
+    /// Moves the buffer back (or forward) to the given position,
+    /// as previously returned by [`position`](Self::position)
+    pub fn rewind(&mut self, position: usize) {
+        self.index = position.min(self.data.len());
+    }
+
+    /// Returns the values that have not been read yet
+    #[must_use]
+    pub fn remaining(&self) -> &[T] {
+        &self.data[self.index..]
+    }
+
+    /// Returns the inner data, including any values already read
+    #[must_use]
+    pub fn into_inner(self) -> Vec<T> {
+        self.data
+    }
+}
+
+impl<T> From<Vec<T>> for VecBuf<T> {
+    fn from(data: Vec<T>) -> Self {
+        Self::new(data)
+    }
+}
+
+impl<T: Clone> Iterator for VecBuf<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let output = self.data.get(self.index).cloned();
+        if output.is_some() {
+            self.index += 1;
+        }
+        output
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.data.len() - self.index;
+        (len, Some(len))
+    }
+}
+
+impl<T: Clone> Buffer<T> for VecBuf<T> {
+    type CursorBase = Self;
+    fn cursor(&mut self) -> Cursor<T, Self::CursorBase> {
+        Cursor::new(self)
+    }
+
+    fn fast_forward(&mut self, n: usize) {
+        self.rewind(self.index + n);
+    }
+
+    fn peek_ahead(&mut self, i: usize) -> Option<T> {
+        self.data.get(self.index + i).cloned()
+    }
+
+    fn offset(&self) -> usize {
+        self.index
+    }
+
+    fn probe(&self) -> Option<&[T]> {
+        Some(self.remaining())
+    }
+}
+
+/// `SliceBuf` is a [`Buffer`] that borrows all of its input, like a [`VecBuf`] that doesn't own its data.
+/// Nothing is copied or allocated when reading or peeking, so it suits input that's already in memory
+///
+/// ```
+/// use nommy::{Buffer, Parse, SliceBuf, text::Tag};
+/// let input: Vec<char> = "foobar".chars().collect();
+/// let mut buffer = SliceBuf::new(&input);
+///
+/// assert!(Tag::<"foo">::peek(&mut buffer.cursor()));
+/// Tag::<"foo">::parse(&mut buffer).unwrap();
+/// assert_eq!(buffer.remaining(), &['b', 'a', 'r']);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SliceBuf<'a, T> {
+    data: &'a [T],
+    index: usize,
+}
+
+impl<'a, T> SliceBuf<'a, T> {
+    /// Create a new `SliceBuf` that reads from the start of `data`
+    #[must_use]
+    pub const fn new(data: &'a [T]) -> Self {
+        Self { data, index: 0 }
+    }
+
+    /// Returns how many values have been read
+    #[must_use]
+    pub const fn position(&self) -> usize {
+        self.index
+    }
+
+    /// Moves the buffer back (or forward) to the given position,
+    /// as previously returned by [`position`](Self::position)
+    pub fn rewind(&mut self, position: usize) {
+        self.index = position.min(self.data.len());
+    }
+
+    /// Returns the values that have not been read yet
+    #[must_use]
+    pub fn remaining(&self) -> &'a [T] {
+        &self.data[self.index..]
+    }
+}
+
+impl<T: Clone> Iterator for SliceBuf<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let output = self.data.get(self.index).cloned();
+        if output.is_some() {
+            self.index += 1;
+        }
+        output
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.data.len() - self.index;
+        (len, Some(len))
+    }
+}
+
+impl<T: Clone> Buffer<T> for SliceBuf<'_, T> {
+    type CursorBase = Self;
+    fn cursor(&mut self) -> Cursor<T, Self::CursorBase> {
+        Cursor::new(self)
+    }
+
+    fn fast_forward(&mut self, n: usize) {
+        self.rewind(self.index + n);
+    }
+
+    fn peek_ahead(&mut self, i: usize) -> Option<T> {
+        self.data.get(self.index + i).cloned()
+    }
+
+    fn offset(&self) -> usize {
+        self.index
+    }
+
+    fn probe(&self) -> Option<&[T]> {
+        Some(self.remaining())
+    }
 }
 
 /// `Cursor` is a [`Buffer`] that non-destructively reads from it's parent's buffer using [`Buffer::peek_ahead`]
@@ -133,7 +435,7 @@ pub struct Cursor<'a, T, B: Buffer<T>> {
 }
 
 impl<'a, T, B: Buffer<T>> Cursor<'a, T, B> {
-    fn new(buf: &'a mut B) -> Self {
+    pub(crate) fn new(buf: &'a mut B) -> Self {
         Self {
             buf,
             base: 0,
@@ -165,6 +467,24 @@ impl<'a, T, B: Buffer<T>> Cursor<'a, T, B> {
         self.index
     }
 
+    /// Returns how many tokens the cursor has advanced past it's parent, the same as [`Cursor::position`].
+    /// Useful for peeks that need a match of some length, or for picking the longest of several matches
+    ///
+    /// ```
+    /// use nommy::{Buffer, IntoBuf, Parse, text::AnyOf1};
+    /// let mut input = "ab1".chars().into_buf();
+    ///
+    /// // an identifier needs at least 2 letters
+    /// let mut cursor = input.cursor();
+    /// let ident = AnyOf1::<"abcdefghijklmnopqrstuvwxyz">::peek(&mut cursor) && cursor.consumed() >= 2;
+    /// assert!(ident);
+    /// assert_eq!(cursor.consumed(), 2);
+    /// ```
+    #[must_use]
+    pub fn consumed(&self) -> usize {
+        self.index
+    }
+
     /// Resets the cursor back to where it started
     /// Returns true for use in pattern matching/short circuit shenanigans
     pub fn reset_internal(&mut self) -> bool {
@@ -182,6 +502,10 @@ impl<'a, T, B: Buffer<T>> Buffer<T> for Cursor<'a, T, B> {
         self.buf.peek_ahead(self.base + self.index + i)
     }
 
+    fn offset(&self) -> usize {
+        self.buf.offset() + self.base + self.index
+    }
+
     type CursorBase = B;
     fn cursor(&mut self) -> Cursor<T, Self::CursorBase> {
         Cursor {
@@ -191,6 +515,11 @@ impl<'a, T, B: Buffer<T>> Buffer<T> for Cursor<'a, T, B> {
             _t: PhantomData,
         }
     }
+
+    fn probe(&self) -> Option<&[T]> {
+        let data = self.buf.probe()?;
+        Some(data.get(self.base + self.index..).unwrap_or_default())
+    }
 }
 
 impl<'a, T, B: Buffer<T>> Iterator for Cursor<'a, T, B> {
@@ -203,6 +532,87 @@ impl<'a, T, B: Buffer<T>> Iterator for Cursor<'a, T, B> {
     }
 }
 
+/// `Limit` is a [`Buffer`] that ends after a number of values from it's parent, even if the parent continues.
+/// Made by [`Buffer::limit`]
+///
+/// ```
+/// use nommy::{Buffer, IntoBuf};
+/// let mut input = "abcdef".chars().into_buf();
+/// let mut limit = input.limit(2);
+/// assert_eq!(limit.peek_ahead(1), Some('b'));
+/// assert_eq!(limit.peek_ahead(2), None);
+/// limit.fast_forward(1);
+/// assert_eq!(limit.remaining(), 1);
+/// assert_eq!(limit.next(), Some('b'));
+/// assert_eq!(limit.next(), None);
+/// assert_eq!(input.next(), Some('c'));
+/// ```
+pub struct Limit<'a, T, B: Buffer<T>> {
+    buf: &'a mut B,
+    remaining: usize,
+    _t: PhantomData<T>,
+}
+
+impl<'a, T, B: Buffer<T>> Limit<'a, T, B> {
+    /// Reads at most `limit` values from `buf`
+    pub fn new(buf: &'a mut B, limit: usize) -> Self {
+        Self {
+            buf,
+            remaining: limit,
+            _t: PhantomData,
+        }
+    }
+
+    /// How many values can still be read
+    #[must_use]
+    pub const fn remaining(&self) -> usize {
+        self.remaining
+    }
+}
+
+impl<T, B: Buffer<T>> Iterator for Limit<'_, T, B> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.remaining == 0 {
+            return None;
+        }
+        let output = self.buf.next()?;
+        self.remaining -= 1;
+        Some(output)
+    }
+}
+
+impl<T, B: Buffer<T>> Buffer<T> for Limit<'_, T, B> {
+    type CursorBase = Self;
+    fn cursor(&mut self) -> Cursor<T, Self::CursorBase> {
+        Cursor::new(self)
+    }
+
+    fn fast_forward(&mut self, n: usize) {
+        let n = n.min(self.remaining);
+        self.buf.fast_forward(n);
+        self.remaining -= n;
+    }
+
+    fn peek_ahead(&mut self, i: usize) -> Option<T> {
+        if i < self.remaining {
+            self.buf.peek_ahead(i)
+        } else {
+            None
+        }
+    }
+
+    fn offset(&self) -> usize {
+        self.buf.offset()
+    }
+
+    fn probe(&self) -> Option<&[T]> {
+        let data = self.buf.probe()?;
+        Some(&data[..data.len().min(self.remaining)])
+    }
+}
+
 // use std::io::Read;
 
 // /// Implements [`Buffer`] for types that implement [`Read`]
@@ -329,7 +739,7 @@ impl<'a, T, B: Buffer<T>> Iterator for Cursor<'a, T, B> {
 mod tests {
     use crate::{IntoBuf};
 
-    use super::Buffer;
+    use super::{Buffer, SliceBuf};
 
     #[test]
     fn cursor_isolation() {
@@ -372,6 +782,89 @@ mod tests {
         assert!(buffer.buffer.is_empty());
     }
 
+    #[test]
+    fn probe() {
+        let data = [0, 1, 2, 3];
+        let mut buffer = SliceBuf::new(&data);
+        buffer.fast_forward(1);
+
+        let mut cursor = buffer.cursor();
+        assert_eq!(cursor.next(), Some(1));
+        assert_eq!(cursor.probe(), Some(&data[2..]));
+        cursor.fast_forward(5);
+        assert_eq!(cursor.probe(), Some(&[][..]));
+
+        assert_eq!(buffer.probe(), Some(&data[1..]));
+        assert_eq!((0..).into_buf().cursor().probe(), None);
+    }
+
+    #[test]
+    fn take_collect() {
+        let data: Vec<char> = "abc123".chars().collect();
+        let mut slice = SliceBuf::new(&data);
+        let mut iter = data.iter().copied().into_buf();
+
+        let letters: String = slice.cursor().take_while_collect(char::is_ascii_alphabetic);
+        assert_eq!(letters, "abc");
+        let letters: String = iter.cursor().take_while_collect(char::is_ascii_alphabetic);
+        assert_eq!(letters, "abc");
+
+        slice.fast_forward(4);
+        iter.fast_forward(4);
+        let rest: Vec<char> = slice.take_collect(5);
+        assert_eq!(rest, ['2', '3']);
+        let rest: Vec<char> = iter.take_collect(5);
+        assert_eq!(rest, ['2', '3']);
+        assert_eq!(slice.next(), None);
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn limit() {
+        let data = [0, 1, 2, 3, 4];
+        let mut buffer = SliceBuf::new(&data);
+        {
+            let mut limit = buffer.limit(3);
+            assert_eq!(limit.probe(), Some(&data[..3]));
+
+            // a cursor over the limit stops at the same place
+            let mut cursor = limit.cursor();
+            assert_eq!(cursor.take_collect::<Vec<_>>(5), [0, 1, 2]);
+            assert_eq!(cursor.next(), None);
+
+            // and so does a limit nested inside it
+            let mut inner = limit.limit(5);
+            assert_eq!(inner.next(), Some(0));
+            assert_eq!(inner.remaining(), 4);
+            assert_eq!(limit.remaining(), 2);
+            limit.fast_forward(5);
+            assert_eq!(limit.next(), None);
+        }
+        assert_eq!(buffer.next(), Some(3));
+    }
+
+    #[test]
+    fn offset() {
+        let mut buffer = (0..10).into_buf();
+        assert_eq!(buffer.peek_ahead(3), Some(3));
+        assert_eq!(buffer.offset(), 0);
+        assert_eq!(buffer.next(), Some(0));
+        buffer.fast_forward(2);
+        assert_eq!(buffer.offset(), 3);
+        buffer.fast_forward(20);
+        assert_eq!(buffer.offset(), 10);
+
+        let data = [0, 1, 2, 3];
+        let mut buffer = SliceBuf::new(&data);
+        buffer.fast_forward(1);
+        let mut cursor = buffer.cursor();
+        cursor.fast_forward(1);
+        let mut inner = cursor.cursor();
+        assert_eq!(inner.next(), Some(2));
+        assert_eq!(inner.offset(), 3);
+        assert_eq!(buffer.limit(2).offset(), 1);
+    }
+
     // #[test]
     // fn bufread() {
     //     let read: &[u8] = b"Hello World!";
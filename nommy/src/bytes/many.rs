@@ -1,4 +1,10 @@
-use crate::{eyre, Buffer, Parse};
+use crate::{
+    eyre,
+    message::Message,
+    process::Process,
+    unparse::{Emit, Unparse},
+    Buffer, Parse,
+};
 
 use super::OneOf;
 
@@ -19,20 +25,26 @@ impl<const BYTES: &'static [u8]> From<AnyOf1<BYTES>> for Vec<u8> {
     }
 }
 
+impl<const BYTES: &'static [u8]> Unparse<u8> for AnyOf1<BYTES> {
+    fn unparse(&self, output: &mut impl Emit<u8>) {
+        self.0.unparse(output);
+    }
+}
+
+impl<const BYTES: &'static [u8]> Process for AnyOf1<BYTES> {
+    type Output = Vec<u8>;
+
+    fn process(self) -> Self::Output {
+        self.0
+    }
+}
+
 impl<const BYTES: &'static [u8]> Parse<u8> for AnyOf1<BYTES> {
     fn parse(input: &mut impl Buffer<u8>) -> eyre::Result<Self> {
-        let mut output = Vec::new();
-
-        while OneOf::<BYTES>::peek(&mut input.cursor()) {
-            output.push(
-                OneOf::<BYTES>::parse(input)
-                    .expect("peek succeeded but parse failed")
-                    .into(),
-            );
-        }
+        let output: Vec<u8> = input.take_while_collect(|b| BYTES.contains(b));
 
         if output.is_empty() {
-            Err(eyre::eyre!("no characters found"))
+            Err(eyre::Report::new(Message::NoCharacters))
         } else {
             Ok(Self(output))
         }
@@ -1,4 +1,10 @@
-use crate::{eyre, Buffer, Parse};
+use crate::{
+    eyre,
+    message::Message,
+    process::Process,
+    unparse::{Emit, Unparse},
+    Buffer, Parse,
+};
 
 #[derive(Debug, Copy, Clone, PartialEq)]
 /// `OneOf` is a generic type that implements [`Parse`] to match one character within the given string
@@ -17,21 +23,39 @@ impl<const BYTES: &'static [u8]> From<OneOf<BYTES>> for u8 {
     }
 }
 
+impl<const BYTES: &'static [u8]> Unparse<u8> for OneOf<BYTES> {
+    fn unparse(&self, output: &mut impl Emit<u8>) {
+        output.emit(self.0);
+    }
+}
+
+impl<const BYTES: &'static [u8]> Process for OneOf<BYTES> {
+    type Output = u8;
+
+    fn process(self) -> Self::Output {
+        self.0
+    }
+}
+
 impl<const BYTES: &'static [u8]> Parse<u8> for OneOf<BYTES> {
+    const MAX_PEEK: Option<usize> = Some(1);
+
     fn parse(input: &mut impl Buffer<u8>) -> eyre::Result<Self> {
         match input.next() {
             Some(c) => {
                 if BYTES.contains(&c) {
                     Ok(Self(c))
                 } else {
-                    Err(eyre::eyre!(
-                        "error parsing one of {:?}, found {:?}",
-                        BYTES,
-                        c
-                    ))
+                    Err(eyre::Report::new(Message::BytesOneOf {
+                        bytes: BYTES,
+                        found: Some(c),
+                    }))
                 }
             }
-            None => Err(eyre::eyre!("error parsing one of {:?}, reached EOF", BYTES)),
+            None => Err(eyre::Report::new(Message::BytesOneOf {
+                bytes: BYTES,
+                found: None,
+            })),
         }
     }
 
@@ -1,6 +1,12 @@
-use crate::{eyre, Buffer, Parse};
+use crate::{
+    eyre,
+    message::Message,
+    process::Process,
+    unparse::{Emit, Unparse},
+    Buffer, Parse,
+};
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
 /// `Tag` is a generic type that implements [`Parse`] to match the given string exactly
 ///
 /// ```
@@ -11,17 +17,45 @@ use crate::{eyre, Buffer, Parse};
 /// ```
 pub struct Tag<const TAG: &'static [u8]>;
 
+impl<const TAG: &'static [u8]> Unparse<u8> for Tag<TAG> {
+    fn unparse(&self, output: &mut impl Emit<u8>) {
+        TAG.unparse(output);
+    }
+}
+
+impl<const TAG: &'static [u8]> Process for Tag<TAG> {
+    type Output = ();
+
+    fn process(self) -> Self::Output {}
+}
+
 impl<const TAG: &'static [u8]> Parse<u8> for Tag<TAG> {
+    const MAX_PEEK: Option<usize> = Some(TAG.len());
+
     fn parse(input: &mut impl Buffer<u8>) -> eyre::Result<Self> {
-        let b: Vec<u8> = input.take(TAG.len()).collect();
-        if TAG == b {
-            Ok(Self)
-        } else {
-            Err(eyre::eyre!("failed to parse tag {:?}, found {:?}", TAG, b))
+        // stops before the first byte that doesn't match, so the input is left where it diverged
+        let mut found = Vec::new();
+        for &b in TAG {
+            match input.peek_ahead(0) {
+                Some(next) if next == b => {
+                    found.push(next);
+                    input.fast_forward(1);
+                }
+                next => {
+                    found.extend(next);
+                    return Err(eyre::Report::new(Message::BytesTag { tag: TAG, found }));
+                }
+            }
         }
+        Ok(Self)
     }
 
     fn peek(input: &mut impl Buffer<u8>) -> bool {
+        if let Some(data) = input.probe() {
+            let found = data.starts_with(TAG);
+            input.fast_forward(TAG.len());
+            return found;
+        }
         TAG.iter().cloned().eq(input.take(TAG.len()))
     }
 }
@@ -59,4 +93,15 @@ mod tests {
             "failed to parse tag [41], found [49]"
         );
     }
+
+    #[test]
+    fn test_parse_stops_at_mismatch() {
+        let mut input = "fxo".bytes().into_buf();
+        let res = Tag::<b"foo">::parse(&mut input);
+        assert_eq!(
+            format!("{}", res.unwrap_err()),
+            "failed to parse tag [102, 111, 111], found [102, 120]"
+        );
+        assert_eq!(input.next(), Some(b'x'));
+    }
 }
@@ -0,0 +1,133 @@
+//! Reading input from a channel, so that one thread can feed a parser running on another
+//!
+//! [`Recv`] is an iterator over a [`Receiver`], which can be turned into a [`Buffer`](crate::Buffer)
+//! with [`IntoBuf`](crate::IntoBuf). The input ends when every sender has been dropped.
+//! Receivers from other channel libraries that implement [`IntoIterator`], such as crossbeam's,
+//! can be turned into a buffer directly, but won't support timeouts.
+//!
+//! ```
+//! use std::{sync::mpsc, thread};
+//! use nommy::{channel::Recv, IntoBuf, Parse, text::Tag};
+//!
+//! let (sender, receiver) = mpsc::channel();
+//! let lexer = thread::spawn(move || {
+//!     for c in "foobar".chars() {
+//!         sender.send(c).unwrap();
+//!     }
+//! });
+//!
+//! let mut input = Recv::new(receiver).into_buf();
+//! Tag::<"foo">::parse(&mut input).unwrap();
+//! Tag::<"bar">::parse(&mut input).unwrap();
+//! assert_eq!(input.next(), None);
+//! lexer.join().unwrap();
+//! ```
+
+use std::{
+    sync::mpsc::{Receiver, RecvTimeoutError},
+    time::Duration,
+};
+
+/// `Recv` is an [`Iterator`] that blocks on a [`Receiver`] for each value.
+///
+/// It ends when the channel is closed, or if a timeout is set and no value arrives in time.
+/// A timed out `Recv` can still be read from again, and [`Recv::timed_out`] tells the two ends apart
+///
+/// ```
+/// use std::{sync::mpsc, time::Duration};
+/// use nommy::{channel::Recv, IntoBuf, Parse, text::Tag};
+///
+/// let (sender, receiver) = mpsc::channel();
+/// sender.send('f').unwrap();
+///
+/// let mut input = Recv::with_timeout(receiver, Duration::from_millis(10)).into_buf();
+/// assert!(Tag::<"foo">::parse(&mut input).is_err());
+/// assert!(input.get_ref().timed_out());
+/// ```
+#[derive(Debug)]
+pub struct Recv<T> {
+    receiver: Receiver<T>,
+    timeout: Option<Duration>,
+    timed_out: bool,
+}
+
+impl<T> Recv<T> {
+    /// Create a new `Recv` that waits for as long as it takes for each value
+    #[must_use]
+    pub const fn new(receiver: Receiver<T>) -> Self {
+        Self {
+            receiver,
+            timeout: None,
+            timed_out: false,
+        }
+    }
+
+    /// Create a new `Recv` that ends the input if a value takes longer than `timeout` to arrive
+    #[must_use]
+    pub const fn with_timeout(receiver: Receiver<T>, timeout: Duration) -> Self {
+        Self {
+            receiver,
+            timeout: Some(timeout),
+            timed_out: false,
+        }
+    }
+
+    /// Whether the last read ended because of the timeout, rather than the channel closing
+    #[must_use]
+    pub const fn timed_out(&self) -> bool {
+        self.timed_out
+    }
+
+    /// Returns the receiver
+    #[must_use]
+    pub fn into_inner(self) -> Receiver<T> {
+        self.receiver
+    }
+}
+
+impl<T> Iterator for Recv<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.timed_out = false;
+        match self.timeout {
+            None => self.receiver.recv().ok(),
+            Some(timeout) => match self.receiver.recv_timeout(timeout) {
+                Ok(value) => Some(value),
+                Err(RecvTimeoutError::Timeout) => {
+                    self.timed_out = true;
+                    None
+                }
+                Err(RecvTimeoutError::Disconnected) => None,
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::mpsc, thread, time::Duration};
+
+    use super::Recv;
+    use crate::{text::Tag, IntoBuf, Parse};
+
+    #[test]
+    fn timeout_then_resume() {
+        let (sender, receiver) = mpsc::channel();
+        let mut input = Recv::with_timeout(receiver, Duration::from_millis(10)).into_buf();
+
+        assert_eq!(input.next(), None);
+        assert!(input.get_ref().timed_out());
+
+        let writer = thread::spawn(move || {
+            for c in "foo".chars() {
+                sender.send(c).unwrap();
+            }
+        });
+        writer.join().unwrap();
+
+        Tag::<"foo">::parse(&mut input).unwrap();
+        assert_eq!(input.next(), None);
+        assert!(!input.get_ref().timed_out());
+    }
+}
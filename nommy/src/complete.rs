@@ -0,0 +1,151 @@
+//! Completion hints, for autocomplete engines and REPLs.
+//!
+//! [`completions`] parses a prefix of the input and collects what the parsers expected
+//! to find when they reached the end of it. [`Tag`](crate::text::Tag)s and [`ITag`](crate::text::ITag)s
+//! report themselves, and other parsers can report what they expect using [`expect`].
+//! Rules registered in a [`Grammar`](crate::extension::Grammar) are included when the
+//! completions are collected with [`Grammar::completions`](crate::extension::Grammar::completions).
+//!
+//! ```
+//! use nommy::{complete::{self, Expected}, text::{Tag, WhiteSpace}, Parse};
+//!
+//! #[derive(Parse)]
+//! #[nommy(ignore = WhiteSpace)]
+//! enum Stmt {
+//!     Let(Tag<"let">, Tag<"=">),
+//!     Loop(Tag<"loop">),
+//!     Print(Tag<"print">),
+//! }
+//!
+//! let next = complete::completions::<Stmt>("l");
+//! assert_eq!(next, vec![
+//!     Expected::Tag { tag: "let", typed: 1 },
+//!     Expected::Tag { tag: "loop", typed: 1 },
+//! ]);
+//! assert_eq!(next[0].remaining(), "et");
+//!
+//! let next = complete::completions::<Stmt>("let ");
+//! assert_eq!(next, vec![Expected::Tag { tag: "=", typed: 0 }]);
+//! ```
+
+use std::cell::RefCell;
+
+use crate::{IntoBuf, Parse};
+
+/// Something a parser expected to find at the end of the input
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Expected {
+    /// The text of a tag. The first `typed` characters of it are already at the end of the input
+    Tag {
+        /// The full text of the tag
+        tag: &'static str,
+        /// How many characters of the tag were already typed
+        typed: usize,
+    },
+    /// A description of what was expected, such as `"identifier"`
+    Named(&'static str),
+}
+
+impl Expected {
+    /// The text that would complete this tag, or the description if this isn't a tag
+    #[must_use]
+    pub fn remaining(&self) -> &'static str {
+        match *self {
+            Self::Tag { tag, typed } => match tag.char_indices().nth(typed) {
+                Some((i, _)) => &tag[i..],
+                None => "",
+            },
+            Self::Named(name) => name,
+        }
+    }
+}
+
+thread_local! {
+    static ACTIVE: RefCell<Vec<Vec<Expected>>> = RefCell::new(Vec::new());
+}
+
+/// Stops collecting completions when dropped, even if parsing panicked
+struct CollectGuard;
+
+impl Drop for CollectGuard {
+    fn drop(&mut self) {
+        ACTIVE.with(|active| active.borrow_mut().pop());
+    }
+}
+
+/// Reports that a parser expected `expected` but reached the end of the input.
+/// Does nothing unless completions are being collected
+pub fn expect(expected: Expected) {
+    ACTIVE.with(|active| {
+        if let Some(collected) = active.borrow_mut().last_mut() {
+            if !collected.contains(&expected) {
+                collected.push(expected);
+            }
+        }
+    });
+}
+
+/// Returns what could legally come next after `prefix` when parsing `P`,
+/// in the order the parsers tried them.
+///
+/// Returns nothing if `prefix` has an error before its end
+#[must_use]
+pub fn completions<P: Parse<char>>(prefix: &str) -> Vec<Expected> {
+    ACTIVE.with(|active| active.borrow_mut().push(Vec::new()));
+    let _guard = CollectGuard;
+
+    let _ = P::parse(&mut prefix.chars().into_buf());
+    ACTIVE.with(|active| {
+        active
+            .borrow_mut()
+            .last_mut()
+            .map(std::mem::take)
+            .unwrap_or_default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        punctuated::Punctuated,
+        text::{ITag, Tag},
+    };
+
+    type List = Punctuated<Tag<"ab">, Tag<",">>;
+
+    #[test]
+    fn partial_tag() {
+        assert_eq!(
+            completions::<List>("ab,a"),
+            vec![Expected::Tag {
+                tag: "ab",
+                typed: 1
+            }]
+        );
+        assert_eq!(
+            completions::<List>("ab"),
+            vec![Expected::Tag { tag: ",", typed: 0 }]
+        );
+        assert_eq!(completions::<List>("ab,x"), vec![]);
+    }
+
+    #[test]
+    fn ignore_case() {
+        let next = completions::<ITag<"select">>("SEL");
+        assert_eq!(
+            next,
+            vec![Expected::Tag {
+                tag: "select",
+                typed: 3
+            }]
+        );
+        assert_eq!(next[0].remaining(), "ect");
+    }
+
+    #[test]
+    fn not_collecting() {
+        expect(Expected::Named("ignored"));
+        assert_eq!(completions::<Option<Tag<"a">>>("b"), vec![]);
+    }
+}
@@ -0,0 +1,50 @@
+//! Compile tests for grammars that use `#[derive(Parse)]`, using [trybuild](https://docs.rs/trybuild).
+//! Requires the `derive_test` feature
+//!
+//! Each test is a file with a `main` function. Files given to [`DeriveTests::pass`] must compile and run
+//! without panicking, which checks that a combination of attributes parses what it should.
+//! Files given to [`DeriveTests::compile_fail`] must fail to compile, with the errors written in a `.stderr` file
+//! next to them, which checks the error and where it points to. Running with `TRYBUILD=overwrite` writes the
+//! `.stderr` files from the current errors.
+//!
+//! ```no_run
+//! use nommy::derive_test::DeriveTests;
+//!
+//! let tests = DeriveTests::new();
+//! tests.pass("tests/grammar.rs");
+//! tests.compile_fail("tests/ui/*.rs");
+//! ```
+//!
+//! The tests are compiled and run when the `DeriveTests` is dropped.
+
+/// A set of compile tests, run when it's dropped
+pub struct DeriveTests {
+    cases: trybuild::TestCases,
+}
+
+impl DeriveTests {
+    /// Create an empty set of tests
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            cases: trybuild::TestCases::new(),
+        }
+    }
+
+    /// Checks that the files matching `path` compile and run successfully. `path` can be a glob
+    pub fn pass(&self, path: impl AsRef<std::path::Path>) {
+        self.cases.pass(path);
+    }
+
+    /// Checks that the files matching `path` fail to compile with the errors in their `.stderr` files.
+    /// `path` can be a glob
+    pub fn compile_fail(&self, path: impl AsRef<std::path::Path>) {
+        self.cases.compile_fail(path);
+    }
+}
+
+impl Default for DeriveTests {
+    fn default() -> Self {
+        Self::new()
+    }
+}
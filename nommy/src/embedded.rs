@@ -0,0 +1,125 @@
+//! Hands part of the input to a parser that isn't written with nommy, such as a regex engine,
+//! an SQL parser or the parser of a template language.
+//!
+//! The foreign parser is given a [`Cursor`](crate::Cursor) over the rest of the input. It can read as far ahead as it needs,
+//! or take the input as a slice with [`Buffer::probe`] if the buffer holds it, then returns its value and how many
+//! values it consumed. Only those values are consumed from the input. Inside a [`Buffer::limit`],
+//! the foreign parser can't read past the end of the region.
+//!
+//! [`Embedded`] does this for a [`Foreign`] type, and the `raw = "path::to::fn"` field attribute does it for a function.
+//!
+//! ```
+//! use nommy::{embedded::{Embedded, Foreign}, eyre, parse, text::Tag, Buffer, Parse};
+//!
+//! /// The contents of a `{{ ... }}` template block, up to the closing braces
+//! #[derive(Debug)]
+//! struct Template(String);
+//!
+//! impl Foreign<char> for Template {
+//!     fn parse_foreign(input: &mut impl Buffer<char>) -> eyre::Result<(Self, usize)> {
+//!         let mut text = String::new();
+//!         while let Some(c) = input.next() {
+//!             if c == '}' && input.peek_ahead(0) == Some('}') {
+//!                 let consumed = text.chars().count();
+//!                 return Ok((Template(text), consumed));
+//!             }
+//!             text.push(c);
+//!         }
+//!         Err(eyre::eyre!("unclosed template block"))
+//!     }
+//! }
+//!
+//! #[derive(Debug, Parse)]
+//! struct Block {
+//!     #[nommy(prefix = Tag<"{{">, suffix = Tag<"}}">)]
+//!     body: Embedded<Template>,
+//! }
+//!
+//! let block: Block = parse("{{ user.name }}".chars()).unwrap();
+//! assert_eq!((block.body.0).0, " user.name ");
+//! assert!(parse::<Block, _>("{{ user.name".chars()).is_err());
+//! ```
+
+use crate::{
+    eyre,
+    unparse::{Emit, Unparse},
+    Buffer, Parse,
+};
+
+/// A type parsed by code that doesn't use nommy
+pub trait Foreign<T>: Sized {
+    /// Parses the value from the start of `input`, returning it and how many values it consumed.
+    /// Reading from `input` doesn't consume anything, so the parser can read past the end of its value
+    ///
+    /// # Errors
+    /// If the input doesn't start with the value
+    fn parse_foreign(input: &mut impl Buffer<T>) -> eyre::Result<(Self, usize)>;
+}
+
+/// Parses `F` with its [`Foreign`] impl, then consumes as much of the input as it says it used
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Embedded<F>(pub F);
+
+impl<T, F: Foreign<T>> Parse<T> for Embedded<F> {
+    fn parse(input: &mut impl Buffer<T>) -> eyre::Result<Self> {
+        let (value, consumed) = F::parse_foreign(&mut input.cursor())?;
+        input.fast_forward(consumed);
+        Ok(Self(value))
+    }
+
+    fn peek(input: &mut impl Buffer<T>) -> bool {
+        match F::parse_foreign(&mut input.cursor()) {
+            Ok((_, consumed)) => {
+                input.fast_forward(consumed);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+}
+
+impl<T, F: Unparse<T>> Unparse<T> for Embedded<F> {
+    fn unparse(&self, output: &mut impl Emit<T>) {
+        self.0.unparse(output);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Embedded, Foreign};
+    use crate::{eyre, Buffer, IntoBuf, Parse};
+
+    /// A run of digits, read from a slice when the buffer has one
+    #[derive(Debug, PartialEq)]
+    struct Digits(Vec<u8>);
+
+    impl Foreign<u8> for Digits {
+        fn parse_foreign(input: &mut impl Buffer<u8>) -> eyre::Result<(Self, usize)> {
+            let digits: Vec<u8> = input.take_while_collect(u8::is_ascii_digit);
+            match digits.len() {
+                0 => Err(eyre::eyre!("expected a digit")),
+                n => Ok((Self(digits), n)),
+            }
+        }
+    }
+
+    #[test]
+    fn consumes_what_it_used() {
+        let mut input = b"123abc".iter().copied().into_buf();
+        let digits = Embedded::<Digits>::parse(&mut input).unwrap();
+        assert_eq!(digits.0, Digits(b"123".to_vec()));
+        assert_eq!(input.next(), Some(b'a'));
+
+        assert!(!Embedded::<Digits>::peek(&mut input.cursor()));
+        assert!(Embedded::<Digits>::parse(&mut input).is_err());
+        assert_eq!(input.next(), Some(b'b'));
+    }
+
+    #[test]
+    fn stays_within_a_limit() {
+        let mut input = crate::SliceBuf::new(b"12345");
+        let digits = Embedded::<Digits>::parse(&mut input.limit(2)).unwrap();
+        assert_eq!(digits.0, Digits(b"12".to_vec()));
+        assert_eq!(input.next(), Some(b'3'));
+    }
+}
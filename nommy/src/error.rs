@@ -0,0 +1,277 @@
+//! Error types returned by derived parsers
+
+use std::{cell::Cell, fmt};
+
+use crate::{eyre, message::Message};
+
+/// The error from attempting to parse a single enum variant
+#[derive(Debug)]
+pub struct VariantError {
+    /// Name of the variant that was attempted
+    pub variant: &'static str,
+    /// How much input was read before the variant failed to parse
+    pub position: usize,
+    /// The reason the variant failed to parse
+    pub error: eyre::Report,
+}
+
+/// Error returned by derived enum parsers when none of the variants could be parsed.
+///
+/// The error of every variant attempted is kept, in the order they were attempted.
+/// [`source`](std::error::Error::source) is the error of the variant that read the most input
+/// before failing, preferring the earliest variant when there's a tie.
+/// If one variant read more input than all of the others, the message names it,
+/// since that's almost always the variant the input was meant to be.
+///
+/// ```
+/// use nommy::{error::VariantErrors, parse, text::Tag, Parse};
+///
+/// #[derive(Debug, Parse)]
+/// enum Bracket {
+///     Round(Tag<"(">, Tag<")">),
+///     Square(Tag<"[">, Tag<"]">),
+/// }
+///
+/// let err = parse::<Bracket, _>("[)".chars()).unwrap_err();
+/// let errors = err.downcast_ref::<VariantErrors>().unwrap();
+/// assert_eq!(errors.errors().len(), 2);
+/// assert_eq!(errors.furthest().unwrap().variant, "Square");
+/// assert_eq!(err.to_string(), "failed to parse Bracket as variant `Square`");
+/// assert_eq!(
+///     format!("{}", err.root_cause()),
+///     "failed to parse tag \"]\", found \")\""
+/// );
+/// ```
+///
+/// If a variant failed after a [`Cut`], the other variants aren't attempted,
+/// and the error of that variant is the one reported
+#[derive(Debug)]
+pub struct VariantErrors {
+    name: &'static str,
+    errors: Vec<VariantError>,
+    committed: bool,
+}
+
+impl VariantErrors {
+    /// Create a new error for the enum `name`, with no variant errors recorded
+    #[must_use]
+    pub const fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            errors: Vec::new(),
+            committed: false,
+        }
+    }
+
+    /// Reports the error of the last variant attempted, which failed after a [`Cut`],
+    /// however far the other variants got
+    pub fn commit(&mut self) {
+        self.committed = true;
+    }
+
+    /// Records the error for a variant that failed to parse
+    pub fn push(&mut self, variant: &'static str, position: usize, error: eyre::Report) {
+        self.errors.push(VariantError {
+            variant,
+            position,
+            error,
+        });
+    }
+
+    /// The errors of each variant attempted, in order
+    #[must_use]
+    pub fn errors(&self) -> &[VariantError] {
+        &self.errors
+    }
+
+    /// The error of the variant that read the most input before failing
+    #[must_use]
+    pub fn furthest(&self) -> Option<&VariantError> {
+        if self.committed {
+            return self.errors.last();
+        }
+        // max_by_key returns the last maximum, so iterate in reverse to prefer the earliest variant
+        self.errors.iter().rev().max_by_key(|e| e.position)
+    }
+}
+
+impl fmt::Display for VariantErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // only name the furthest variant if it's clearly ahead of the others
+        match self.furthest() {
+            Some(furthest)
+                if self.committed
+                    || self
+                        .errors
+                        .iter()
+                        .filter(|e| e.position >= furthest.position)
+                        .count()
+                        == 1 =>
+            {
+                let message = Message::Variant {
+                    name: self.name,
+                    variant: furthest.variant,
+                };
+                fmt::Display::fmt(&message, f)
+            }
+            _ => fmt::Display::fmt(&Message::NoVariants { name: self.name }, f),
+        }
+    }
+}
+
+impl std::error::Error for VariantErrors {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.furthest().map(|e| {
+            let error: &(dyn std::error::Error + 'static) = e.error.as_ref();
+            error
+        })
+    }
+}
+
+thread_local! {
+    static CUT: Cell<bool> = Cell::new(false);
+}
+
+/// Made by a derived parser with a `cut`. If the parser fails after it passes the cut,
+/// the enum it's a variant of doesn't attempt the rest of its variants.
+/// The cut applies to the nearest enum that's parsing, and isn't passed on to the enums outside of it
+///
+/// ```
+/// use nommy::{error::VariantErrors, parse, text::{AnyOf1, Tag}, Parse};
+///
+/// #[derive(Debug, Parse)]
+/// enum Item {
+///     #[nommy(prefix = Tag<"fn ">, cut)]
+///     Function(#[nommy(parser = AnyOf1<"abc">)] String),
+///     Word(#[nommy(parser = AnyOf1<"abcdefghijklmnopqrstuvwxyz ">)] String),
+/// }
+///
+/// // without the cut, this would be parsed as a `Word`
+/// let err = parse::<Item, _>("fn xyz".chars()).unwrap_err();
+/// assert_eq!(err.to_string(), "failed to parse Item as variant `Function`");
+/// assert_eq!(err.downcast_ref::<VariantErrors>().unwrap().errors().len(), 1);
+/// ```
+#[derive(Debug)]
+pub struct Cut {
+    armed: bool,
+}
+
+impl Cut {
+    /// Creates a cut that commits to nothing until it's armed
+    #[must_use]
+    #[allow(clippy::new_without_default)]
+    pub const fn new() -> Self {
+        Self { armed: false }
+    }
+
+    /// Commits the parser to the variant it's parsing, if it fails before [`Cut::pass`] is called
+    pub fn arm(&mut self) {
+        self.armed = true;
+    }
+
+    /// The parser succeeded, so nothing is committed
+    pub fn pass(mut self) {
+        self.armed = false;
+    }
+
+    /// Forgets any cut left over from an earlier parser
+    pub fn reset() {
+        CUT.with(|cut| cut.set(false));
+    }
+
+    /// Whether the variant that just failed failed after a cut, clearing the cut so that
+    /// the enums outside this one can still attempt their other variants
+    #[must_use]
+    pub fn committed() -> bool {
+        CUT.with(|cut| cut.replace(false))
+    }
+}
+
+impl Drop for Cut {
+    fn drop(&mut self) {
+        if self.armed {
+            CUT.with(|cut| cut.set(true));
+        }
+    }
+}
+
+/// The error of the `FromStr` impl made by `#[nommy(from_str)]`. It implements [`std::error::Error`],
+/// which [`eyre::Report`] doesn't, so it can be returned wherever a standard error is expected.
+/// It displays the whole chain of errors
+///
+/// ```
+/// use nommy::{text::{AnyOf1, Tag}, Parse};
+///
+/// #[derive(Debug, Parse)]
+/// #[nommy(from_str, prefix = Tag<"#">)]
+/// struct Hex(#[nommy(parser = AnyOf1<"0123456789abcdef">)] String);
+///
+/// let hex: Hex = "#ff0".parse().unwrap();
+/// assert_eq!(hex.0, "ff0");
+///
+/// let err = "ff0".parse::<Hex>().unwrap_err();
+/// assert_eq!(err.to_string(), "failed to parse prefix for struct `Hex`: failed to parse tag \"#\", found \"f\"");
+/// assert!("#ff0 ".parse::<Hex>().is_err());
+/// ```
+#[derive(Debug)]
+pub struct ParseError(eyre::Report);
+
+impl ParseError {
+    /// Returns the underlying report
+    pub fn into_report(self) -> eyre::Report {
+        self.0
+    }
+}
+
+impl From<eyre::Report> for ParseError {
+    fn from(report: eyre::Report) -> Self {
+        Self(report)
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#}", self.0)
+    }
+}
+
+impl std::error::Error for ParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        self.0.chain().nth(1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn furthest_prefers_earliest() {
+        let mut errors = VariantErrors::new("Foo");
+        assert!(std::error::Error::source(&errors).is_none());
+
+        errors.push("A", 1, eyre::eyre!("a"));
+        errors.push("B", 2, eyre::eyre!("b"));
+        errors.push("C", 2, eyre::eyre!("c"));
+
+        assert_eq!(errors.furthest().unwrap().variant, "B");
+
+        let report = eyre::Report::new(errors);
+        let chain: Vec<String> = report.chain().map(ToString::to_string).collect();
+        assert_eq!(chain, vec!["no variants of Foo could be parsed", "b"]);
+    }
+
+    #[test]
+    fn names_furthest() {
+        let mut errors = VariantErrors::new("Foo");
+        errors.push("A", 1, eyre::eyre!("a"));
+        errors.push("B", 3, eyre::eyre!("b"));
+        errors.push("C", 2, eyre::eyre!("c"));
+        assert_eq!(errors.to_string(), "failed to parse Foo as variant `B`");
+
+        errors.push("D", 0, eyre::eyre!("d"));
+        errors.commit();
+        assert_eq!(errors.furthest().unwrap().variant, "D");
+        assert_eq!(errors.to_string(), "failed to parse Foo as variant `D`");
+    }
+}
@@ -0,0 +1,400 @@
+//! Grammar rules registered at runtime, for plugin extensible grammars.
+//!
+//! Rules are registered into a [`Grammar`], which is then used to parse the input.
+//! Enums deriving [`Parse`] with `#[nommy(extensible)]` will try the rules registered
+//! for that enum in the active grammar after all of the statically defined variants fail.
+//!
+//! ```
+//! use nommy::{extension::{DynBuffer, Grammar}, text::Tag, Parse};
+//!
+//! #[derive(Debug, PartialEq, Parse)]
+//! #[nommy(extensible)]
+//! #[nommy(parse_type = char)]
+//! enum Keyword {
+//!     Let(Tag<"let">),
+//!     Fn(Tag<"fn">),
+//! }
+//!
+//! let mut grammar = Grammar::new();
+//! let res: Result<Keyword, _> = grammar.parse("func".chars());
+//! assert!(res.is_err());
+//!
+//! // a plugin adds `func` as an alias for `fn`
+//! grammar.register(|input: &mut DynBuffer<char>| {
+//!     Tag::<"func">::parse(input)?;
+//!     Ok(Keyword::Fn(Tag))
+//! });
+//!
+//! let keyword: Keyword = grammar.parse("func".chars()).unwrap();
+//! assert_eq!(keyword, Keyword::Fn(Tag));
+//! ```
+
+use std::{
+    any::{type_name, Any, TypeId},
+    cell::RefCell,
+    collections::HashMap,
+    fmt,
+    sync::Arc,
+};
+
+use crate::{eyre, Buffer, Context, Cursor, IntoBuf, Parse};
+
+/// Object safe version of [`Buffer`]. Implemented for all [`Buffer`]s
+pub trait DynamicBuffer<T> {
+    /// See [`Iterator::next`]
+    fn dyn_next(&mut self) -> Option<T>;
+    /// See [`Buffer::fast_forward`]
+    fn dyn_fast_forward(&mut self, n: usize);
+    /// See [`Buffer::peek_ahead`]
+    fn dyn_peek_ahead(&mut self, i: usize) -> Option<T>;
+    /// See [`Buffer::offset`]
+    fn dyn_offset(&self) -> usize;
+}
+
+impl<T, B: Buffer<T>> DynamicBuffer<T> for B {
+    fn dyn_next(&mut self) -> Option<T> {
+        self.next()
+    }
+    fn dyn_fast_forward(&mut self, n: usize) {
+        self.fast_forward(n);
+    }
+    fn dyn_peek_ahead(&mut self, i: usize) -> Option<T> {
+        self.peek_ahead(i)
+    }
+    fn dyn_offset(&self) -> usize {
+        self.offset()
+    }
+}
+
+/// Type erased [`Buffer`] that is passed into registered rules
+pub struct DynBuffer<'a, T> {
+    inner: &'a mut dyn DynamicBuffer<T>,
+}
+
+impl<'a, T> DynBuffer<'a, T> {
+    /// Wrap the given buffer
+    pub fn new(inner: &'a mut dyn DynamicBuffer<T>) -> Self {
+        Self { inner }
+    }
+}
+
+impl<T> Iterator for DynBuffer<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.inner.dyn_next()
+    }
+}
+
+impl<T> Buffer<T> for DynBuffer<'_, T> {
+    type CursorBase = Self;
+    fn cursor(&mut self) -> Cursor<T, Self::CursorBase> {
+        Cursor::new(self)
+    }
+
+    fn fast_forward(&mut self, n: usize) {
+        self.inner.dyn_fast_forward(n);
+    }
+
+    fn peek_ahead(&mut self, i: usize) -> Option<T> {
+        self.inner.dyn_peek_ahead(i)
+    }
+
+    fn offset(&self) -> usize {
+        self.inner.dyn_offset()
+    }
+}
+
+type Rule<T, E> = Arc<dyn Fn(&mut DynBuffer<T>) -> eyre::Result<E> + Send + Sync>;
+
+/// A set of rules for parsing extensible types, registered at runtime.
+///
+/// Derived parsers only see the rules of the grammar that is currently active,
+/// which is set by [`Grammar::parse`] and [`Grammar::scope`]. Different grammars
+/// can be used for different inputs in the same process, and a grammar can be
+/// shared between threads.
+#[derive(Clone, Default)]
+pub struct Grammar {
+    rules: HashMap<(TypeId, TypeId), Arc<dyn Any + Send + Sync>>,
+}
+
+impl fmt::Debug for Grammar {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Grammar")
+            .field("rule_sets", &self.rules.len())
+            .finish()
+    }
+}
+
+thread_local! {
+    static ACTIVE: RefCell<Vec<Grammar>> = RefCell::new(Vec::new());
+}
+
+/// Removes the active grammar when dropped, even if parsing panicked
+struct ActiveGuard;
+
+impl Drop for ActiveGuard {
+    fn drop(&mut self) {
+        ACTIVE.with(|active| active.borrow_mut().pop());
+    }
+}
+
+impl Grammar {
+    /// Create a new grammar with no rules
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the rules registered to parse `E` from `T`
+    fn rules<T: 'static, E: 'static>(&self) -> Vec<Rule<T, E>> {
+        self.rules
+            .get(&(TypeId::of::<T>(), TypeId::of::<E>()))
+            .and_then(|rules| rules.downcast_ref::<Vec<Rule<T, E>>>())
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Register a rule for parsing `E` from the input.
+    /// Rules are attempted in the order they were registered
+    pub fn register<T, E, F>(&mut self, rule: F) -> &mut Self
+    where
+        T: 'static,
+        E: 'static,
+        F: Fn(&mut DynBuffer<T>) -> eyre::Result<E> + Send + Sync + 'static,
+    {
+        let mut rules = self.rules::<T, E>();
+        rules.push(Arc::new(rule));
+        self.rules
+            .insert((TypeId::of::<T>(), TypeId::of::<E>()), Arc::new(rules));
+        self
+    }
+
+    /// Register the parser `P` as a rule for parsing `E`
+    pub fn register_parser<T, E, P>(&mut self) -> &mut Self
+    where
+        T: 'static,
+        E: 'static,
+        P: Parse<T> + Into<E>,
+    {
+        self.register(|input: &mut DynBuffer<T>| Ok(P::parse(input)?.into()))
+    }
+
+    /// Removes all rules registered for parsing `E`
+    pub fn clear<T: 'static, E: 'static>(&mut self) -> &mut Self {
+        self.rules.remove(&(TypeId::of::<T>(), TypeId::of::<E>()));
+        self
+    }
+
+    /// Runs `f` with this grammar active on the current thread
+    pub fn scope<R>(&self, f: impl FnOnce() -> R) -> R {
+        ACTIVE.with(|active| active.borrow_mut().push(self.clone()));
+        let _guard = ActiveGuard;
+        f()
+    }
+
+    /// Parses the input into `P`, with this grammar active. See [`parse`](crate::parse)
+    ///
+    /// # Errors
+    /// If `P` failed to parse the input
+    pub fn parse<P, I>(&self, iter: I) -> eyre::Result<P>
+    where
+        P: Parse<<I::Iter as Iterator>::Item>,
+        I: IntoBuf,
+        <I::Iter as Iterator>::Item: Clone,
+    {
+        self.scope(|| crate::parse(iter))
+    }
+
+    /// Returns what could come next after `prefix` when parsing `P`, including
+    /// the rules in this grammar. See [`completions`](crate::complete::completions)
+    #[must_use]
+    pub fn completions<P: Parse<char>>(&self, prefix: &str) -> Vec<crate::complete::Expected> {
+        self.scope(|| crate::complete::completions::<P>(prefix))
+    }
+}
+
+/// Returns the rules for parsing `E` in the active grammar
+fn active_rules<T: 'static, E: 'static>() -> Option<Vec<Rule<T, E>>> {
+    ACTIVE.with(|active| active.borrow().last().map(Grammar::rules))
+}
+
+/// Attempts to parse `E` using the rules of the active [`Grammar`],
+/// returning the result of the first that succeeds.
+/// If they all fail, the error of the rule that read the most input is returned
+///
+/// # Errors
+/// If there's no active grammar, or none of its rules could parse the input
+pub fn parse<T: 'static, E: 'static>(input: &mut impl Buffer<T>) -> eyre::Result<E> {
+    let rules = active_rules::<T, E>().ok_or_else(|| {
+        eyre::eyre!(
+            "no grammar is active to parse extensions of {}. Use `Grammar::parse` or `Grammar::scope`",
+            type_name::<E>()
+        )
+    })?;
+
+    // like enum variants, the error from the rule that read the most input is kept
+    let mut error: Option<(usize, eyre::Report)> = None;
+    for rule in rules {
+        let mut cursor = input.cursor();
+        let res = rule(&mut DynBuffer::new(&mut cursor));
+        let pos = cursor.position();
+        match res {
+            Ok(e) => {
+                input.fast_forward(pos);
+                return Ok(e);
+            }
+            Err(_) if matches!(&error, Some((furthest, _)) if *furthest >= pos) => {}
+            Err(err) => error = Some((pos, err)),
+        }
+    }
+
+    match error {
+        Some((pos, err)) => {
+            // leave the input where the error happened, so callers can see how far it got
+            input.fast_forward(pos);
+            Err(err).wrap_err(format!(
+                "no extension rules for {} could be parsed",
+                type_name::<E>()
+            ))
+        }
+        None => Err(eyre::eyre!(
+            "no extension rules for {} are registered",
+            type_name::<E>()
+        )),
+    }
+}
+
+/// Returns true if any of the rules for `E` in the active [`Grammar`] can parse the input
+pub fn peek<T: 'static, E: 'static>(input: &mut impl Buffer<T>) -> bool {
+    for rule in active_rules::<T, E>().unwrap_or_default() {
+        let mut cursor = input.cursor();
+        if rule(&mut DynBuffer::new(&mut cursor)).is_ok() {
+            let pos = cursor.position();
+            input.fast_forward(pos);
+            return true;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{complete::Expected, text::Tag};
+
+    #[derive(Debug, PartialEq)]
+    struct Op(&'static str);
+
+    impl From<Tag<"+">> for Op {
+        fn from(_: Tag<"+">) -> Self {
+            Self("+")
+        }
+    }
+
+    fn minus(input: &mut DynBuffer<char>) -> eyre::Result<Op> {
+        Tag::<"-">::parse(input)?;
+        Ok(Op("-"))
+    }
+
+    #[test]
+    fn rules_in_order() {
+        let mut grammar = Grammar::new();
+        grammar
+            .register_parser::<char, Op, Tag<"+">>()
+            .register(minus);
+
+        let mut input = "+-.".chars().into_buf();
+        grammar.scope(|| {
+            assert_eq!(parse::<char, Op>(&mut input).unwrap(), Op("+"));
+            assert!(peek::<char, Op>(&mut input));
+
+            // both rules read 1 character, so the first rule's error is kept
+            let err = parse::<char, Op>(&mut input).unwrap_err();
+            assert_eq!(
+                err.root_cause().to_string(),
+                "failed to parse tag \"+\", found \".\""
+            );
+        });
+
+        grammar.clear::<char, Op>();
+        let mut input = "+".chars().into_buf();
+        assert!(!grammar.scope(|| peek::<char, Op>(&mut input)));
+    }
+
+    #[test]
+    fn completions() {
+        /// Parses any extension of `Op`
+        struct AnyOp;
+        impl Parse<char> for AnyOp {
+            fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
+                parse::<char, Op>(input)?;
+                Ok(Self)
+            }
+        }
+
+        let mut grammar = Grammar::new();
+        grammar
+            .register_parser::<char, Op, Tag<"+">>()
+            .register(minus);
+
+        assert_eq!(
+            grammar.completions::<AnyOp>(""),
+            vec![
+                Expected::Tag { tag: "+", typed: 0 },
+                Expected::Tag { tag: "-", typed: 0 },
+            ]
+        );
+    }
+
+    #[test]
+    fn no_active_grammar() {
+        let mut input = "+".chars().into_buf();
+        let err = parse::<char, Op>(&mut input).unwrap_err();
+        assert!(err.to_string().starts_with("no grammar is active"));
+    }
+
+    #[test]
+    fn separate_grammars() {
+        let mut plus = Grammar::new();
+        plus.register_parser::<char, Op, Tag<"+">>();
+        let mut minus_grammar = Grammar::new();
+        minus_grammar.register(minus);
+
+        let mut input = "-".chars().into_buf();
+        assert!(plus
+            .scope(|| parse::<char, Op>(&mut input.cursor()))
+            .is_err());
+        assert_eq!(
+            minus_grammar
+                .scope(|| parse::<char, Op>(&mut input))
+                .unwrap(),
+            Op("-")
+        );
+
+        // scopes nest, with the innermost grammar active
+        let mut input = "-".chars().into_buf();
+        let op = plus.scope(|| minus_grammar.scope(|| parse::<char, Op>(&mut input)));
+        assert_eq!(op.unwrap(), Op("-"));
+    }
+
+    #[test]
+    fn shared_between_threads() {
+        let mut grammar = Grammar::new();
+        grammar.register(minus);
+
+        let grammar = Arc::new(grammar);
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let grammar = Arc::clone(&grammar);
+                std::thread::spawn(move || {
+                    let mut input = "-".chars().into_buf();
+                    grammar.scope(|| parse::<char, Op>(&mut input)).unwrap()
+                })
+            })
+            .collect();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), Op("-"));
+        }
+    }
+}
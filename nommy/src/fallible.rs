@@ -0,0 +1,142 @@
+//! Parsing input from iterators that can fail part way through, like a decompression stream
+//! or a lexer over a file being read.
+//!
+//! Parsers only see `Ok` tokens. If the iterator yields an `Err`, the parsers see the end of
+//! the input at that point, and [`parse`] returns the upstream error as [`Error::Upstream`],
+//! rather than whatever error (or truncated value) the parser came up with after running out of input.
+//!
+//! ```
+//! use nommy::{fallible::{self, Error}, text::AnyOf1};
+//!
+//! let input = vec![Ok('a'), Ok('b'), Err("connection reset"), Ok('c')];
+//! let err = fallible::parse::<AnyOf1<"abc">, _, _>(input).unwrap_err();
+//! assert!(matches!(err, Error::Upstream { position: 2, error: "connection reset" }));
+//!
+//! let input: Vec<Result<char, &str>> = vec![Ok('a'), Ok('b'), Ok('c')];
+//! let value: AnyOf1<"abc"> = fallible::parse(input).unwrap();
+//! assert_eq!(String::from(value), "abc");
+//! ```
+
+use std::{cell::RefCell, fmt};
+
+use crate::{eyre, IntoBuf, Parse};
+
+/// Error returned by [`parse`]
+#[derive(Debug)]
+pub enum Error<E> {
+    /// The input iterator failed
+    Upstream {
+        /// How many tokens were read successfully before the failure
+        position: usize,
+        /// The error the input iterator returned
+        error: E,
+    },
+    /// The parser failed, without the input iterator failing
+    Parse(eyre::Report),
+}
+
+impl<E: fmt::Display> fmt::Display for Error<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Upstream { position, error } => {
+                write!(f, "input failed after {} tokens: {}", position, error)
+            }
+            Self::Parse(error) => fmt::Display::fmt(error, f),
+        }
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for Error<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Upstream { error, .. } => Some(error),
+            Self::Parse(error) => error.source(),
+        }
+    }
+}
+
+/// Parses the `Ok` tokens of the given iterator into `P`, like [`parse`](crate::parse).
+/// The iterator isn't read past the first `Err`.
+///
+/// # Errors
+/// [`Error::Upstream`] if the parser read as far as an `Err` from the iterator, even if the parser
+/// succeeded, since it could only have done so by treating the error as the end of the input.
+/// Otherwise [`Error::Parse`] if `P` failed to parse the input
+pub fn parse<P, T, E>(iter: impl IntoIterator<Item = Result<T, E>>) -> Result<P, Error<E>>
+where
+    P: Parse<T>,
+    T: Clone,
+{
+    let failure = RefCell::new(None);
+    let mut buffer = Fallible {
+        iter: iter.into_iter(),
+        position: 0,
+        failure: &failure,
+    }
+    .into_buf();
+
+    let output = P::parse(&mut buffer);
+    drop(buffer);
+    match (failure.into_inner(), output) {
+        (Some((position, error)), _) => Err(Error::Upstream { position, error }),
+        (None, output) => output.map_err(Error::Parse),
+    }
+}
+
+/// Iterator over the `Ok` tokens, that stops at the first `Err` and stores it
+struct Fallible<'a, I, E> {
+    iter: I,
+    position: usize,
+    failure: &'a RefCell<Option<(usize, E)>>,
+}
+
+impl<T, E, I: Iterator<Item = Result<T, E>>> Iterator for Fallible<'_, I, E> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.failure.borrow().is_some() {
+            return None;
+        }
+        match self.iter.next()? {
+            Ok(token) => {
+                self.position += 1;
+                Some(token)
+            }
+            Err(error) => {
+                *self.failure.borrow_mut() = Some((self.position, error));
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::{AnyOf1, Tag};
+
+    #[test]
+    fn truncated_success() {
+        // without the upstream error, this would look like a successful parse of "ab"
+        let input = vec![Ok('a'), Ok('b'), Err(()), Ok('a')];
+        let err = parse::<AnyOf1<"ab">, _, _>(input).unwrap_err();
+        assert!(matches!(
+            err,
+            Error::Upstream {
+                position: 2,
+                error: ()
+            }
+        ));
+    }
+
+    #[test]
+    fn not_read() {
+        // the parser never reads as far as the error
+        let input = vec![Ok('a'), Err(())];
+        parse::<Tag<"a">, _, _>(input).unwrap();
+
+        let input = vec![Ok('b'), Err(())];
+        let err = parse::<Tag<"a">, _, _>(input).unwrap_err();
+        assert!(matches!(err, Error::Parse(_)));
+    }
+}
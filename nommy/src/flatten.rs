@@ -0,0 +1,49 @@
+//! Support for `#[nommy(flatten)]` fields, which parse the fields of a nested struct
+//! as if they were written in the parent.
+//!
+//! The flattened fields are separated by the parent's ignored input, rather than the
+//! nested struct's own `ignore` types, so a shared run of fields can be reused by grammars
+//! with different whitespace or comment rules
+//!
+//! ```
+//! use nommy::{parse, text::{AnyOf1, Space, Tag, WhiteSpace}, Parse};
+//!
+//! type Ident = AnyOf1<"abcdefghijklmnopqrstuvwxyz">;
+//!
+//! /// A name and its type, shared by lets and function parameters
+//! #[derive(Debug, Parse)]
+//! #[nommy(ignore = Space)]
+//! struct Binding {
+//!     #[nommy(parser = Ident)]
+//!     name: String,
+//!     #[nommy(prefix = Tag<":">, parser = Ident)]
+//!     ty: String,
+//! }
+//!
+//! #[derive(Debug, Parse)]
+//! #[nommy(ignore = WhiteSpace)]
+//! struct Let {
+//!     #[nommy(prefix = Tag<"let">, flatten)]
+//!     binding: Binding,
+//!     #[nommy(prefix = Tag<"=">, parser = Ident)]
+//!     value: String,
+//! }
+//!
+//! // the new line is only ignored because `Let` ignores `WhiteSpace`
+//! let value: Let = parse("let x\n: int = y".chars()).unwrap();
+//! assert_eq!(value.binding.ty, "int");
+//! ```
+
+use crate::{eyre, Buffer, Parse};
+
+/// Parses a type as part of its parent's sequence of fields. Implemented by `#[derive(Parse)]` on structs
+pub trait Flatten<T>: Sized {
+    /// Parses each field of the type, skipping the input matched by `I` after each of them
+    ///
+    /// # Errors
+    /// Will return an error if any of the fields fail to parse
+    fn parse_flat<I: Parse<T>>(input: &mut impl Buffer<T>) -> eyre::Result<Self>;
+
+    /// Peeks each field of the type, skipping the input matched by `I` after each of them
+    fn peek_flat<I: Parse<T>>(input: &mut impl Buffer<T>) -> bool;
+}
@@ -0,0 +1,425 @@
+//! Frames with a checked or delimited body, as found in binary protocols.
+//!
+//! [`Framed`] parses a header, a body and a [`Checksum`] trailer, then checks the trailer against
+//! the input the body was parsed from. Derived parsers can do the same with the `checksum` field attribute,
+//! which checks the field against the input read by the fields before it.
+//!
+//! ```
+//! use nommy::{bytes::{AnyOf1, Tag}, framed::{Framed, Xor8}, message::Message, parse};
+//!
+//! type Packet = Framed<Tag<b"P">, AnyOf1<b"abc">, Xor8>;
+//!
+//! let packet: Packet = parse(b"Pabc\x60".iter().copied()).unwrap();
+//! assert_eq!(packet.checksum, Xor8::of(b"abc"));
+//!
+//! let err = parse::<Packet, _>(b"Pabc\x00".iter().copied()).unwrap_err();
+//! assert_eq!(err.downcast_ref::<Message>(), Some(&Message::Checksum));
+//! ```
+//!
+//! [`LengthPrefixed`] parses a [`Length`], then a body that has to read exactly that many values.
+//!
+//! ```
+//! use nommy::{bytes::AnyOf1, framed::{LengthPrefixed, U8}, parse};
+//!
+//! type Name = LengthPrefixed<U8, AnyOf1<b"abcdefghijklmnopqrstuvwxyz">>;
+//!
+//! let name: Name = parse(b"\x03abc".iter().copied()).unwrap();
+//! assert_eq!(Vec::from(name.body), b"abc");
+//!
+//! // the body has to read the whole frame
+//! assert!(parse::<Name, _>(b"\x03ab1".iter().copied()).is_err());
+//! ```
+
+use crate::{
+    eyre, lookahead,
+    message::Message,
+    unparse::{Emit, Unparse},
+    Buffer, Cursor, Parse,
+};
+
+/// A trailer that checks the input before it, such as a CRC
+pub trait Checksum<T>: Parse<T> {
+    /// Whether `data`, the input that the checksum covers, has this checksum
+    fn matches(&self, data: &[T]) -> bool;
+}
+
+/// Parses a `Header`, then a `Body`, then a [`Checksum`] of the input the body was parsed from
+#[derive(Debug, Clone, PartialEq)]
+pub struct Framed<H, B, C> {
+    /// the input before the body, which the checksum doesn't cover
+    pub header: H,
+    /// the input the checksum covers
+    pub body: B,
+    /// the checksum of the body's input
+    pub checksum: C,
+}
+
+impl<T: Clone, H: Parse<T>, B: Parse<T>, C: Checksum<T>> Parse<T> for Framed<H, B, C> {
+    const MAX_PEEK: Option<usize> = lookahead::then(
+        lookahead::then(H::MAX_PEEK, B::MAX_PEEK),
+        C::MAX_PEEK,
+    );
+
+    fn parse(input: &mut impl Buffer<T>) -> eyre::Result<Self> {
+        let header = H::parse(input)?;
+        let mut record = Record::new(input);
+        let body = B::parse(&mut record)?;
+        let data = record.into_recorded();
+        let checksum = C::parse(input)?;
+        if !checksum.matches(&data) {
+            return Err(eyre::Report::new(Message::Checksum));
+        }
+        Ok(Self {
+            header,
+            body,
+            checksum,
+        })
+    }
+
+    // the checksum is only parsed, not checked, so peeking doesn't have to keep the body's input
+    fn peek(input: &mut impl Buffer<T>) -> bool {
+        H::peek(input) && B::peek(input) && C::peek(input)
+    }
+}
+
+impl<T, H: Unparse<T>, B: Unparse<T>, C: Unparse<T>> Unparse<T> for Framed<H, B, C> {
+    fn unparse(&self, output: &mut impl Emit<T>) {
+        self.header.unparse(output);
+        self.body.unparse(output);
+        self.checksum.unparse(output);
+    }
+}
+
+/// A [`Buffer`] that keeps a copy of the input read through it, so that a [`Checksum`] can be checked against it.
+/// Input read by its cursors is only kept once the cursor's position is fast forwarded past it
+///
+/// ```
+/// use nommy::{framed::Record, Buffer, IntoBuf, Parse, text::{AnyOf1, Tag}};
+/// let mut input = "abc;".chars().into_buf();
+/// let mut record = Record::new(&mut input);
+/// AnyOf1::<"abc">::parse(&mut record).unwrap();
+/// assert_eq!(record.recorded(), &['a', 'b', 'c']);
+/// assert_eq!(input.next(), Some(';'));
+/// ```
+pub struct Record<'a, T, B: Buffer<T>> {
+    buf: &'a mut B,
+    recorded: Vec<T>,
+}
+
+impl<'a, T, B: Buffer<T>> Record<'a, T, B> {
+    /// Records the input read from `buf`
+    pub fn new(buf: &'a mut B) -> Self {
+        Self {
+            buf,
+            recorded: Vec::new(),
+        }
+    }
+
+    /// The input read so far
+    #[must_use]
+    pub fn recorded(&self) -> &[T] {
+        &self.recorded
+    }
+
+    /// Returns the input read
+    #[must_use]
+    pub fn into_recorded(self) -> Vec<T> {
+        self.recorded
+    }
+}
+
+impl<T: Clone, B: Buffer<T>> Iterator for Record<'_, T, B> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        let output = self.buf.next()?;
+        self.recorded.push(output.clone());
+        Some(output)
+    }
+}
+
+impl<T: Clone, B: Buffer<T>> Buffer<T> for Record<'_, T, B> {
+    type CursorBase = B;
+    fn cursor(&mut self) -> Cursor<T, Self::CursorBase> {
+        Cursor::new(self.buf)
+    }
+
+    fn fast_forward(&mut self, n: usize) {
+        let skipped: Vec<T> = self.buf.take_collect(n);
+        self.recorded.extend(skipped);
+    }
+
+    fn peek_ahead(&mut self, i: usize) -> Option<T> {
+        self.buf.peek_ahead(i)
+    }
+
+    fn offset(&self) -> usize {
+        self.buf.offset()
+    }
+
+    fn probe(&self) -> Option<&[T]> {
+        self.buf.probe()
+    }
+}
+
+/// A parsed length, giving how many values a [`LengthPrefixed`] body has
+pub trait Length {
+    /// The number of values
+    fn length(&self) -> usize;
+}
+
+/// Parses a `Len`, then a `Body` from exactly that many of the values that follow.
+/// The body can't read past the end of the frame, and it's an error if it doesn't read all of it
+#[derive(Debug, Clone, PartialEq)]
+pub struct LengthPrefixed<L, B> {
+    /// the length of the body
+    pub length: L,
+    /// the body, read from the `length` values after it
+    pub body: B,
+}
+
+impl<T, L: Parse<T> + Length, B: Parse<T>> Parse<T> for LengthPrefixed<L, B> {
+    const MAX_PEEK: Option<usize> = lookahead::then(L::MAX_PEEK, B::MAX_PEEK);
+
+    fn parse(input: &mut impl Buffer<T>) -> eyre::Result<Self> {
+        let length = L::parse(input)?;
+        let mut limit = input.limit(length.length());
+        let body = B::parse(&mut limit)?;
+        if limit.remaining() > 0 {
+            return Err(eyre::Report::new(Message::Incomplete));
+        }
+        Ok(Self { length, body })
+    }
+
+    // the length has to be parsed to know where the body ends, but parsing a `Length` doesn't allocate
+    fn peek(input: &mut impl Buffer<T>) -> bool {
+        let length = match L::parse(input) {
+            Ok(length) => length,
+            Err(_) => return false,
+        };
+        let mut limit = input.limit(length.length());
+        B::peek(&mut limit) && limit.remaining() == 0
+    }
+}
+
+impl<T, L: Unparse<T>, B: Unparse<T>> Unparse<T> for LengthPrefixed<L, B> {
+    fn unparse(&self, output: &mut impl Emit<T>) {
+        self.length.unparse(output);
+        self.body.unparse(output);
+    }
+}
+
+/// A one byte length
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct U8(pub u8);
+
+impl Length for U8 {
+    fn length(&self) -> usize {
+        self.0.into()
+    }
+}
+
+impl Parse<u8> for U8 {
+    const MAX_PEEK: Option<usize> = Some(1);
+
+    fn parse(input: &mut impl Buffer<u8>) -> eyre::Result<Self> {
+        match input.next() {
+            Some(b) => Ok(Self(b)),
+            None => Err(eyre::Report::new(Message::EndOfInput)),
+        }
+    }
+
+    fn peek(input: &mut impl Buffer<u8>) -> bool {
+        input.next().is_some()
+    }
+}
+
+impl Unparse<u8> for U8 {
+    fn unparse(&self, output: &mut impl Emit<u8>) {
+        output.emit(self.0);
+    }
+}
+
+/// A two byte, big endian, length
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct U16Be(pub u16);
+
+impl Length for U16Be {
+    fn length(&self) -> usize {
+        self.0.into()
+    }
+}
+
+impl Parse<u8> for U16Be {
+    const MAX_PEEK: Option<usize> = Some(2);
+
+    fn parse(input: &mut impl Buffer<u8>) -> eyre::Result<Self> {
+        let mut bytes = [0; 2];
+        for b in &mut bytes {
+            *b = input
+                .next()
+                .ok_or_else(|| eyre::Report::new(Message::EndOfInput))?;
+        }
+        Ok(Self(u16::from_be_bytes(bytes)))
+    }
+
+    fn peek(input: &mut impl Buffer<u8>) -> bool {
+        (0..2).all(|_| input.next().is_some())
+    }
+}
+
+impl Unparse<u8> for U16Be {
+    fn unparse(&self, output: &mut impl Emit<u8>) {
+        self.0.to_be_bytes().unparse(output);
+    }
+}
+
+/// A one byte checksum, the XOR of every byte it covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Xor8(pub u8);
+
+impl Xor8 {
+    /// The checksum of `data`
+    #[must_use]
+    pub fn of(data: &[u8]) -> Self {
+        Self(data.iter().fold(0, |sum, b| sum ^ b))
+    }
+}
+
+impl Parse<u8> for Xor8 {
+    const MAX_PEEK: Option<usize> = Some(1);
+
+    fn parse(input: &mut impl Buffer<u8>) -> eyre::Result<Self> {
+        match input.next() {
+            Some(b) => Ok(Self(b)),
+            None => Err(eyre::Report::new(Message::EndOfInput)),
+        }
+    }
+
+    fn peek(input: &mut impl Buffer<u8>) -> bool {
+        input.next().is_some()
+    }
+}
+
+impl Checksum<u8> for Xor8 {
+    fn matches(&self, data: &[u8]) -> bool {
+        *self == Self::of(data)
+    }
+}
+
+impl Unparse<u8> for Xor8 {
+    fn unparse(&self, output: &mut impl Emit<u8>) {
+        output.emit(self.0);
+    }
+}
+
+/// The CRC-32 used by zlib, PNG and Ethernet, read as 4 big endian bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Crc32(pub u32);
+
+impl Crc32 {
+    /// The checksum of `data`
+    #[must_use]
+    pub fn of(data: &[u8]) -> Self {
+        let mut crc = !0_u32;
+        for &b in data {
+            crc ^= u32::from(b);
+            for _ in 0..8 {
+                crc = if crc & 1 == 0 {
+                    crc >> 1
+                } else {
+                    (crc >> 1) ^ 0xEDB8_8320
+                };
+            }
+        }
+        Self(!crc)
+    }
+}
+
+impl Parse<u8> for Crc32 {
+    const MAX_PEEK: Option<usize> = Some(4);
+
+    fn parse(input: &mut impl Buffer<u8>) -> eyre::Result<Self> {
+        let mut bytes = [0; 4];
+        for b in &mut bytes {
+            *b = input
+                .next()
+                .ok_or_else(|| eyre::Report::new(Message::EndOfInput))?;
+        }
+        Ok(Self(u32::from_be_bytes(bytes)))
+    }
+
+    fn peek(input: &mut impl Buffer<u8>) -> bool {
+        (0..4).all(|_| input.next().is_some())
+    }
+}
+
+impl Checksum<u8> for Crc32 {
+    fn matches(&self, data: &[u8]) -> bool {
+        *self == Self::of(data)
+    }
+}
+
+impl Unparse<u8> for Crc32 {
+    fn unparse(&self, output: &mut impl Emit<u8>) {
+        self.0.to_be_bytes().unparse(output);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{bytes::AnyOf1, parse, IntoBuf};
+
+    #[test]
+    fn crc32_check_value() {
+        assert_eq!(Crc32::of(b"123456789"), Crc32(0xCBF4_3926));
+        assert_eq!(Crc32::of(b""), Crc32(0));
+    }
+
+    #[test]
+    fn framed_crc32() {
+        type Frame = Framed<crate::bytes::Tag<b"F">, AnyOf1<b"0123456789">, Crc32>;
+        let mut input = b"F123456789\xcb\xf4\x39\x26".to_vec();
+        let frame: Frame = parse(input.iter().copied()).unwrap();
+        assert_eq!(Vec::from(frame.body), b"123456789");
+
+        input[1] = b'0';
+        let err = parse::<Frame, _>(input.iter().copied()).unwrap_err();
+        assert_eq!(err.to_string(), "checksum does not match the input");
+
+        let err = parse::<Frame, _>(b"F1\xcb".iter().copied()).unwrap_err();
+        assert_eq!(err.to_string(), "reached the end of the input");
+    }
+
+    #[test]
+    fn length_prefixed() {
+        type Frame = LengthPrefixed<U16Be, AnyOf1<b"abc">>;
+        let frame: Frame = parse(b"\x00\x03abc".iter().copied()).unwrap();
+        assert_eq!(Vec::from(frame.body), b"abc");
+
+        // the body doesn't read the whole frame
+        let err = parse::<Frame, _>(b"\x00\x04abcd".iter().copied()).unwrap_err();
+        assert_eq!(err.to_string(), "input was not parsed completely");
+        assert!(!Frame::peek(&mut b"\x00\x04abcd".iter().copied().into_buf()));
+
+        // the frame is longer than the input
+        assert!(parse::<Frame, _>(b"\x00\x04abc".iter().copied()).is_err());
+
+        let mut input = b"\x00\x02abc".iter().copied().into_buf();
+        assert!(Frame::peek(&mut input.cursor()));
+        Frame::parse(&mut input).unwrap();
+        assert_eq!(input.next(), Some(b'c'));
+    }
+
+    #[test]
+    fn record_cursor() {
+        let mut input = b"abc".iter().copied().into_buf();
+        let mut record = Record::new(&mut input);
+        let mut cursor = record.cursor();
+        assert_eq!(cursor.next(), Some(b'a'));
+        assert!(record.recorded().is_empty());
+        record.fast_forward(2);
+        assert_eq!(record.recorded(), b"ab");
+    }
+}
@@ -0,0 +1,413 @@
+//! Parser for HTTP/1.1 messages, reading bytes straight from the connection.
+//!
+//! [`Request`] and [`Response`] parse the start line, the headers and the body. The body is read
+//! using the `Content-Length` header, or decoded from chunks if the `Transfer-Encoding` is `chunked`.
+//! The input is only read as far as the end of the message, so several messages can be
+//! parsed one after another from the same [`Buffer`].
+//!
+//! Combined with [`fallible::parse`](crate::fallible::parse), messages can be parsed from an
+//! [`io::Read`](std::io::Read) as the bytes arrive, with any IO error kept separate from parse errors
+//!
+//! ```
+//! use std::io::Read;
+//! use nommy::{fallible, http::Response};
+//!
+//! let conn: &[u8] = b"HTTP/1.1 200 OK\r\n\
+//!     Transfer-Encoding: chunked\r\n\
+//!     \r\n\
+//!     5\r\nhello\r\n\
+//!     7\r\n, world\r\n\
+//!     0\r\n\
+//!     \r\n";
+//!
+//! let response: Response = fallible::parse(conn.bytes()).unwrap();
+//! assert_eq!(response.status, 200);
+//! assert_eq!(response.header("transfer-encoding"), Some("chunked"));
+//! assert_eq!(response.body, b"hello, world");
+//! ```
+
+use crate::{
+    bytes::{AnyOf1, Tag},
+    eyre::{self, WrapErr},
+    Buffer, Parse,
+};
+
+/// The version of HTTP a message uses, such as `HTTP/1.1`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Version {
+    /// The major version
+    pub major: u8,
+    /// The minor version
+    pub minor: u8,
+}
+
+impl Parse<u8> for Version {
+    fn parse(input: &mut impl Buffer<u8>) -> eyre::Result<Self> {
+        Tag::<b"HTTP/">::parse(input)?;
+        let major = digit(input)?;
+        Tag::<b".">::parse(input)?;
+        let minor = digit(input)?;
+        Ok(Self { major, minor })
+    }
+}
+
+/// Parses a single decimal digit
+fn digit(input: &mut impl Buffer<u8>) -> eyre::Result<u8> {
+    match input.next() {
+        Some(b) if b.is_ascii_digit() => Ok(b - b'0'),
+        _ => Err(eyre::eyre!("expected a digit")),
+    }
+}
+
+/// A header field, such as `Content-Length: 5`
+#[derive(Debug, Clone, PartialEq, Eq, Parse)]
+#[nommy(parse_type = u8)]
+#[nommy(suffix = Tag<b"\r\n">)]
+pub struct Header {
+    /// The name of the header, which is case insensitive
+    #[nommy(parser = Token)]
+    pub name: String,
+    /// The value of the header, without surrounding spaces
+    #[nommy(prefix = Tag<b":">, parser = FieldText)]
+    pub value: String,
+}
+
+/// An HTTP request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    /// The request method, such as `GET`
+    pub method: String,
+    /// The request target, such as `/index.html`
+    pub target: String,
+    /// The HTTP version
+    pub version: Version,
+    /// The headers, in the order they were sent
+    pub headers: Vec<Header>,
+    /// The body, with any chunked transfer coding removed
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    /// The value of the first header called `name`, ignoring case
+    #[must_use]
+    pub fn header(&self, name: &str) -> Option<&str> {
+        header(&self.headers, name)
+    }
+}
+
+/// The start line of a request
+#[derive(Parse)]
+#[nommy(parse_type = u8)]
+#[nommy(suffix = Tag<b"\r\n">)]
+struct RequestLine {
+    #[nommy(parser = Token, suffix = Tag<b" ">)]
+    method: String,
+    #[nommy(parser = Target, suffix = Tag<b" ">)]
+    target: String,
+    version: Version,
+}
+
+/// A request without a `Content-Length` or chunked `Transfer-Encoding` has no body
+impl Parse<u8> for Request {
+    fn parse(input: &mut impl Buffer<u8>) -> eyre::Result<Self> {
+        let RequestLine {
+            method,
+            target,
+            version,
+        } = RequestLine::parse(input).wrap_err("could not parse request line")?;
+        let headers = parse_headers(input)?;
+        let body = parse_body(input, &headers, false)?;
+        Ok(Self {
+            method,
+            target,
+            version,
+            headers,
+            body,
+        })
+    }
+}
+
+/// An HTTP response
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    /// The HTTP version
+    pub version: Version,
+    /// The status code, such as `404`
+    pub status: u16,
+    /// The reason phrase, such as `Not Found`
+    pub reason: String,
+    /// The headers, in the order they were sent
+    pub headers: Vec<Header>,
+    /// The body, with any chunked transfer coding removed
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    /// The value of the first header called `name`, ignoring case
+    #[must_use]
+    pub fn header(&self, name: &str) -> Option<&str> {
+        header(&self.headers, name)
+    }
+}
+
+/// The start line of a response
+#[derive(Parse)]
+#[nommy(parse_type = u8)]
+#[nommy(suffix = Tag<b"\r\n">)]
+struct StatusLine {
+    #[nommy(suffix = Tag<b" ">)]
+    version: Version,
+    #[nommy(parser = Status)]
+    status: u16,
+    #[nommy(prefix = Tag<b" ">, parser = FieldText)]
+    reason: String,
+}
+
+/// A response without a `Content-Length` or chunked `Transfer-Encoding` has a body that runs to the end of the input
+impl Parse<u8> for Response {
+    fn parse(input: &mut impl Buffer<u8>) -> eyre::Result<Self> {
+        let StatusLine {
+            version,
+            status,
+            reason,
+        } = StatusLine::parse(input).wrap_err("could not parse status line")?;
+        let headers = parse_headers(input)?;
+        let body = parse_body(input, &headers, true)?;
+        Ok(Self {
+            version,
+            status,
+            reason,
+            headers,
+            body,
+        })
+    }
+}
+
+fn header<'a>(headers: &'a [Header], name: &str) -> Option<&'a str> {
+    headers
+        .iter()
+        .find(|h| h.name.eq_ignore_ascii_case(name))
+        .map(|h| h.value.as_str())
+}
+
+/// Parses the headers, and the empty line that ends them
+fn parse_headers(input: &mut impl Buffer<u8>) -> eyre::Result<Vec<Header>> {
+    let headers = Vec::<Header>::parse(input)?;
+    Tag::<b"\r\n">::parse(input).wrap_err("could not parse header")?;
+    Ok(headers)
+}
+
+/// Reads the body described by the headers
+fn parse_body(
+    input: &mut impl Buffer<u8>,
+    headers: &[Header],
+    to_end: bool,
+) -> eyre::Result<Vec<u8>> {
+    // chunked is always the last transfer coding, if it's used
+    let last_coding = header(headers, "transfer-encoding").and_then(|te| te.rsplit(',').next());
+    let chunked =
+        matches!(last_coding, Some(coding) if coding.trim().eq_ignore_ascii_case("chunked"));
+
+    if chunked {
+        let mut body = Vec::new();
+        loop {
+            let size = ChunkSize::parse(input).wrap_err("could not parse chunk size")?;
+            if size.0 == 0 {
+                // trailers are parsed, but not kept
+                parse_headers(input)?;
+                return Ok(body);
+            }
+            take(input, size.0, &mut body)?;
+            Tag::<b"\r\n">::parse(input).wrap_err("chunk was longer than its size")?;
+        }
+    }
+
+    match header(headers, "content-length") {
+        Some(len) => {
+            let len = len.parse().wrap_err("invalid content length")?;
+            let mut body = Vec::with_capacity(len);
+            take(input, len, &mut body)?;
+            Ok(body)
+        }
+        None if to_end => Ok(input.collect()),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Reads exactly `len` bytes into `output`
+fn take(input: &mut impl Buffer<u8>, len: usize, output: &mut Vec<u8>) -> eyre::Result<()> {
+    let start = output.len();
+    output.extend(input.take(len));
+    if output.len() - start == len {
+        Ok(())
+    } else {
+        Err(eyre::eyre!(
+            "body ended after {} of {} bytes",
+            output.len() - start,
+            len
+        ))
+    }
+}
+
+/// Characters allowed in methods and header names
+type TChars =
+    AnyOf1<b"!#$%&'*+-.^_`|~0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ">;
+
+/// A method or header name
+struct Token(String);
+
+impl Parse<u8> for Token {
+    fn parse(input: &mut impl Buffer<u8>) -> eyre::Result<Self> {
+        let bytes: Vec<u8> = TChars::parse(input)?.into();
+        // all token chars are ascii
+        Ok(Self(String::from_utf8_lossy(&bytes).into_owned()))
+    }
+}
+
+impl From<Token> for String {
+    fn from(v: Token) -> Self {
+        v.0
+    }
+}
+
+/// A request target, any visible ascii characters
+struct Target(String);
+
+impl Parse<u8> for Target {
+    fn parse(input: &mut impl Buffer<u8>) -> eyre::Result<Self> {
+        let mut target = String::new();
+        while let Some(b) = input.peek_ahead(0).filter(u8::is_ascii_graphic) {
+            target.push(b.into());
+            input.fast_forward(1);
+        }
+        if target.is_empty() {
+            Err(eyre::eyre!("expected a request target"))
+        } else {
+            Ok(Self(target))
+        }
+    }
+}
+
+impl From<Target> for String {
+    fn from(v: Target) -> Self {
+        v.0
+    }
+}
+
+/// Text up to the end of the line, without surrounding spaces or tabs
+struct FieldText(String);
+
+impl Parse<u8> for FieldText {
+    fn parse(input: &mut impl Buffer<u8>) -> eyre::Result<Self> {
+        let text: Vec<u8> = input.take_while_collect(|&b| b != b'\r' && b != b'\n');
+        let text = String::from_utf8_lossy(&text);
+        Ok(Self(
+            text.trim_matches(|c| c == ' ' || c == '\t').to_owned(),
+        ))
+    }
+}
+
+impl From<FieldText> for String {
+    fn from(v: FieldText) -> Self {
+        v.0
+    }
+}
+
+/// A 3 digit status code
+struct Status(u16);
+
+impl Parse<u8> for Status {
+    fn parse(input: &mut impl Buffer<u8>) -> eyre::Result<Self> {
+        let mut status = 0;
+        for _ in 0..3 {
+            status = status * 10 + u16::from(digit(input)?);
+        }
+        Ok(Self(status))
+    }
+}
+
+impl From<Status> for u16 {
+    fn from(v: Status) -> Self {
+        v.0
+    }
+}
+
+/// The line before each chunk, giving its size in hex. Chunk extensions are ignored
+struct ChunkSize(usize);
+
+impl Parse<u8> for ChunkSize {
+    fn parse(input: &mut impl Buffer<u8>) -> eyre::Result<Self> {
+        let digits: Vec<u8> = AnyOf1::<b"0123456789abcdefABCDEF">::parse(input)?.into();
+        let digits = String::from_utf8_lossy(&digits);
+        let size = usize::from_str_radix(&digits, 16).wrap_err("chunk size too large")?;
+        FieldText::parse(input)?;
+        Tag::<b"\r\n">::parse(input)?;
+        Ok(Self(size))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse, IntoBuf};
+
+    #[test]
+    fn pipelined_requests() {
+        let mut input = b"POST /submit?a=1 HTTP/1.1\r\n\
+            Host: example.com\r\n\
+            content-length: 5\r\n\
+            \r\n\
+            helloGET / HTTP/1.0\r\n\
+            \r\n"
+            .iter()
+            .copied()
+            .into_buf();
+
+        let post = Request::parse(&mut input).unwrap();
+        assert_eq!(post.method, "POST");
+        assert_eq!(post.target, "/submit?a=1");
+        assert_eq!(post.header("HOST"), Some("example.com"));
+        assert_eq!(post.body, b"hello");
+
+        let get = Request::parse(&mut input).unwrap();
+        assert_eq!(get.version, Version { major: 1, minor: 0 });
+        assert!(get.headers.is_empty());
+        assert!(get.body.is_empty());
+        assert_eq!(input.next(), None);
+    }
+
+    #[test]
+    fn response_to_end() {
+        let response: Response = parse(
+            b"HTTP/1.1 404 Not Found\r\nServer:  x \r\n\r\nmissing"
+                .iter()
+                .copied(),
+        )
+        .unwrap();
+        assert_eq!(response.status, 404);
+        assert_eq!(response.reason, "Not Found");
+        assert_eq!(response.header("server"), Some("x"));
+        assert_eq!(response.body, b"missing");
+    }
+
+    #[test]
+    fn errors() {
+        let error = |input: &[u8]| {
+            parse::<Response, _>(input.iter().copied())
+                .unwrap_err()
+                .to_string()
+        };
+        assert_eq!(
+            error(b"HTTP/1.1 200 OK\r\nContent-Length: 10\r\n\r\nshort"),
+            "body ended after 5 of 10 bytes"
+        );
+        assert_eq!(
+            error(b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n2\r\nabc\r\n"),
+            "chunk was longer than its size"
+        );
+        assert_eq!(
+            error(b"HTTP/1.1 20 OK\r\n\r\n"),
+            "could not parse status line"
+        );
+    }
+}
@@ -1,23 +1,47 @@
 //! Implemtations of [`Parse`] and [`Peek`] for types in
 //! the rust standard library
-use crate::{eyre, Buffer, Context, Parse};
-use std::mem::MaybeUninit;
-
-/// Result is `None` if parsing `P` fails, otherwise, result is `Some(p)`
+use crate::{
+    eyre,
+    message::Message,
+    process::Process,
+    unparse::{Emit, Unparse},
+    Buffer, Context, Parse, Peek,
+};
+use std::{fmt, marker::PhantomData, mem::MaybeUninit};
+
+/// Result is `None` if `P` can't be peeked, without reading any input. Otherwise, `P` is parsed,
+/// and any error parsing it is returned rather than backtracking to `None`,
+/// so the error isn't hidden once the input has started to match `P`.
+///
+/// ```
+/// use nommy::{parse, text::Tag, Buffer, IntoBuf, Parse};
+///
+/// #[derive(Parse)]
+/// #[nommy(peek_limit = 1)]
+/// struct Call(Tag<"f">, Tag<"()">);
+///
+/// let mut input = "g".chars().into_buf();
+/// assert!(Option::<Call>::parse(&mut input).unwrap().is_none());
+/// assert_eq!(input.next(), Some('g'));
+/// // `f` peeks as a `Call`, so the missing `()` is an error
+/// assert!(parse::<Option<Call>, _>("f(".chars()).is_err());
+/// ```
 impl<P: Parse<T>, T> Parse<T> for Option<P> {
+    const MAX_PEEK: Option<usize> = P::MAX_PEEK;
+
     fn parse(input: &mut impl Buffer<T>) -> eyre::Result<Self> {
+        if !P::peek(&mut input.cursor()) {
+            return Ok(None);
+        }
+
         let mut cursor = input.cursor();
-        match P::parse(&mut cursor) {
-            Ok(p) => {
-                if cfg!(debug_assertions) && cursor.position() == 0 {
-                    panic!("parsing succeeded with 0 elements read - fix: remove `Option<_>`");
-                }
-                let pos = cursor.position();
-                input.fast_forward(pos);
-                Ok(Some(p))
-            }
-            Err(_) => Ok(None),
+        let p = P::parse(&mut cursor);
+        let pos = cursor.position();
+        if cfg!(debug_assertions) && p.is_ok() && pos == 0 {
+            panic!("parsing succeeded with 0 elements read - fix: remove `Option<_>`");
         }
+        input.fast_forward(pos);
+        p.map(Some)
     }
 
     fn peek(input: &mut impl Buffer<T>) -> bool {
@@ -38,16 +62,16 @@ impl<P: Parse<T>, T> Parse<T> for Option<P> {
 
 /// Repeatedly attempts to parse `P`, Result is all successful attempts
 impl<P: Parse<T>, T> Parse<T> for Vec<P> {
-    /// # Panics
-    /// If `P` is able to parse 0 tokens successfully, it would result in an infinite loop.
-    /// In debug builds, we detect this and panic
+    /// # Errors
+    /// If `P` is able to parse 0 tokens successfully, it would be parsed forever, so this is an error.
+    /// It's also an error to parse more elements than the [`vec::max_len`](crate::vec::max_len)
     ///
-    /// ```should_panic
-    /// use nommy::{IntoBuf, Parse, text::Tag};
+    /// ```
+    /// use nommy::{message::Message, IntoBuf, Parse, text::Tag};
     /// let mut input = "...".chars().into_buf();
-    /// Vec::<Option<Tag<".">>>::parse(&mut input);
+    /// let err = Vec::<Option<Tag<",">>>::parse(&mut input).unwrap_err();
+    /// assert_eq!(err.downcast_ref(), Some(&Message::NoProgress));
     /// ```
-    #[track_caller]
     fn parse(input: &mut impl Buffer<T>) -> eyre::Result<Self> {
         let mut output = Self::new();
         loop {
@@ -57,25 +81,24 @@ impl<P: Parse<T>, T> Parse<T> for Vec<P> {
                 Err(_) => break,
             }
             let pos = cursor.position();
-            if cfg!(debug_assertions) && pos == 0 {
-                panic!("parsing succeeded with 0 elements read. infinite loop detected");
-            }
+            crate::vec::check_element(output.len(), pos == 0)?;
             input.fast_forward(pos);
         }
 
         Ok(output)
     }
 
-    #[track_caller]
     fn peek(input: &mut impl Buffer<T>) -> bool {
+        let mut count = 0;
         loop {
             let mut cursor = input.cursor();
             if !P::peek(&mut cursor) {
                 break;
             }
             let pos = cursor.position();
-            if cfg!(debug_assertions) && pos == 0 {
-                panic!("parsing succeeded with 0 elements read. infinite loop detected");
+            count += 1;
+            if crate::vec::check_element(count, pos == 0).is_err() {
+                return false;
             }
             input.fast_forward(pos);
         }
@@ -83,6 +106,72 @@ impl<P: Parse<T>, T> Parse<T> for Vec<P> {
     }
 }
 
+/// Peeked parses over the input that `P` peeks, for a type that can only be peeked,
+/// such as one with a derived [`Peek`](derive@crate::Peek). It fails with [`Message::NotFound`] if `P` can't be peeked
+///
+/// ```
+/// use nommy::{parse, text::Tag, Peeked};
+///
+/// #[derive(nommy::Peek)]
+/// struct Arrow(Tag<"=>">);
+///
+/// let _: Peeked<Arrow> = parse("=>".chars()).unwrap();
+/// assert!(parse::<Peeked<Arrow>, _>("->".chars()).is_err());
+/// ```
+pub struct Peeked<P>(PhantomData<P>);
+
+impl<P> Default for Peeked<P> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<P> Clone for Peeked<P> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<P> Copy for Peeked<P> {}
+
+impl<P> PartialEq for Peeked<P> {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl<P> fmt::Debug for Peeked<P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Peeked<{}>", std::any::type_name::<P>())
+    }
+}
+
+impl<P> Process for Peeked<P> {
+    type Output = ();
+
+    fn process(self) -> Self::Output {}
+}
+
+impl<P: Peek<T>, T> Parse<T> for Peeked<P> {
+    const MAX_PEEK: Option<usize> = P::MAX_PEEK;
+
+    fn parse(input: &mut impl Buffer<T>) -> eyre::Result<Self> {
+        let mut cursor = input.cursor();
+        if !P::peek(&mut cursor) {
+            return Err(eyre::Report::new(Message::NotFound {
+                name: std::any::type_name::<P>(),
+            }));
+        }
+        let pos = cursor.position();
+        input.fast_forward(pos);
+        Ok(Self(PhantomData))
+    }
+
+    fn peek(input: &mut impl Buffer<T>) -> bool {
+        P::peek(input)
+    }
+}
+
 /// Vec1 is similar to [`Vec`] but implements [`Parse`] such that it will error if it fails to parse at least once
 #[derive(Debug, Clone, PartialEq)]
 pub struct Vec1<P>(Vec<P>);
@@ -105,6 +194,20 @@ impl<P> From<Vec1<P>> for Vec<P> {
     }
 }
 
+impl<P: Unparse<T>, T> Unparse<T> for Vec1<P> {
+    fn unparse(&self, output: &mut impl Emit<T>) {
+        self.0.unparse(output);
+    }
+}
+
+impl<P: Process> Process for Vec1<P> {
+    type Output = Vec<P::Output>;
+
+    fn process(self) -> Self::Output {
+        self.0.process()
+    }
+}
+
 /// Repeatedly attempt to parse `P`, Result is all successful attempts
 /// Must parse `P` at least once
 impl<P: Parse<T>, T> Parse<T> for Vec1<P> {
@@ -116,10 +219,8 @@ impl<P: Parse<T>, T> Parse<T> for Vec1<P> {
                 Ok(p) => output.push(p),
                 Err(_) => break,
             }
-            if cfg!(debug_assertions) && cursor.position() == 0 {
-                panic!("parsing succeeded with 0 elements read. infinite loop detected");
-            }
             let pos = cursor.position();
+            crate::vec::check_element(output.len(), pos == 0)?;
             input.fast_forward(pos);
         }
 
@@ -131,15 +232,17 @@ impl<P: Parse<T>, T> Parse<T> for Vec1<P> {
             return false;
         }
 
+        let mut count = 1;
         loop {
             let mut cursor = input.cursor();
             if !P::peek(&mut cursor) {
                 break;
             }
-            if cfg!(debug_assertions) && cursor.position() == 0 {
-                panic!("parsing succeeded with 0 elements read. infinite loop detected");
-            }
             let pos = cursor.position();
+            count += 1;
+            if crate::vec::check_element(count, pos == 0).is_err() {
+                return false;
+            }
             input.fast_forward(pos);
         }
 
@@ -154,6 +257,8 @@ impl<P: Parse<T>, T> Parse<T> for Vec1<P> {
 /// let _: [Tag<".">; 3] = parse_terminated("...".chars()).unwrap();
 /// ```
 impl<P: Parse<T>, T, const N: usize> Parse<T> for [P; N] {
+    const MAX_PEEK: Option<usize> = crate::lookahead::repeat(P::MAX_PEEK, N);
+
     fn parse(input: &mut impl Buffer<T>) -> eyre::Result<Self> {
         // safety: we only return the new data if no errors occured,
         // and if no errors occured, then we definitely filled all N spaces
@@ -182,6 +287,8 @@ impl<P: Parse<T>, T, const N: usize> Parse<T> for [P; N] {
 
 /// Parse
 impl<P: Parse<T>, T> Parse<T> for Box<P> {
+    const MAX_PEEK: Option<usize> = P::MAX_PEEK;
+
     fn parse(input: &mut impl Buffer<T>) -> eyre::Result<Self> {
         Ok(Box::new(P::parse(input)?))
     }
@@ -191,6 +298,34 @@ impl<P: Parse<T>, T> Parse<T> for Box<P> {
     }
 }
 
+/// Repeatedly attempts to parse `P`, Result is all successful attempts.
+/// The first `A::size()` values are stored inline. Requires the `smallvec` feature
+#[cfg(feature = "smallvec")]
+impl<A: smallvec::Array, T> Parse<T> for smallvec::SmallVec<A>
+where
+    A::Item: Parse<T>,
+{
+    fn parse(input: &mut impl Buffer<T>) -> eyre::Result<Self> {
+        let mut output = Self::new();
+        loop {
+            let mut cursor = input.cursor();
+            match A::Item::parse(&mut cursor) {
+                Ok(p) => output.push(p),
+                Err(_) => break,
+            }
+            let pos = cursor.position();
+            crate::vec::check_element(output.len(), pos == 0)?;
+            input.fast_forward(pos);
+        }
+
+        Ok(output)
+    }
+
+    fn peek(input: &mut impl Buffer<T>) -> bool {
+        Vec::<A::Item>::peek(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -243,6 +378,13 @@ mod tests {
         assert!(res.is_empty())
     }
 
+    #[test]
+    fn sequence_no_progress() {
+        let mut input = "...".chars().into_buf();
+        assert!(!Vec::<Option<Tag<",">>>::peek(&mut input.cursor()));
+        assert!(Vec::<Option<Tag<",">>>::parse(&mut input).is_err());
+    }
+
     #[test]
     fn sequence_at_least_one() {
         let res: Vec1<Tag<".">> = parse("...".chars()).unwrap();
@@ -257,4 +399,12 @@ mod tests {
             "failed to parse tag \".\", found \"-\""
         );
     }
+
+    #[cfg(feature = "smallvec")]
+    #[test]
+    fn parse_smallvec() {
+        let res: smallvec::SmallVec<[crate::text::OneOf<".">; 2]> = parse("...-".chars()).unwrap();
+        assert_eq!(res.len(), 3);
+        assert!(res.spilled());
+    }
 }
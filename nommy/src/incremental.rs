@@ -0,0 +1,289 @@
+//! Incremental reparsing of a sequence of items
+//!
+//! Only the top level sequence is incremental. Each item that is affected by an edit
+//! is parsed again in full, including any nested values it contains, so this works best
+//! when the input is a long list of small items (eg the statements of a file).
+//!
+//! ```
+//! use nommy::{incremental::{Edit, Incremental}, text::{AnyOf1, WhiteSpace}, Parse};
+//!
+//! #[derive(Debug, Parse, PartialEq)]
+//! #[nommy(ignore = WhiteSpace)]
+//! struct Word(#[nommy(parser = AnyOf1<"abcdefghijklmnopqrstuvwxyz">)] String);
+//!
+//! let mut doc = Incremental::<Word>::parse("foo bar baz".chars()).unwrap();
+//! assert_eq!(doc.items().len(), 3);
+//!
+//! // replace "bar" with "quux". Only the items touching the edit are parsed again.
+//! // "foo " (span 0..4) ends where the edit starts, so it is parsed again too,
+//! // since it might have continued into the edited text
+//! let reparsed = doc.edit("foo quux baz".chars(), Edit { range: 4..7, len: 4 }).unwrap();
+//! assert_eq!(reparsed, 0..2);
+//! assert_eq!(doc.items()[1].value, Word("quux".to_string()));
+//! assert_eq!(doc.items()[2].span, 9..12);
+//! ```
+
+use std::ops::Range;
+
+use crate::{eyre, Buffer, Context, IntoBuf, Parse};
+
+/// A single parsed item, along with the range of input it was parsed from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Item<P> {
+    /// The parsed value
+    pub value: P,
+    /// The range of the input this value was parsed from
+    pub span: Range<usize>,
+}
+
+/// A change made to the input. The elements in `range` of the old input were replaced
+/// by `len` new elements
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edit {
+    /// Range of the old input that was replaced
+    pub range: Range<usize>,
+    /// How many elements were inserted in place of `range`
+    pub len: usize,
+}
+
+/// `Incremental` stores the results of parsing a sequence of `P`, along with their spans.
+///
+/// After the input is edited, only the items that overlap the edit are parsed again,
+/// and the rest are spliced back in.
+///
+/// An item is assumed to only depend on the input it consumed, plus a single element of lookahead.
+/// Items that touch the edited range are always parsed again.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Incremental<P> {
+    items: Vec<Item<P>>,
+    len: usize,
+}
+
+impl<P> Incremental<P> {
+    /// The items parsed, in order
+    #[must_use]
+    pub fn items(&self) -> &[Item<P>] {
+        &self.items
+    }
+
+    /// Consumes the parse result, returning the parsed items
+    #[must_use]
+    pub fn into_items(self) -> Vec<Item<P>> {
+        self.items
+    }
+
+    /// The total length of input that was parsed
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if no input was parsed
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Parses the input completely into a sequence of `P`
+    ///
+    /// # Errors
+    /// Will error if the input could not be completely parsed
+    pub fn parse<T, I>(input: I) -> eyre::Result<Self>
+    where
+        P: Parse<T>,
+        T: Clone,
+        I: IntoIterator<Item = T>,
+    {
+        let mut buffer = input.into_iter().into_buf();
+        let (items, len, _) = parse_items(&mut buffer, 0, 0, &[])?;
+        Ok(Self { items, len })
+    }
+
+    /// Parses the items affected by `edit` again. `input` is the full input after the edit was applied.
+    /// Returns the range of item indices that were parsed again. On error, `self` is left unchanged.
+    ///
+    /// # Errors
+    /// Will error if the new input could not be completely parsed
+    ///
+    /// # Panics
+    /// Panics if `edit` is out of bounds of the previously parsed input
+    pub fn edit<T, I>(&mut self, input: I, edit: Edit) -> eyre::Result<Range<usize>>
+    where
+        P: Parse<T>,
+        T: Clone,
+        I: IntoIterator<Item = T>,
+    {
+        let Edit { range, len } = edit;
+        assert!(
+            range.end <= self.len,
+            "edit {:?} out of bounds of input with length {}",
+            range,
+            self.len
+        );
+
+        // items ending before the edit are untouched
+        let keep = self
+            .items
+            .iter()
+            .take_while(|item| item.span.end < range.start)
+            .count();
+        // items starting after the edit can be reused if parsing realigns with them
+        let reuse = self
+            .items
+            .iter()
+            .position(|item| item.span.start > range.end)
+            .unwrap_or(self.items.len());
+
+        let start = self.items[..keep].last().map_or(0, |item| item.span.end);
+        let edit_end = range.start + len;
+        let shift = |pos: usize| pos - range.end + edit_end;
+        let tail_starts: Vec<usize> = self.items[reuse..]
+            .iter()
+            .map(|item| shift(item.span.start))
+            .collect();
+
+        let mut buffer = input.into_iter().skip(start).into_buf();
+        let (parsed, end, realigned) = parse_items(&mut buffer, start, edit_end, &tail_starts)?;
+
+        let tail = self.items.split_off(reuse);
+        self.items.truncate(keep);
+        let reparsed = keep..keep + parsed.len();
+        self.items.extend(parsed);
+        if let Some(i) = realigned {
+            self.items.extend(tail.into_iter().skip(i).map(|item| Item {
+                value: item.value,
+                span: shift(item.span.start)..shift(item.span.end),
+            }));
+            self.len = self.len - range.len() + len;
+        } else {
+            self.len = end;
+        }
+
+        Ok(reparsed)
+    }
+}
+
+/// Parses items starting from `pos` until the input is empty.
+/// Once parsing has passed `edit_end` and realigns with one of `tail_starts`, parsing stops early
+/// so that the remaining items can be reused.
+/// Returns the parsed items, the end position, and the index into `tail_starts` that parsing realigned with
+fn parse_items<P, T>(
+    input: &mut impl Buffer<T>,
+    mut pos: usize,
+    edit_end: usize,
+    tail_starts: &[usize],
+) -> eyre::Result<(Vec<Item<P>>, usize, Option<usize>)>
+where
+    P: Parse<T>,
+{
+    let mut output = Vec::new();
+    loop {
+        if pos >= edit_end {
+            if let Ok(i) = tail_starts.binary_search(&pos) {
+                return Ok((output, pos, Some(i)));
+            }
+        }
+
+        if input.cursor().next().is_none() {
+            return Ok((output, pos, None));
+        }
+
+        let mut cursor = input.cursor();
+        let value = P::parse(&mut cursor)
+            .wrap_err_with(|| format!("failed to parse item at position {}", pos))?;
+        let len = cursor.position();
+        if len == 0 {
+            return Err(eyre::eyre!(
+                "parsing succeeded with 0 elements read at position {}",
+                pos
+            ));
+        }
+        input.fast_forward(len);
+
+        output.push(Item {
+            value,
+            span: pos..pos + len,
+        });
+        pos += len;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::{AnyOf1, Space};
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Word(String);
+
+    impl Parse<char> for Word {
+        fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
+            let word = AnyOf1::<"abcdefghijklmnopqrstuvwxyz">::parse(input)?;
+            Vec::<Space>::peek(input);
+            Ok(Self(word.into()))
+        }
+    }
+
+    fn words(doc: &Incremental<Word>) -> Vec<String> {
+        doc.items()
+            .iter()
+            .map(|item| item.value.0.clone())
+            .collect()
+    }
+
+    #[test]
+    fn edit_middle() {
+        let mut doc = Incremental::<Word>::parse("foo bar baz".chars()).unwrap();
+        assert_eq!(words(&doc), vec!["foo", "bar", "baz"]);
+
+        // "bar" -> "quux"
+        let reparsed = doc
+            .edit(
+                "foo quux baz".chars(),
+                Edit {
+                    range: 4..7,
+                    len: 4,
+                },
+            )
+            .unwrap();
+        assert_eq!(reparsed, 0..2);
+        assert_eq!(words(&doc), vec!["foo", "quux", "baz"]);
+        assert_eq!(doc.items()[2].span, 9..12);
+        assert_eq!(doc.len(), 12);
+    }
+
+    #[test]
+    fn edit_merges_items() {
+        let mut doc = Incremental::<Word>::parse("foo bar baz".chars()).unwrap();
+
+        // remove the space between "foo" and "bar"
+        let reparsed = doc
+            .edit(
+                "foobar baz".chars(),
+                Edit {
+                    range: 3..4,
+                    len: 0,
+                },
+            )
+            .unwrap();
+        assert_eq!(reparsed, 0..1);
+        assert_eq!(words(&doc), vec!["foobar", "baz"]);
+        assert_eq!(doc.items()[1].span, 7..10);
+    }
+
+    #[test]
+    fn edit_error_unchanged() {
+        let mut doc = Incremental::<Word>::parse("foo bar baz".chars()).unwrap();
+        let before = doc.clone();
+
+        let res = doc.edit(
+            "foo b4r baz".chars(),
+            Edit {
+                range: 5..6,
+                len: 1,
+            },
+        );
+        assert!(res.is_err());
+        assert_eq!(doc, before);
+    }
+}
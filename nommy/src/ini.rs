@@ -0,0 +1,230 @@
+//! Parser for INI style files, with `[section]` headers, `key = value` entries and `;` or `#` comments.
+//!
+//! Keys before the first section header are kept in [`Ini::globals`]. Values run to the end of the line,
+//! or the start of a comment, with any surrounding spaces trimmed.
+//!
+//! ```
+//! use nommy::ini;
+//!
+//! let file = ini::parse("
+//! name = example ; the name
+//!
+//! [server]
+//! host = localhost
+//! port = 8080
+//! ").unwrap();
+//!
+//! assert_eq!(file.globals[0].value, "example");
+//! assert_eq!(file.section("server").unwrap().get("port"), Some("8080"));
+//!
+//! let err = ini::parse("[server]\nhost localhost").unwrap_err();
+//! assert_eq!(err.span, 14..15);
+//! ```
+
+use crate::{
+    eyre,
+    message::Message,
+    span::{self, SpannedError},
+    text::{AnyOf1, LineEnding, OneOf, Space, Tag, WhileNot1},
+    trivia::{self, Trivia},
+    Buffer, Parse,
+};
+
+/// A parsed INI file
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Ini {
+    /// The entries before the first section header
+    pub globals: Vec<Entry>,
+    /// The sections, in the order they appear
+    pub sections: Vec<Section>,
+}
+
+impl Ini {
+    /// The first section called `name`
+    #[must_use]
+    pub fn section(&self, name: &str) -> Option<&Section> {
+        self.sections.iter().find(|s| s.name == name)
+    }
+}
+
+/// A `[section]` header and the entries following it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section {
+    /// The name between the brackets
+    pub name: String,
+    /// The entries in the section, in the order they appear
+    pub entries: Vec<Entry>,
+}
+
+impl Section {
+    /// The value of the first entry with the given key
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .find(|e| e.key == key)
+            .map(|e| e.value.as_str())
+    }
+}
+
+/// A `key = value` entry
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    /// The text before the `=`
+    pub key: String,
+    /// The text after the `=`, with surrounding spaces trimmed
+    pub value: String,
+}
+
+/// Parses an INI file
+///
+/// # Errors
+/// If any line isn't a section header, an entry, or blank. The error is reported at the span
+/// of the furthest char read, as with [`span::parse_at`]
+pub fn parse(source: &str) -> Result<Ini, SpannedError> {
+    let lines = span::parse_at::<File>(source, 0)?;
+    let end = lines.span.end;
+    if end < source.chars().count() {
+        // the lines stopped early, so find out why the next one couldn't be parsed
+        return match span::parse_at::<Line>(source, end) {
+            Err(err) => Err(err),
+            Ok(_) => Err(SpannedError::new(
+                end..end,
+                eyre::Report::new(Message::Incomplete),
+            )),
+        };
+    }
+
+    let mut ini = Ini::default();
+    for line in lines.value.lines {
+        match line {
+            Line::Section(name, _) => ini.sections.push(Section {
+                name,
+                entries: Vec::new(),
+            }),
+            Line::Entry(key, value, _) => {
+                let entry = Entry {
+                    key,
+                    value: value.0,
+                };
+                match ini.sections.last_mut() {
+                    Some(section) => section.entries.push(entry),
+                    None => ini.globals.push(entry),
+                }
+            }
+            Line::Blank(_) => {}
+        }
+    }
+    Ok(ini)
+}
+
+type Key = AnyOf1<"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789_-.">;
+
+#[derive(Parse)]
+#[nommy(prefix = OneOf<";#">)]
+struct Comment {
+    _text: Option<WhileNot1<"\n">>,
+}
+
+/// Spaces and comments. Line endings are significant, so they aren't trivia
+struct Inline;
+impl Trivia<char> for Inline {
+    fn skip(input: &mut impl Buffer<char>) -> bool {
+        trivia::skip::<Space, _>(input) || trivia::skip::<Comment, _>(input)
+    }
+}
+
+/// The rest of the line, up to a comment
+struct Value(String);
+
+impl Parse<char> for Value {
+    fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
+        let mut value = String::new();
+        loop {
+            match input.peek_ahead(0) {
+                Some(c) if !"\r\n;#".contains(c) => value.push(c),
+                _ => break,
+            }
+            input.fast_forward(1);
+        }
+        Ok(Self(value.trim_end().to_owned()))
+    }
+}
+
+/// The end of a line, or of the input
+struct Eol;
+
+impl Parse<char> for Eol {
+    fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
+        if Self::peek(input) {
+            Ok(Self)
+        } else {
+            Err(eyre::eyre!("expected the end of the line"))
+        }
+    }
+
+    fn peek(input: &mut impl Buffer<char>) -> bool {
+        input.peek_ahead(0).is_none() || LineEnding::peek(input)
+    }
+}
+
+#[derive(Parse)]
+#[nommy(ignore_trivia = Inline)]
+enum Line {
+    Section(
+        #[nommy(prefix = Tag<"[">, parser = Key, suffix = Tag<"]">)] String,
+        Eol,
+    ),
+    Entry(
+        #[nommy(parser = Key)] String,
+        #[nommy(prefix = Tag<"=">)] Value,
+        Eol,
+    ),
+    Blank(LineEnding),
+}
+
+/// Leading trivia, then each line. Trivia at the start of the other lines is
+/// skipped by the line before
+#[derive(Parse)]
+#[nommy(ignore_trivia = Inline)]
+#[nommy(parse_type = char)]
+struct File {
+    #[nommy(prefix = Tag<"">, inner_parser = Line)]
+    lines: Vec<Line>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sections() {
+        let file = parse(
+            "; header comment\n  a=1\n[one]\n\tkey = some value # comment\r\nempty =\n[ two ]\n\n[one]",
+        )
+        .unwrap();
+        assert_eq!(
+            file.globals,
+            vec![Entry {
+                key: "a".to_owned(),
+                value: "1".to_owned()
+            }]
+        );
+        let names: Vec<&str> = file.sections.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["one", "two", "one"]);
+
+        let one = file.section("one").unwrap();
+        assert_eq!(one.get("key"), Some("some value"));
+        assert_eq!(one.get("empty"), Some(""));
+        assert_eq!(one.get("missing"), None);
+    }
+
+    #[test]
+    fn error_span() {
+        let err = parse("[a]\n[b\n").unwrap_err();
+        assert_eq!(err.span, 6..7);
+        assert_eq!(err.error.to_string(), "failed to parse Line as variant `Section`");
+
+        assert_eq!(parse("").unwrap(), Ini::default());
+    }
+}
@@ -0,0 +1,245 @@
+//! Interning parsed text into compact symbols, for identifier heavy grammars.
+//!
+//! [`Interned`] parses text with `P` and interns it into the active [`Interner`],
+//! storing only the symbol. An interner is made active with [`scope`], the same way
+//! a [`Grammar`](crate::extension::Grammar) is.
+//!
+//! ```
+//! use nommy::{intern::{self, Interned, Interner}, parse, text::{AnyOf1, WhiteSpace}, Parse};
+//!
+//! /// Interns each word as its index in a list
+//! #[derive(Default)]
+//! struct Words(Vec<String>);
+//!
+//! impl Interner for Words {
+//!     type Symbol = usize;
+//!     fn intern(&mut self, text: &str) -> usize {
+//!         match self.0.iter().position(|w| w == text) {
+//!             Some(i) => i,
+//!             None => {
+//!                 self.0.push(text.to_owned());
+//!                 self.0.len() - 1
+//!             }
+//!         }
+//!     }
+//! }
+//!
+//! type Word = Interned<AnyOf1<"abcdefghijklmnopqrstuvwxyz">, Words>;
+//!
+//! #[derive(Parse)]
+//! #[nommy(ignore = WhiteSpace)]
+//! struct Sentence(#[nommy(inner_parser = Word)] Vec<Word>);
+//!
+//! let mut words = Words::default();
+//! let sentence: Sentence = intern::scope(&mut words, || parse("the cat and the hat".chars())).unwrap();
+//!
+//! let symbols: Vec<usize> = sentence.0.iter().map(|w| w.symbol()).collect();
+//! assert_eq!(symbols, vec![0, 1, 2, 0, 3]);
+//! assert_eq!(words.0, vec!["the", "cat", "and", "hat"]);
+//! ```
+
+use std::{
+    any::{type_name, Any},
+    cell::RefCell,
+    fmt,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+};
+
+use crate::{eyre, Buffer, Parse};
+
+/// An interner that maps text to compact symbols
+pub trait Interner: 'static {
+    /// The symbol that represents some interned text
+    type Symbol;
+
+    /// Returns the symbol for `text`, interning it if it hasn't been seen before
+    fn intern(&mut self, text: &str) -> Self::Symbol;
+}
+
+/// Requires the `lasso` feature
+#[cfg(feature = "lasso")]
+impl<K, S> Interner for lasso::Rodeo<K, S>
+where
+    K: lasso::Key + 'static,
+    S: std::hash::BuildHasher + Clone + 'static,
+{
+    type Symbol = K;
+    fn intern(&mut self, text: &str) -> K {
+        self.get_or_intern(text)
+    }
+}
+
+thread_local! {
+    static ACTIVE: RefCell<Vec<Box<dyn Any>>> = RefCell::new(Vec::new());
+}
+
+/// Moves the active interner back to where it was borrowed from when dropped,
+/// even if parsing panicked
+struct Restore<'a, I: Interner> {
+    interner: &'a mut I,
+}
+
+impl<I: Interner> Drop for Restore<'_, I> {
+    fn drop(&mut self) {
+        let active = ACTIVE.with(|active| active.borrow_mut().pop());
+        if let Some(Ok(interner)) = active.map(<Box<dyn Any>>::downcast::<I>) {
+            *self.interner = *interner;
+        }
+    }
+}
+
+/// Runs `f` with `interner` active on the current thread.
+/// [`Interned`] values parsed inside `f` are interned into it.
+///
+/// Scopes can be nested, and the innermost interner of the right type is used
+pub fn scope<I: Interner + Default, R>(interner: &mut I, f: impl FnOnce() -> R) -> R {
+    let active = Box::new(std::mem::take(interner));
+    ACTIVE.with(|a| a.borrow_mut().push(active));
+    let _restore = Restore { interner };
+    f()
+}
+
+/// Interns `text` into the innermost active interner of type `I`
+///
+/// # Errors
+/// If there's no active interner of type `I`
+pub fn intern<I: Interner>(text: &str) -> eyre::Result<I::Symbol> {
+    ACTIVE.with(|active| {
+        active
+            .borrow_mut()
+            .iter_mut()
+            .rev()
+            .find_map(|a| a.downcast_mut::<I>())
+            .map(|interner| interner.intern(text))
+            .ok_or_else(|| {
+                eyre::eyre!(
+                    "no interner of type {} is active. Use `intern::scope`",
+                    type_name::<I>()
+                )
+            })
+    })
+}
+
+/// `Interned` parses `P` and interns the text into the active interner `I`,
+/// storing only the symbol. See the [module level docs](self)
+pub struct Interned<P, I: Interner> {
+    symbol: I::Symbol,
+    _p: PhantomData<P>,
+}
+
+impl<P, I: Interner> Interned<P, I> {
+    /// The symbol of the interned text
+    pub fn symbol(&self) -> I::Symbol
+    where
+        I::Symbol: Copy,
+    {
+        self.symbol
+    }
+
+    /// Returns the inner symbol
+    pub fn into_symbol(self) -> I::Symbol {
+        self.symbol
+    }
+}
+
+impl<P: Parse<char> + Into<String>, I: Interner> Parse<char> for Interned<P, I> {
+    fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
+        let text: String = P::parse(input)?.into();
+        Ok(Self {
+            symbol: intern::<I>(&text)?,
+            _p: PhantomData,
+        })
+    }
+
+    fn peek(input: &mut impl Buffer<char>) -> bool {
+        P::peek(input)
+    }
+}
+
+// implemented by hand so that only the symbol needs to implement them
+
+impl<P, I: Interner> fmt::Debug for Interned<P, I>
+where
+    I::Symbol: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Interned").field(&self.symbol).finish()
+    }
+}
+
+impl<P, I: Interner> Clone for Interned<P, I>
+where
+    I::Symbol: Clone,
+{
+    fn clone(&self) -> Self {
+        Self {
+            symbol: self.symbol.clone(),
+            _p: PhantomData,
+        }
+    }
+}
+
+impl<P, I: Interner> Copy for Interned<P, I> where I::Symbol: Copy {}
+
+impl<P, I: Interner> PartialEq for Interned<P, I>
+where
+    I::Symbol: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.symbol == other.symbol
+    }
+}
+
+impl<P, I: Interner> Eq for Interned<P, I> where I::Symbol: Eq {}
+
+impl<P, I: Interner> Hash for Interned<P, I>
+where
+    I::Symbol: Hash,
+{
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.symbol.hash(state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse, text::AnyOf1};
+
+    #[derive(Default)]
+    struct Counter(usize);
+
+    impl Interner for Counter {
+        type Symbol = usize;
+        fn intern(&mut self, _: &str) -> usize {
+            self.0 += 1;
+            self.0
+        }
+    }
+
+    type Word = Interned<AnyOf1<"abc">, Counter>;
+
+    #[test]
+    fn no_active_interner() {
+        let err = parse::<Word, _>("abc".chars()).unwrap_err();
+        assert!(err.to_string().starts_with("no interner of type"));
+    }
+
+    #[test]
+    fn nested_scopes() {
+        let mut outer = Counter(10);
+        let mut inner = Counter(20);
+        let (a, b) = scope(&mut outer, || {
+            let a: Word = parse("a".chars()).unwrap();
+            let b: Word = scope(&mut inner, || parse("b".chars())).unwrap();
+            (a, b)
+        });
+        assert_eq!(a.symbol(), 11);
+        assert_eq!(b.symbol(), 21);
+
+        // the interners are moved back out of the scope
+        assert_eq!(outer.0, 11);
+        assert_eq!(inner.0, 21);
+    }
+}
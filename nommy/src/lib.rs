@@ -59,15 +59,50 @@
 //! );
 //! ```
 
+#[cfg(feature = "arena")]
+pub mod arena;
 mod buffer;
 pub use buffer::*;
 pub mod bytes;
+pub mod channel;
+pub mod complete;
+#[cfg(feature = "derive_test")]
+pub mod derive_test;
+pub mod embedded;
+pub mod error;
+pub mod extension;
+pub mod fallible;
+pub mod flatten;
+pub mod framed;
+#[cfg(feature = "http")]
+pub mod http;
 mod impls;
+pub mod incremental;
+#[cfg(feature = "ini")]
+pub mod ini;
+pub mod intern;
+pub mod lookahead;
+#[cfg(feature = "lsp")]
+pub mod lsp;
+pub mod map;
+pub mod message;
+pub mod operator;
+#[cfg(feature = "rayon")]
+pub mod parallel;
+pub mod prelude;
+pub mod process;
+pub mod punctuated;
+pub mod span;
+pub mod testing;
 pub mod text;
+pub mod trivia;
+pub mod unparse;
 pub mod vec;
 
 use eyre::Context;
-pub use impls::Vec1;
+pub use impls::{Peeked, Vec1};
+pub use process::Process;
+pub use unparse::Unparse;
 
 /// Derive Parse for structs or enums
 ///
@@ -122,7 +157,104 @@ pub use impls::Vec1;
 /// ```
 pub use nommy_derive::Parse;
 
+/// Derive [`Peek`](trait@Peek) for structs or enums, from the same attributes as [`Parse`](derive@Parse).
+///
+/// Only the peek function is derived, so the fields don't need to be converted from what their parsers output,
+/// since they're never built. This suits types that are only ever looked for, such as the sentinel that ends a list.
+/// [`Peeked`] parses over the input that such a type peeks.
+/// To derive `Parse` without the peek function instead, keeping the default that parses the value, use `#[nommy(no_peek)]`
+///
+/// ```
+/// use std::marker::PhantomData;
+/// use nommy::{parse, text::{Tag, WhiteSpace}, IntoBuf, Peek, Peeked};
+///
+/// /// the end of a block, `;` then `end`
+/// #[derive(Peek)]
+/// #[nommy(ignore = WhiteSpace)]
+/// struct End {
+///     #[nommy(parser = Tag<";">)]
+///     semi: PhantomData<()>,
+///     #[nommy(parser = Tag<"end">)]
+///     end: PhantomData<()>,
+/// }
+///
+/// assert!(End::peek(&mut "; end".chars().into_buf()));
+/// assert!(parse::<Peeked<End>, _>(";end".chars()).is_ok());
+/// assert!(parse::<Peeked<End>, _>(";and".chars()).is_err());
+/// ```
+pub use nommy_derive::Peek;
+
+/// Derive Unparse for structs or enums, from the same attributes as [`Parse`](derive@Parse).
+///
+/// Prefixes, suffixes and separators are written out using their [`Default`] value, and the first
+/// `ignore` type is written out wherever the parser would ignore input
+///
+/// ```
+/// use nommy::{parse, text::*, unparse, Parse, Unparse};
+///
+/// type Letters = AnyOf1<"abcdefghijklmnopqrstuvwxyz">;
+///
+/// #[derive(Debug, Parse, Unparse, PartialEq)]
+/// #[nommy(prefix = Tag<"let">, suffix = Tag<";">)]
+/// #[nommy(ignore = WhiteSpace)]
+/// #[nommy(parse_type = char)]
+/// struct Let {
+///     #[nommy(parser = Letters)]
+///     name: String,
+///
+///     #[nommy(prefix = Tag<"=">, inner_parser = Letters, seperated_by = Tag<"+">)]
+///     value: Vec<String>,
+/// }
+///
+/// let value: Let = parse("let  x=a +b+ c;".chars()).unwrap();
+/// let output = unparse::to_string(&value);
+/// assert_eq!(output, "let x = a + b + c;");
+/// assert_eq!(parse::<Let, _>(output.chars()).unwrap(), value);
+/// ```
+pub use nommy_derive::Unparse;
+
+/// Derive Process for structs or enums, generating an output type named after the derived type
+/// with `Output` appended. The output type has the same fields and variants,
+/// where each field holds the [`Process::Output`](process::Process::Output) of the original field.
+///
+/// The output type has the same visibility as the derived type. It derives nothing unless
+/// the traits are listed with `#[nommy(output_derive = "...")]`.
+/// With type parameters, those derives bound the parameters themselves as well as their outputs
+///
+/// ```
+/// use nommy::{parse, text::*, Parse, Process};
+///
+/// #[derive(Parse, Process)]
+/// #[nommy(ignore = WhiteSpace)]
+/// #[nommy(output_derive = "Debug, PartialEq")]
+/// struct Let {
+///     keyword: Tag<"let">,
+///     name: AnyOf1<"abcdefghijklmnopqrstuvwxyz">,
+///     equals: Tag<"=">,
+///     value: Vec<OneOf<"0123456789">>,
+/// }
+///
+/// let value: Let = parse("let x = 12".chars()).unwrap();
+/// assert_eq!(
+///     value.process(),
+///     LetOutput {
+///         keyword: (),
+///         name: "x".to_owned(),
+///         equals: (),
+///         value: vec!['1', '2'],
+///     }
+/// );
+/// ```
+pub use nommy_derive::Process;
+
+// lets the derive macros be used within this crate
+extern crate self as nommy;
+
 pub use eyre;
+#[cfg(feature = "smallvec")]
+pub use smallvec;
+#[cfg(feature = "smartstring")]
+pub use smartstring;
 
 /// `parse` takes the given iterator, putting it through [`P::parse`](Parse::parse)
 ///
@@ -169,7 +301,7 @@ where
     let mut buffer = iter.into_buf();
     let output = P::parse(&mut buffer)?;
     if buffer.next().is_some() {
-        Err(eyre::eyre!("input was not parsed completely"))
+        Err(eyre::Report::new(message::Message::Incomplete))
     } else {
         Ok(output)
     }
@@ -196,7 +328,28 @@ pub trait Parse<T>: Sized {
     /// Peek reads the input buffer, returning true if the value could be found,
     /// Otherwise, returns false.
     /// Not required, but usually provides better performance if implemented
+    ///
+    /// The default implementation parses the value, so it can allocate. The peeks of the tags and character sets
+    /// in [`text`] and [`bytes`], of [`Vec`], [`Vec1`] and [`Option`], and of derived types made from them don't.
+    /// On a [`SliceBuf`] or [`VecBuf`], those only read the input in place, whereas [`Buf`] stores every value it peeks
     fn peek(input: &mut impl Buffer<T>) -> bool {
         Self::parse(input).is_ok()
     }
+
+    /// The most values that [`peek`](Self::peek) reads from the input, or `None` if there's no limit.
+    ///
+    /// Derived impls combine the lookahead of their fields with the functions in [`lookahead`],
+    /// so a type that ignores whitespace, or has a field that repeats, is unbounded
+    const MAX_PEEK: Option<usize> = None;
+}
+
+/// A type that can only be looked for in the input, without being parsed into a value.
+/// It can be derived with [`Peek`](derive@Peek), and parsed over with [`Peeked`]
+pub trait Peek<T> {
+    /// Reads the input buffer, returning true if the value could be found, like [`Parse::peek`]
+    fn peek(input: &mut impl Buffer<T>) -> bool;
+
+    /// The most values that [`peek`](Self::peek) reads from the input, or `None` if there's no limit,
+    /// like [`Parse::MAX_PEEK`]
+    const MAX_PEEK: Option<usize> = None;
 }
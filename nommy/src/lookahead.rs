@@ -0,0 +1,56 @@
+//! Combining the lookahead of parsers, as given by [`Parse::MAX_PEEK`](crate::Parse::MAX_PEEK)
+//!
+//! `None` means the lookahead is unbounded, and stays unbounded when combined with anything else.
+//!
+//! ```
+//! use nommy::{lookahead, text::{AnyOf1, Tag}, Parse};
+//!
+//! const ARROW: Option<usize> = <Tag<"=>"> as Parse<char>>::MAX_PEEK;
+//! assert_eq!(ARROW, Some(2));
+//! assert_eq!(lookahead::then(ARROW, <Tag<">"> as Parse<char>>::MAX_PEEK), Some(3));
+//! assert_eq!(lookahead::then(ARROW, <AnyOf1<"ab"> as Parse<char>>::MAX_PEEK), None);
+//! ```
+
+/// The lookahead of peeking `a` and then `b`
+#[must_use]
+pub const fn then(a: Option<usize>, b: Option<usize>) -> Option<usize> {
+    match (a, b) {
+        (Some(a), Some(b)) => a.checked_add(b),
+        _ => None,
+    }
+}
+
+/// The lookahead of peeking either `a` or `b`, from the same position
+#[must_use]
+pub const fn either(a: Option<usize>, b: Option<usize>) -> Option<usize> {
+    match (a, b) {
+        (Some(a), Some(b)) if a > b => Some(a),
+        (Some(_), Some(b)) => Some(b),
+        _ => None,
+    }
+}
+
+/// The lookahead of peeking `a`, `n` times in a row
+#[must_use]
+pub const fn repeat(a: Option<usize>, n: usize) -> Option<usize> {
+    match a {
+        Some(a) => a.checked_mul(n),
+        None => None,
+    }
+}
+
+/// The number of `char`s in `s`
+#[must_use]
+pub const fn chars(s: &str) -> usize {
+    let bytes = s.as_bytes();
+    let mut count = 0;
+    let mut i = 0;
+    while i < bytes.len() {
+        // every char has exactly one byte that isn't a continuation byte
+        if bytes[i] & 0xC0 != 0x80 {
+            count += 1;
+        }
+        i += 1;
+    }
+    count
+}
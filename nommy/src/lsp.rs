@@ -0,0 +1,208 @@
+//! Conversions from nommy errors into [`lsp_types`] diagnostics, for language servers built on nommy.
+//! Requires the `lsp` feature
+//!
+//! nommy errors don't carry spans, so [`parse`] reports an error at the furthest
+//! character the parser read before failing. This is usually, but not always, where the mistake is.
+//! Each context frame of the error, such as the field that failed, is related information
+//! spanning from where that field started.
+//!
+//! ```
+//! use nommy::{lsp, text::Tag};
+//! use lsp_types::{Position, Url};
+//!
+//! let uri = Url::parse("file:///main.txt").unwrap();
+//! let diagnostic = lsp::parse::<Tag<"foo">>(&uri, "fob").unwrap_err();
+//! assert_eq!(diagnostic.range.start, Position::new(0, 2));
+//! assert_eq!(diagnostic.message, "failed to parse tag \"foo\", found \"fob\"");
+//! ```
+
+use std::{convert::TryFrom, ops::Range};
+
+use lsp_types::{Diagnostic, DiagnosticRelatedInformation, DiagnosticSeverity, Location, Position, Url};
+
+use crate::{
+    eyre,
+    message::Message,
+    span::{self, Spanned, SpannedError},
+    Parse,
+};
+
+/// Converts a char offset into `source` into an LSP [`Position`] (line and UTF-16 column)
+#[must_use]
+pub fn position(source: &str, offset: usize) -> Position {
+    let mut line = 0;
+    let mut character = 0;
+    for c in source.chars().take(offset) {
+        if c == '\n' {
+            line += 1;
+            character = 0;
+        } else {
+            character += c.len_utf16();
+        }
+    }
+    Position::new(
+        u32::try_from(line).unwrap_or(u32::MAX),
+        u32::try_from(character).unwrap_or(u32::MAX),
+    )
+}
+
+/// Converts a char span of `source` into an LSP [`Range`](lsp_types::Range)
+#[must_use]
+pub fn range(source: &str, span: Range<usize>) -> lsp_types::Range {
+    lsp_types::Range::new(position(source, span.start), position(source, span.end))
+}
+
+/// Creates a [`Diagnostic`] for `error` in the document `uri` with the text `source`.
+///
+/// The message is the root cause of the error. Each context frame wrapping it
+/// (eg `failed to parse field ...`) is related information at the frame's span, innermost first
+#[must_use]
+pub fn diagnostic(
+    uri: &Url,
+    source: &str,
+    severity: DiagnosticSeverity,
+    error: &SpannedError,
+) -> Diagnostic {
+    let mut related: Vec<DiagnosticRelatedInformation> = error
+        .error
+        .chain()
+        .zip(&error.frames)
+        .map(|(frame, span)| DiagnosticRelatedInformation {
+            location: Location::new(uri.clone(), range(source, span.clone())),
+            message: frame.to_string(),
+        })
+        .collect();
+    related.reverse();
+
+    Diagnostic {
+        range: range(source, error.span.clone()),
+        severity: Some(severity),
+        source: Some("nommy".to_string()),
+        message: error.error.root_cause().to_string(),
+        related_information: Some(related).filter(|related| !related.is_empty()),
+        ..Diagnostic::default()
+    }
+}
+
+/// Parses all of `source` into `P`, converting any error into a [`Diagnostic`].
+///
+/// Errors are reported at the furthest character the parser read, or at the end
+/// of the document if the parser ran out of input. This is a heuristic, since the
+/// errors themselves don't record where they happened.
+///
+/// # Errors
+/// If `P` failed to parse `source`, or did not consume all of it
+#[allow(clippy::result_large_err)]
+pub fn parse<P: Parse<char>>(uri: &Url, source: &str) -> Result<P, Diagnostic> {
+    let Spanned { value, span } = parse_at(uri, source, 0)?;
+    if source.chars().nth(span.end).is_some() {
+        let error = SpannedError::new(
+            span.end..span.end + 1,
+            eyre::Report::new(Message::Incomplete),
+        );
+        Err(diagnostic(uri, source, DiagnosticSeverity::ERROR, &error))
+    } else {
+        Ok(value)
+    }
+}
+
+/// Parses `P` from `source` starting at the char `offset`, converting any error into a [`Diagnostic`].
+/// Ranges are relative to the whole of `source`. See [`span::parse_at`](crate::span::parse_at)
+///
+/// # Errors
+/// If `P` failed to parse `source` at `offset`
+#[allow(clippy::result_large_err)]
+pub fn parse_at<P: Parse<char>>(
+    uri: &Url,
+    source: &str,
+    offset: usize,
+) -> Result<Spanned<P>, Diagnostic> {
+    span::parse_at(source, offset)
+        .map_err(|err| diagnostic(uri, source, DiagnosticSeverity::ERROR, &err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::Tag;
+
+    fn uri() -> Url {
+        Url::parse("file:///test.txt").unwrap()
+    }
+
+    #[test]
+    fn positions() {
+        assert_eq!(position("ab\ncd", 4), Position::new(1, 1));
+        // '😀' takes up 2 UTF-16 code units
+        assert_eq!(position("😀x", 1), Position::new(0, 2));
+    }
+
+    #[test]
+    fn context_as_related_information() {
+        let error = eyre::eyre!("failed to parse tag")
+            .wrap_err("failed to parse field `foo`")
+            .wrap_err("failed to parse struct `Foo`");
+        let error = SpannedError {
+            span: 1..2,
+            error,
+            frames: vec![0..2, 1..2],
+        };
+        let diagnostic = diagnostic(&uri(), "abc", DiagnosticSeverity::WARNING, &error);
+        assert_eq!(diagnostic.message, "failed to parse tag");
+        assert_eq!(diagnostic.severity, Some(DiagnosticSeverity::WARNING));
+        assert_eq!(
+            diagnostic.range,
+            lsp_types::Range::new(Position::new(0, 1), Position::new(0, 2))
+        );
+
+        let related = diagnostic.related_information.unwrap();
+        let related: Vec<_> = related
+            .iter()
+            .map(|info| (info.message.as_str(), info.location.range))
+            .collect();
+        assert_eq!(
+            related,
+            vec![
+                (
+                    "failed to parse field `foo`",
+                    lsp_types::Range::new(Position::new(0, 1), Position::new(0, 2))
+                ),
+                (
+                    "failed to parse struct `Foo`",
+                    lsp_types::Range::new(Position::new(0, 0), Position::new(0, 2))
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn no_context() {
+        let diagnostic = parse::<Tag<"foo">>(&uri(), "fob").unwrap_err();
+        assert_eq!(diagnostic.related_information, None);
+    }
+
+    #[test]
+    fn parse_eof() {
+        let diagnostic = parse::<Tag<"\nfoo">>(&uri(), "\nf").unwrap_err();
+        assert_eq!(
+            diagnostic.range,
+            lsp_types::Range::new(Position::new(1, 1), Position::new(1, 1))
+        );
+    }
+
+    #[test]
+    fn parse_at_offset() {
+        let diagnostic = parse_at::<Tag<"foo">>(&uri(), "foo\nfob", 4).unwrap_err();
+        assert_eq!(
+            diagnostic.range,
+            lsp_types::Range::new(Position::new(1, 2), Position::new(1, 3))
+        );
+    }
+
+    #[test]
+    fn parse_incomplete() {
+        let diagnostic = parse::<Tag<"foo">>(&uri(), "foo.").unwrap_err();
+        assert_eq!(diagnostic.message, "input was not parsed completely");
+        assert_eq!(diagnostic.range.start, Position::new(0, 3));
+    }
+}
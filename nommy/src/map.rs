@@ -0,0 +1,60 @@
+//! Support for `#[nommy(map = "path")]`, which passes a freshly parsed value through a function
+//! before it's returned.
+//!
+//! The function can return the value, for transformations that can't fail, or a `Result` to reject it.
+//! On a type, it's given the whole parsed value. On a field, it's given the field's value,
+//! and its errors are reported for that field.
+//!
+//! ```
+//! use nommy::{eyre, parse, text::{AnyOf1, Tag, WhiteSpace}, Parse};
+//!
+//! fn lowercase(name: String) -> String {
+//!     name.to_lowercase()
+//! }
+//!
+//! fn check_range(range: Range) -> eyre::Result<Range> {
+//!     if range.start > range.end {
+//!         eyre::bail!("range starts after it ends")
+//!     }
+//!     Ok(range)
+//! }
+//!
+//! #[derive(Debug, Parse)]
+//! #[nommy(ignore = WhiteSpace, map = "check_range")]
+//! struct Range {
+//!     #[nommy(parser = AnyOf1<"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ">, map = "lowercase")]
+//!     start: String,
+//!     #[nommy(prefix = Tag<"..">, parser = AnyOf1<"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ">, map = "lowercase")]
+//!     end: String,
+//! }
+//!
+//! let range: Range = parse("A .. z".chars()).unwrap();
+//! assert_eq!(range.start, "a");
+//!
+//! let err = parse::<Range, _>("z..a".chars()).unwrap_err();
+//! assert_eq!(err.to_string(), "range starts after it ends");
+//! ```
+
+use crate::eyre;
+
+/// What a `map` function can return, converted to the result of the parser.
+/// Implemented for the value itself, and for `Result`s of the value
+pub trait MapResult<T> {
+    /// Converts the output of the `map` function into the result of the parser
+    ///
+    /// # Errors
+    /// If the `map` function returned an error
+    fn into_result(self) -> eyre::Result<T>;
+}
+
+impl<T> MapResult<T> for T {
+    fn into_result(self) -> eyre::Result<T> {
+        Ok(self)
+    }
+}
+
+impl<T, E: Into<eyre::Report>> MapResult<T> for Result<T, E> {
+    fn into_result(self) -> eyre::Result<T> {
+        self.map_err(Into::into)
+    }
+}
@@ -0,0 +1,376 @@
+//! The messages of the errors reported by nommy's parsers and by derived parsers, kept in one place.
+//!
+//! Errors are reported as a [`Message`], which can be downcast from the error to check which error it was
+//! without relying on its text. Each message has a default, English, format, which an application
+//! can replace with [`set_format`], for example to translate the messages.
+//!
+//! ```
+//! use std::fmt;
+//! use nommy::{message::{self, Message}, parse, text::Tag};
+//!
+//! fn format(message: &Message, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+//!     match message {
+//!         Message::Tag { tag, .. } => write!(f, "expected `{}`", tag),
+//!         _ => message.fmt_default(f),
+//!     }
+//! }
+//!
+//! let err = parse::<Tag<"foo">, _>("bar".chars()).unwrap_err();
+//! assert!(matches!(err.downcast_ref(), Some(Message::Tag { tag: "foo", .. })));
+//!
+//! message::set_format(format);
+//! assert_eq!(err.to_string(), "expected `foo`");
+//! message::reset_format();
+//! assert_eq!(err.to_string(), "failed to parse tag \"foo\", found \"b\"");
+//! ```
+//!
+//! Translations that are loaded at runtime can be given as a [`Localize`] implementation instead,
+//! with [`set_localizer`]. Each kind of message has a stable [`key`](Message::key) to look its phrasing up by.
+//!
+//! ```
+//! use std::{collections::HashMap, fmt};
+//! use nommy::{message::{self, Localize, Message}, parse, text::OneOf};
+//!
+//! struct Catalog(HashMap<&'static str, &'static str>);
+//!
+//! impl Localize for Catalog {
+//!     fn localize(&self, message: &Message, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+//!         match self.0.get(message.key()) {
+//!             Some(phrase) => f.write_str(phrase),
+//!             None => message.fmt_default(f),
+//!         }
+//!     }
+//! }
+//!
+//! let catalog = Catalog(vec![("one_of", "caractère inattendu")].into_iter().collect());
+//! message::set_localizer(catalog);
+//!
+//! let err = parse::<OneOf<"abc">, _>("d".chars()).unwrap_err();
+//! assert_eq!(err.to_string(), "caractère inattendu");
+//! ```
+//!
+//! Messages are formatted each time they're displayed, so the format applies to errors that already exist.
+//! Parsers for particular formats, such as the ones in [`text`](crate::text), report their own messages
+
+use std::{
+    fmt,
+    sync::{Arc, PoisonError, RwLock},
+};
+
+/// An error reported by one of nommy's parsers, or context added to an error by a derived parser
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Message {
+    /// A [`text::Tag`](crate::text::Tag) didn't match the input.
+    /// `found` is the input that was read, ending with the character that didn't match
+    Tag {
+        /// the tag that was expected
+        tag: &'static str,
+        /// the input that was read
+        found: String,
+    },
+    /// A [`text::ITag`](crate::text::ITag) didn't match the input
+    ITag {
+        /// the tag that was expected, ignoring case
+        tag: &'static str,
+        /// the input that was read
+        found: String,
+    },
+    /// A [`bytes::Tag`](crate::bytes::Tag) didn't match the input
+    BytesTag {
+        /// the tag that was expected
+        tag: &'static [u8],
+        /// the input that was read
+        found: Vec<u8>,
+    },
+    /// None of the tags of a [`TagOneOf`](crate::text::TagOneOf) matched the input
+    TagOneOf {
+        /// the tags that were expected
+        tags: &'static [&'static str],
+        /// the next character of the input
+        found: Option<char>,
+    },
+    /// None of the tags of the active [`TagSet`](crate::text::TagSet) matched the input
+    TagSet {
+        /// the name of the type that names the set
+        set: &'static str,
+        /// the next character of the input
+        found: Option<char>,
+    },
+    /// The next character wasn't one of `chars`, or the input ended if `found` is `None`
+    OneOf {
+        /// the characters that were expected
+        chars: &'static str,
+        /// the next character of the input
+        found: Option<char>,
+    },
+    /// The next byte wasn't one of `bytes`, or the input ended if `found` is `None`
+    BytesOneOf {
+        /// the bytes that were expected
+        bytes: &'static [u8],
+        /// the next byte of the input
+        found: Option<u8>,
+    },
+    /// A parser that reads one or more characters didn't find any
+    NoCharacters,
+    /// The input ended before a value of a fixed length, such as a [`framed::Crc32`](crate::framed::Crc32), was read
+    EndOfInput,
+    /// A [`Checksum`](crate::framed::Checksum) didn't match the input it covers
+    Checksum,
+    /// The input continued after the value was parsed
+    Incomplete,
+    /// A type marked `#[nommy(complete)]` was parsed, but the input continued after it
+    Trailing {
+        /// the next token of the input, formatted with `Debug`
+        found: String,
+    },
+    /// An element of a repetition was parsed without reading any input, so parsing it again would never end
+    NoProgress,
+    /// A repetition had more elements than the limit set with [`vec::with_max_len`](crate::vec::with_max_len)
+    TooLong {
+        /// the most elements a repetition can have
+        max_len: usize,
+    },
+    /// A reserved keyword was found where an identifier was expected
+    Reserved {
+        /// the keyword that was found
+        keyword: String,
+    },
+    /// A field of a derived parser failed to parse
+    Field {
+        /// the name of the field, or `elem0`, `elem1`, ... for tuple fields
+        field: &'static str,
+    },
+    /// A field marked `not` found the type it excludes where its value starts
+    Excluded {
+        /// the name of the field, or `elem0`, `elem1`, ... for tuple fields
+        field: &'static str,
+    },
+    /// A field marked `peek` didn't find the type it peeks
+    Lookahead {
+        /// the name of the field, or `elem0`, `elem1`, ... for tuple fields
+        field: &'static str,
+    },
+    /// A field given an `expected` description failed to parse
+    Expected {
+        /// the name of the field, or `elem0`, `elem1`, ... for tuple fields
+        field: &'static str,
+        /// what the field was expected to be, such as "an identifier"
+        expected: &'static str,
+    },
+    /// A field marked `try_into` was parsed, but couldn't be converted into the field's type
+    Convert {
+        /// the name of the field, or `elem0`, `elem1`, ... for tuple fields
+        field: &'static str,
+    },
+    /// The prefix of a type, variant or field failed to parse
+    Prefix {
+        /// what the prefix belongs to
+        of: Item,
+    },
+    /// The suffix of a type, variant or field failed to parse
+    Suffix {
+        /// what the suffix belongs to
+        of: Item,
+    },
+    /// A variant was parsed, but its guard was false
+    Guard {
+        /// the name of the variant
+        variant: &'static str,
+    },
+    /// An enum failed to parse, and `variant` read more input than the others before failing
+    Variant {
+        /// the name of the enum
+        name: &'static str,
+        /// the name of the variant that got the furthest
+        variant: &'static str,
+    },
+    /// An enum failed to parse, and no variant got further than all the others
+    NoVariants {
+        /// the name of the enum
+        name: &'static str,
+    },
+    /// A type that can only be peeked couldn't be found by [`Peeked`](crate::Peeked)
+    NotFound {
+        /// the name of the type
+        name: &'static str,
+    },
+}
+
+/// The item a prefix or suffix belongs to
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[non_exhaustive]
+pub enum Item {
+    /// a struct, by name
+    Struct(&'static str),
+    /// an enum, by name
+    Enum(&'static str),
+    /// an enum variant, by name
+    Variant(&'static str),
+    /// a field, by name
+    Field(&'static str),
+}
+
+/// Formats a [`Message`], in place of its default format
+pub type Format = fn(&Message, &mut fmt::Formatter<'_>) -> fmt::Result;
+
+/// Supplies an application's own phrasing of each [`Message`], such as a translation.
+/// Implemented for functions and closures with the signature of [`Format`]
+pub trait Localize: Send + Sync {
+    /// Writes the message, in place of its default format
+    ///
+    /// # Errors
+    /// If the formatter fails to write
+    fn localize(&self, message: &Message, f: &mut fmt::Formatter<'_>) -> fmt::Result;
+}
+
+impl<F> Localize for F
+where
+    F: Fn(&Message, &mut fmt::Formatter<'_>) -> fmt::Result + Send + Sync,
+{
+    fn localize(&self, message: &Message, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self(message, f)
+    }
+}
+
+static LOCALIZER: RwLock<Option<Arc<dyn Localize>>> = RwLock::new(None);
+
+/// Formats every message with `localizer`, instead of its default format
+pub fn set_localizer(localizer: impl Localize + 'static) {
+    *LOCALIZER.write().unwrap_or_else(PoisonError::into_inner) = Some(Arc::new(localizer));
+}
+
+/// Formats every message with `format`, instead of its default format
+pub fn set_format(format: Format) {
+    set_localizer(format);
+}
+
+/// Formats messages with their default format again
+pub fn reset_format() {
+    *LOCALIZER.write().unwrap_or_else(PoisonError::into_inner) = None;
+}
+
+impl Message {
+    /// A name for the kind of message, which stays the same between versions,
+    /// for looking up the message's phrasing in a [`Localize`] implementation
+    #[must_use]
+    pub const fn key(&self) -> &'static str {
+        match self {
+            Self::Tag { .. } => "tag",
+            Self::ITag { .. } => "itag",
+            Self::BytesTag { .. } => "bytes_tag",
+            Self::TagOneOf { .. } => "tag_one_of",
+            Self::TagSet { .. } => "tag_set",
+            Self::OneOf { .. } => "one_of",
+            Self::BytesOneOf { .. } => "bytes_one_of",
+            Self::NoCharacters => "no_characters",
+            Self::EndOfInput => "end_of_input",
+            Self::Checksum => "checksum",
+            Self::Incomplete => "incomplete",
+            Self::Trailing { .. } => "trailing",
+            Self::NoProgress => "no_progress",
+            Self::TooLong { .. } => "too_long",
+            Self::Reserved { .. } => "reserved",
+            Self::Field { .. } => "field",
+            Self::Excluded { .. } => "excluded",
+            Self::Lookahead { .. } => "lookahead",
+            Self::Expected { .. } => "expected",
+            Self::Convert { .. } => "convert",
+            Self::Prefix { .. } => "prefix",
+            Self::Suffix { .. } => "suffix",
+            Self::Guard { .. } => "guard",
+            Self::Variant { .. } => "variant",
+            Self::NoVariants { .. } => "no_variants",
+            Self::NotFound { .. } => "not_found",
+        }
+    }
+
+    /// Writes the default format of the message, whatever format has been set
+    ///
+    /// # Errors
+    /// If the formatter fails to write
+    pub fn fmt_default(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Tag { tag, found } => {
+                write!(f, "failed to parse tag {:?}, found {:?}", tag, found)
+            }
+            Self::ITag { tag, found } => write!(
+                f,
+                "failed to parse tag {:?} (ignoring case), found {:?}",
+                tag, found
+            ),
+            Self::BytesTag { tag, found } => {
+                write!(f, "failed to parse tag {:?}, found {:?}", tag, found)
+            }
+            Self::TagOneOf { tags, found } => write!(
+                f,
+                "failed to parse one of the tags {:?}, found {:?}",
+                tags, found
+            ),
+            Self::TagSet { set, found } => {
+                write!(f, "failed to parse one of the tags of {}, found {:?}", set, found)
+            }
+            Self::OneOf { chars, found } => match found {
+                Some(c) => write!(f, "error parsing one of {:?}, found {:?}", chars, c),
+                None => write!(f, "error parsing one of {:?}, reached EOF", chars),
+            },
+            Self::BytesOneOf { bytes, found } => match found {
+                Some(b) => write!(f, "error parsing one of {:?}, found {:?}", bytes, b),
+                None => write!(f, "error parsing one of {:?}, reached EOF", bytes),
+            },
+            Self::NoCharacters => write!(f, "no characters found"),
+            Self::EndOfInput => write!(f, "reached the end of the input"),
+            Self::Checksum => write!(f, "checksum does not match the input"),
+            Self::Incomplete => write!(f, "input was not parsed completely"),
+            Self::Trailing { found } => write!(f, "expected the end of the input, found {}", found),
+            Self::NoProgress => write!(
+                f,
+                "an element was parsed without reading any input, so the repetition would never end"
+            ),
+            Self::TooLong { max_len } => {
+                write!(f, "repetition has more than the limit of {} elements", max_len)
+            }
+            Self::Reserved { keyword } => write!(f, "found reserved keyword {:?}", keyword),
+            Self::Field { field } => write!(f, "failed to parse field `{}`", field),
+            Self::Excluded { field } => write!(f, "field `{}` found the type it excludes", field),
+            Self::Lookahead { field } => write!(f, "field `{}` did not find the type it peeks", field),
+            Self::Expected { expected, .. } => write!(f, "expected {}", expected),
+            Self::Convert { field } => write!(f, "failed to convert field `{}`", field),
+            Self::Prefix { of } => write!(f, "failed to parse prefix for {}", of),
+            Self::Suffix { of } => write!(f, "failed to parse suffix for {}", of),
+            Self::Guard { variant } => write!(f, "variant `{}` was rejected by its guard", variant),
+            Self::Variant { name, variant } => {
+                write!(f, "failed to parse {} as variant `{}`", name, variant)
+            }
+            Self::NoVariants { name } => write!(f, "no variants of {} could be parsed", name),
+            Self::NotFound { name } => write!(f, "{} was not found", name),
+        }
+    }
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // cloned out, so the localizer can set another localizer without deadlocking
+        let localizer = LOCALIZER
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone();
+        match localizer {
+            Some(localizer) => localizer.localize(self, f),
+            None => self.fmt_default(f),
+        }
+    }
+}
+
+impl std::error::Error for Message {}
+
+impl fmt::Display for Item {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Struct(name) => write!(f, "struct `{}`", name),
+            Self::Enum(name) => write!(f, "enum `{}`", name),
+            Self::Variant(name) => write!(f, "variant `{}`", name),
+            Self::Field(name) => write!(f, "field `{}`", name),
+        }
+    }
+}
@@ -0,0 +1,384 @@
+//! Expression parsing driven by an operator table built at runtime
+//!
+//! ```
+//! use nommy::{IntoBuf, operator::{Associativity, Expr, OperatorTable}, text::{AnyOf1, Space}};
+//!
+//! let table = OperatorTable::new()
+//!     .infix("+".chars(), 1, Associativity::Left)
+//!     .infix("*".chars(), 2, Associativity::Left)
+//!     .prefix("-".chars(), 3);
+//!
+//! let mut input = "1 + -2 * 3".chars().into_buf();
+//! let expr = table.parse_ignoring::<AnyOf1<"0123456789">, Space, _>(&mut input).unwrap();
+//! let expr = expr.map(&mut |n| String::from(n));
+//!
+//! assert_eq!(
+//!     expr,
+//!     Expr::Infix(
+//!         vec!['+'],
+//!         Box::new(Expr::Operand("1".to_string())),
+//!         Box::new(Expr::Infix(
+//!             vec!['*'],
+//!             Box::new(Expr::Prefix(vec!['-'], Box::new(Expr::Operand("2".to_string())))),
+//!             Box::new(Expr::Operand("3".to_string())),
+//!         )),
+//!     )
+//! );
+//! ```
+
+use crate::{eyre, Buffer, Context, Parse};
+
+/// How operators of the same precedence group together
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Associativity {
+    /// `a - b - c` is parsed as `(a - b) - c`
+    Left,
+    /// `a ^ b ^ c` is parsed as `a ^ (b ^ c)`
+    Right,
+    /// `a == b == c` is an error
+    None,
+}
+
+/// Where the operator sits in relation to its operands
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Fixity {
+    /// Unary operator before the operand, eg `-a`
+    Prefix,
+    /// Binary operator between the operands, eg `a + b`
+    Infix,
+    /// Unary operator after the operand, eg `a!`
+    Postfix,
+}
+
+/// A single entry of an [`OperatorTable`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Operator<T> {
+    /// The tokens that make up this operator
+    pub symbol: Vec<T>,
+    /// Operators with a higher precedence bind tighter
+    pub precedence: u32,
+    /// How this operator groups with other operators of the same precedence.
+    /// Only used by infix operators
+    pub associativity: Associativity,
+    /// Where the operator sits in relation to its operands
+    pub fixity: Fixity,
+}
+
+/// Expression tree produced by [`OperatorTable::parse`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr<O, T> {
+    /// A single operand
+    Operand(O),
+    /// Prefix operator symbol and its operand
+    Prefix(Vec<T>, Box<Expr<O, T>>),
+    /// Postfix operator symbol and its operand
+    Postfix(Vec<T>, Box<Expr<O, T>>),
+    /// Infix operator symbol and its left and right operands
+    Infix(Vec<T>, Box<Expr<O, T>>, Box<Expr<O, T>>),
+}
+
+impl<O, T> Expr<O, T> {
+    /// Converts every operand in the tree using `f`
+    pub fn map<Q>(self, f: &mut impl FnMut(O) -> Q) -> Expr<Q, T> {
+        match self {
+            Self::Operand(o) => Expr::Operand(f(o)),
+            Self::Prefix(op, expr) => Expr::Prefix(op, Box::new(expr.map(f))),
+            Self::Postfix(op, expr) => Expr::Postfix(op, Box::new(expr.map(f))),
+            Self::Infix(op, lhs, rhs) => {
+                Expr::Infix(op, Box::new(lhs.map(f)), Box::new(rhs.map(f)))
+            }
+        }
+    }
+}
+
+/// `OperatorTable` holds a set of operators that can be configured at runtime,
+/// for languages with user defined operators.
+///
+/// When multiple operators match the input, the longest symbol is chosen.
+/// After an operand, postfix and infix operators are considered together, so a postfix `!`
+/// will not prevent an infix `!=` from matching
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OperatorTable<T> {
+    operators: Vec<Operator<T>>,
+}
+
+impl<T> Default for OperatorTable<T> {
+    fn default() -> Self {
+        Self {
+            operators: Vec::new(),
+        }
+    }
+}
+
+/// Parser that never matches, used when there is nothing to ignore
+struct Never;
+
+impl<T> Parse<T> for Never {
+    const MAX_PEEK: Option<usize> = Some(0);
+
+    fn parse(_: &mut impl Buffer<T>) -> eyre::Result<Self> {
+        Err(eyre::eyre!("never"))
+    }
+
+    fn peek(_: &mut impl Buffer<T>) -> bool {
+        false
+    }
+}
+
+impl<T: PartialEq + Clone> OperatorTable<T> {
+    /// Create a new empty operator table
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an operator to the table
+    pub fn add(&mut self, operator: Operator<T>) {
+        self.operators.push(operator);
+    }
+
+    /// Adds an infix operator to the table
+    #[must_use]
+    pub fn infix(
+        mut self,
+        symbol: impl IntoIterator<Item = T>,
+        precedence: u32,
+        associativity: Associativity,
+    ) -> Self {
+        self.add(Operator {
+            symbol: symbol.into_iter().collect(),
+            precedence,
+            associativity,
+            fixity: Fixity::Infix,
+        });
+        self
+    }
+
+    /// Adds a prefix operator to the table
+    #[must_use]
+    pub fn prefix(mut self, symbol: impl IntoIterator<Item = T>, precedence: u32) -> Self {
+        self.add(Operator {
+            symbol: symbol.into_iter().collect(),
+            precedence,
+            associativity: Associativity::Right,
+            fixity: Fixity::Prefix,
+        });
+        self
+    }
+
+    /// Adds a postfix operator to the table
+    #[must_use]
+    pub fn postfix(mut self, symbol: impl IntoIterator<Item = T>, precedence: u32) -> Self {
+        self.add(Operator {
+            symbol: symbol.into_iter().collect(),
+            precedence,
+            associativity: Associativity::Left,
+            fixity: Fixity::Postfix,
+        });
+        self
+    }
+
+    /// The operators in this table
+    #[must_use]
+    pub fn operators(&self) -> &[Operator<T>] {
+        &self.operators
+    }
+
+    /// Parses an expression of operands `O` combined with the operators in this table
+    ///
+    /// # Errors
+    /// If an operand could not be parsed, or non-associative operators were chained
+    pub fn parse<O, B>(&self, input: &mut B) -> eyre::Result<Expr<O, T>>
+    where
+        O: Parse<T>,
+        B: Buffer<T>,
+    {
+        self.parse_ignoring::<O, Never, B>(input)
+    }
+
+    /// Parses an expression of operands `O` combined with the operators in this table,
+    /// ignoring any `Ignore` around operators and operands
+    ///
+    /// # Errors
+    /// If an operand could not be parsed, or non-associative operators were chained
+    pub fn parse_ignoring<O, Ignore, B>(&self, input: &mut B) -> eyre::Result<Expr<O, T>>
+    where
+        O: Parse<T>,
+        Ignore: Parse<T>,
+        B: Buffer<T>,
+    {
+        self.parse_expr::<O, Ignore, B>(input, 0)
+    }
+
+    fn parse_expr<O, Ignore, B>(
+        &self,
+        input: &mut B,
+        min_precedence: u32,
+    ) -> eyre::Result<Expr<O, T>>
+    where
+        O: Parse<T>,
+        Ignore: Parse<T>,
+        B: Buffer<T>,
+    {
+        Vec::<Ignore>::peek(input);
+
+        let mut lhs = if let Some(op) = self.find(input, &[Fixity::Prefix]) {
+            input.fast_forward(op.symbol.len());
+            let expr = self.parse_expr::<O, Ignore, B>(input, op.precedence)?;
+            Expr::Prefix(op.symbol.clone(), Box::new(expr))
+        } else {
+            Expr::Operand(O::parse(input).wrap_err("failed to parse operand")?)
+        };
+
+        let mut non_associative = None;
+        loop {
+            let mut cursor = input.cursor();
+            Vec::<Ignore>::peek(&mut cursor);
+            let ignored = cursor.position();
+
+            // postfix and infix operators both follow an operand, so the longest of either is chosen
+            let op = match self.find(&mut cursor, &[Fixity::Postfix, Fixity::Infix]) {
+                Some(op) if op.precedence >= min_precedence => op,
+                _ => break,
+            };
+            if op.fixity == Fixity::Postfix {
+                input.fast_forward(ignored + op.symbol.len());
+                lhs = Expr::Postfix(op.symbol.clone(), Box::new(lhs));
+                continue;
+            }
+
+            if op.associativity == Associativity::None {
+                if non_associative == Some(op.precedence) {
+                    return Err(eyre::eyre!(
+                        "non-associative operators with precedence {} cannot be chained",
+                        op.precedence
+                    ));
+                }
+                non_associative = Some(op.precedence);
+            }
+            input.fast_forward(ignored + op.symbol.len());
+
+            let next_precedence = match op.associativity {
+                Associativity::Right => op.precedence,
+                Associativity::Left | Associativity::None => op.precedence + 1,
+            };
+            let rhs = self.parse_expr::<O, Ignore, B>(input, next_precedence)?;
+            lhs = Expr::Infix(op.symbol.clone(), Box::new(lhs), Box::new(rhs));
+        }
+
+        Ok(lhs)
+    }
+
+    /// Finds the longest operator with one of the given fixities at the start of the input.
+    /// Postfix operators are preferred over infix operators with the same length. Does not move the input
+    fn find(&self, input: &mut impl Buffer<T>, fixities: &[Fixity]) -> Option<&Operator<T>> {
+        self.operators
+            .iter()
+            .filter(|op| fixities.contains(&op.fixity))
+            .filter(|op| {
+                !op.symbol.is_empty()
+                    && op
+                        .symbol
+                        .iter()
+                        .enumerate()
+                        .all(|(i, t)| input.peek_ahead(i).as_ref() == Some(t))
+            })
+            .max_by_key(|op| (op.symbol.len(), op.fixity == Fixity::Postfix))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        text::{AnyOf1, Space},
+        IntoBuf,
+    };
+
+    type Number = AnyOf1<"0123456789">;
+
+    fn table() -> OperatorTable<char> {
+        OperatorTable::new()
+            .infix("==".chars(), 0, Associativity::None)
+            .infix("!=".chars(), 0, Associativity::None)
+            .infix("=".chars(), 0, Associativity::Right)
+            .infix("+".chars(), 1, Associativity::Left)
+            .infix("-".chars(), 1, Associativity::Left)
+            .infix("*".chars(), 2, Associativity::Left)
+            .infix("^".chars(), 3, Associativity::Right)
+            .prefix("-".chars(), 4)
+            .postfix("!".chars(), 5)
+    }
+
+    fn show(expr: Expr<Number, char>) -> String {
+        match expr {
+            Expr::Operand(n) => n.into(),
+            Expr::Prefix(op, e) => format!("({}{})", op.iter().collect::<String>(), show(*e)),
+            Expr::Postfix(op, e) => format!("({}{})", show(*e), op.iter().collect::<String>()),
+            Expr::Infix(op, l, r) => format!(
+                "({} {} {})",
+                show(*l),
+                op.iter().collect::<String>(),
+                show(*r)
+            ),
+        }
+    }
+
+    fn parse(input: &str) -> eyre::Result<String> {
+        let mut input = input.chars().into_buf();
+        let expr = table().parse_ignoring::<Number, Space, _>(&mut input)?;
+        Ok(show(expr))
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(parse("1 + 2 * 3").unwrap(), "(1 + (2 * 3))");
+        assert_eq!(parse("1 * 2 + 3").unwrap(), "((1 * 2) + 3)");
+    }
+
+    #[test]
+    fn associativity() {
+        assert_eq!(parse("1 - 2 - 3").unwrap(), "((1 - 2) - 3)");
+        assert_eq!(parse("1 ^ 2 ^ 3").unwrap(), "(1 ^ (2 ^ 3))");
+        assert_eq!(parse("1 = 2 = 3").unwrap(), "(1 = (2 = 3))");
+    }
+
+    #[test]
+    fn non_associative() {
+        assert_eq!(parse("1 == 2").unwrap(), "(1 == 2)");
+        assert_eq!(
+            format!("{}", parse("1 == 2 == 3").unwrap_err()),
+            "non-associative operators with precedence 0 cannot be chained"
+        );
+    }
+
+    #[test]
+    fn unary() {
+        assert_eq!(parse("-1 - -2!").unwrap(), "((-1) - (-(2!)))");
+    }
+
+    #[test]
+    fn longest_across_fixities() {
+        // postfix `!` is a prefix of infix `!=`
+        assert_eq!(parse("1 != 2").unwrap(), "(1 != 2)");
+        assert_eq!(parse("1! != 2").unwrap(), "((1!) != 2)");
+        // `!=` has a lower precedence than `+`, but must still win over `!`
+        assert_eq!(parse("1 + 2 != 3").unwrap(), "((1 + 2) != 3)");
+    }
+
+    #[test]
+    fn missing_operand() {
+        let err = parse("1 +").unwrap_err();
+        assert_eq!(format!("{}", err), "failed to parse operand");
+    }
+
+    #[test]
+    fn stops_at_unknown() {
+        let mut input = "1 + 2 ) 3".chars().into_buf();
+        let expr = table()
+            .parse_ignoring::<Number, Space, _>(&mut input)
+            .unwrap();
+        assert_eq!(show(expr), "(1 + 2)");
+        assert_eq!(input.collect::<String>(), " ) 3");
+    }
+}
@@ -0,0 +1,148 @@
+//! Parsing independent records in parallel, using [rayon](https://docs.rs/rayon)
+//!
+//! The input is split into records first, then every record is parsed on its own,
+//! and must be consumed completely. The results are returned in the order of the input,
+//! so the error for a record can be matched up with where it was.
+//!
+//! ```
+//! use nommy::{parallel::par_parse_lines, text::DotNumber};
+//!
+//! let results = par_parse_lines::<DotNumber>("1,000\n2.5\nthree\n4");
+//! assert_eq!(results.len(), 4);
+//! assert_eq!(f64::from(*results[1].as_ref().unwrap()), 2.5);
+//! assert_eq!(results[2].as_ref().unwrap_err().to_string(), "failed to parse line 3");
+//! ```
+
+use rayon::prelude::*;
+
+use crate::{eyre, parse_terminated, Context, Parse};
+
+/// Parses each line of `input` as a `P`, in parallel.
+/// Line endings are `\n` or `\r\n`, and a final line ending doesn't start an empty line
+///
+/// # Errors
+/// Each line that fails to parse has an error, wrapped with the line number (starting from 1)
+#[must_use]
+pub fn par_parse_lines<P>(input: &str) -> Vec<eyre::Result<P>>
+where
+    P: Parse<char> + Send,
+{
+    let lines: Vec<&str> = input.lines().collect();
+    parse_all(&lines, "line")
+}
+
+/// Parses each record of `input` as a `P`, in parallel, where records are separated by `separator`
+///
+/// Separators inside brackets (`()`, `[]` or `{}`) or a `"` quoted string don't end the record,
+/// so records can contain nested lists. A final separator doesn't start an empty record
+///
+/// ```
+/// use nommy::{parallel::par_parse_records, text::{AnyOf1, Tag}, Parse};
+///
+/// #[derive(Debug, Parse)]
+/// struct Call {
+///     #[nommy(parser = AnyOf1<"abcdefghijklmnopqrstuvwxyz">)]
+///     name: String,
+///     #[nommy(prefix = Tag<"(">, suffix = Tag<")">, parser = AnyOf1<"0123456789,">)]
+///     args: String,
+/// }
+///
+/// let calls = par_parse_records::<Call>("foo(1,2);bar(3);", ';');
+/// assert_eq!(calls.len(), 2);
+/// assert_eq!(calls[0].as_ref().unwrap().args, "1,2");
+///
+/// let calls = par_parse_records::<Call>("foo(1,2),bar(3)", ',');
+/// assert_eq!(calls[1].as_ref().unwrap().name, "bar");
+/// ```
+///
+/// # Errors
+/// Each record that fails to parse has an error, wrapped with the record number (starting from 1)
+#[must_use]
+pub fn par_parse_records<P>(input: &str, separator: char) -> Vec<eyre::Result<P>>
+where
+    P: Parse<char> + Send,
+{
+    parse_all(&split_records(input, separator), "record")
+}
+
+fn parse_all<P>(records: &[&str], what: &str) -> Vec<eyre::Result<P>>
+where
+    P: Parse<char> + Send,
+{
+    records
+        .par_iter()
+        .enumerate()
+        .map(|(i, record)| {
+            parse_terminated(record.chars())
+                .wrap_err_with(|| format!("failed to parse {} {}", what, i + 1))
+        })
+        .collect()
+}
+
+/// Splits `input` at each `separator` that isn't nested in brackets or a string
+fn split_records(input: &str, separator: char) -> Vec<&str> {
+    let mut records = Vec::new();
+    let mut depth = 0_usize;
+    let mut in_string = false;
+    let mut escaped = false;
+    let mut start = 0;
+    for (i, c) in input.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match c {
+            _ if c == separator && depth == 0 => {
+                records.push(&input[start..i]);
+                start = i + c.len_utf8();
+            }
+            '"' => in_string = true,
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    if start < input.len() {
+        records.push(&input[start..]);
+    }
+    records
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::Tag;
+
+    #[test]
+    fn split() {
+        assert_eq!(
+            split_records(r#"a,"b,\",c",[d,(e,f)],,g,"#, ','),
+            vec!["a", r#""b,\",c""#, "[d,(e,f)]", "", "g"]
+        );
+        assert!(split_records("", ',').is_empty());
+    }
+
+    #[test]
+    fn errors_in_order() {
+        let input = "a\r\nb\na\nab\n";
+        let results = par_parse_lines::<Tag<"a">>(input);
+        let errors: Vec<_> = results
+            .iter()
+            .map(|res| res.as_ref().err().map(ToString::to_string))
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                None,
+                Some("failed to parse line 2".to_owned()),
+                None,
+                Some("failed to parse line 4".to_owned())
+            ]
+        );
+    }
+}
@@ -0,0 +1,28 @@
+//! The traits, derives and parsers that most grammars use, so they can be imported at once.
+//!
+//! Items are only ever added here, so `use nommy::prelude::*` keeps compiling as nommy grows.
+//!
+//! ```
+//! use nommy::prelude::*;
+//!
+//! #[derive(Debug, PartialEq, Parse)]
+//! #[nommy(ignore = WhiteSpace)]
+//! struct Call {
+//!     #[nommy(parser = AnyOf1<"abcdefghijklmnopqrstuvwxyz">)]
+//!     name: String,
+//!     #[nommy(prefix = Tag<"(">, suffix = Tag<")">)]
+//!     #[nommy(inner_parser = AnyOf1<"0123456789">, seperated_by = Tag<",">)]
+//!     args: Vec<String>,
+//! }
+//!
+//! let call: Call = parse("max(1, 2)".chars()).unwrap();
+//! assert_eq!(call.args, vec!["1", "2"]);
+//! ```
+
+pub use crate::{
+    eyre, parse, parse_terminated,
+    text::{
+        AnyOf, AnyOf1, ITag, OneOf, Space, Tag, TagOneOf, UnicodeWhiteSpace, WhileNot1, WhiteSpace,
+    },
+    Buf, Buffer, IntoBuf, Parse, Peek, Process, Unparse,
+};
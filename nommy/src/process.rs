@@ -0,0 +1,166 @@
+//! Unwrapping parsed values into plain data.
+//!
+//! Parsed types are full of wrapper types, like [`Tag`](crate::text::Tag) or [`OneOf`](crate::text::OneOf),
+//! that only describe how the input was read. [`Process`] turns them into the data they hold:
+//! tags become `()`, `OneOf` becomes a [`char`], `AnyOf1` becomes a [`String`], and so on.
+//!
+//! `#[derive(Process)]` builds a companion type with the same shape, where each field holds
+//! the processed output of the original field. See [`derive@Process`](crate::Process)
+//!
+//! ```
+//! use nommy::{parse, process::Process, text::{AnyOf1, OneOf}, Vec1};
+//!
+//! let value: Vec1<OneOf<"abc">> = parse("cab".chars()).unwrap();
+//! assert_eq!(value.process(), vec!['c', 'a', 'b']);
+//!
+//! let value: Option<AnyOf1<"0123456789">> = parse("12".chars()).unwrap();
+//! assert_eq!(value.process(), Some("12".to_owned()));
+//! ```
+
+use std::marker::PhantomData;
+
+/// `Process` turns a parsed value into the plain data that it holds
+pub trait Process {
+    /// The plain data that the value holds
+    type Output;
+
+    /// Unwrap the value into its output
+    fn process(self) -> Self::Output;
+}
+
+/// Types that are already plain data are their own output
+macro_rules! identity {
+    ($($ty:ty),*) => {
+        $(
+            impl Process for $ty {
+                type Output = Self;
+
+                fn process(self) -> Self::Output {
+                    self
+                }
+            }
+        )*
+    };
+}
+
+identity!(
+    (),
+    bool,
+    char,
+    String,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    f32,
+    f64,
+    crate::span::Span
+);
+
+impl Process for &str {
+    type Output = Self;
+
+    fn process(self) -> Self::Output {
+        self
+    }
+}
+
+impl<T: ?Sized> Process for PhantomData<T> {
+    type Output = Self;
+
+    fn process(self) -> Self::Output {
+        self
+    }
+}
+
+impl<P: Process> Process for Option<P> {
+    type Output = Option<P::Output>;
+
+    fn process(self) -> Self::Output {
+        self.map(P::process)
+    }
+}
+
+impl<P: Process> Process for Vec<P> {
+    type Output = Vec<P::Output>;
+
+    fn process(self) -> Self::Output {
+        self.into_iter().map(P::process).collect()
+    }
+}
+
+impl<P: Process> Process for Box<P> {
+    type Output = Box<P::Output>;
+
+    fn process(self) -> Self::Output {
+        Box::new((*self).process())
+    }
+}
+
+macro_rules! tuple {
+    ($($T:ident),*) => {
+        /// Each element of the tuple is processed, in order
+        impl<$($T: Process),*> Process for ($($T,)*) {
+            type Output = ($($T::Output,)*);
+
+            fn process(self) -> Self::Output {
+                #[allow(non_snake_case)]
+                let ($($T,)*) = self;
+                ($($T.process(),)*)
+            }
+        }
+    };
+}
+
+tuple!(A);
+tuple!(A, B);
+tuple!(A, B, C);
+tuple!(A, B, C, D);
+tuple!(A, B, C, D, E);
+tuple!(A, B, C, D, E, F);
+tuple!(A, B, C, D, E, F, G);
+tuple!(A, B, C, D, E, F, G, H);
+
+#[cfg(test)]
+mod tests {
+    use super::Process;
+    use crate::{
+        bytes,
+        text::{AnyOf1, OneOf, Tag, TagOneOf, WhiteSpace},
+        IntoBuf, Parse, Vec1,
+    };
+
+    #[test]
+    fn process_text() {
+        let mut input = "x ==ab".chars().into_buf();
+        let value = (
+            AnyOf1::<"abcdefghijklmnopqrstuvwxyz">::parse(&mut input).unwrap(),
+            WhiteSpace::parse(&mut input).unwrap(),
+            TagOneOf::<{ &["=", "=="] }>::parse(&mut input).unwrap(),
+            Option::<Tag<"!">>::parse(&mut input).unwrap(),
+            Vec1::<OneOf<"ab">>::parse(&mut input).unwrap(),
+        );
+        assert_eq!(
+            value.process(),
+            ("x".to_owned(), (), "==", None, vec!['a', 'b'])
+        );
+    }
+
+    #[test]
+    fn process_bytes() {
+        let mut input = "0x12".bytes().into_buf();
+        let value = (
+            bytes::Tag::<b"0x">::parse(&mut input).unwrap(),
+            bytes::AnyOf1::<b"0123456789">::parse(&mut input).unwrap(),
+        );
+        assert_eq!(value.process(), ((), b"12".to_vec()));
+    }
+}
@@ -0,0 +1,264 @@
+//! Lists that keep their separators, for tools that re-emit or modify the original source
+
+use std::ops::Range;
+
+use crate::{
+    eyre,
+    unparse::{Emit, Unparse},
+    Buffer, Parse,
+};
+
+/// A value in a [`Punctuated`] list, along with the separator that follows it
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pair<T, P> {
+    /// The value
+    pub value: T,
+    /// Where the value was parsed, relative to the start of the list.
+    /// `None` if the value was pushed rather than parsed
+    pub value_span: Option<Range<usize>>,
+    /// The separator following the value, if any
+    pub punct: Option<P>,
+    /// Where the separator was parsed, relative to the start of the list.
+    /// `None` if the separator was pushed rather than parsed
+    pub punct_span: Option<Range<usize>>,
+}
+
+/// `Punctuated` parses a list of `T` separated by `P`, keeping the separators.
+/// The list can be empty, and the last value can optionally be followed by a separator
+///
+/// ```
+/// use nommy::{parse, punctuated::Punctuated, text::{AnyOf1, Tag}};
+///
+/// let list: Punctuated<AnyOf1<"abc">, Tag<",">> = parse("a,bc,".chars()).unwrap();
+/// let values: Vec<String> = list.into_values().map(String::from).collect();
+/// assert_eq!(values, vec!["a", "bc"]);
+///
+/// let list: Punctuated<AnyOf1<"abc">, Tag<",">> = parse("a,bc,".chars()).unwrap();
+/// assert!(list.trailing_punct());
+/// assert_eq!(list.pairs()[1].value_span, Some(2..4));
+/// assert_eq!(list.pairs()[1].punct_span, Some(4..5));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Punctuated<T, P> {
+    pairs: Vec<Pair<T, P>>,
+}
+
+impl<T, P> Default for Punctuated<T, P> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, P> Punctuated<T, P> {
+    /// Creates an empty list
+    #[must_use]
+    pub const fn new() -> Self {
+        Self { pairs: Vec::new() }
+    }
+
+    /// The number of values in the list
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.pairs.len()
+    }
+
+    /// Returns true if there are no values in the list
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.pairs.is_empty()
+    }
+
+    /// Returns true if the last value is followed by a separator
+    #[must_use]
+    pub fn trailing_punct(&self) -> bool {
+        matches!(self.pairs.last(), Some(Pair { punct: Some(_), .. }))
+    }
+
+    /// The values and the separators that follow them
+    #[must_use]
+    pub fn pairs(&self) -> &[Pair<T, P>] {
+        &self.pairs
+    }
+
+    /// Mutable access to the values and the separators that follow them
+    pub fn pairs_mut(&mut self) -> &mut [Pair<T, P>] {
+        &mut self.pairs
+    }
+
+    /// Iterates over the values in the list
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.pairs.iter().map(|pair| &pair.value)
+    }
+
+    /// Iterates over the separators in the list
+    pub fn puncts(&self) -> impl Iterator<Item = &P> {
+        self.pairs.iter().filter_map(|pair| pair.punct.as_ref())
+    }
+
+    /// Converts the list into its values, dropping the separators
+    pub fn into_values(self) -> impl Iterator<Item = T> {
+        self.pairs.into_iter().map(|pair| pair.value)
+    }
+
+    /// Appends a value to the end of the list
+    ///
+    /// # Panics
+    /// If the list is not empty and the last value isn't followed by a separator
+    pub fn push_value(&mut self, value: T) {
+        assert!(
+            self.is_empty() || self.trailing_punct(),
+            "push_value called on a list without a trailing separator"
+        );
+        self.pairs.push(Pair {
+            value,
+            value_span: None,
+            punct: None,
+            punct_span: None,
+        });
+    }
+
+    /// Appends a separator after the last value
+    ///
+    /// # Panics
+    /// If the list is empty or already has a trailing separator
+    pub fn push_punct(&mut self, punct: P) {
+        let last = self
+            .pairs
+            .last_mut()
+            .expect("push_punct called on an empty list");
+        assert!(
+            last.punct.is_none(),
+            "push_punct called on a list that already has a trailing separator"
+        );
+        last.punct = Some(punct);
+    }
+}
+
+impl<T: Parse<I>, P: Parse<I>, I> Parse<I> for Punctuated<T, P> {
+    fn parse(input: &mut impl Buffer<I>) -> eyre::Result<Self> {
+        let mut pairs = Vec::new();
+        let mut offset = 0;
+        loop {
+            let mut cursor = input.cursor();
+            let value = match T::parse(&mut cursor) {
+                Ok(value) => value,
+                Err(_) => break,
+            };
+            let value_span = offset..offset + cursor.position();
+            offset = value_span.end;
+            let pos = cursor.position();
+            input.fast_forward(pos);
+
+            let mut cursor = input.cursor();
+            let (punct, punct_span) = match P::parse(&mut cursor) {
+                Ok(punct) => {
+                    let span = offset..offset + cursor.position();
+                    offset = span.end;
+                    let pos = cursor.position();
+                    input.fast_forward(pos);
+                    (Some(punct), Some(span))
+                }
+                Err(_) => (None, None),
+            };
+
+            let end = punct.is_none();
+            pairs.push(Pair {
+                value,
+                value_span: Some(value_span),
+                punct,
+                punct_span,
+            });
+            if end {
+                break;
+            }
+        }
+
+        Ok(Self { pairs })
+    }
+
+    fn peek(input: &mut impl Buffer<I>) -> bool {
+        loop {
+            let mut cursor = input.cursor();
+            if !T::peek(&mut cursor) {
+                break;
+            }
+            let pos = cursor.position();
+            input.fast_forward(pos);
+
+            let mut cursor = input.cursor();
+            if !P::peek(&mut cursor) {
+                break;
+            }
+            let pos = cursor.position();
+            input.fast_forward(pos);
+        }
+        true
+    }
+}
+
+/// Writes each value followed by its separator, if it has one
+impl<T: Unparse<I>, P: Unparse<I>, I> Unparse<I> for Punctuated<T, P> {
+    fn unparse(&self, output: &mut impl Emit<I>) {
+        for pair in &self.pairs {
+            pair.value.unparse(output);
+            pair.punct.unparse(output);
+        }
+    }
+}
+
+impl<T, P> IntoIterator for Punctuated<T, P> {
+    type Item = Pair<T, P>;
+    type IntoIter = std::vec::IntoIter<Pair<T, P>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.pairs.into_iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        parse,
+        text::{AnyOf1, Tag},
+        IntoBuf,
+    };
+
+    type List = Punctuated<AnyOf1<"abc">, Tag<",">>;
+
+    #[test]
+    fn without_trailing() {
+        let mut input = "a,b,c;".chars().into_buf();
+        let list = List::parse(&mut input).unwrap();
+        assert_eq!(list.len(), 3);
+        assert_eq!(list.puncts().count(), 2);
+        assert!(!list.trailing_punct());
+        assert_eq!(input.collect::<String>(), ";");
+    }
+
+    #[test]
+    fn empty() {
+        let list: List = parse("".chars()).unwrap();
+        assert!(list.is_empty());
+        assert!(!list.trailing_punct());
+    }
+
+    #[test]
+    fn modify() {
+        let mut list: List = parse("a".chars()).unwrap();
+        list.push_punct(Tag);
+        list.push_value(parse("bc".chars()).unwrap());
+
+        let values: Vec<String> = list.values().cloned().map(String::from).collect();
+        assert_eq!(values, vec!["a", "bc"]);
+        assert_eq!(list.pairs()[0].punct_span, None);
+        assert_eq!(list.pairs()[1].value_span, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "without a trailing separator")]
+    fn push_value_without_punct() {
+        let mut list: List = parse("a".chars()).unwrap();
+        list.push_value(parse("b".chars()).unwrap());
+    }
+}
@@ -0,0 +1,480 @@
+//! Spans for parsing token streams that record where each token came from.
+//!
+//! When the input items implement [`HasSpan`], [`Spanned`] records the span of
+//! the tokens a parser consumed, and [`parse`] reports errors at the span of the
+//! furthest token read, without any changes to the parsers themselves.
+//!
+//! Tokens can be any type implementing [`HasSpan`], including `(token, span)` pairs
+//! such as those produced by `logos::Lexer::spanned`.
+//!
+//! Text can be parsed starting part way through a larger document with [`parse_at`],
+//! which reports spans as char offsets into the whole document. [`line_column`]
+//! converts those offsets into lines and columns.
+//!
+//! ```
+//! use nommy::{eyre, span::{self, Spanned}, Buffer, Parse};
+//!
+//! #[derive(Debug, Clone, PartialEq)]
+//! enum Tok { Num, Plus }
+//!
+//! /// Parses a single `Tok::Num`
+//! #[derive(Debug)]
+//! struct Num;
+//! impl Parse<(Tok, std::ops::Range<usize>)> for Num {
+//!     fn parse(input: &mut impl Buffer<(Tok, std::ops::Range<usize>)>) -> eyre::Result<Self> {
+//!         match input.next() {
+//!             Some((Tok::Num, _)) => Ok(Num),
+//!             _ => Err(eyre::eyre!("expected a number")),
+//!         }
+//!     }
+//! }
+//!
+//! #[derive(Debug, Parse)]
+//! struct Pair(Spanned<Num>, Spanned<Num>);
+//!
+//! // "12 3"
+//! let pair: Pair = span::parse(vec![(Tok::Num, 0..2), (Tok::Num, 3..4)]).unwrap();
+//! assert_eq!(pair.1.span, 3..4);
+//!
+//! // "12 + 3"
+//! let tokens = vec![(Tok::Num, 0..2), (Tok::Plus, 3..4), (Tok::Num, 5..6)];
+//! let err = span::parse::<Pair, _>(tokens).unwrap_err();
+//! assert_eq!(err.span, 3..4);
+//! assert_eq!(err.error.root_cause().to_string(), "expected a number");
+//! ```
+
+use std::{
+    cell::{Cell, RefCell},
+    collections::HashMap,
+    fmt,
+    ops::Range,
+    rc::Rc,
+};
+
+use crate::{
+    eyre,
+    unparse::{Emit, Unparse},
+    Buffer, IntoBuf, Parse,
+};
+
+/// Input items that know where they came from in the source
+pub trait HasSpan {
+    /// The span of the source this item was read from
+    fn span(&self) -> Range<usize>;
+}
+
+impl<T> HasSpan for (T, Range<usize>) {
+    fn span(&self) -> Range<usize> {
+        self.1.clone()
+    }
+}
+
+/// `Spanned` parses `P`, recording the span covered by the tokens it consumed
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spanned<P> {
+    /// The parsed value
+    pub value: P,
+    /// From the start of the first token to the end of the last token consumed.
+    /// Empty, at the start of the next token, if no tokens were consumed
+    pub span: Range<usize>,
+}
+
+impl<P: Parse<T>, T: HasSpan> Parse<T> for Spanned<P> {
+    const MAX_PEEK: Option<usize> = P::MAX_PEEK;
+
+    fn parse(input: &mut impl Buffer<T>) -> eyre::Result<Self> {
+        let start = input.peek_ahead(0).map(|t| t.span());
+
+        let mut cursor = input.cursor();
+        let value = P::parse(&mut cursor)?;
+        let pos = cursor.position();
+
+        let span = match (start, pos) {
+            (Some(start), pos) if pos > 0 => {
+                let end = input
+                    .peek_ahead(pos - 1)
+                    .map_or(start.end, |t| t.span().end);
+                start.start..end
+            }
+            (Some(start), _) => start.start..start.start,
+            (None, _) => 0..0,
+        };
+        input.fast_forward(pos);
+
+        Ok(Self { value, span })
+    }
+
+    fn peek(input: &mut impl Buffer<T>) -> bool {
+        P::peek(input)
+    }
+}
+
+impl<P: Unparse<T>, T> Unparse<T> for Spanned<P> {
+    fn unparse(&self, output: &mut impl Emit<T>) {
+        self.value.unparse(output);
+    }
+}
+
+/// Where a value was parsed from, as offsets into the input. Filled in by the derive for fields marked `#[nommy(span)]`,
+/// from where the type starts to the end of the last thing it parsed, not counting the ignored input after it
+///
+/// ```
+/// use nommy::{parse, span::Span, text::{AnyOf1, Tag, WhiteSpace}, Parse};
+///
+/// #[derive(Debug, Parse)]
+/// #[nommy(ignore = WhiteSpace)]
+/// struct Call {
+///     #[nommy(parser = AnyOf1<"abcdefghijklmnopqrstuvwxyz">)]
+///     name: String,
+///     #[nommy(prefix = Tag<"(">, suffix = Tag<")">)]
+///     args: Vec<Arg>,
+///     #[nommy(span)]
+///     span: Span,
+/// }
+///
+/// #[derive(Debug, Parse)]
+/// #[nommy(ignore = WhiteSpace)]
+/// struct Arg {
+///     #[nommy(parser = AnyOf1<"0123456789">)]
+///     value: String,
+///     #[nommy(span)]
+///     span: Span,
+/// }
+///
+/// let call: Call = parse("f(1 23 )".chars()).unwrap();
+/// assert_eq!(call.span, Span::new(0, 8));
+/// assert_eq!(call.args[1].span, Span::new(4, 6));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Span {
+    /// The offset of the first value read
+    pub start: usize,
+    /// The offset after the last value read
+    pub end: usize,
+}
+
+impl Span {
+    /// A span from `start` up to `end`
+    #[must_use]
+    pub const fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+}
+
+impl From<Span> for Range<usize> {
+    fn from(span: Span) -> Self {
+        span.start..span.end
+    }
+}
+
+/// Error returned by [`parse`], located at the token where parsing failed
+#[derive(Debug)]
+pub struct SpannedError {
+    /// The span of the furthest token the parser read, or an empty span
+    /// at the end of the input if it ran out of tokens
+    pub span: Range<usize>,
+    /// The error returned by the parser
+    pub error: eyre::Report,
+    /// The span of each context frame wrapping the root cause of `error`, outermost first.
+    /// [`parse_at`] spans the frames made by the derive from where the field, variant or type
+    /// they name started up to the end of `span`. Other frames have the same span as the error
+    pub frames: Vec<Range<usize>>,
+}
+
+impl SpannedError {
+    /// Locates `error`, and each of its context frames, at `span`
+    #[must_use]
+    pub fn new(span: Range<usize>, error: eyre::Report) -> Self {
+        let frames = vec![span.clone(); error.chain().count() - 1];
+        Self {
+            span,
+            error,
+            frames,
+        }
+    }
+}
+
+impl fmt::Display for SpannedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at {}..{}",
+            self.error, self.span.start, self.span.end
+        )
+    }
+}
+
+impl std::error::Error for SpannedError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(self.error.as_ref())
+    }
+}
+
+/// Parses the given tokens into `P`, like [`parse`](crate::parse). If parsing fails,
+/// the error is reported at the span of the furthest token the parser read.
+///
+/// Like [`lsp::parse`](crate::lsp::parse), this is a heuristic, since the errors themselves
+/// don't record where they happened, but the furthest token read is usually where the mistake is.
+///
+/// # Errors
+/// If `P` failed to parse the input
+pub fn parse<P, I>(iter: I) -> Result<P, SpannedError>
+where
+    I: IntoIterator,
+    I::Item: HasSpan + Clone,
+    P: Parse<I::Item>,
+{
+    let furthest = Rc::new(RefCell::new(Furthest::default()));
+    let mut buffer = Tracked {
+        iter: iter.into_iter(),
+        furthest: Rc::clone(&furthest),
+    }
+    .into_buf();
+
+    P::parse(&mut buffer).map_err(|error| {
+        let furthest = furthest.borrow();
+        let span = match (&furthest.last, furthest.eof) {
+            (Some(last), true) => last.end..last.end,
+            (Some(last), false) => last.clone(),
+            (None, _) => 0..0,
+        };
+        SpannedError::new(span, error)
+    })
+}
+
+/// Parses `P` from `source`, starting at the char `offset` rather than the start of the document.
+/// `P` doesn't have to consume the rest of the document.
+///
+/// The span of the parsed value, and of any error, are char offsets into the whole of `source`,
+/// so a single item or an edited region can be parsed again in place.
+/// Like [`parse`], errors are reported at the furthest character read
+///
+/// ```
+/// use nommy::{span::{self, LineColumn}, text::Tag};
+///
+/// let source = "let a;\nlet b;\nlet c;";
+/// let stmt = span::parse_at::<Tag<"let b;">>(source, 7).unwrap();
+/// assert_eq!(stmt.span, 7..13);
+///
+/// let err = span::parse_at::<Tag<"let b;">>(source, 14).unwrap_err();
+/// assert_eq!(err.span, 18..19);
+/// assert_eq!(span::line_column(source, err.span.start), LineColumn { line: 2, column: 4 });
+/// ```
+///
+/// # Errors
+/// If `P` failed to parse the input at `offset`
+pub fn parse_at<P: Parse<char>>(source: &str, offset: usize) -> Result<Spanned<P>, SpannedError> {
+    let read = Cell::new(offset);
+    let eof = Cell::new(false);
+    let mut buffer = Chars {
+        iter: source.chars().skip(offset),
+        read: &read,
+        eof: &eof,
+    }
+    .into_buf();
+
+    let mut cursor = buffer.cursor();
+    let outer = FRAMES.with(|frames| frames.replace(Some(HashMap::new())));
+    let result = P::parse(&mut cursor);
+    let starts = FRAMES.with(|frames| frames.replace(outer)).unwrap_or_default();
+    match result {
+        Ok(value) => Ok(Spanned {
+            value,
+            span: offset..offset + cursor.position(),
+        }),
+        Err(error) => {
+            let read = read.get();
+            let span = if eof.get() || read == offset {
+                read..read
+            } else {
+                read - 1..read
+            };
+            let frames = error
+                .chain()
+                .take(error.chain().count() - 1)
+                .map(|frame| match starts.get(&address(frame)) {
+                    Some((start, message)) if *message == frame.to_string() => {
+                        (offset + start).min(span.start)..span.end
+                    }
+                    _ => span.clone(),
+                })
+                .collect();
+            Err(SpannedError {
+                span,
+                error,
+                frames,
+            })
+        }
+    }
+}
+
+thread_local! {
+    /// Where the value that each context frame failed to parse started, and the frame's message,
+    /// by the address of the frame. Only recorded while [`parse_at`] is running
+    static FRAMES: RefCell<Option<HashMap<usize, (usize, String)>>> = RefCell::new(None);
+}
+
+/// The address of an error in a chain, which doesn't change as more frames are wrapped around it
+fn address(error: &(dyn std::error::Error + 'static)) -> usize {
+    (error as *const dyn std::error::Error).cast::<()>() as usize
+}
+
+/// Records that the value the outermost frame of `error` failed to parse started at the offset `start`,
+/// for [`parse_at`] to span the frame. Used by the derive
+#[doc(hidden)]
+pub fn locate(error: eyre::Report, start: usize) -> eyre::Report {
+    FRAMES.with(|frames| {
+        if let Some(frames) = frames.borrow_mut().as_mut() {
+            frames.insert(address(error.as_ref()), (start, error.to_string()));
+        }
+    });
+    error
+}
+
+/// A position in a text document
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct LineColumn {
+    /// The line, starting at 0
+    pub line: usize,
+    /// The number of chars since the start of the line
+    pub column: usize,
+}
+
+/// Converts a char offset into `source` into a line and column
+#[must_use]
+pub fn line_column(source: &str, offset: usize) -> LineColumn {
+    let mut pos = LineColumn { line: 0, column: 0 };
+    for c in source.chars().take(offset) {
+        if c == '\n' {
+            pos.line += 1;
+            pos.column = 0;
+        } else {
+            pos.column += 1;
+        }
+    }
+    pos
+}
+
+/// Chars of a document that record how far into the document they have been read
+struct Chars<'a, I> {
+    iter: I,
+    read: &'a Cell<usize>,
+    eof: &'a Cell<bool>,
+}
+
+impl<I: Iterator<Item = char>> Iterator for Chars<'_, I> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        let next = self.iter.next();
+        if next.is_some() {
+            self.read.set(self.read.get() + 1);
+        } else {
+            self.eof.set(true);
+        }
+        next
+    }
+}
+
+/// The last token read from the input
+#[derive(Default)]
+struct Furthest {
+    last: Option<Range<usize>>,
+    eof: bool,
+}
+
+/// Iterator that records the span of the last token read
+struct Tracked<I> {
+    iter: I,
+    furthest: Rc<RefCell<Furthest>>,
+}
+
+impl<I> Iterator for Tracked<I>
+where
+    I: Iterator,
+    I::Item: HasSpan,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = self.iter.next();
+        let mut furthest = self.furthest.borrow_mut();
+        match &next {
+            Some(token) => furthest.last = Some(token.span()),
+            None => furthest.eof = true,
+        }
+        next
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Token = (char, Range<usize>);
+
+    /// Parses the token `C`
+    #[derive(Debug, PartialEq)]
+    struct Is<const C: char>;
+
+    impl<const C: char> Parse<Token> for Is<C> {
+        fn parse(input: &mut impl Buffer<Token>) -> eyre::Result<Self> {
+            match input.next() {
+                Some((c, _)) if c == C => Ok(Self),
+                _ => Err(eyre::eyre!("expected {:?}", C)),
+            }
+        }
+    }
+
+    /// Parses `A` then `B`
+    #[derive(Debug, PartialEq)]
+    struct Seq<A, B>(A, B);
+
+    impl<A: Parse<Token>, B: Parse<Token>> Parse<Token> for Seq<A, B> {
+        fn parse(input: &mut impl Buffer<Token>) -> eyre::Result<Self> {
+            Ok(Self(A::parse(input)?, B::parse(input)?))
+        }
+    }
+
+    fn tokens() -> Vec<Token> {
+        vec![('a', 0..1), ('b', 2..5), ('c', 6..7)]
+    }
+
+    #[test]
+    fn spanned() {
+        let res: Seq<Is<'a'>, Spanned<Seq<Is<'b'>, Is<'c'>>>> = parse(tokens()).unwrap();
+        assert_eq!(res.1.span, 2..7);
+
+        let res: Spanned<Option<Is<'x'>>> = parse(tokens()).unwrap();
+        assert_eq!(res.span, 0..0);
+    }
+
+    #[test]
+    fn error_span() {
+        let err = parse::<Seq<Is<'a'>, Is<'c'>>, _>(tokens()).unwrap_err();
+        assert_eq!(err.span, 2..5);
+        assert_eq!(err.to_string(), "expected 'c' at 2..5");
+
+        let err =
+            parse::<Seq<Seq<Is<'a'>, Is<'b'>>, Seq<Is<'c'>, Is<'d'>>>, _>(tokens()).unwrap_err();
+        assert_eq!(err.span, 7..7);
+    }
+
+    #[test]
+    fn parse_at_offset() {
+        use crate::text::Tag;
+
+        let source = "ab\ncd";
+        let res = parse_at::<Tag<"cd">>(source, 3).unwrap();
+        assert_eq!(res.span, 3..5);
+
+        // ran out of input
+        let err = parse_at::<Tag<"cde">>(source, 3).unwrap_err();
+        assert_eq!(err.span, 5..5);
+        assert_eq!(line_column(source, 5), LineColumn { line: 1, column: 2 });
+
+        // nothing read past the offset
+        let err = parse_at::<Tag<"x">>(source, 5).unwrap_err();
+        assert_eq!(err.span, 5..5);
+    }
+}
@@ -0,0 +1,138 @@
+//! Helpers for testing grammars
+//!
+//! [`assert_ignores_whitespace`] checks that a type's `ignore` setting covers every place whitespace can go.
+//! It takes a canonical input, with a single space everywhere whitespace is allowed,
+//! and parses copies of it with that whitespace changed, such as [`unparse`](crate::unparse) writes.
+//!
+//! ```
+//! use nommy::{testing::assert_ignores_whitespace, text::{AnyOf1, Tag, WhiteSpace}, Parse};
+//!
+//! #[derive(Debug, PartialEq, Parse)]
+//! #[nommy(ignore = WhiteSpace)]
+//! struct Assign {
+//!     #[nommy(parser = AnyOf1<"abc">, suffix = Tag<"=">)]
+//!     name: String,
+//!     #[nommy(parser = AnyOf1<"abc">)]
+//!     value: String,
+//! }
+//!
+//! assert_ignores_whitespace::<Assign>("a = b", &[]);
+//! ```
+
+use std::fmt::Debug;
+
+use crate::{parse_terminated, Parse};
+
+/// The whitespace tried in place of each run of whitespace in the input
+const FILLERS: [&str; 4] = ["  ", "\n", "\t", " \r\n\t "];
+
+/// Returns copies of `input` with its whitespace changed.
+///
+/// Each copy replaces every run of whitespace with one of the fillers: double spaces, a newline, a tab, a mix of them,
+/// and each of `extra`, such as a comment that the grammar ignores. Whitespace inside `"` quoted strings is kept
+///
+/// ```
+/// use nommy::testing::perturb;
+///
+/// let inputs = perturb("a = \"b c\"", &[" /* note */ "]);
+/// assert!(inputs.contains(&"a\n=\n\"b c\"".to_owned()));
+/// assert!(inputs.contains(&"a /* note */ = /* note */ \"b c\"".to_owned()));
+/// ```
+#[must_use]
+pub fn perturb(input: &str, extra: &[&str]) -> Vec<String> {
+    let runs = whitespace_runs(input);
+    if runs.is_empty() {
+        return Vec::new();
+    }
+    FILLERS
+        .iter()
+        .chain(extra)
+        .map(|filler| {
+            let mut output = String::with_capacity(input.len());
+            let mut last = 0;
+            for (start, end) in &runs {
+                output.push_str(&input[last..*start]);
+                output.push_str(filler);
+                last = *end;
+            }
+            output.push_str(&input[last..]);
+            output
+        })
+        .collect()
+}
+
+/// Asserts that every input from [`perturb`] parses to the same value as `input`.
+///
+/// # Panics
+/// If `input` doesn't parse, or one of its perturbed copies doesn't parse to the same value.
+/// The message shows the copy that failed
+#[track_caller]
+pub fn assert_ignores_whitespace<P>(input: &str, extra: &[&str])
+where
+    P: Parse<char> + PartialEq + Debug,
+{
+    let expected: P = match parse_terminated(input.chars()) {
+        Ok(value) => value,
+        Err(err) => panic!("failed to parse the canonical input {:?}: {:?}", input, err),
+    };
+    for perturbed in perturb(input, extra) {
+        match parse_terminated::<P, _>(perturbed.chars()) {
+            Ok(value) => assert_eq!(
+                value, expected,
+                "{:?} parsed differently to {:?}",
+                perturbed, input
+            ),
+            Err(err) => panic!(
+                "failed to parse {:?}, changed from {:?}: {:?}",
+                perturbed, input, err
+            ),
+        }
+    }
+}
+
+/// The byte ranges of the runs of whitespace in `input` that aren't in a `"` quoted string
+fn whitespace_runs(input: &str) -> Vec<(usize, usize)> {
+    let mut runs = Vec::new();
+    let mut start = None;
+    let mut in_string = false;
+    let mut escaped = false;
+    for (i, c) in input.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        if c.is_whitespace() {
+            start.get_or_insert(i);
+            continue;
+        }
+        if let Some(start) = start.take() {
+            runs.push((start, i));
+        }
+        if c == '"' {
+            in_string = true;
+        }
+    }
+    if let Some(start) = start {
+        runs.push((start, input.len()));
+    }
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs() {
+        assert_eq!(
+            whitespace_runs(" a  \"b c\"\n"),
+            vec![(0, 1), (2, 4), (9, 10)]
+        );
+        assert!(perturb("abc", &[]).is_empty());
+    }
+}
@@ -0,0 +1,231 @@
+use crate::{
+    eyre,
+    unparse::{Emit, Unparse},
+    Buffer, Parse,
+};
+
+/// Reads a run of `allowed` characters. If `wrapped`, whitespace between the characters is skipped,
+/// but whitespace after the last one isn't consumed
+fn run(input: &mut impl Buffer<char>, allowed: fn(char) -> bool, wrapped: bool) -> String {
+    let mut text = String::new();
+    loop {
+        match input.peek_ahead(0) {
+            Some(c) if allowed(c) => {
+                text.push(c);
+                input.fast_forward(1);
+            }
+            Some(c) if wrapped && c.is_whitespace() && !text.is_empty() => {
+                let mut len = 1;
+                while matches!(input.peek_ahead(len), Some(c) if c.is_whitespace()) {
+                    len += 1;
+                }
+                if !matches!(input.peek_ahead(len), Some(c) if allowed(c)) {
+                    return text;
+                }
+                input.fast_forward(len);
+            }
+            _ => return text,
+        }
+    }
+}
+
+fn parse_hex(input: &mut impl Buffer<char>, wrapped: bool) -> eyre::Result<Vec<u8>> {
+    let digits = run(input, |c| c.is_ascii_hexdigit(), wrapped);
+    if digits.is_empty() {
+        return Err(eyre::eyre!("could not parse hex"));
+    }
+    if digits.len() % 2 != 0 {
+        return Err(eyre::eyre!("odd number of hex digits"));
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|i| Ok(u8::from_str_radix(&digits[i..i + 2], 16)?))
+        .collect()
+}
+
+fn write_hex(bytes: &[u8], output: &mut impl Emit<char>) {
+    for b in bytes {
+        format!("{:02x}", b).unparse(output);
+    }
+}
+
+fn parse_base64(input: &mut impl Buffer<char>, wrapped: bool) -> eyre::Result<Vec<u8>> {
+    let text = run(
+        input,
+        |c| c.is_ascii_alphanumeric() || "+/=".contains(c),
+        wrapped,
+    );
+    if text.is_empty() {
+        return Err(eyre::eyre!("could not parse base64"));
+    }
+
+    let digits = text.trim_end_matches('=');
+    let padding = text.len() - digits.len();
+    if digits.contains('=') || padding > 2 {
+        return Err(eyre::eyre!("base64 padding must only be at the end"));
+    }
+    if digits.len() % 4 == 1 || (padding > 0 && text.len() % 4 != 0) {
+        return Err(eyre::eyre!("invalid base64 length"));
+    }
+
+    let mut bytes = Vec::with_capacity(digits.len() * 3 / 4);
+    let mut acc = 0_u16;
+    let mut bits = 0;
+    for c in digits.bytes() {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            _ => 63,
+        };
+        acc = acc << 6 | u16::from(value);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            let [_, byte] = (acc >> bits).to_be_bytes();
+            bytes.push(byte);
+        }
+    }
+    Ok(bytes)
+}
+
+fn write_base64(bytes: &[u8], output: &mut impl Emit<char>) {
+    let alphabet = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    for chunk in bytes.chunks(3) {
+        let acc =
+            chunk.iter().fold(0_u32, |acc, &b| acc << 8 | u32::from(b)) << (8 * (3 - chunk.len()));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                output.emit(alphabet[(acc >> (18 - 6 * i) & 0x3f) as usize].into());
+            } else {
+                output.emit('=');
+            }
+        }
+    }
+}
+
+macro_rules! blob {
+    ($(#[$meta:meta])* $name:ident, $parse:ident($wrapped:literal), $write:ident) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq)]
+        pub struct $name(Vec<u8>);
+
+        impl $name {
+            /// Returns the decoded bytes
+            #[must_use]
+            pub fn into_inner(self) -> Vec<u8> {
+                self.0
+            }
+        }
+
+        impl From<$name> for Vec<u8> {
+            fn from(v: $name) -> Self {
+                v.0
+            }
+        }
+
+        impl Unparse<char> for $name {
+            fn unparse(&self, output: &mut impl Emit<char>) {
+                $write(&self.0, output);
+            }
+        }
+
+        impl Parse<char> for $name {
+            fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
+                $parse(input, $wrapped).map(Self)
+            }
+        }
+    };
+}
+
+blob! {
+    /// `Hex` parses a run of hex digits, decoding each pair of digits into a byte
+    ///
+    /// ```
+    /// use nommy::{parse, text::Hex};
+    /// let bytes: Vec<u8> = parse::<Hex, _>("DEADbeef".chars()).unwrap().into();
+    /// assert_eq!(bytes, vec![0xde, 0xad, 0xbe, 0xef]);
+    /// ```
+    Hex, parse_hex(false), write_hex
+}
+
+blob! {
+    /// `WrappedHex` is like [`Hex`], but allows whitespace between the digits, such as in hex dumps.
+    /// Whitespace after the last digit isn't consumed
+    ///
+    /// ```
+    /// use nommy::{parse, text::WrappedHex};
+    /// let bytes: Vec<u8> = parse::<WrappedHex, _>("de ad\nbe ef\n".chars()).unwrap().into();
+    /// assert_eq!(bytes, vec![0xde, 0xad, 0xbe, 0xef]);
+    /// ```
+    WrappedHex, parse_hex(true), write_hex
+}
+
+blob! {
+    /// `Base64` parses a run of standard base64, decoding it into bytes. The `=` padding is optional,
+    /// but must be correct if present
+    ///
+    /// ```
+    /// use nommy::{parse, text::Base64};
+    /// let bytes: Vec<u8> = parse::<Base64, _>("aGVsbG8=".chars()).unwrap().into();
+    /// assert_eq!(bytes, b"hello");
+    /// let bytes: Vec<u8> = parse::<Base64, _>("aGVsbG8".chars()).unwrap().into();
+    /// assert_eq!(bytes, b"hello");
+    /// ```
+    Base64, parse_base64(false), write_base64
+}
+
+blob! {
+    /// `WrappedBase64` is like [`Base64`], but allows whitespace between the characters,
+    /// such as in line wrapped PEM files. Whitespace after the last character isn't consumed
+    ///
+    /// ```
+    /// use nommy::{parse, text::WrappedBase64};
+    /// let bytes: Vec<u8> = parse::<WrappedBase64, _>("aGVs\nbG8=\n".chars()).unwrap().into();
+    /// assert_eq!(bytes, b"hello");
+    /// ```
+    WrappedBase64, parse_base64(true), write_base64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse, unparse, IntoBuf};
+
+    #[test]
+    fn base64_round_trip() {
+        for input in &["", "f", "fo", "foo", "foob", "fooba", "foobar"] {
+            let encoded = unparse::to_string(&Base64(input.as_bytes().to_vec()));
+            if !input.is_empty() {
+                let decoded: Base64 = parse(encoded.chars()).unwrap();
+                assert_eq!(decoded.into_inner(), input.as_bytes());
+            }
+        }
+        assert_eq!(unparse::to_string(&Base64(b"foob".to_vec())), "Zm9vYg==");
+    }
+
+    #[test]
+    fn wrapped_stops_at_whitespace() {
+        let mut input = "00 ff  -".chars().into_buf();
+        let hex = WrappedHex::parse(&mut input).unwrap();
+        assert_eq!(unparse::to_string(&hex), "00ff");
+        assert_eq!(input.collect::<String>(), "  -");
+
+        let mut input = "00 ff".chars().into_buf();
+        let hex = Hex::parse(&mut input).unwrap();
+        assert_eq!(hex.into_inner(), vec![0]);
+    }
+
+    #[test]
+    fn errors() {
+        let error = |input: &str| parse::<Base64, _>(input.chars()).unwrap_err().to_string();
+        assert_eq!(error("Zm9vY"), "invalid base64 length");
+        assert_eq!(error("Zm9=vYg"), "base64 padding must only be at the end");
+        assert_eq!(error("Zm9vYg="), "invalid base64 length");
+        assert_eq!(error("!"), "could not parse base64");
+
+        let res: Result<Hex, _> = parse("abc".chars());
+        assert_eq!(res.unwrap_err().to_string(), "odd number of hex digits");
+    }
+}
@@ -0,0 +1,249 @@
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use crate::{
+    eyre,
+    unparse::{Emit, Unparse},
+    Buffer, Parse,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// `Email` parses an email address, `local@domain`, accepting the addresses people write in practice.
+///
+/// The local part is made of dot separated words of letters, digits and ``!#$%&'*+-/=?^_`{|}~``.
+/// The domain must have at least two labels, each made of letters, digits and `-` (not at either end).
+/// Non-ASCII letters are allowed in both. A trailing `.` isn't consumed, so addresses can end a sentence.
+/// See [`StrictEmail`] to follow RFC 5321 instead
+///
+/// ```
+/// use nommy::{parse, text::Email};
+///
+/// let email: Email = parse("first.last+tag@mail.example.com.".chars()).unwrap();
+/// assert_eq!(email.local, "first.last+tag");
+/// assert_eq!(email.domain, "mail.example.com");
+///
+/// let res: Result<Email, _> = parse("user@localhost".chars());
+/// assert_eq!(format!("{}", res.unwrap_err()), "domain `localhost` must have at least two labels");
+/// ```
+pub struct Email {
+    /// The part before the `@`
+    pub local: String,
+    /// The part after the `@`
+    pub domain: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// `StrictEmail` parses an email address following the mailbox syntax of RFC 5321.
+///
+/// Only ASCII is allowed. The local part can be quoted, and is at most 64 characters.
+/// The domain can be a single label or an address literal like `[192.0.2.1]` or `[IPv6:::1]`,
+/// and is at most 255 characters
+///
+/// ```
+/// use nommy::{parse, text::StrictEmail};
+///
+/// let email = parse::<StrictEmail, _>(r#""john smith"@[192.0.2.1]"#.chars()).unwrap().into_inner();
+/// assert_eq!(email.local, r#""john smith""#);
+/// assert_eq!(email.domain, "[192.0.2.1]");
+///
+/// let res: Result<StrictEmail, _> = parse("josé@example.com".chars());
+/// assert!(res.is_err());
+/// ```
+pub struct StrictEmail(Email);
+
+impl StrictEmail {
+    /// Returns the parsed address
+    #[must_use]
+    pub fn into_inner(self) -> Email {
+        self.0
+    }
+}
+
+impl From<StrictEmail> for Email {
+    fn from(v: StrictEmail) -> Self {
+        v.0
+    }
+}
+
+fn atext(c: char) -> bool {
+    c.is_alphanumeric() || "!#$%&'*+-/=?^_`{|}~".contains(c)
+}
+
+fn label_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '-'
+}
+
+/// Reads words of `allowed` characters separated by single dots.
+/// A dot that isn't followed by another word isn't consumed
+fn dot_atom(input: &mut impl Buffer<char>, allowed: impl Fn(char) -> bool) -> String {
+    let mut text = String::new();
+    loop {
+        match input.peek_ahead(0) {
+            Some(c) if allowed(c) => {
+                text.push(c);
+                input.fast_forward(1);
+            }
+            Some('.')
+                if !text.is_empty() && matches!(input.peek_ahead(1), Some(c) if allowed(c)) =>
+            {
+                text.push('.');
+                input.fast_forward(1);
+            }
+            _ => return text,
+        }
+    }
+}
+
+/// Checks each label of a domain name
+fn check_labels(domain: &str) -> eyre::Result<()> {
+    for label in domain.split('.') {
+        if label.starts_with('-') || label.ends_with('-') || label.chars().count() > 63 {
+            return Err(eyre::eyre!("invalid domain label `{}`", label));
+        }
+    }
+    Ok(())
+}
+
+impl Unparse<char> for Email {
+    fn unparse(&self, output: &mut impl Emit<char>) {
+        self.local.unparse(output);
+        output.emit('@');
+        self.domain.unparse(output);
+    }
+}
+
+impl Parse<char> for Email {
+    fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
+        let local = dot_atom(input, atext);
+        if local.is_empty() {
+            return Err(eyre::eyre!("could not parse email address"));
+        }
+        if input.next() != Some('@') {
+            return Err(eyre::eyre!("expected `@` after `{}`", local));
+        }
+
+        let domain = dot_atom(input, label_char);
+        if !domain.contains('.') {
+            return Err(eyre::eyre!(
+                "domain `{}` must have at least two labels",
+                domain
+            ));
+        }
+        check_labels(&domain)?;
+
+        Ok(Self { local, domain })
+    }
+}
+
+impl Unparse<char> for StrictEmail {
+    fn unparse(&self, output: &mut impl Emit<char>) {
+        self.0.unparse(output);
+    }
+}
+
+impl Parse<char> for StrictEmail {
+    fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
+        let local = if input.peek_ahead(0) == Some('"') {
+            input.fast_forward(1);
+            let mut local = String::from('"');
+            loop {
+                match input.next() {
+                    Some('"') => break,
+                    Some('\\') => match input.next() {
+                        Some(c @ ' '..='~') => {
+                            local.push('\\');
+                            local.push(c);
+                        }
+                        _ => return Err(eyre::eyre!("invalid quoted pair in local part")),
+                    },
+                    Some(c @ ' '..='~') => local.push(c),
+                    _ => return Err(eyre::eyre!("unterminated quoted local part")),
+                }
+            }
+            local.push('"');
+            local
+        } else {
+            dot_atom(input, |c| c.is_ascii() && atext(c))
+        };
+
+        if local.is_empty() {
+            return Err(eyre::eyre!("could not parse email address"));
+        }
+        if local.len() > 64 {
+            return Err(eyre::eyre!("local part is longer than 64 characters"));
+        }
+        if input.next() != Some('@') {
+            return Err(eyre::eyre!("expected `@` after `{}`", local));
+        }
+
+        let domain = if input.peek_ahead(0) == Some('[') {
+            input.fast_forward(1);
+            let literal: String = input.take_while(|&c| c != ']').collect();
+            let valid = match literal.strip_prefix("IPv6:") {
+                Some(ip) => ip.parse::<Ipv6Addr>().is_ok(),
+                None => literal.parse::<Ipv4Addr>().is_ok(),
+            };
+            if !valid {
+                return Err(eyre::eyre!("invalid address literal `[{}]`", literal));
+            }
+            format!("[{}]", literal)
+        } else {
+            let domain = dot_atom(input, |c| c.is_ascii() && label_char(c));
+            if domain.is_empty() {
+                return Err(eyre::eyre!("expected a domain after `@`"));
+            }
+            check_labels(&domain)?;
+            domain
+        };
+        if domain.len() > 255 {
+            return Err(eyre::eyre!("domain is longer than 255 characters"));
+        }
+
+        Ok(Self(Email { local, domain }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse, unparse, IntoBuf};
+
+    fn error<P: Parse<char> + std::fmt::Debug>(input: &str) -> String {
+        parse::<P, _>(input.chars()).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn pragmatic() {
+        let mut input = "<josé@exämple.com>".chars().into_buf();
+        assert_eq!(input.next(), Some('<'));
+        let email = Email::parse(&mut input).unwrap();
+        assert_eq!(unparse::to_string(&email), "josé@exämple.com");
+        assert_eq!(input.next(), Some('>'));
+
+        assert_eq!(error::<Email>("a..b@x.com"), "expected `@` after `a`");
+        assert_eq!(error::<Email>("@x.com"), "could not parse email address");
+        assert_eq!(error::<Email>("a@-x.com"), "invalid domain label `-x`");
+    }
+
+    #[test]
+    fn strict() {
+        let email: StrictEmail = parse(r#""a\"b"@localhost"#.chars()).unwrap();
+        assert_eq!(unparse::to_string(&email), r#""a\"b"@localhost"#);
+
+        let email: StrictEmail = parse("x@[IPv6:2001:db8::1]".chars()).unwrap();
+        assert_eq!(email.into_inner().domain, "[IPv6:2001:db8::1]");
+
+        let long = format!("{}@x.com", "a".repeat(65));
+        assert_eq!(
+            error::<StrictEmail>(&long),
+            "local part is longer than 64 characters"
+        );
+        assert_eq!(
+            error::<StrictEmail>("a@[300.0.0.1]"),
+            "invalid address literal `[300.0.0.1]`"
+        );
+        assert_eq!(
+            error::<StrictEmail>(r#""abc@x.com"#),
+            "unterminated quoted local part"
+        );
+    }
+}
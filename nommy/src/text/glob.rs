@@ -0,0 +1,272 @@
+use std::ops::RangeInclusive;
+
+use crate::{
+    eyre,
+    unparse::{Emit, Unparse},
+    Buffer, Parse,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// A part of a [`Glob`] pattern
+pub enum GlobToken {
+    /// Text that must match exactly, with escapes decoded
+    Literal(String),
+    /// `?`, matching any single character other than `/`
+    AnyChar,
+    /// `*`, matching any text that doesn't contain `/`
+    Star,
+    /// `**`, matching any text, including `/`
+    Globstar,
+    /// `[a-z_]`, matching any single character in one of the ranges.
+    /// If `negated`, written `[!a-z_]` or `[^a-z_]`, it matches any character not in the ranges
+    Class {
+        /// Whether the class matches the characters outside the ranges
+        negated: bool,
+        /// The characters in the class. Single characters are stored as a range of one
+        ranges: Vec<RangeInclusive<char>>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// `Glob` parses a shell style glob pattern, such as `src/**/*.rs` or `img[0-9]?.png`.
+///
+/// The pattern ends at the first whitespace character. A `\` escapes the character after it,
+/// both in the pattern and inside character classes
+///
+/// ```
+/// use nommy::{parse, text::{Glob, GlobToken}};
+///
+/// let glob: Glob = parse("src/**/*.rs".chars()).unwrap();
+/// assert_eq!(glob.tokens()[1], GlobToken::Globstar);
+/// assert!(glob.matches("src/text/glob.rs"));
+/// assert!(!glob.matches("src/text/glob.md"));
+///
+/// let glob: Glob = parse("[!a-c]?".chars()).unwrap();
+/// assert_eq!(glob.tokens()[0], GlobToken::Class { negated: true, ranges: vec!['a'..='c'] });
+/// assert!(glob.matches("d1"));
+/// ```
+pub struct Glob(Vec<GlobToken>);
+
+impl Glob {
+    /// The tokens of the pattern, in order
+    #[must_use]
+    pub fn tokens(&self) -> &[GlobToken] {
+        &self.0
+    }
+
+    /// Returns the tokens of the pattern, in order
+    #[must_use]
+    pub fn into_inner(self) -> Vec<GlobToken> {
+        self.0
+    }
+
+    /// Whether the whole of `path` matches the pattern
+    #[must_use]
+    pub fn matches(&self, path: &str) -> bool {
+        let path: Vec<char> = path.chars().collect();
+        matches(&self.0, &path)
+    }
+}
+
+fn matches(tokens: &[GlobToken], path: &[char]) -> bool {
+    let (token, rest) = match tokens.split_first() {
+        Some(split) => split,
+        None => return path.is_empty(),
+    };
+    match token {
+        GlobToken::Literal(text) => {
+            let len = text.chars().count();
+            path.len() >= len
+                && text.chars().eq(path[..len].iter().copied())
+                && matches(rest, &path[len..])
+        }
+        GlobToken::AnyChar => match path.split_first() {
+            Some((&c, path)) => c != '/' && matches(rest, path),
+            None => false,
+        },
+        GlobToken::Class { negated, ranges } => match path.split_first() {
+            Some((&c, path)) => {
+                c != '/' && ranges.iter().any(|r| r.contains(&c)) != *negated && matches(rest, path)
+            }
+            None => false,
+        },
+        GlobToken::Star => {
+            let end = path.iter().position(|&c| c == '/').unwrap_or(path.len());
+            (0..=end).any(|i| matches(rest, &path[i..]))
+        }
+        GlobToken::Globstar => (0..=path.len()).any(|i| matches(rest, &path[i..])),
+    }
+}
+
+/// Writes `c`, escaped if it's one of `special`
+fn write_char(c: char, special: &str, output: &mut impl Emit<char>) {
+    if special.contains(c) || c.is_whitespace() {
+        output.emit('\\');
+    }
+    output.emit(c);
+}
+
+/// Writes the tokens, escaping any special characters in literals and classes
+impl Unparse<char> for Glob {
+    fn unparse(&self, output: &mut impl Emit<char>) {
+        for token in &self.0 {
+            match token {
+                GlobToken::Literal(text) => {
+                    for c in text.chars() {
+                        write_char(c, "\\*?[", output);
+                    }
+                }
+                GlobToken::AnyChar => output.emit('?'),
+                GlobToken::Star => output.emit('*'),
+                GlobToken::Globstar => "**".unparse(output),
+                GlobToken::Class { negated, ranges } => {
+                    output.emit('[');
+                    if *negated {
+                        output.emit('!');
+                    }
+                    for range in ranges {
+                        write_char(*range.start(), "\\]-!^", output);
+                        if range.start() != range.end() {
+                            output.emit('-');
+                            write_char(*range.end(), "\\]-!^", output);
+                        }
+                    }
+                    output.emit(']');
+                }
+            }
+        }
+    }
+}
+
+/// Reads a character of a class, decoding an escape
+fn class_char(input: &mut impl Buffer<char>) -> eyre::Result<char> {
+    match input.next() {
+        Some('\\') => input.next(),
+        c => c,
+    }
+    .ok_or_else(|| eyre::eyre!("unterminated character class"))
+}
+
+fn class(input: &mut impl Buffer<char>) -> eyre::Result<GlobToken> {
+    let negated = matches!(input.peek_ahead(0), Some(c) if c == '!' || c == '^');
+    if negated {
+        input.fast_forward(1);
+    }
+
+    let mut ranges = Vec::new();
+    loop {
+        // a `]` at the start of the class is a literal
+        if input.peek_ahead(0) == Some(']') && !ranges.is_empty() {
+            input.fast_forward(1);
+            return Ok(GlobToken::Class { negated, ranges });
+        }
+        let start = class_char(input)?;
+        let end = if input.peek_ahead(0) == Some('-') && input.peek_ahead(1) != Some(']') {
+            input.fast_forward(1);
+            class_char(input)?
+        } else {
+            start
+        };
+        if end < start {
+            return Err(eyre::eyre!("invalid character range `{}-{}`", start, end));
+        }
+        ranges.push(start..=end);
+    }
+}
+
+impl Parse<char> for Glob {
+    fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
+        let mut tokens = Vec::new();
+        loop {
+            let token = match input.peek_ahead(0) {
+                Some('*') if input.peek_ahead(1) == Some('*') => {
+                    input.fast_forward(2);
+                    GlobToken::Globstar
+                }
+                Some('*') => {
+                    input.fast_forward(1);
+                    GlobToken::Star
+                }
+                Some('?') => {
+                    input.fast_forward(1);
+                    GlobToken::AnyChar
+                }
+                Some('[') => {
+                    input.fast_forward(1);
+                    class(input)?
+                }
+                Some('\\') => match input.peek_ahead(1) {
+                    Some(c) => {
+                        input.fast_forward(2);
+                        GlobToken::Literal(c.to_string())
+                    }
+                    None => return Err(eyre::eyre!("glob pattern ends with an escape")),
+                },
+                Some(c) if !c.is_whitespace() => {
+                    input.fast_forward(1);
+                    GlobToken::Literal(c.to_string())
+                }
+                _ => break,
+            };
+
+            match (tokens.last_mut(), token) {
+                (Some(GlobToken::Literal(text)), GlobToken::Literal(c)) => text.push_str(&c),
+                (_, token) => tokens.push(token),
+            }
+        }
+
+        if tokens.is_empty() {
+            Err(eyre::eyre!("could not parse glob pattern"))
+        } else {
+            Ok(Self(tokens))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse, unparse, IntoBuf};
+
+    #[test]
+    fn tokens() {
+        let mut input = r"a\*b[]a-]*.txt other".chars().into_buf();
+        let glob = Glob::parse(&mut input).unwrap();
+        assert_eq!(
+            glob.tokens(),
+            &[
+                GlobToken::Literal("a*b".to_owned()),
+                GlobToken::Class {
+                    negated: false,
+                    ranges: vec![']'..=']', 'a'..='a', '-'..='-']
+                },
+                GlobToken::Star,
+                GlobToken::Literal(".txt".to_owned()),
+            ]
+        );
+        assert_eq!(input.next(), Some(' '));
+        assert_eq!(unparse::to_string(&glob), r"a\*b[\]a\-]*.txt");
+    }
+
+    #[test]
+    fn matching() {
+        let glob: Glob = parse("**/test_?.rs".chars()).unwrap();
+        assert!(glob.matches("/test_a.rs"));
+        assert!(glob.matches("nommy/tests/test_b.rs"));
+        assert!(!glob.matches("test_a.rs"));
+        assert!(!glob.matches("nommy/test_ab.rs"));
+
+        let glob: Glob = parse("*.rs".chars()).unwrap();
+        assert!(glob.matches(".rs"));
+        assert!(!glob.matches("src/lib.rs"));
+    }
+
+    #[test]
+    fn errors() {
+        let error = |input: &str| parse::<Glob, _>(input.chars()).unwrap_err().to_string();
+        assert_eq!(error("[abc"), "unterminated character class");
+        assert_eq!(error("[z-a]"), "invalid character range `z-a`");
+        assert_eq!(error(r"abc\"), "glob pattern ends with an escape");
+        assert_eq!(error(" "), "could not parse glob pattern");
+    }
+}
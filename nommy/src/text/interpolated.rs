@@ -0,0 +1,162 @@
+use crate::{
+    eyre::{self, WrapErr},
+    unparse::{Emit, Unparse},
+    Buffer, Parse,
+};
+
+use super::{literal::escape, Tag};
+
+#[derive(Debug, Clone, PartialEq)]
+/// A part of an [`Interpolated`] string
+pub enum Segment<P> {
+    /// Text between interpolations, with escapes decoded
+    Literal(String),
+    /// A value parsed by `P`, from between the interpolation delimiters
+    Value(P),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// `Interpolated` parses a double quoted string containing values parsed by `P`,
+/// each surrounded by `OPEN` and `CLOSE`, such as `"hello ${name}!"`.
+///
+/// Text between the values is split into [`Segment::Literal`]s, with the same escapes as
+/// [`CharLiteral`](super::CharLiteral) decoded. A `\` before the first character of `OPEN`
+/// stops it from starting an interpolation
+///
+/// ```
+/// use nommy::{parse, text::{AnyOf1, Interpolated, Segment}};
+///
+/// type Name = AnyOf1<"abcdefghijklmnopqrstuvwxyz">;
+/// let s: Interpolated<Name, "${", "}"> = parse(r#""hello ${name}!\n""#.chars()).unwrap();
+/// let segments = s.into_inner();
+/// assert_eq!(segments.len(), 3);
+/// assert_eq!(segments[0], Segment::Literal("hello ".to_owned()));
+/// assert!(matches!(&segments[1], Segment::Value(name) if String::from(name.clone()) == "name"));
+/// assert_eq!(segments[2], Segment::Literal("!\n".to_owned()));
+///
+/// let s: Interpolated<Name, "${", "}"> = parse(r#""costs \${price}""#.chars()).unwrap();
+/// assert_eq!(s.into_inner(), vec![Segment::Literal("costs ${price}".to_owned())]);
+/// ```
+pub struct Interpolated<P, const OPEN: &'static str, const CLOSE: &'static str>(Vec<Segment<P>>);
+
+impl<P, const OPEN: &'static str, const CLOSE: &'static str> Interpolated<P, OPEN, CLOSE> {
+    /// The segments of the string, in order
+    #[must_use]
+    pub fn segments(&self) -> &[Segment<P>] {
+        &self.0
+    }
+
+    /// Returns the segments of the string, in order
+    #[must_use]
+    pub fn into_inner(self) -> Vec<Segment<P>> {
+        self.0
+    }
+}
+
+/// Writes the literal text escaped, and each value between `OPEN` and `CLOSE`
+impl<P: Unparse<char>, const OPEN: &'static str, const CLOSE: &'static str> Unparse<char>
+    for Interpolated<P, OPEN, CLOSE>
+{
+    fn unparse(&self, output: &mut impl Emit<char>) {
+        output.emit('"');
+        for segment in &self.0 {
+            match segment {
+                Segment::Literal(text) => {
+                    for c in text.chars() {
+                        if OPEN.starts_with(c) {
+                            output.emit('\\');
+                            output.emit(c);
+                        } else {
+                            for c in c.escape_debug() {
+                                output.emit(c);
+                            }
+                        }
+                    }
+                }
+                Segment::Value(value) => {
+                    OPEN.unparse(output);
+                    value.unparse(output);
+                    CLOSE.unparse(output);
+                }
+            }
+        }
+        output.emit('"');
+    }
+}
+
+impl<P: Parse<char>, const OPEN: &'static str, const CLOSE: &'static str> Parse<char>
+    for Interpolated<P, OPEN, CLOSE>
+{
+    fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
+        if input.next() != Some('"') {
+            return Err(eyre::eyre!("could not parse interpolated string"));
+        }
+
+        let mut segments = Vec::new();
+        let mut literal = String::new();
+        loop {
+            let mut cursor = input.cursor();
+            if Tag::<OPEN>::peek(&mut cursor) {
+                let pos = cursor.position();
+                input.fast_forward(pos);
+                if !literal.is_empty() {
+                    segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                }
+                let value = P::parse(input).wrap_err("could not parse interpolated value")?;
+                Tag::<CLOSE>::parse(input).wrap_err_with(|| {
+                    format!("unterminated interpolation, expected `{}`", CLOSE)
+                })?;
+                segments.push(Segment::Value(value));
+                continue;
+            }
+
+            match input.next() {
+                Some('"') => break,
+                Some('\\') => match input.peek_ahead(0) {
+                    Some(c) if OPEN.starts_with(c) => {
+                        input.fast_forward(1);
+                        literal.push(c);
+                    }
+                    _ => literal.push(escape(input)?),
+                },
+                Some(c) => literal.push(c),
+                None => return Err(eyre::eyre!("unterminated interpolated string")),
+            }
+        }
+
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+        Ok(Self(segments))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse, text::OneOf, unparse};
+
+    type Digits = Interpolated<OneOf<"0123456789">, "{", "}">;
+
+    #[test]
+    fn segments() {
+        let s: Digits = parse(r#""{1}{2} \{3}""#.chars()).unwrap();
+        assert_eq!(s.segments().len(), 3);
+        assert!(matches!(s.segments()[2], Segment::Literal(ref text) if text == " {3}"));
+        assert_eq!(unparse::to_string(&s), r#""{1}{2} \{3}""#);
+
+        let s: Digits = parse(r#""""#.chars()).unwrap();
+        assert!(s.segments().is_empty());
+    }
+
+    #[test]
+    fn errors() {
+        let error = |input: &str| parse::<Digits, _>(input.chars()).unwrap_err().to_string();
+        assert_eq!(
+            error(r#""a {1"#),
+            "unterminated interpolation, expected `}`"
+        );
+        assert_eq!(error(r#""a {x}""#), "could not parse interpolated value");
+        assert_eq!(error(r#""a"#), "unterminated interpolated string");
+    }
+}
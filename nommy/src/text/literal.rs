@@ -0,0 +1,414 @@
+use crate::{
+    eyre,
+    unparse::{Emit, Unparse},
+    Buffer, Parse,
+};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+/// `CharLiteral` parses a Rust style character literal, such as `'a'`, `'\n'` or `'\u{1F600}'`,
+/// decoding any escape sequence into the char it represents
+///
+/// ```
+/// use nommy::{parse, text::CharLiteral};
+///
+/// let c: char = parse::<CharLiteral, _>(r"'\u{1F600}'".chars()).unwrap().into();
+/// assert_eq!(c, '😀');
+///
+/// let res: Result<CharLiteral, _> = parse(r"'\q'".chars());
+/// assert_eq!(format!("{}", res.unwrap_err()), r"unknown character escape `\q`");
+/// ```
+pub struct CharLiteral(char);
+
+impl CharLiteral {
+    /// Returns the decoded char
+    #[must_use]
+    pub const fn into_inner(self) -> char {
+        self.0
+    }
+}
+
+impl From<CharLiteral> for char {
+    fn from(v: CharLiteral) -> Self {
+        v.0
+    }
+}
+
+/// Decodes the escape sequence following a `\`
+pub(super) fn escape(input: &mut impl Buffer<char>) -> eyre::Result<char> {
+    match input.next() {
+        Some('n') => Ok('\n'),
+        Some('r') => Ok('\r'),
+        Some('t') => Ok('\t'),
+        Some('0') => Ok('\0'),
+        Some('\\') => Ok('\\'),
+        Some('\'') => Ok('\''),
+        Some('"') => Ok('"'),
+        Some('x') => {
+            let digits: String = input.take(2).collect();
+            match u8::from_str_radix(&digits, 16) {
+                Ok(b) if digits.len() == 2 && b <= 0x7f => Ok(b.into()),
+                Ok(_) if digits.len() == 2 => Err(eyre::eyre!(
+                    "hex escape `\\x{}` must be at most `\\x7f`",
+                    digits
+                )),
+                _ => Err(eyre::eyre!("invalid hex escape `\\x{}`", digits)),
+            }
+        }
+        Some('u') => {
+            if input.next() != Some('{') {
+                return Err(eyre::eyre!("unicode escape must start with `\\u{{`"));
+            }
+            let mut digits = String::new();
+            loop {
+                match input.next() {
+                    Some('}') => break,
+                    Some(c) if c.is_ascii_hexdigit() && digits.len() < 6 => digits.push(c),
+                    _ => return Err(eyre::eyre!("unterminated unicode escape")),
+                }
+            }
+            u32::from_str_radix(&digits, 16)
+                .ok()
+                .and_then(std::char::from_u32)
+                .ok_or_else(|| eyre::eyre!("invalid unicode escape `\\u{{{}}}`", digits))
+        }
+        Some(c) => Err(eyre::eyre!("unknown character escape `\\{}`", c)),
+        None => Err(eyre::eyre!("unterminated character escape")),
+    }
+}
+
+/// Writes the char, escaped if it needs to be
+impl Unparse<char> for CharLiteral {
+    fn unparse(&self, output: &mut impl Emit<char>) {
+        output.emit('\'');
+        for c in self.0.escape_debug() {
+            output.emit(c);
+        }
+        output.emit('\'');
+    }
+}
+
+impl Parse<char> for CharLiteral {
+    fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
+        if input.next() != Some('\'') {
+            return Err(eyre::eyre!("could not parse char literal"));
+        }
+
+        let c = match input.next() {
+            Some('\\') => escape(input)?,
+            Some('\'') => return Err(eyre::eyre!("empty char literal")),
+            Some(c) if matches!(c, '\n' | '\r' | '\t') => {
+                return Err(eyre::eyre!("{:?} must be escaped in a char literal", c))
+            }
+            Some(c) => c,
+            None => return Err(eyre::eyre!("unterminated char literal")),
+        };
+
+        match input.next() {
+            Some('\'') => Ok(Self(c)),
+            Some(_) => Err(eyre::eyre!(
+                "char literal must contain exactly one character"
+            )),
+            None => Err(eyre::eyre!("unterminated char literal")),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// `RawString` parses a Rust style raw string literal, such as `r"C:\path"` or `r#"say "hi""#`.
+/// The string ends at the first `"` followed by as many `#`s as the string started with,
+/// and the contents aren't unescaped
+///
+/// ```
+/// use nommy::{parse, text::RawString};
+///
+/// let s: String = parse::<RawString, _>(r###"r##"a "# b"##"###.chars()).unwrap().into();
+/// assert_eq!(s, r##"a "# b"##);
+///
+/// let res: Result<RawString, _> = parse(r##"r#"abc""##.chars());
+/// assert_eq!(format!("{}", res.unwrap_err()), "unterminated raw string");
+/// ```
+pub struct RawString {
+    value: String,
+    hashes: usize,
+}
+
+impl RawString {
+    /// Returns the contents of the string
+    #[must_use]
+    pub fn into_inner(self) -> String {
+        self.value
+    }
+
+    /// How many `#`s surround the string
+    #[must_use]
+    pub const fn hashes(&self) -> usize {
+        self.hashes
+    }
+}
+
+impl From<RawString> for String {
+    fn from(v: RawString) -> Self {
+        v.value
+    }
+}
+
+/// Writes the string with the same number of `#`s it was parsed with
+impl Unparse<char> for RawString {
+    fn unparse(&self, output: &mut impl Emit<char>) {
+        let hashes = "#".repeat(self.hashes);
+        output.emit('r');
+        hashes.unparse(output);
+        output.emit('"');
+        self.value.unparse(output);
+        output.emit('"');
+        hashes.unparse(output);
+    }
+}
+
+impl Parse<char> for RawString {
+    fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
+        if input.next() != Some('r') {
+            return Err(eyre::eyre!("could not parse raw string"));
+        }
+
+        let mut hashes = 0;
+        loop {
+            match input.next() {
+                Some('#') => hashes += 1,
+                Some('"') => break,
+                _ => return Err(eyre::eyre!("could not parse raw string")),
+            }
+        }
+
+        let mut value = String::new();
+        loop {
+            match input.next() {
+                Some('"') => {
+                    // only the end of the string if followed by enough hashes
+                    let mut cursor = input.cursor();
+                    if (0..hashes).all(|_| cursor.next() == Some('#')) {
+                        input.fast_forward(hashes);
+                        return Ok(Self { value, hashes });
+                    }
+                    value.push('"');
+                }
+                Some(c) => value.push(c),
+                None => return Err(eyre::eyre!("unterminated raw string")),
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+/// `Heredoc` parses a heredoc, `<<MARKER` followed by lines of text up until a line containing only `MARKER`.
+/// The marker is made up of letters, digits and `_`. The body keeps the newline at the end of each line,
+/// and the newline after the closing marker isn't consumed
+///
+/// ```
+/// use nommy::{parse, text::Heredoc};
+///
+/// let doc: Heredoc = parse("<<SQL\nSELECT *\nFROM t\nSQL\n".chars()).unwrap();
+/// assert_eq!(doc.marker(), "SQL");
+/// assert_eq!(doc.body(), "SELECT *\nFROM t\n");
+///
+/// let res: Result<Heredoc, _> = parse("<<SQL\nSELECT *\nSQLITE".chars());
+/// assert_eq!(format!("{}", res.unwrap_err()), "unterminated heredoc, expected `SQL`");
+/// ```
+pub struct Heredoc {
+    marker: String,
+    body: String,
+}
+
+impl Heredoc {
+    /// The marker that started and ended the heredoc
+    #[must_use]
+    pub fn marker(&self) -> &str {
+        &self.marker
+    }
+
+    /// The text between the markers
+    #[must_use]
+    pub fn body(&self) -> &str {
+        &self.body
+    }
+
+    /// Returns the text between the markers
+    #[must_use]
+    pub fn into_inner(self) -> String {
+        self.body
+    }
+}
+
+impl From<Heredoc> for String {
+    fn from(v: Heredoc) -> Self {
+        v.body
+    }
+}
+
+/// Returns true if the next line contains only `marker`, without consuming the line ending
+fn closes(input: &mut impl Buffer<char>, marker: &str) -> bool {
+    let mut cursor = input.cursor();
+    if !marker.chars().all(|c| cursor.next() == Some(c)) {
+        return false;
+    }
+    match cursor.next() {
+        None | Some('\n') => true,
+        Some('\r') => cursor.next() == Some('\n'),
+        Some(_) => false,
+    }
+}
+
+impl Unparse<char> for Heredoc {
+    fn unparse(&self, output: &mut impl Emit<char>) {
+        "<<".unparse(output);
+        self.marker.unparse(output);
+        output.emit('\n');
+        self.body.unparse(output);
+        self.marker.unparse(output);
+    }
+}
+
+impl Parse<char> for Heredoc {
+    fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
+        if input.next() != Some('<') || input.next() != Some('<') {
+            return Err(eyre::eyre!("could not parse heredoc"));
+        }
+
+        let mut marker = String::new();
+        loop {
+            match input.next() {
+                Some(c) if c.is_alphanumeric() || c == '_' => marker.push(c),
+                Some('\n') => break,
+                Some('\r') if input.next() == Some('\n') => break,
+                _ => return Err(eyre::eyre!("heredoc marker must be followed by a new line")),
+            }
+        }
+        if marker.is_empty() {
+            return Err(eyre::eyre!("heredoc marker must not be empty"));
+        }
+
+        let mut body = String::new();
+        loop {
+            if closes(input, &marker) {
+                input.fast_forward(marker.chars().count());
+                return Ok(Self { marker, body });
+            }
+            loop {
+                match input.next() {
+                    Some('\n') => {
+                        body.push('\n');
+                        break;
+                    }
+                    Some(c) => body.push(c),
+                    None => return Err(eyre::eyre!("unterminated heredoc, expected `{}`", marker)),
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse, unparse, IntoBuf};
+
+    fn error(input: &str) -> String {
+        parse::<CharLiteral, _>(input.chars())
+            .unwrap_err()
+            .to_string()
+    }
+
+    #[test]
+    fn escapes() {
+        for (input, expected) in &[
+            ("'a'", 'a'),
+            (r"'\n'", '\n'),
+            (r"'\''", '\''),
+            (r"'\\'", '\\'),
+            (r"'\x41'", 'A'),
+            (r"'\u{e9}'", 'é'),
+            (r"'\u{1F600}'", '😀'),
+        ] {
+            let c: CharLiteral = parse(input.chars()).unwrap();
+            assert_eq!(c.into_inner(), *expected);
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        for input in &["'a'", r"'\n'", r"'\''", r"'\\'", r"'\0'", "'é'"] {
+            let c: CharLiteral = parse(input.chars()).unwrap();
+            assert_eq!(unparse::to_string(&c), *input);
+        }
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(error("'a"), "unterminated char literal");
+        assert_eq!(error("''"), "empty char literal");
+        assert_eq!(
+            error("'ab'"),
+            "char literal must contain exactly one character"
+        );
+        assert_eq!(error("'\n'"), "'\\n' must be escaped in a char literal");
+        assert_eq!(
+            error(r"'\x80'"),
+            r"hex escape `\x80` must be at most `\x7f`"
+        );
+        assert_eq!(error(r"'\xg0'"), r"invalid hex escape `\xg0`");
+        assert_eq!(error(r"'\u{d800}'"), r"invalid unicode escape `\u{d800}`");
+        assert_eq!(error(r"'\u{1234567}'"), "unterminated unicode escape");
+    }
+
+    #[test]
+    fn raw_string() {
+        let mut input = r##"r"a\n"r#"b"c"#r"""##.chars().into_buf();
+        for (value, hashes) in &[(r"a\n", 0), (r#"b"c"#, 1), ("", 0)] {
+            let s = RawString::parse(&mut input).unwrap();
+            assert_eq!(s.hashes(), *hashes);
+            assert_eq!(
+                unparse::to_string(&s),
+                format!("r{0}\"{1}\"{0}", "#".repeat(*hashes), value)
+            );
+            assert_eq!(s.into_inner(), *value);
+        }
+        assert_eq!(input.next(), None);
+    }
+
+    #[test]
+    fn raw_string_errors() {
+        let mut input = r#"r#"a""#.chars().into_buf();
+        assert_eq!(
+            RawString::parse(&mut input).unwrap_err().to_string(),
+            "unterminated raw string"
+        );
+        let mut input = "r#a".chars().into_buf();
+        assert_eq!(
+            RawString::parse(&mut input).unwrap_err().to_string(),
+            "could not parse raw string"
+        );
+    }
+
+    #[test]
+    fn heredoc() {
+        let mut input = "<<EOF\nEOF is\r\n\nnot EOF\nEOF\r\nrest".chars().into_buf();
+        let doc = Heredoc::parse(&mut input).unwrap();
+        assert_eq!(doc.body(), "EOF is\r\n\nnot EOF\n");
+        assert_eq!(unparse::to_string(&doc), "<<EOF\nEOF is\r\n\nnot EOF\nEOF");
+        assert_eq!(input.collect::<String>(), "\r\nrest");
+
+        let doc: Heredoc = parse("<<A\nA".chars()).unwrap();
+        assert_eq!(doc.into_inner(), "");
+    }
+
+    #[test]
+    fn heredoc_errors() {
+        let error = |input: &str| parse::<Heredoc, _>(input.chars()).unwrap_err().to_string();
+        assert_eq!(error("<<\nA"), "heredoc marker must not be empty");
+        assert_eq!(
+            error("<<A B\nA"),
+            "heredoc marker must be followed by a new line"
+        );
+        assert_eq!(error("<<A\nb\n A"), "unterminated heredoc, expected `A`");
+    }
+}
@@ -1,9 +1,31 @@
 use std::{convert::TryFrom, str::FromStr};
 
-use crate::{eyre, Buffer, Parse};
+use crate::{
+    eyre,
+    message::Message,
+    process::Process,
+    unparse::{Emit, Unparse},
+    Buffer, Parse,
+};
 
 use super::OneOf;
 
+/// Storage for the collected text. With the `smartstring` feature,
+/// short text is stored inline rather than on the heap
+#[cfg(feature = "smartstring")]
+type Text = smartstring::alias::String;
+#[cfg(not(feature = "smartstring"))]
+type Text = String;
+
+#[cfg(feature = "smartstring")]
+fn into_string(text: Text) -> String {
+    text.into()
+}
+#[cfg(not(feature = "smartstring"))]
+fn into_string(text: Text) -> String {
+    text
+}
+
 #[derive(Debug, Clone, PartialEq)]
 /// `AnyOf` is a generic type that implements [`Parse`] to match many characters within the given string
 ///
@@ -13,27 +35,38 @@ use super::OneOf;
 /// let c: String = AnyOf::<"-_">::parse(&mut buffer).unwrap().into();
 /// assert_eq!(c, "-_-");
 /// ```
-pub struct AnyOf<const CHARS: &'static str>(String);
+pub struct AnyOf<const CHARS: &'static str>(Text);
 
 impl<const CHARS: &'static str> From<AnyOf<CHARS>> for String {
+    fn from(v: AnyOf<CHARS>) -> Self {
+        into_string(v.0)
+    }
+}
+
+#[cfg(feature = "smartstring")]
+impl<const CHARS: &'static str> From<AnyOf<CHARS>> for smartstring::alias::String {
     fn from(v: AnyOf<CHARS>) -> Self {
         v.0
     }
 }
 
+impl<const CHARS: &'static str> Unparse<char> for AnyOf<CHARS> {
+    fn unparse(&self, output: &mut impl Emit<char>) {
+        self.0.unparse(output);
+    }
+}
+
+impl<const CHARS: &'static str> Process for AnyOf<CHARS> {
+    type Output = String;
+
+    fn process(self) -> Self::Output {
+        into_string(self.0)
+    }
+}
+
 impl<const CHARS: &'static str> Parse<char> for AnyOf<CHARS> {
     fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
-        let mut output = String::new();
-
-        loop {
-            let mut cursor = input.cursor();
-            match OneOf::<CHARS>::parse(&mut cursor) {
-                Ok(c) => output.push(c.into()),
-                Err(_) => break,
-            }
-            let pos = cursor.position();
-            input.fast_forward(pos);
-        }
+        let output: Text = input.take_while_collect(|&c| CHARS.contains(c));
 
         Ok(Self(output))
     }
@@ -60,43 +93,54 @@ impl<const CHARS: &'static str> Parse<char> for AnyOf<CHARS> {
 /// let c: String = WhileNot1::<".">::parse(&mut buffer).unwrap().into();
 /// assert_eq!(c, "-_-");
 /// ```
-pub struct WhileNot1<const CHARS: &'static str>(String);
+pub struct WhileNot1<const CHARS: &'static str>(Text);
 
 impl<const CHARS: &'static str> From<WhileNot1<CHARS>> for String {
+    fn from(v: WhileNot1<CHARS>) -> Self {
+        into_string(v.0)
+    }
+}
+
+#[cfg(feature = "smartstring")]
+impl<const CHARS: &'static str> From<WhileNot1<CHARS>> for smartstring::alias::String {
     fn from(v: WhileNot1<CHARS>) -> Self {
         v.0
     }
 }
 
+impl<const CHARS: &'static str> Unparse<char> for WhileNot1<CHARS> {
+    fn unparse(&self, output: &mut impl Emit<char>) {
+        self.0.unparse(output);
+    }
+}
+
+impl<const CHARS: &'static str> Process for WhileNot1<CHARS> {
+    type Output = String;
+
+    fn process(self) -> Self::Output {
+        into_string(self.0)
+    }
+}
+
 impl<const CHARS: &'static str> Parse<char> for WhileNot1<CHARS> {
     fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
-        let mut output = String::new();
-
-        while !OneOf::<CHARS>::peek(&mut input.cursor()) {
-            match input.next() {
-                None => break,
-                Some(c) => output.push(c),
-            }
-        }
+        let output: Text = input.take_while_collect(|&c| !CHARS.contains(c));
 
         if output.is_empty() {
-            Err(eyre::eyre!("no characters found"))
+            Err(eyre::Report::new(Message::NoCharacters))
         } else {
             Ok(Self(output))
         }
     }
 
     fn peek(input: &mut impl Buffer<char>) -> bool {
-        if OneOf::<CHARS>::peek(input) {
+        // stops at the end of the input as well as at one of the chars
+        let continues = |c: Option<char>| matches!(c, Some(c) if !CHARS.contains(c));
+        if !continues(input.peek_ahead(0)) {
             return false;
         }
-        loop {
-            let mut cursor = input.cursor();
-            if OneOf::<CHARS>::peek(&mut cursor) {
-                break;
-            }
-            let pos = cursor.position();
-            input.fast_forward(pos);
+        while continues(input.peek_ahead(0)) {
+            input.fast_forward(1);
         }
         true
     }
@@ -111,9 +155,16 @@ impl<const CHARS: &'static str> Parse<char> for WhileNot1<CHARS> {
 /// let c: String = AnyOf1::<"-_">::parse(&mut buffer).unwrap().into();
 /// assert_eq!(c, "-_-");
 /// ```
-pub struct AnyOf1<const CHARS: &'static str>(String);
+pub struct AnyOf1<const CHARS: &'static str>(Text);
 
 impl<const CHARS: &'static str> From<AnyOf1<CHARS>> for String {
+    fn from(v: AnyOf1<CHARS>) -> Self {
+        into_string(v.0)
+    }
+}
+
+#[cfg(feature = "smartstring")]
+impl<const CHARS: &'static str> From<AnyOf1<CHARS>> for smartstring::alias::String {
     fn from(v: AnyOf1<CHARS>) -> Self {
         v.0
     }
@@ -126,22 +177,26 @@ impl<const CHARS: &'static str> TryFrom<AnyOf1<CHARS>> for usize {
     }
 }
 
+impl<const CHARS: &'static str> Unparse<char> for AnyOf1<CHARS> {
+    fn unparse(&self, output: &mut impl Emit<char>) {
+        self.0.unparse(output);
+    }
+}
+
+impl<const CHARS: &'static str> Process for AnyOf1<CHARS> {
+    type Output = String;
+
+    fn process(self) -> Self::Output {
+        into_string(self.0)
+    }
+}
+
 impl<const CHARS: &'static str> Parse<char> for AnyOf1<CHARS> {
     fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
-        let mut output = String::new();
-
-        loop {
-            let mut cursor = input.cursor();
-            match OneOf::<CHARS>::parse(&mut cursor) {
-                Ok(c) => output.push(c.into()),
-                Err(_) => break,
-            }
-            let pos = cursor.position();
-            input.fast_forward(pos);
-        }
+        let output: Text = input.take_while_collect(|&c| CHARS.contains(c));
 
         if output.is_empty() {
-            Err(eyre::eyre!("no characters found"))
+            Err(eyre::Report::new(Message::NoCharacters))
         } else {
             Ok(Self(output))
         }
@@ -175,4 +230,15 @@ mod tests {
         assert!(AnyOf::<"0123456789">::peek(&mut cursor));
         assert_eq!(cursor.next(), Some('$'));
     }
+
+    #[test]
+    fn while_not1_peek_end() {
+        let mut buffer = "abc".chars().into_buf();
+        let mut cursor = buffer.cursor();
+        assert!(WhileNot1::<";">::peek(&mut cursor));
+        assert_eq!(cursor.position(), 3);
+
+        assert!(!WhileNot1::<";">::peek(&mut "".chars().into_buf()));
+        assert!(!WhileNot1::<";">::peek(&mut ";".chars().into_buf()));
+    }
 }
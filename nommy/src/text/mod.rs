@@ -2,17 +2,55 @@
 
 mod tag;
 pub use tag::*;
+mod tag_set;
+pub use tag_set::*;
 mod one_of;
 pub use one_of::*;
 mod many;
 pub use many::*;
+mod reserved;
+pub use reserved::*;
+mod literal;
+pub use literal::*;
+mod interpolated;
+pub use interpolated::*;
+mod uri;
+pub use uri::*;
+mod email;
+pub use email::*;
+mod binary;
+pub use binary::*;
+mod units;
+pub use units::*;
+mod glob;
+pub use glob::*;
+mod number;
+pub use number::*;
 
-use crate::{eyre, Buffer, Parse};
+use crate::{
+    eyre,
+    process::Process,
+    unparse::{Emit, Unparse},
+    Buffer, Parse,
+};
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
 /// Parses newline `"\n"` or carriage return `"\r\n"`
 pub struct LineEnding;
 
+/// Writes a newline
+impl Unparse<char> for LineEnding {
+    fn unparse(&self, output: &mut impl Emit<char>) {
+        "\n".unparse(output);
+    }
+}
+
+impl Process for LineEnding {
+    type Output = ();
+
+    fn process(self) -> Self::Output {}
+}
+
 impl Parse<char> for LineEnding {
     fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
         if Self::peek(input) {
@@ -31,11 +69,26 @@ impl Parse<char> for LineEnding {
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
 /// Type that parses any space characters (tabs, spaces)
 pub struct Space;
 
+/// Writes a single space
+impl Unparse<char> for Space {
+    fn unparse(&self, output: &mut impl Emit<char>) {
+        " ".unparse(output);
+    }
+}
+
+impl Process for Space {
+    type Output = ();
+
+    fn process(self) -> Self::Output {}
+}
+
 impl Parse<char> for Space {
+    const MAX_PEEK: Option<usize> = Some(1);
+
     fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
         if Self::peek(input) {
             Ok(Self)
@@ -49,11 +102,26 @@ impl Parse<char> for Space {
     }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
 /// Type that parses any whitespace characters (tabs, spaces, newlines and carriage returns)
 pub struct WhiteSpace;
 
+/// Writes a single space
+impl Unparse<char> for WhiteSpace {
+    fn unparse(&self, output: &mut impl Emit<char>) {
+        " ".unparse(output);
+    }
+}
+
+impl Process for WhiteSpace {
+    type Output = ();
+
+    fn process(self) -> Self::Output {}
+}
+
 impl Parse<char> for WhiteSpace {
+    const MAX_PEEK: Option<usize> = Some(2);
+
     fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
         if Self::peek(input) {
             Ok(Self)
@@ -71,6 +139,40 @@ impl Parse<char> for WhiteSpace {
     }
 }
 
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+/// Type that parses any Unicode whitespace character, as given by [`char::is_whitespace`],
+/// such as non-breaking spaces and line separators as well as those in [`WhiteSpace`]
+pub struct UnicodeWhiteSpace;
+
+/// Writes a single space
+impl Unparse<char> for UnicodeWhiteSpace {
+    fn unparse(&self, output: &mut impl Emit<char>) {
+        " ".unparse(output);
+    }
+}
+
+impl Process for UnicodeWhiteSpace {
+    type Output = ();
+
+    fn process(self) -> Self::Output {}
+}
+
+impl Parse<char> for UnicodeWhiteSpace {
+    const MAX_PEEK: Option<usize> = Some(1);
+
+    fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
+        if Self::peek(input) {
+            Ok(Self)
+        } else {
+            Err(eyre::eyre!("could not parse whitespace"))
+        }
+    }
+
+    fn peek(input: &mut impl Buffer<char>) -> bool {
+        matches!(input.next(), Some(c) if c.is_whitespace())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -91,6 +193,14 @@ mod tests {
         assert_eq!(cursor.next(), Some('.'));
     }
 
+    #[test]
+    fn unicode_whitespace() {
+        let mut input = " \u{a0}\u{2028}\n.".chars().into_buf();
+        assert_eq!(Vec::<UnicodeWhiteSpace>::parse(&mut input).unwrap().len(), 4);
+        assert_eq!(input.next(), Some('.'));
+        assert!(!WhiteSpace::peek(&mut "\u{a0}".chars().into_buf()));
+    }
+
     #[test]
     fn parse_newline() {
         let mut input = "\n.\r\n.".chars().into_buf();
@@ -0,0 +1,223 @@
+use crate::{
+    eyre,
+    unparse::{Emit, Unparse},
+    Buffer, Parse,
+};
+
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+/// `Number` parses a decimal number written with `DECIMAL` before the fraction and
+/// `GROUP` between groups of thousands, such as `-1,234.56` or `1.234,56`.
+///
+/// The grouping is optional, but if used, every group after the first must have 3 digits.
+/// A `GROUP` that isn't followed by a digit isn't consumed, so numbers can be separated by it.
+/// See [`DotNumber`] and [`CommaNumber`] for the common locales
+///
+/// ```
+/// use nommy::{parse, text::{CommaNumber, DotNumber, Number}};
+///
+/// let n: f64 = parse::<DotNumber, _>("1,234.5".chars()).unwrap().into();
+/// assert_eq!(n, 1234.5);
+/// let n: f64 = parse::<CommaNumber, _>("-1.234,5".chars()).unwrap().into();
+/// assert_eq!(n, -1234.5);
+/// let n: f64 = parse::<Number<',', ' '>, _>("1 000 000".chars()).unwrap().into();
+/// assert_eq!(n, 1e6);
+///
+/// let res: Result<DotNumber, _> = parse("1,23".chars());
+/// assert_eq!(format!("{}", res.unwrap_err()), "digit groups must have 3 digits, found `23`");
+/// ```
+pub struct Number<const DECIMAL: char, const GROUP: char>(f64);
+
+/// A [`Number`] like `1,234.56`
+pub type DotNumber = Number<'.', ','>;
+
+/// A [`Number`] like `1.234,56`
+pub type CommaNumber = Number<',', '.'>;
+
+impl<const DECIMAL: char, const GROUP: char> Number<DECIMAL, GROUP> {
+    /// Returns the parsed number
+    #[must_use]
+    pub const fn into_inner(self) -> f64 {
+        self.0
+    }
+}
+
+impl<const DECIMAL: char, const GROUP: char> From<Number<DECIMAL, GROUP>> for f64 {
+    fn from(v: Number<DECIMAL, GROUP>) -> Self {
+        v.0
+    }
+}
+
+fn digits(input: &mut impl Buffer<char>) -> String {
+    input.take_while_collect(char::is_ascii_digit)
+}
+
+/// Writes the number with its integer part grouped into thousands
+impl<const DECIMAL: char, const GROUP: char> Unparse<char> for Number<DECIMAL, GROUP> {
+    fn unparse(&self, output: &mut impl Emit<char>) {
+        let text = self.0.to_string();
+        let (sign, text) = text
+            .strip_prefix('-')
+            .map_or(("", text.as_str()), |text| ("-", text));
+        let (int, fraction) = text
+            .find('.')
+            .map_or((text, None), |i| (&text[..i], Some(&text[i + 1..])));
+
+        sign.unparse(output);
+        for (i, c) in int.chars().enumerate() {
+            if i > 0 && (int.len() - i) % 3 == 0 {
+                output.emit(GROUP);
+            }
+            output.emit(c);
+        }
+        if let Some(fraction) = fraction {
+            output.emit(DECIMAL);
+            fraction.unparse(output);
+        }
+    }
+}
+
+impl<const DECIMAL: char, const GROUP: char> Parse<char> for Number<DECIMAL, GROUP> {
+    fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
+        let mut number = String::new();
+        match input.peek_ahead(0) {
+            Some('-') => {
+                number.push('-');
+                input.fast_forward(1);
+            }
+            Some('+') => input.fast_forward(1),
+            _ => {}
+        }
+
+        let first = digits(input);
+        if first.is_empty() {
+            return Err(eyre::eyre!("could not parse number"));
+        }
+        number.push_str(&first);
+
+        while input.peek_ahead(0) == Some(GROUP) && matches!(input.peek_ahead(1), Some('0'..='9')) {
+            if first.len() > 3 {
+                return Err(eyre::eyre!(
+                    "digit groups must have 3 digits, found `{}`",
+                    first
+                ));
+            }
+            input.fast_forward(1);
+            let group = digits(input);
+            if group.len() != 3 {
+                return Err(eyre::eyre!(
+                    "digit groups must have 3 digits, found `{}`",
+                    group
+                ));
+            }
+            number.push_str(&group);
+        }
+
+        if input.peek_ahead(0) == Some(DECIMAL) && matches!(input.peek_ahead(1), Some('0'..='9')) {
+            input.fast_forward(1);
+            number.push('.');
+            number.push_str(&digits(input));
+        }
+
+        Ok(Self(number.parse()?))
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, PartialOrd)]
+/// `Bounded` parses `P`, failing if its value is less than `MIN` or greater than `MAX`,
+/// so out of range values are reported where they were parsed
+///
+/// ```
+/// use nommy::{parse, text::{Bounded, DotNumber}};
+///
+/// type Percent = Bounded<DotNumber, 0, 100>;
+/// let n: f64 = parse::<Percent, _>("99.5".chars()).unwrap().into_inner().into();
+/// assert_eq!(n, 99.5);
+///
+/// let res: Result<Percent, _> = parse("1,000".chars());
+/// assert_eq!(format!("{}", res.unwrap_err()), "`1000` is out of the range 0..=100");
+/// ```
+pub struct Bounded<P, const MIN: i32, const MAX: i32>(P);
+
+impl<P, const MIN: i32, const MAX: i32> Bounded<P, MIN, MAX> {
+    /// Returns the parsed value
+    #[must_use]
+    pub fn into_inner(self) -> P {
+        self.0
+    }
+}
+
+impl<P: Unparse<char>, const MIN: i32, const MAX: i32> Unparse<char> for Bounded<P, MIN, MAX> {
+    fn unparse(&self, output: &mut impl Emit<char>) {
+        self.0.unparse(output);
+    }
+}
+
+impl<P, const MIN: i32, const MAX: i32> Parse<char> for Bounded<P, MIN, MAX>
+where
+    P: Parse<char> + Clone + Into<f64>,
+{
+    fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
+        let value = P::parse(input)?;
+        let n: f64 = value.clone().into();
+        if n < f64::from(MIN) || n > f64::from(MAX) {
+            return Err(eyre::eyre!("`{}` is out of the range {}..={}", n, MIN, MAX));
+        }
+        Ok(Self(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse, unparse, IntoBuf};
+
+    #[test]
+    fn separators() {
+        let mut input = "1.000,5;2.".chars().into_buf();
+        let n = CommaNumber::parse(&mut input).unwrap();
+        assert_eq!(n.into_inner().to_string(), "1000.5");
+        assert_eq!(input.next(), Some(';'));
+        let n = CommaNumber::parse(&mut input).unwrap();
+        assert_eq!(n.into_inner().to_string(), "2");
+        assert_eq!(input.next(), Some('.'));
+
+        let mut input = "12.".chars().into_buf();
+        let n = DotNumber::parse(&mut input).unwrap();
+        assert_eq!(n.into_inner().to_string(), "12");
+        assert_eq!(input.next(), Some('.'));
+    }
+
+    #[test]
+    fn round_trip() {
+        for input in &["-1,234,567.125", "100", "1,000", "0.5"] {
+            let n: DotNumber = parse(input.chars()).unwrap();
+            assert_eq!(unparse::to_string(&n), *input);
+        }
+        let n: CommaNumber = parse("+12.345,75".chars()).unwrap();
+        assert_eq!(unparse::to_string(&n), "12.345,75");
+    }
+
+    #[test]
+    fn errors() {
+        let error = |input: &str| {
+            parse::<DotNumber, _>(input.chars())
+                .unwrap_err()
+                .to_string()
+        };
+        assert_eq!(
+            error("1234,567"),
+            "digit groups must have 3 digits, found `1234`"
+        );
+        assert_eq!(
+            error("1,2345"),
+            "digit groups must have 3 digits, found `2345`"
+        );
+        assert_eq!(error("-.5"), "could not parse number");
+
+        let res: Result<Bounded<CommaNumber, -10, 10>, _> = parse("-10,5".chars());
+        assert_eq!(
+            res.unwrap_err().to_string(),
+            "`-10.5` is out of the range -10..=10"
+        );
+    }
+}
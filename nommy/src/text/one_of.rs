@@ -1,4 +1,10 @@
-use crate::{eyre, Buffer, Parse};
+use crate::{
+    eyre,
+    message::Message,
+    process::Process,
+    unparse::{Emit, Unparse},
+    Buffer, Parse,
+};
 
 #[derive(Debug, Copy, Clone, PartialEq)]
 /// `OneOf` is a generic type that implements [`Parse`] to match one character within the given string
@@ -17,21 +23,39 @@ impl<const CHARS: &'static str> From<OneOf<CHARS>> for char {
     }
 }
 
+impl<const CHARS: &'static str> Unparse<char> for OneOf<CHARS> {
+    fn unparse(&self, output: &mut impl Emit<char>) {
+        output.emit(self.0);
+    }
+}
+
+impl<const CHARS: &'static str> Process for OneOf<CHARS> {
+    type Output = char;
+
+    fn process(self) -> Self::Output {
+        self.0
+    }
+}
+
 impl<const CHARS: &'static str> Parse<char> for OneOf<CHARS> {
+    const MAX_PEEK: Option<usize> = Some(1);
+
     fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
         match input.next() {
             Some(c) => {
                 if CHARS.contains(c) {
                     Ok(Self(c))
                 } else {
-                    Err(eyre::eyre!(
-                        "error parsing one of {:?}, found {:?}",
-                        CHARS,
-                        c
-                    ))
+                    Err(eyre::Report::new(Message::OneOf {
+                        chars: CHARS,
+                        found: Some(c),
+                    }))
                 }
             }
-            None => Err(eyre::eyre!("error parsing one of {:?}, reached EOF", CHARS)),
+            None => Err(eyre::Report::new(Message::OneOf {
+                chars: CHARS,
+                found: None,
+            })),
         }
     }
 
@@ -0,0 +1,137 @@
+use crate::{
+    eyre,
+    message::Message,
+    unparse::{Emit, Unparse},
+    Buffer, Parse,
+};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+/// `Reserved` parses `P`, but fails if the text `P` consumed is one of the
+/// whitespace separated keywords in `KEYWORDS`. Whitespace surrounding the
+/// parsed text is ignored when comparing against the keywords
+///
+/// ```
+/// use nommy::{parse, text::{AnyOf1, Reserved}};
+/// type Ident = Reserved<AnyOf1<"abcdefghijklmnopqrstuvwxyz">, "if else while">;
+///
+/// let ident: String = parse::<Ident, _>("iffy".chars()).unwrap().into();
+/// assert_eq!(ident, "iffy");
+///
+/// let res: Result<Ident, _> = parse("if".chars());
+/// assert_eq!(format!("{}", res.unwrap_err()), "found reserved keyword \"if\"");
+/// ```
+pub struct Reserved<P, const KEYWORDS: &'static str>(P);
+
+impl<P, const KEYWORDS: &'static str> Reserved<P, KEYWORDS> {
+    /// Returns the inner parsed value
+    pub fn into_inner(self) -> P {
+        self.0
+    }
+}
+
+impl<P: Into<String>, const KEYWORDS: &'static str> From<Reserved<P, KEYWORDS>> for String {
+    fn from(v: Reserved<P, KEYWORDS>) -> Self {
+        v.0.into()
+    }
+}
+
+#[cfg(feature = "smartstring")]
+impl<P: Into<smartstring::alias::String>, const KEYWORDS: &'static str>
+    From<Reserved<P, KEYWORDS>> for smartstring::alias::String
+{
+    fn from(v: Reserved<P, KEYWORDS>) -> Self {
+        v.0.into()
+    }
+}
+
+/// Returns the keyword that the next `len` characters match, if any
+fn keyword<const KEYWORDS: &'static str>(
+    input: &mut impl Buffer<char>,
+    len: usize,
+) -> Option<&'static str> {
+    let text: String = input.cursor().take(len).collect();
+    let text = text.trim();
+    KEYWORDS.split_whitespace().find(|&kw| kw == text)
+}
+
+impl<P: Unparse<char>, const KEYWORDS: &'static str> Unparse<char> for Reserved<P, KEYWORDS> {
+    fn unparse(&self, output: &mut impl Emit<char>) {
+        self.0.unparse(output);
+    }
+}
+
+impl<P: Parse<char>, const KEYWORDS: &'static str> Parse<char> for Reserved<P, KEYWORDS> {
+    fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
+        let mut cursor = input.cursor();
+        let p = P::parse(&mut cursor)?;
+        let len = cursor.position();
+
+        if let Some(kw) = keyword::<KEYWORDS>(input, len) {
+            return Err(eyre::Report::new(Message::Reserved {
+                keyword: kw.to_string(),
+            }));
+        }
+
+        input.fast_forward(len);
+        Ok(Self(p))
+    }
+
+    fn peek(input: &mut impl Buffer<char>) -> bool {
+        let mut cursor = input.cursor();
+        if !P::peek(&mut cursor) {
+            return false;
+        }
+        let len = cursor.position();
+
+        if keyword::<KEYWORDS>(input, len).is_some() {
+            return false;
+        }
+
+        input.fast_forward(len);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse, text::AnyOf1, IntoBuf};
+
+    type Ident = Reserved<AnyOf1<"abcdefghijklmnopqrstuvwxyz">, "fn let\nmut">;
+
+    #[test]
+    fn not_reserved() {
+        let mut input = "letter mutt".chars().into_buf();
+        let ident: String = Ident::parse(&mut input).unwrap().into();
+        assert_eq!(ident, "letter");
+        assert_eq!(input.next(), Some(' '));
+    }
+
+    #[test]
+    fn reserved() {
+        let mut input = "mut x".chars().into_buf();
+        let res = Ident::parse(&mut input);
+        assert_eq!(
+            format!("{}", res.unwrap_err()),
+            "found reserved keyword \"mut\""
+        );
+    }
+
+    #[test]
+    fn peek_reserved() {
+        let mut input = "fn foo".chars().into_buf();
+        let mut cursor = input.cursor();
+        assert!(!Ident::peek(&mut cursor));
+
+        let mut input = "foo fn".chars().into_buf();
+        let mut cursor = input.cursor();
+        assert!(Ident::peek(&mut cursor));
+        assert_eq!(cursor.next(), Some(' '));
+    }
+
+    #[test]
+    fn reserved_ignores_whitespace() {
+        let res: Result<Reserved<AnyOf1<"elt ">, "let">, _> = parse("let ".chars());
+        assert!(res.is_err());
+    }
+}
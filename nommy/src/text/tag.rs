@@ -1,8 +1,41 @@
-use std::iter::FromIterator;
+use crate::{
+    complete::{self, Expected},
+    eyre,
+    message::Message,
+    process::Process,
+    unparse::{Emit, Unparse},
+    Buffer, Parse,
+};
 
-use crate::{eyre, Buffer, Parse};
+/// Reads the input for as long as it matches `tag`, stopping before the first character that doesn't,
+/// so a failed parse leaves the input where it diverged from the tag.
+/// If it doesn't match, returns the text that was found, ending with the character that didn't match
+fn match_tag(
+    input: &mut impl Buffer<char>,
+    tag: &'static str,
+    eq: impl Fn(char, char) -> bool,
+) -> Result<(), String> {
+    let mut found = String::new();
+    for (typed, c) in tag.chars().enumerate() {
+        match input.peek_ahead(0) {
+            Some(next) if eq(next, c) => {
+                found.push(next);
+                input.fast_forward(1);
+            }
+            Some(next) => {
+                found.push(next);
+                return Err(found);
+            }
+            None => {
+                complete::expect(Expected::Tag { tag, typed });
+                return Err(found);
+            }
+        }
+    }
+    Ok(())
+}
 
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
 /// `Tag` is a generic type that implements [`Parse`] to match the given string exactly
 ///
 /// ```
@@ -13,18 +46,239 @@ use crate::{eyre, Buffer, Parse};
 /// ```
 pub struct Tag<const TAG: &'static str>;
 
+impl<const TAG: &'static str> Unparse<char> for Tag<TAG> {
+    fn unparse(&self, output: &mut impl Emit<char>) {
+        TAG.unparse(output);
+    }
+}
+
+impl<const TAG: &'static str> Process for Tag<TAG> {
+    type Output = ();
+
+    fn process(self) -> Self::Output {}
+}
+
 impl<const TAG: &'static str> Parse<char> for Tag<TAG> {
+    const MAX_PEEK: Option<usize> = Some(crate::lookahead::chars(TAG));
+
+    fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
+        match match_tag(input, TAG, |a, b| a == b) {
+            Ok(()) => Ok(Self),
+            Err(found) => Err(eyre::Report::new(Message::Tag { tag: TAG, found })),
+        }
+    }
+
+    fn peek(input: &mut impl Buffer<char>) -> bool {
+        for (typed, c) in TAG.chars().enumerate() {
+            match input.next() {
+                Some(next) if next == c => {}
+                Some(_) => return false,
+                None => {
+                    complete::expect(Expected::Tag { tag: TAG, typed });
+                    return false;
+                }
+            }
+        }
+        true
+    }
+}
+
+#[derive(Debug, Default, Copy, Clone, PartialEq)]
+/// `ITag` is a generic type that implements [`Parse`] to match the given string, ignoring case.
+///
+/// By default, only ASCII characters are compared case insensitively.
+/// Enabling the `unicode-case` feature compares all characters using Unicode simple case folding
+///
+/// ```
+/// use nommy::{Parse, IntoBuf, text::ITag};
+/// let mut buffer = "SELECT Select".chars().into_buf();
+/// ITag::<"select">::parse(&mut buffer).unwrap();
+/// ITag::<" select">::parse(&mut buffer).unwrap();
+/// ```
+pub struct ITag<const TAG: &'static str>;
+
+/// Folds the case of `c` so that it can be compared case insensitively
+#[cfg(not(feature = "unicode-case"))]
+fn fold(c: char) -> char {
+    c.to_ascii_lowercase()
+}
+
+/// Folds the case of `c` so that it can be compared case insensitively
+#[cfg(feature = "unicode-case")]
+fn fold(c: char) -> char {
+    unicode_case_mapping::case_folded(c)
+        .and_then(|c| std::char::from_u32(c.get()))
+        .unwrap_or(c)
+}
+
+/// Writes the tag as it was given, since the case of the parsed input isn't stored
+impl<const TAG: &'static str> Unparse<char> for ITag<TAG> {
+    fn unparse(&self, output: &mut impl Emit<char>) {
+        TAG.unparse(output);
+    }
+}
+
+impl<const TAG: &'static str> Process for ITag<TAG> {
+    type Output = ();
+
+    fn process(self) -> Self::Output {}
+}
+
+impl<const TAG: &'static str> Parse<char> for ITag<TAG> {
+    const MAX_PEEK: Option<usize> = Some(crate::lookahead::chars(TAG));
+
     fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
-        let s = String::from_iter(input.take(TAG.len()));
-        if TAG == s {
-            Ok(Self)
-        } else {
-            Err(eyre::eyre!("failed to parse tag {:?}, found {:?}", TAG, s))
+        match match_tag(input, TAG, |a, b| fold(a) == fold(b)) {
+            Ok(()) => Ok(Self),
+            Err(found) => Err(eyre::Report::new(Message::ITag { tag: TAG, found })),
         }
     }
 
     fn peek(input: &mut impl Buffer<char>) -> bool {
-        TAG.chars().eq(input.take(TAG.len()))
+        for (typed, c) in TAG.chars().map(fold).enumerate() {
+            match input.next() {
+                Some(next) if fold(next) == c => {}
+                Some(_) => return false,
+                None => {
+                    complete::expect(Expected::Tag { tag: TAG, typed });
+                    return false;
+                }
+            }
+        }
+        true
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+/// `TagOneOf` is a generic type that implements [`Parse`] to match the longest of the given strings,
+/// so operators like `=`, `==` and `=>` don't need to be ordered by hand.
+/// If more than one tag is the same, the first is used.
+///
+/// The tags are compared one character at a time, dropping the tags that stop matching,
+/// like walking down a trie, so each character of input is only read once. There can be at most 128 tags
+///
+/// ```
+/// use nommy::{Parse, IntoBuf, text::TagOneOf};
+///
+/// type Op = TagOneOf<{ &["=", "==", "=>", "!="] }>;
+///
+/// let mut buffer = "===>".chars().into_buf();
+/// assert_eq!(Op::parse(&mut buffer).unwrap().into_inner(), "==");
+/// assert_eq!(Op::parse(&mut buffer).unwrap().into_inner(), "=>");
+/// assert!(Op::parse(&mut buffer).is_err());
+/// ```
+pub struct TagOneOf<const TAGS: &'static [&'static str]>(&'static str);
+
+impl<const TAGS: &'static [&'static str]> TagOneOf<TAGS> {
+    const FITS: () = assert!(TAGS.len() <= 128, "TagOneOf supports at most 128 tags");
+
+    /// Returns the tag that was matched
+    #[must_use]
+    pub const fn into_inner(self) -> &'static str {
+        self.0
+    }
+
+    /// Finds the longest tag at the start of the input, returning its index and length in characters.
+    /// If the input ends while some tags could still match, they're reported as completions
+    fn longest(input: &mut impl Buffer<char>) -> Option<(usize, usize)> {
+        let () = Self::FITS;
+        let mut alive = u128::MAX >> (128 - TAGS.len());
+        let mut best = None;
+        // every alive tag has the same first `offset` bytes, which match the first `typed` characters
+        let mut offset = 0;
+        let mut typed = 0;
+        loop {
+            let mut ended = false;
+            for (i, tag) in TAGS.iter().enumerate() {
+                if alive & 1 << i != 0 && tag.len() == offset {
+                    alive &= !(1 << i);
+                    if !ended {
+                        best = Some((i, typed));
+                        ended = true;
+                    }
+                }
+            }
+            if alive == 0 {
+                return best;
+            }
+
+            let c = match input.peek_ahead(typed) {
+                Some(c) => c,
+                None => {
+                    for (i, tag) in TAGS.iter().enumerate() {
+                        if alive & 1 << i != 0 {
+                            complete::expect(Expected::Tag { tag, typed });
+                        }
+                    }
+                    return best;
+                }
+            };
+            let mut bytes = [0; 4];
+            let bytes = c.encode_utf8(&mut bytes).as_bytes();
+            for (i, tag) in TAGS.iter().enumerate() {
+                if tag.as_bytes().get(offset..offset + bytes.len()) != Some(bytes) {
+                    alive &= !(1 << i);
+                }
+            }
+            offset += bytes.len();
+            typed += 1;
+        }
+    }
+}
+
+impl<const TAGS: &'static [&'static str]> From<TagOneOf<TAGS>> for &'static str {
+    fn from(v: TagOneOf<TAGS>) -> Self {
+        v.0
+    }
+}
+
+impl<const TAGS: &'static [&'static str]> Unparse<char> for TagOneOf<TAGS> {
+    fn unparse(&self, output: &mut impl Emit<char>) {
+        self.0.unparse(output);
+    }
+}
+
+/// The output is the tag that was matched
+impl<const TAGS: &'static [&'static str]> Process for TagOneOf<TAGS> {
+    type Output = &'static str;
+
+    fn process(self) -> Self::Output {
+        self.0
+    }
+}
+
+impl<const TAGS: &'static [&'static str]> Parse<char> for TagOneOf<TAGS> {
+    const MAX_PEEK: Option<usize> = {
+        let mut longest = Some(0);
+        let mut i = 0;
+        while i < TAGS.len() {
+            longest = crate::lookahead::either(longest, Some(crate::lookahead::chars(TAGS[i])));
+            i += 1;
+        }
+        longest
+    };
+
+    fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
+        match Self::longest(input) {
+            Some((i, len)) => {
+                input.fast_forward(len);
+                Ok(Self(TAGS[i]))
+            }
+            None => Err(eyre::Report::new(Message::TagOneOf {
+                tags: TAGS,
+                found: input.peek_ahead(0),
+            })),
+        }
+    }
+
+    fn peek(input: &mut impl Buffer<char>) -> bool {
+        match Self::longest(input) {
+            Some((_, len)) => {
+                input.fast_forward(len);
+                true
+            }
+            None => false,
+        }
     }
 }
 
@@ -76,4 +330,70 @@ mod tests {
             "failed to parse tag \")\", found \"1\""
         );
     }
+
+    #[test]
+    fn test_parse_stops_at_mismatch() {
+        let mut input = "fxo".chars().into_buf();
+        let res = Tag::<"foo">::parse(&mut input);
+        assert_eq!(
+            format!("{}", res.unwrap_err()),
+            "failed to parse tag \"foo\", found \"fx\""
+        );
+        assert_eq!(input.next(), Some('x'));
+
+        let mut input = "FX".chars().into_buf();
+        assert!(ITag::<"foo">::parse(&mut input).is_err());
+        assert_eq!(input.next(), Some('X'));
+    }
+
+    #[test]
+    fn test_tag_one_of() {
+        type Op = TagOneOf<{ &["<", "<=", "<<=", "", "<="] }>;
+
+        let mut input = "<<=<=<a".chars().into_buf();
+        let mut cursor = input.cursor();
+        assert!(Op::peek(&mut cursor));
+        assert_eq!(cursor.position(), 3);
+
+        for op in &["<<=", "<=", "<", ""] {
+            assert_eq!(Op::parse(&mut input).unwrap().into_inner(), *op);
+        }
+        assert_eq!(input.next(), Some('a'));
+
+        let res: Result<TagOneOf<{ &["a", "bc"] }>, _> = parse("bd".chars());
+        assert_eq!(
+            format!("{}", res.unwrap_err()),
+            "failed to parse one of the tags [\"a\", \"bc\"], found Some('b')"
+        );
+    }
+
+    #[test]
+    fn test_itag() {
+        let mut input = "FoO".chars().into_buf();
+        let mut cursor = input.cursor();
+        assert!(ITag::<"foo">::peek(&mut cursor));
+
+        ITag::<"fOo">::parse(&mut input).unwrap();
+        assert!(input.next().is_none());
+
+        let res: Result<ITag<"foo">, _> = parse("fob".chars());
+        assert_eq!(
+            format!("{}", res.unwrap_err()),
+            "failed to parse tag \"foo\" (ignoring case), found \"fob\""
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "unicode-case")]
+    fn test_itag_unicode() {
+        let res: Result<ITag<"σας">, _> = parse("ΣΑΣ".chars());
+        res.unwrap();
+    }
+
+    #[test]
+    #[cfg(not(feature = "unicode-case"))]
+    fn test_itag_ascii_only() {
+        let res: Result<ITag<"σας">, _> = parse("ΣΑΣ".chars());
+        assert!(res.is_err());
+    }
 }
@@ -0,0 +1,274 @@
+use std::{
+    any::{type_name, TypeId},
+    cell::RefCell,
+    fmt,
+    marker::PhantomData,
+    sync::Arc,
+};
+
+use crate::{
+    eyre,
+    message::Message,
+    unparse::{Emit, Unparse},
+    Buffer, Parse,
+};
+
+/// A set of tags that's only known at runtime, such as keywords or operators read from a config file.
+/// It's built once, then made active with [`TagSet::scope`] for [`AnyTag`] to parse.
+///
+/// Like [`TagOneOf`](super::TagOneOf), the longest tag that matches is found. The tags are kept sorted,
+/// and each character of input narrows down the tags that still match with a binary search,
+/// so each character is only read once, however many tags there are
+#[derive(Clone, Default)]
+pub struct TagSet {
+    /// sorted, without duplicates
+    tags: Arc<[Box<[char]>]>,
+}
+
+thread_local! {
+    static ACTIVE: RefCell<Vec<(TypeId, TagSet)>> = RefCell::new(Vec::new());
+}
+
+/// Removes the active tag set when dropped, even if parsing panicked
+struct Restore;
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        ACTIVE.with(|active| active.borrow_mut().pop());
+    }
+}
+
+impl TagSet {
+    /// Builds the set of `tags`
+    pub fn new<S: AsRef<str>>(tags: impl IntoIterator<Item = S>) -> Self {
+        let mut tags: Vec<Box<[char]>> = tags
+            .into_iter()
+            .map(|tag| tag.as_ref().chars().collect())
+            .collect();
+        tags.sort_unstable();
+        tags.dedup();
+        Self { tags: tags.into() }
+    }
+
+    /// Runs `f` with this set active on the current thread, for [`AnyTag<K>`] to parse.
+    /// `K` names the set, so a grammar can have more than one.
+    ///
+    /// Scopes can be nested, and the innermost set for `K` is used
+    pub fn scope<K: 'static, R>(&self, f: impl FnOnce() -> R) -> R {
+        ACTIVE.with(|active| active.borrow_mut().push((TypeId::of::<K>(), self.clone())));
+        let _restore = Restore;
+        f()
+    }
+
+    /// Finds the longest tag at the start of the input, without consuming it
+    ///
+    /// ```
+    /// use nommy::{text::TagSet, IntoBuf};
+    /// let ops = TagSet::new(["=", "==", "=>"]);
+    /// assert_eq!(ops.longest(&mut "===".chars().into_buf()), Some(&['=', '='][..]));
+    /// assert_eq!(ops.longest(&mut "!=".chars().into_buf()), None);
+    /// ```
+    pub fn longest(&self, input: &mut impl Buffer<char>) -> Option<&[char]> {
+        let tags = &self.tags[..];
+        let mut best = None;
+        // every tag in `start..end` starts with the `depth` characters read so far
+        let (mut start, mut end) = (0, tags.len());
+        let mut depth = 0;
+        while start < end {
+            // a tag that's all of the characters read sorts before the longer ones
+            if tags[start].len() == depth {
+                best = Some(&*tags[start]);
+                start += 1;
+                if start == end {
+                    break;
+                }
+            }
+            let c = match input.peek_ahead(depth) {
+                Some(c) => c,
+                None => break,
+            };
+            let alive = &tags[start..end];
+            end = start + alive.partition_point(|tag| tag[depth] <= c);
+            start += alive.partition_point(|tag| tag[depth] < c);
+            depth += 1;
+        }
+        best
+    }
+}
+
+impl fmt::Debug for TagSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set()
+            .entries(self.tags.iter().map(|tag| tag.iter().collect::<String>()))
+            .finish()
+    }
+}
+
+/// `AnyTag` parses the longest tag of the [`TagSet`] that's active for `K`.
+/// It fails if there's no active set for `K`
+///
+/// ```
+/// use nommy::{parse, text::{AnyTag, TagSet, WhiteSpace}, Parse};
+///
+/// /// names the set of operators
+/// struct Ops;
+///
+/// #[derive(Debug, Parse)]
+/// #[nommy(ignore = WhiteSpace)]
+/// struct Expr(#[nommy(inner_parser = AnyTag<Ops>)] Vec<AnyTag<Ops>>);
+///
+/// // read from the user's config
+/// let ops = TagSet::new(vec!["+".to_owned(), "++".to_owned(), "<>".to_owned()]);
+///
+/// let expr: Expr = ops.scope::<Ops, _>(|| parse("+++ <>".chars())).unwrap();
+/// let ops: Vec<&str> = expr.0.iter().map(AnyTag::as_str).collect();
+/// assert_eq!(ops, ["++", "+", "<>"]);
+///
+/// assert!(parse::<AnyTag<Ops>, _>("+".chars()).is_err());
+/// ```
+pub struct AnyTag<K> {
+    tag: String,
+    _k: PhantomData<fn() -> K>,
+}
+
+impl<K> AnyTag<K> {
+    /// Returns the tag that was matched
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.tag
+    }
+
+    /// Returns the tag that was matched
+    #[must_use]
+    pub fn into_inner(self) -> String {
+        self.tag
+    }
+}
+
+impl<K: 'static> AnyTag<K> {
+    /// Calls `f` with the innermost active set for `K`
+    fn with_active<R>(f: impl FnOnce(Option<&TagSet>) -> R) -> R {
+        ACTIVE.with(|active| {
+            let active = active.borrow();
+            let set = active
+                .iter()
+                .rev()
+                .find(|(k, _)| *k == TypeId::of::<K>())
+                .map(|(_, set)| set);
+            f(set)
+        })
+    }
+}
+
+impl<K> From<AnyTag<K>> for String {
+    fn from(v: AnyTag<K>) -> Self {
+        v.tag
+    }
+}
+
+// implemented by hand so that `K` doesn't need to implement them
+
+impl<K> fmt::Debug for AnyTag<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("AnyTag").field(&self.tag).finish()
+    }
+}
+
+impl<K> Clone for AnyTag<K> {
+    fn clone(&self) -> Self {
+        Self {
+            tag: self.tag.clone(),
+            _k: PhantomData,
+        }
+    }
+}
+
+impl<K> PartialEq for AnyTag<K> {
+    fn eq(&self, other: &Self) -> bool {
+        self.tag == other.tag
+    }
+}
+
+impl<K> Eq for AnyTag<K> {}
+
+impl<K> Unparse<char> for AnyTag<K> {
+    fn unparse(&self, output: &mut impl Emit<char>) {
+        self.tag.as_str().unparse(output);
+    }
+}
+
+impl<K: 'static> Parse<char> for AnyTag<K> {
+    fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
+        let tag = Self::with_active(|set| {
+            let set = set.ok_or_else(|| {
+                eyre::eyre!(
+                    "no tag set for {} is active. Use `TagSet::scope`",
+                    type_name::<K>()
+                )
+            })?;
+            match set.longest(input) {
+                Some(tag) => Ok(tag.iter().collect::<String>()),
+                None => Err(eyre::Report::new(Message::TagSet {
+                    set: type_name::<K>(),
+                    found: input.peek_ahead(0),
+                })),
+            }
+        })?;
+        input.fast_forward(tag.chars().count());
+        Ok(Self {
+            tag,
+            _k: PhantomData,
+        })
+    }
+
+    fn peek(input: &mut impl Buffer<char>) -> bool {
+        let len =
+            Self::with_active(|set| set.and_then(|set| set.longest(input)).map(<[char]>::len));
+        match len {
+            Some(len) => {
+                input.fast_forward(len);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::IntoBuf;
+
+    struct Keywords;
+
+    #[test]
+    fn longest_of_many() {
+        let words = ["in", "int", "integer", "if", "i", "for", "format", "i"];
+        let set = TagSet::new(words);
+        let longest = |text: &str| -> Option<String> {
+            let mut input = text.chars().into_buf();
+            set.longest(&mut input).map(|tag| tag.iter().collect())
+        };
+        assert_eq!(longest("integ").as_deref(), Some("int"));
+        assert_eq!(longest("integers").as_deref(), Some("integer"));
+        assert_eq!(longest("ix").as_deref(), Some("i"));
+        assert_eq!(longest("forma").as_deref(), Some("for"));
+        assert_eq!(longest("f"), None);
+        assert_eq!(longest(""), None);
+    }
+
+    #[test]
+    fn scopes() {
+        let outer = TagSet::new(["a"]);
+        let inner = TagSet::new(["b"]);
+        let mut input = "ab".chars().into_buf();
+        outer.scope::<Keywords, _>(|| {
+            assert!(AnyTag::<Keywords>::parse(&mut input).is_ok());
+            inner.scope::<Keywords, _>(|| {
+                assert_eq!(AnyTag::<Keywords>::parse(&mut input).unwrap().as_str(), "b");
+            });
+        });
+        let err = AnyTag::<Keywords>::parse(&mut "a".chars().into_buf()).unwrap_err();
+        assert!(err.to_string().starts_with("no tag set for"));
+    }
+}
@@ -0,0 +1,244 @@
+use crate::{
+    eyre,
+    unparse::{Emit, Unparse},
+    Buffer, Parse,
+};
+
+/// Reads a number followed by its unit, such as `250ms`
+fn quantity(input: &mut impl Buffer<char>, what: &str) -> eyre::Result<(u64, String)> {
+    let digits: String = input.take_while_collect(char::is_ascii_digit);
+    if digits.is_empty() {
+        return Err(eyre::eyre!("could not parse {}", what));
+    }
+    let number = digits
+        .parse()
+        .map_err(|_| eyre::eyre!("{} is too large", what))?;
+
+    let unit = input.take_while_collect(|c: &char| c.is_alphabetic());
+    Ok((number, unit))
+}
+
+const NANOS: [(&str, u64); 7] = [
+    ("d", 86_400_000_000_000),
+    ("h", 3_600_000_000_000),
+    ("m", 60_000_000_000),
+    ("s", 1_000_000_000),
+    ("ms", 1_000_000),
+    ("us", 1_000),
+    ("ns", 1),
+];
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+/// `Duration` parses a duration made of numbers and units, largest unit first, such as `1h30m` or `250ms`.
+///
+/// The units are `d`, `h`, `m`, `s`, `ms`, `us` (or `µs`) and `ns`
+///
+/// ```
+/// use std::time;
+/// use nommy::{parse, text::Duration};
+///
+/// let duration: time::Duration = parse::<Duration, _>("1h30m".chars()).unwrap().into();
+/// assert_eq!(duration, time::Duration::from_secs(5400));
+///
+/// let res: Result<Duration, _> = parse("30".chars());
+/// assert_eq!(format!("{}", res.unwrap_err()), "expected a unit after `30`");
+/// ```
+pub struct Duration(std::time::Duration);
+
+impl Duration {
+    /// Returns the parsed duration
+    #[must_use]
+    pub const fn into_inner(self) -> std::time::Duration {
+        self.0
+    }
+}
+
+impl From<Duration> for std::time::Duration {
+    fn from(v: Duration) -> Self {
+        v.0
+    }
+}
+
+/// Writes each non-zero unit, such as `1h30m`, or `0s` for an empty duration
+impl Unparse<char> for Duration {
+    fn unparse(&self, output: &mut impl Emit<char>) {
+        let mut nanos = self.0.as_nanos();
+        if nanos == 0 {
+            "0s".unparse(output);
+        }
+        for &(unit, size) in &NANOS {
+            let size = u128::from(size);
+            if nanos >= size {
+                format!("{}{}", nanos / size, unit).unparse(output);
+                nanos %= size;
+            }
+        }
+    }
+}
+
+impl Parse<char> for Duration {
+    fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
+        let mut nanos = 0_u64;
+        let mut smallest = None;
+        loop {
+            let (number, unit) = quantity(input, "duration")?;
+            let unit = if unit == "µs" { "us" } else { &unit };
+            let index = match NANOS.iter().position(|&(u, _)| u == unit) {
+                Some(index) => index,
+                None if unit.is_empty() => {
+                    return Err(eyre::eyre!("expected a unit after `{}`", number))
+                }
+                None => return Err(eyre::eyre!("unknown duration unit `{}`", unit)),
+            };
+            if matches!(smallest, Some(i) if i >= index) {
+                return Err(eyre::eyre!(
+                    "duration units must go from largest to smallest"
+                ));
+            }
+            smallest = Some(index);
+
+            nanos = number
+                .checked_mul(NANOS[index].1)
+                .and_then(|n| n.checked_add(nanos))
+                .ok_or_else(|| eyre::eyre!("duration is too large"))?;
+
+            if !matches!(input.peek_ahead(0), Some('0'..='9')) {
+                return Ok(Self(std::time::Duration::from_nanos(nanos)));
+            }
+        }
+    }
+}
+
+/// Sizes from largest to smallest, so the first that divides a value is the best unit to write it in
+const BYTES: [(&str, u64); 11] = [
+    ("PiB", 1 << 50),
+    ("PB", 1_000_000_000_000_000),
+    ("TiB", 1 << 40),
+    ("TB", 1_000_000_000_000),
+    ("GiB", 1 << 30),
+    ("GB", 1_000_000_000),
+    ("MiB", 1 << 20),
+    ("MB", 1_000_000),
+    ("KiB", 1 << 10),
+    ("kB", 1_000),
+    ("B", 1),
+];
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+/// `Size` parses a number of bytes with an optional unit, such as `10MiB` or `4kB`.
+///
+/// The decimal units are `B`, `kB` (or `KB`), `MB`, `GB`, `TB` and `PB`,
+/// and the binary units are `KiB`, `MiB`, `GiB`, `TiB` and `PiB`
+///
+/// ```
+/// use nommy::{parse, text::Size};
+///
+/// let bytes: u64 = parse::<Size, _>("10MiB".chars()).unwrap().into();
+/// assert_eq!(bytes, 10 * 1024 * 1024);
+/// let bytes: u64 = parse::<Size, _>("4kB".chars()).unwrap().into();
+/// assert_eq!(bytes, 4000);
+/// ```
+pub struct Size(u64);
+
+impl Size {
+    /// Returns the number of bytes
+    #[must_use]
+    pub const fn into_inner(self) -> u64 {
+        self.0
+    }
+}
+
+impl From<Size> for u64 {
+    fn from(v: Size) -> Self {
+        v.0
+    }
+}
+
+/// Writes the size in the largest unit that divides it exactly
+impl Unparse<char> for Size {
+    fn unparse(&self, output: &mut impl Emit<char>) {
+        let (unit, size) = BYTES
+            .iter()
+            .find(|&&(_, size)| self.0 != 0 && self.0.checked_rem(size) == Some(0))
+            .copied()
+            .unwrap_or(("B", 1));
+        format!("{}{}", self.0 / size, unit).unparse(output);
+    }
+}
+
+impl Parse<char> for Size {
+    fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
+        let (number, unit) = quantity(input, "size")?;
+        let size = match unit.as_str() {
+            "" => 1,
+            "KB" => 1_000,
+            unit => match BYTES.iter().find(|&&(u, _)| u == unit) {
+                Some(&(_, size)) => size,
+                None => return Err(eyre::eyre!("unknown size unit `{}`", unit)),
+            },
+        };
+        number
+            .checked_mul(size)
+            .map(Self)
+            .ok_or_else(|| eyre::eyre!("size is too large"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse, unparse, IntoBuf};
+
+    fn error<P: Parse<char> + std::fmt::Debug>(input: &str) -> String {
+        parse::<P, _>(input.chars()).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn duration() {
+        let duration: Duration = parse("2d3h0m4s5ms6µs7ns".chars()).unwrap();
+        assert_eq!(unparse::to_string(&duration), "2d3h4s5ms6us7ns");
+
+        let mut input = "250ms, 1s".chars().into_buf();
+        let duration = Duration::parse(&mut input).unwrap();
+        assert_eq!(duration.into_inner().as_millis(), 250);
+        assert_eq!(input.next(), Some(','));
+
+        assert_eq!(
+            unparse::to_string(&Duration(std::time::Duration::default())),
+            "0s"
+        );
+    }
+
+    #[test]
+    fn duration_errors() {
+        assert_eq!(error::<Duration>("5y"), "unknown duration unit `y`");
+        assert_eq!(
+            error::<Duration>("30m1h"),
+            "duration units must go from largest to smallest"
+        );
+        assert_eq!(
+            error::<Duration>("1s1s"),
+            "duration units must go from largest to smallest"
+        );
+        assert_eq!(error::<Duration>("99999999999h"), "duration is too large");
+        assert_eq!(error::<Duration>("s"), "could not parse duration");
+    }
+
+    #[test]
+    fn size() {
+        for (input, bytes, output) in &[
+            ("512", 512, "512B"),
+            ("4KB", 4000, "4kB"),
+            ("4096B", 4096, "4KiB"),
+            ("3072MiB", 3072 << 20, "3GiB"),
+            ("0GB", 0, "0B"),
+        ] {
+            let size: Size = parse(input.chars()).unwrap();
+            assert_eq!(size.into_inner(), *bytes);
+            assert_eq!(unparse::to_string(&size), *output);
+        }
+
+        assert_eq!(error::<Size>("10mb"), "unknown size unit `mb`");
+        assert_eq!(error::<Size>("20000PiB"), "size is too large");
+    }
+}
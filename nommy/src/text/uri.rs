@@ -0,0 +1,321 @@
+use crate::{
+    eyre,
+    unparse::{Emit, Unparse},
+    Buffer, Parse,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// `Uri` parses an absolute URI, following RFC 3986, such as `https://example.com/a%20b?q#top`.
+///
+/// Each component other than the query is percent-decoded. The URI ends at the first character that isn't allowed
+/// in the component being parsed, so URIs can be picked out of surrounding text
+///
+/// ```
+/// use nommy::{parse, text::Uri};
+///
+/// let uri: Uri = parse("https://user@example.com:8080/a%20b?x=%3D#top".chars()).unwrap();
+/// assert_eq!(uri.scheme, "https");
+/// let authority = uri.authority.unwrap();
+/// assert_eq!(authority.userinfo.as_deref(), Some("user"));
+/// assert_eq!(authority.host, "example.com");
+/// assert_eq!(authority.port, Some(8080));
+/// assert_eq!(uri.path, "/a b");
+/// assert_eq!(uri.query.as_deref(), Some("x=%3D"));
+/// assert_eq!(uri.fragment.as_deref(), Some("top"));
+///
+/// let uri: Uri = parse("mailto:someone@example.com".chars()).unwrap();
+/// assert_eq!(uri.authority, None);
+/// assert_eq!(uri.path, "someone@example.com");
+/// ```
+pub struct Uri {
+    /// The scheme, such as `https`
+    pub scheme: String,
+    /// The authority, present if the scheme is followed by `//`
+    pub authority: Option<Authority>,
+    /// The path, which may be empty
+    pub path: String,
+    /// The query, after the `?`. This isn't decoded, since an encoded `&` or `=`
+    /// would be indistinguishable from the separators in `key=value&key=value` queries
+    pub query: Option<String>,
+    /// The fragment, after the `#`
+    pub fragment: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+/// The authority of a [`Uri`], `[userinfo@]host[:port]`
+pub struct Authority {
+    /// The user information before the `@`
+    pub userinfo: Option<String>,
+    /// The host name or IP address. IPv6 addresses are stored without their brackets
+    pub host: String,
+    /// The port number
+    pub port: Option<u16>,
+}
+
+/// Characters that never need to be percent-encoded
+fn unreserved(c: char) -> bool {
+    c.is_ascii_alphanumeric() || "-._~".contains(c)
+}
+
+fn sub_delim(c: char) -> bool {
+    "!$&'()*+,;=".contains(c)
+}
+
+fn userinfo_char(c: char) -> bool {
+    unreserved(c) || sub_delim(c) || c == ':'
+}
+
+fn host_char(c: char) -> bool {
+    unreserved(c) || sub_delim(c)
+}
+
+fn authority_char(c: char) -> bool {
+    userinfo_char(c) || "@[]".contains(c)
+}
+
+fn path_char(c: char) -> bool {
+    userinfo_char(c) || "@/".contains(c)
+}
+
+fn query_char(c: char) -> bool {
+    path_char(c) || c == '?'
+}
+
+/// Reads the characters allowed by `allowed`, along with percent-encoded characters, without decoding them
+fn raw(input: &mut impl Buffer<char>, allowed: fn(char) -> bool) -> eyre::Result<String> {
+    let mut raw = String::new();
+    loop {
+        match input.peek_ahead(0) {
+            Some('%') => {
+                let hex: String = (1..3).filter_map(|i| input.peek_ahead(i)).collect();
+                if hex.len() != 2 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+                    return Err(eyre::eyre!("invalid percent-encoding `%{}`", hex));
+                }
+                raw.push('%');
+                raw.push_str(&hex);
+                input.fast_forward(3);
+            }
+            Some(c) if allowed(c) => {
+                raw.push(c);
+                input.fast_forward(1);
+            }
+            _ => return Ok(raw),
+        }
+    }
+}
+
+/// Decodes the percent-encoded characters in text read by [`raw`]
+fn decode(raw: &str) -> eyre::Result<String> {
+    let mut bytes = Vec::with_capacity(raw.len());
+    let mut rest = raw.as_bytes();
+    while let Some((&b, tail)) = rest.split_first() {
+        if b == b'%' {
+            // validated by `raw`
+            let hex = std::str::from_utf8(&tail[..2])?;
+            bytes.push(u8::from_str_radix(hex, 16)?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(b);
+            rest = tail;
+        }
+    }
+    String::from_utf8(bytes).map_err(|_| eyre::eyre!("percent-encoded text is not valid UTF-8"))
+}
+
+/// Writes `text`, percent-encoding any characters not allowed by `allowed`
+fn encode(text: &str, allowed: fn(char) -> bool, output: &mut impl Emit<char>) {
+    let mut buf = [0; 4];
+    for c in text.chars() {
+        if allowed(c) {
+            output.emit(c);
+        } else {
+            for b in c.encode_utf8(&mut buf).bytes() {
+                format!("%{:02X}", b).unparse(output);
+            }
+        }
+    }
+}
+
+impl Authority {
+    fn parse_raw(raw: &str) -> eyre::Result<Self> {
+        let (userinfo, hostport) = match raw.find('@') {
+            Some(i) => (Some(decode(&raw[..i])?), &raw[i + 1..]),
+            None => (None, raw),
+        };
+
+        let (host, port) = if let Some(ip) = hostport.strip_prefix('[') {
+            let end = ip
+                .find(']')
+                .ok_or_else(|| eyre::eyre!("unterminated IP literal"))?;
+            (ip[..end].to_owned(), ip[end + 1..].strip_prefix(':'))
+        } else {
+            match hostport.rfind(':') {
+                Some(i) => (decode(&hostport[..i])?, Some(&hostport[i + 1..])),
+                None => (decode(hostport)?, None),
+            }
+        };
+
+        let port = match port {
+            Some("") | None => None,
+            Some(port) => Some(
+                port.parse()
+                    .map_err(|_| eyre::eyre!("invalid port `{}`", port))?,
+            ),
+        };
+        if host.contains(|c: char| "[]@".contains(c)) {
+            return Err(eyre::eyre!("invalid host `{}`", host));
+        }
+
+        Ok(Self {
+            userinfo,
+            host,
+            port,
+        })
+    }
+}
+
+impl Unparse<char> for Authority {
+    fn unparse(&self, output: &mut impl Emit<char>) {
+        if let Some(userinfo) = &self.userinfo {
+            encode(userinfo, userinfo_char, output);
+            output.emit('@');
+        }
+        if self.host.contains(':') {
+            output.emit('[');
+            self.host.unparse(output);
+            output.emit(']');
+        } else {
+            encode(&self.host, host_char, output);
+        }
+        if let Some(port) = self.port {
+            output.emit(':');
+            port.to_string().unparse(output);
+        }
+    }
+}
+
+/// Writes each component, percent-encoding any characters that aren't allowed in it
+impl Unparse<char> for Uri {
+    fn unparse(&self, output: &mut impl Emit<char>) {
+        self.scheme.unparse(output);
+        output.emit(':');
+        if let Some(authority) = &self.authority {
+            "//".unparse(output);
+            authority.unparse(output);
+        }
+        encode(&self.path, path_char, output);
+        if let Some(query) = &self.query {
+            output.emit('?');
+            query.unparse(output);
+        }
+        if let Some(fragment) = &self.fragment {
+            output.emit('#');
+            encode(fragment, query_char, output);
+        }
+    }
+}
+
+impl Parse<char> for Uri {
+    fn parse(input: &mut impl Buffer<char>) -> eyre::Result<Self> {
+        let mut scheme = String::new();
+        while let Some(c) = input.peek_ahead(0) {
+            let allowed = if scheme.is_empty() {
+                c.is_ascii_alphabetic()
+            } else {
+                c.is_ascii_alphanumeric() || "+-.".contains(c)
+            };
+            if !allowed {
+                break;
+            }
+            scheme.push(c);
+            input.fast_forward(1);
+        }
+        if scheme.is_empty() || input.next() != Some(':') {
+            return Err(eyre::eyre!("could not parse URI scheme"));
+        }
+
+        let authority = if input.peek_ahead(0) == Some('/') && input.peek_ahead(1) == Some('/') {
+            input.fast_forward(2);
+            Some(Authority::parse_raw(&raw(input, authority_char)?)?)
+        } else {
+            None
+        };
+
+        // the authority stops at the first `/`, so the path is either empty or starts with `/`
+        let path = decode(&raw(input, path_char)?)?;
+
+        let mut component = |prefix| {
+            if input.peek_ahead(0) == Some(prefix) {
+                input.fast_forward(1);
+                raw(input, query_char).map(Some)
+            } else {
+                Ok(None)
+            }
+        };
+        let query = component('?')?;
+        let fragment = component('#')?.as_deref().map(decode).transpose()?;
+
+        Ok(Self {
+            scheme,
+            authority,
+            path,
+            query,
+            fragment,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse, unparse, IntoBuf};
+
+    #[test]
+    fn round_trip() {
+        for input in &[
+            "https://user:pw@example.com:8080/a%20b/c?x=1&y=%3D#frag",
+            "http://[::1]:80/",
+            "file:///etc/hosts",
+            "urn:isbn:0451450523",
+            "http://example.com?q#",
+        ] {
+            let uri: Uri = parse(input.chars()).unwrap();
+            assert_eq!(unparse::to_string(&uri), *input);
+        }
+    }
+
+    #[test]
+    fn components() {
+        let uri: Uri = parse("http://[::1]:80/%E2%9C%93".chars()).unwrap();
+        let authority = uri.authority.unwrap();
+        assert_eq!(authority.host, "::1");
+        assert_eq!(authority.port, Some(80));
+        assert_eq!(uri.path, "/✓");
+
+        let uri: Uri = parse("file:///etc/hosts".chars()).unwrap();
+        assert_eq!(uri.authority.unwrap().host, "");
+        assert_eq!(uri.path, "/etc/hosts");
+    }
+
+    #[test]
+    fn ends_in_text() {
+        let mut input = "<https://example.com/a>.".chars().into_buf();
+        assert_eq!(input.next(), Some('<'));
+        let uri = Uri::parse(&mut input).unwrap();
+        assert_eq!(uri.path, "/a");
+        assert_eq!(input.collect::<String>(), ">.");
+    }
+
+    #[test]
+    fn errors() {
+        let error = |input: &str| parse::<Uri, _>(input.chars()).unwrap_err().to_string();
+        assert_eq!(error("1http://x"), "could not parse URI scheme");
+        assert_eq!(error("http://x/%zz"), "invalid percent-encoding `%zz`");
+        assert_eq!(
+            error("http://x/%ff"),
+            "percent-encoded text is not valid UTF-8"
+        );
+        assert_eq!(error("http://x:99999/"), "invalid port `99999`");
+        assert_eq!(error("http://[::1/"), "unterminated IP literal");
+    }
+}
@@ -0,0 +1,151 @@
+//! Project wide definitions of trivia, the input ignored between tokens such as whitespace and comments.
+//!
+//! Define the trivia once by implementing [`Trivia`] on a marker type, then use it from
+//! every derive with `#[nommy(ignore_trivia = MyTrivia)]`
+//!
+//! ```
+//! use nommy::{parse, text::{Tag, WhileNot1, WhiteSpace}, trivia::{self, Trivia}, Buffer, Parse};
+//!
+//! #[derive(Parse)]
+//! struct Comment(Tag<"#">, Option<WhileNot1<"\n">>);
+//!
+//! /// Whitespace and `#` comments
+//! struct Hash;
+//! impl Trivia<char> for Hash {
+//!     fn skip(input: &mut impl Buffer<char>) -> bool {
+//!         trivia::skip::<WhiteSpace, _>(input) || trivia::skip::<Comment, _>(input)
+//!     }
+//! }
+//!
+//! #[derive(Parse)]
+//! #[nommy(ignore_trivia = Hash)]
+//! struct Pair(Tag<"a">, Tag<"b">);
+//!
+//! let _: Pair = parse("a # comment\n  b".chars()).unwrap();
+//! ```
+//!
+//! Trivia is dropped by default. Formatters and doc comment extractors can keep it by adding
+//! a field marked with `#[nommy(trivia)]`, which isn't parsed but instead [`Record`]s each run
+//! of ignored input within the type
+//!
+//! ```
+//! use nommy::{parse, text::{AnyOf1, Tag, WhileNot1, WhiteSpace}, Parse};
+//! # #[derive(Parse)]
+//! # struct Comment(Tag<"#">, Option<WhileNot1<"\n">>);
+//!
+//! #[derive(Parse)]
+//! #[nommy(ignore = WhiteSpace, ignore = Comment)]
+//! struct Item {
+//!     #[nommy(trivia)]
+//!     trivia: Vec<String>,
+//!
+//!     #[nommy(prefix = Tag<"fn">, parser = AnyOf1<"abcdefghijklmnopqrstuvwxyz">)]
+//!     name: String,
+//! }
+//!
+//! let item: Item = parse("fn # the name\n main".chars()).unwrap();
+//! assert_eq!(item.trivia, vec![" # the name\n "]);
+//! ```
+
+use std::{iter::FromIterator, marker::PhantomData};
+
+use crate::{
+    eyre,
+    unparse::{Emit, Unparse},
+    Buffer, Parse,
+};
+
+/// Trivia is the input that derived parsers ignore between fields
+pub trait Trivia<T> {
+    /// Skips over a single piece of trivia, returning false if there is none.
+    /// Must read at least 1 element when returning true
+    fn skip(input: &mut impl Buffer<T>) -> bool;
+}
+
+/// Skips over `P` if it can be peeked from the input. Useful for implementing [`Trivia`]
+pub fn skip<P: Parse<T>, T>(input: &mut impl Buffer<T>) -> bool {
+    let mut cursor = input.cursor();
+    if P::peek(&mut cursor) {
+        let pos = cursor.position();
+        input.fast_forward(pos);
+        true
+    } else {
+        false
+    }
+}
+
+/// Collections that record the input ignored by derived parsers, for fields marked `#[nommy(trivia)]`
+pub trait Record<T> {
+    /// Records a single run of ignored input
+    fn record(&mut self, ignored: impl Iterator<Item = T>);
+}
+
+/// Collects each run of ignored input into its own `S`, such as a [`String`]
+impl<T, S: FromIterator<T>> Record<T> for Vec<S> {
+    fn record(&mut self, ignored: impl Iterator<Item = T>) {
+        self.push(ignored.collect());
+    }
+}
+
+/// Skips over any number of `X`, recording the input skipped into `trivia` if there was any.
+/// Used by fields marked `#[nommy(trivia)]`
+pub fn record<X: Parse<T>, T, R: Record<T>>(input: &mut impl Buffer<T>, trivia: &mut R) {
+    let mut cursor = input.cursor();
+    Vec::<X>::peek(&mut cursor);
+    let pos = cursor.position();
+    if pos > 0 {
+        trivia.record(input.cursor().take(pos));
+        input.fast_forward(pos);
+    }
+}
+
+/// `Skip` parses a single piece of the trivia `X`.
+/// `#[nommy(ignore_trivia = X)]` is the same as `#[nommy(ignore = Skip<X>)]`
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Skip<X>(PhantomData<X>);
+
+impl<X> Default for Skip<X> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+/// Writes nothing, since there's no canonical form for trivia
+impl<X, T> Unparse<T> for Skip<X> {
+    fn unparse(&self, _: &mut impl Emit<T>) {}
+}
+
+impl<X: Trivia<T>, T> Parse<T> for Skip<X> {
+    fn parse(input: &mut impl Buffer<T>) -> eyre::Result<Self> {
+        if X::skip(input) {
+            Ok(Self(PhantomData))
+        } else {
+            Err(eyre::eyre!("no trivia found"))
+        }
+    }
+
+    fn peek(input: &mut impl Buffer<T>) -> bool {
+        X::skip(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{text::WhiteSpace, IntoBuf};
+
+    #[test]
+    fn record_runs() {
+        let mut input = "  a\tb".chars().into_buf();
+        let mut trivia: Vec<String> = Vec::new();
+
+        record::<WhiteSpace, _, _>(&mut input, &mut trivia);
+        assert_eq!(input.next(), Some('a'));
+        record::<WhiteSpace, _, _>(&mut input, &mut trivia);
+        assert_eq!(input.next(), Some('b'));
+        // nothing to skip, so nothing is recorded
+        record::<WhiteSpace, _, _>(&mut input, &mut trivia);
+
+        assert_eq!(trivia, vec!["  ", "\t"]);
+    }
+}
@@ -0,0 +1,228 @@
+//! Printing parsed values back out, the reverse of [`Parse`](crate::Parse).
+//!
+//! [`Unparse`] is implemented for the same types as [`Parse`](crate::Parse), so the types that describe
+//! how to parse some input also describe how to write it back out.
+//! Parsers that discard what they read, like [`WhiteSpace`](crate::text::WhiteSpace),
+//! write out a canonical form instead (eg a single space).
+//!
+//! ```
+//! use nommy::{parse, punctuated::Punctuated, text::{AnyOf1, Tag}, unparse};
+//!
+//! let list: Punctuated<AnyOf1<"abc">, Tag<",">> = parse("a,bc,".chars()).unwrap();
+//! assert_eq!(unparse::to_string(&list), "a,bc,");
+//! ```
+
+use std::{fmt, io};
+
+/// `Unparse` writes out the input that would parse into this value
+pub trait Unparse<T> {
+    /// Write the input for this value into `output`
+    fn unparse(&self, output: &mut impl Emit<T>);
+}
+
+/// Output that [`Unparse`] writes into. Implemented for all collections that implement [`Extend`],
+/// such as [`String`] and [`Vec`]
+pub trait Emit<T> {
+    /// Write a single value to the output
+    fn emit(&mut self, value: T);
+}
+
+impl<T, E: Extend<T>> Emit<T> for E {
+    fn emit(&mut self, value: T) {
+        self.extend(Some(value));
+    }
+}
+
+/// Unparses `value` into a new [`String`]
+pub fn to_string<U: Unparse<char> + ?Sized>(value: &U) -> String {
+    let mut output = String::new();
+    value.unparse(&mut output);
+    output
+}
+
+/// Unparses `value` into a new [`Vec`]
+pub fn to_vec<U: Unparse<T> + ?Sized, T>(value: &U) -> Vec<T> {
+    let mut output = Vec::new();
+    value.unparse(&mut output);
+    output
+}
+
+/// Adapts a writer into [`Emit`], storing the first error
+struct Writer<W, E> {
+    inner: W,
+    error: Result<(), E>,
+}
+
+impl<W: fmt::Write> Emit<char> for Writer<W, fmt::Error> {
+    fn emit(&mut self, value: char) {
+        if self.error.is_ok() {
+            self.error = self.inner.write_char(value);
+        }
+    }
+}
+
+impl<W: io::Write> Emit<u8> for Writer<W, io::Error> {
+    fn emit(&mut self, value: u8) {
+        if self.error.is_ok() {
+            self.error = self.inner.write_all(&[value]);
+        }
+    }
+}
+
+/// Unparses `value` into the [`fmt::Write`] writer
+///
+/// # Errors
+/// If the writer returned an error
+pub fn write<U: Unparse<char> + ?Sized>(value: &U, w: impl fmt::Write) -> fmt::Result {
+    let mut writer = Writer {
+        inner: w,
+        error: Ok(()),
+    };
+    value.unparse(&mut writer);
+    writer.error
+}
+
+/// Unparses `value` into the [`io::Write`] writer
+///
+/// # Errors
+/// If the writer returned an error
+pub fn write_bytes<U: Unparse<u8> + ?Sized>(value: &U, w: impl io::Write) -> io::Result<()> {
+    let mut writer = Writer {
+        inner: w,
+        error: Ok(()),
+    };
+    value.unparse(&mut writer);
+    writer.error
+}
+
+impl Unparse<char> for char {
+    fn unparse(&self, output: &mut impl Emit<char>) {
+        output.emit(*self);
+    }
+}
+
+impl Unparse<char> for str {
+    fn unparse(&self, output: &mut impl Emit<char>) {
+        for c in self.chars() {
+            output.emit(c);
+        }
+    }
+}
+
+impl Unparse<char> for String {
+    fn unparse(&self, output: &mut impl Emit<char>) {
+        self.as_str().unparse(output);
+    }
+}
+
+impl Unparse<u8> for u8 {
+    fn unparse(&self, output: &mut impl Emit<u8>) {
+        output.emit(*self);
+    }
+}
+
+impl<U: Unparse<T> + ?Sized, T> Unparse<T> for &U {
+    fn unparse(&self, output: &mut impl Emit<T>) {
+        (**self).unparse(output);
+    }
+}
+
+impl<U: Unparse<T> + ?Sized, T> Unparse<T> for Box<U> {
+    fn unparse(&self, output: &mut impl Emit<T>) {
+        (**self).unparse(output);
+    }
+}
+
+/// Writes nothing for `None`
+impl<U: Unparse<T>, T> Unparse<T> for Option<U> {
+    fn unparse(&self, output: &mut impl Emit<T>) {
+        if let Some(u) = self {
+            u.unparse(output);
+        }
+    }
+}
+
+/// Writes each element in order
+impl<U: Unparse<T>, T> Unparse<T> for [U] {
+    fn unparse(&self, output: &mut impl Emit<T>) {
+        for u in self {
+            u.unparse(output);
+        }
+    }
+}
+
+/// Writes each element in order
+impl<U: Unparse<T>, T, const N: usize> Unparse<T> for [U; N] {
+    fn unparse(&self, output: &mut impl Emit<T>) {
+        self[..].unparse(output);
+    }
+}
+
+/// Writes each element in order
+impl<U: Unparse<T>, T> Unparse<T> for Vec<U> {
+    fn unparse(&self, output: &mut impl Emit<T>) {
+        self[..].unparse(output);
+    }
+}
+
+/// Writes each element in order
+#[cfg(feature = "smallvec")]
+impl<A: smallvec::Array, T> Unparse<T> for smallvec::SmallVec<A>
+where
+    A::Item: Unparse<T>,
+{
+    fn unparse(&self, output: &mut impl Emit<T>) {
+        self[..].unparse(output);
+    }
+}
+
+#[cfg(feature = "smartstring")]
+impl<M: smartstring::SmartStringMode> Unparse<char> for smartstring::SmartString<M> {
+    fn unparse(&self, output: &mut impl Emit<char>) {
+        self.as_str().unparse(output);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        bytes, parse,
+        text::{AnyOf1, LineEnding, OneOf, Tag, WhiteSpace},
+        Vec1,
+    };
+
+    #[test]
+    fn text() {
+        let word: AnyOf1<"abc"> = parse("cab".chars()).unwrap();
+        let c: OneOf<"xyz"> = parse("y".chars()).unwrap();
+        let list: Vec1<Tag<".">> = parse("..".chars()).unwrap();
+
+        let mut output = String::new();
+        word.unparse(&mut output);
+        WhiteSpace.unparse(&mut output);
+        c.unparse(&mut output);
+        Some(list).unparse(&mut output);
+        LineEnding.unparse(&mut output);
+        assert_eq!(output, "cab y..\n");
+    }
+
+    #[test]
+    fn bytes() {
+        let word: bytes::AnyOf1<b"abc"> = parse("cab".bytes()).unwrap();
+        let mut output = Vec::new();
+        write_bytes(&bytes::Tag::<b"<">, &mut output).unwrap();
+        write_bytes(&word, &mut output).unwrap();
+        assert_eq!(output, b"<cab");
+    }
+
+    #[test]
+    fn round_trip() {
+        let input = "a.b.c";
+        let value: crate::punctuated::Punctuated<OneOf<"abc">, Tag<".">> =
+            parse(input.chars()).unwrap();
+        let mut output = String::new();
+        write(&value, &mut output).unwrap();
+        assert_eq!(output, input);
+    }
+}
@@ -1,56 +1,194 @@
 //! Complex vec parsing functions
+//!
+//! Every repetition, including the [`Parse`] impls of [`Vec`](std::vec::Vec) and [`Vec1`](crate::Vec1),
+//! stops with an error if an element is parsed without reading any input, rather than parsing it forever.
+//! [`with_max_len`] also limits how many elements a repetition can have, so that input can't make one grow without bound.
+//!
+//! ```
+//! use nommy::{message::Message, parse, text::Tag, vec};
+//!
+//! let err = vec::with_max_len(2, || parse::<Vec<Tag<".">>, _>("...".chars())).unwrap_err();
+//! assert_eq!(err.downcast_ref(), Some(&Message::TooLong { max_len: 2 }));
+//! assert!(parse::<Vec<Tag<".">>, _>("...".chars()).is_ok());
+//! ```
+
+use std::{cell::Cell, convert::TryInto, error::Error, iter::FromIterator};
+
+use crate::{eyre, message::Message, Buffer, Context, Parse};
+
+thread_local! {
+    static MAX_LEN: Cell<usize> = Cell::new(usize::MAX);
+}
+
+/// Puts back the limit from before [`with_max_len`] when dropped, even if parsing panicked
+struct Restore {
+    max_len: usize,
+}
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        MAX_LEN.with(|max| max.set(self.max_len));
+    }
+}
+
+/// Runs `f` with a limit on how many elements any repetition parsed on the current thread can have.
+/// Parsing more is an error, rather than the end of the repetition.
+///
+/// Scopes can be nested, and the innermost limit is used. Outside of them, there's no limit
+pub fn with_max_len<R>(max_len: usize, f: impl FnOnce() -> R) -> R {
+    let _restore = Restore {
+        max_len: MAX_LEN.with(|max| max.replace(max_len)),
+    };
+    f()
+}
 
-use std::{convert::TryInto, error::Error};
+/// The limit set with [`with_max_len`], if there is one
+#[must_use]
+pub fn max_len() -> Option<usize> {
+    match MAX_LEN.with(Cell::get) {
+        usize::MAX => None,
+        max_len => Some(max_len),
+    }
+}
 
-use crate::{Buffer, Parse};
+/// Checks the `count`th element of a repetition, where `repeats` is whether it read nothing,
+/// so that the next element would be parsed from the same state as it was.
+/// Used by the peek functions of derived repetitions
+///
+/// # Errors
+/// If parsing would repeat forever, or there are more elements than the [`max_len`]
+pub fn check_element(count: usize, repeats: bool) -> eyre::Result<()> {
+    if repeats {
+        return Err(eyre::Report::new(Message::NoProgress));
+    }
+    let max_len = MAX_LEN.with(Cell::get);
+    if count > max_len {
+        return Err(eyre::Report::new(Message::TooLong { max_len }));
+    }
+    Ok(())
+}
 
-/// Parses buffer into a vector, with each value being seperated by `SeperatedBy` and ignoreing any `Ignore`
-pub fn parse_vec<P, Q, Ignore, T, B>(max: usize, input: &mut B) -> eyre::Result<Vec<Q>>
+/// The outcome of attempting to parse one more element
+enum Step<Q> {
+    /// An element was parsed. If the flag is set, nothing was read, and the next element would be parsed
+    /// from the same state, so parsing would never end
+    Next(Q, bool),
+    /// No more elements could be parsed, with the error that stopped parsing
+    Stop(eyre::Report),
+    /// An element was parsed but could not be converted
+    Fail(eyre::Report),
+}
+
+/// Collects the elements produced by `step` into `C`, parsing at most `max` values.
+///
+/// If fewer than `min` values were parsed, the error that stopped parsing is kept as the source.
+/// An element that would repeat forever, or one past the [`max_len`], fails
+fn collect<Q, C: FromIterator<Q>>(
+    min: usize,
+    max: usize,
+    mut step: impl FnMut() -> Step<Q>,
+) -> eyre::Result<C> {
+    let mut count = 0;
+    let mut stopped = None;
+    let mut failed = None;
+    let output = std::iter::from_fn(|| {
+        if count >= max {
+            return None;
+        }
+        match step() {
+            Step::Next(q, repeats) => {
+                count += 1;
+                if let Err(err) = check_element(count, repeats) {
+                    failed = Some(err);
+                    return None;
+                }
+                Some(q)
+            }
+            Step::Stop(err) => {
+                stopped = Some(err);
+                None
+            }
+            Step::Fail(err) => {
+                failed = Some(err);
+                None
+            }
+        }
+    })
+    .collect();
+
+    if let Some(err) = failed {
+        return Err(err);
+    }
+    if count >= min {
+        return Ok(output);
+    }
+    let message = format!(
+        "could not parse enough for vec, expected at least {} but found {}",
+        min, count
+    );
+    match stopped {
+        Some(err) => Err(err).wrap_err(message),
+        None => Err(eyre::eyre!(message)),
+    }
+}
+
+/// Converts a parsed value into an element of the output, where `repeats` is whether parsing would never end
+fn convert<P, Q>(p: P, repeats: bool) -> Step<Q>
+where
+    P: TryInto<Q>,
+    <P as TryInto<Q>>::Error: Error + Send + Sync + 'static,
+{
+    match p.try_into() {
+        Ok(q) => Step::Next(q, repeats),
+        Err(err) => Step::Fail(err.into()),
+    }
+}
+
+/// Parses buffer into a collection `C`, such as a [`Vec`], ignoring any `Ignore` between values.
+/// Parses at most `max` values
+///
+/// # Errors
+/// If fewer than `min` values could be parsed, the error from the value that failed to parse is kept as the source
+pub fn parse_vec<P, Q, Ignore, T, B, C>(min: usize, max: usize, input: &mut B) -> eyre::Result<C>
 where
     Ignore: Parse<T>,
     P: Parse<T>,
     P: TryInto<Q>,
     <P as TryInto<Q>>::Error: Error + Send + Sync + 'static,
     B: Buffer<T>,
+    C: FromIterator<Q>,
 {
-    if max == 0 {
-        return Ok(vec![]);
-    }
-
-    let mut cursor = input.cursor();
-    let first: Q = match P::parse(&mut cursor) {
-        Ok(p) => p.try_into()?,
-        Err(_) => return Ok(vec![]),
-    };
-    let pos = cursor.position();
-    input.fast_forward(pos);
-
-    let mut output = vec![first];
-    loop {
-        if max == output.len() {
-            break;
-        }
-
+    let mut first = true;
+    collect(min, max, || {
         let mut cursor = input.cursor();
-        Vec::<Ignore>::peek(&mut cursor);
+        if !first {
+            Vec::<Ignore>::peek(&mut cursor);
+        }
 
-        match P::parse(&mut cursor) {
-            Ok(p) => output.push(p.try_into()?),
-            Err(_) => break,
+        let p = match P::parse(&mut cursor) {
+            Ok(p) => p,
+            Err(err) => return Step::Stop(err),
         };
 
         let pos = cursor.position();
         input.fast_forward(pos);
-    }
-
-    Ok(output)
+        // the ignored input isn't skipped before the first element, so only later elements can repeat
+        let repeats = pos == 0 && !first;
+        first = false;
+        convert(p, repeats)
+    })
 }
 
-/// Parses buffer into a vector, with each value being seperated by `SeperatedBy` and ignoreing any `Ignore`
-pub fn parse_vec_seperated_by<P, Q, SeperatedBy, Ignore, T, B>(
+/// Parses buffer into a collection `C`, with each value being seperated by `SeperatedBy` and ignoreing any `Ignore`.
+/// Parses at most `max` values
+///
+/// # Errors
+/// If fewer than `min` values could be parsed, the error from the value or seperator that failed to parse is kept as the source
+pub fn parse_vec_seperated_by<P, Q, SeperatedBy, Ignore, T, B, C>(
+    min: usize,
     max: usize,
     input: &mut B,
-) -> eyre::Result<Vec<Q>>
+) -> eyre::Result<C>
 where
     SeperatedBy: Parse<T>,
     Ignore: Parse<T>,
@@ -58,50 +196,44 @@ where
     P: TryInto<Q>,
     <P as TryInto<Q>>::Error: Error + Send + Sync + 'static,
     B: Buffer<T>,
+    C: FromIterator<Q>,
 {
-    if max == 0 {
-        return Ok(vec![]);
-    }
-
-    let mut cursor = input.cursor();
-    let first: Q = match P::parse(&mut cursor) {
-        Ok(p) => p.try_into()?,
-        Err(_) => return Ok(vec![]),
-    };
-    let pos = cursor.position();
-    input.fast_forward(pos);
-
-    let mut output = vec![first];
-    loop {
-        if max == output.len() {
-            break;
-        }
-
+    let mut first = true;
+    collect(min, max, || {
         let mut cursor = input.cursor();
 
-        Vec::<Ignore>::peek(&mut cursor);
-        if !SeperatedBy::peek(&mut cursor) {
-            break;
+        if !first {
+            Vec::<Ignore>::peek(&mut cursor);
+            if let Err(err) = SeperatedBy::parse(&mut cursor) {
+                return Step::Stop(err);
+            }
+            Vec::<Ignore>::peek(&mut cursor);
         }
-        Vec::<Ignore>::peek(&mut cursor);
 
-        match P::parse(&mut cursor) {
-            Ok(p) => output.push(p.try_into()?),
-            Err(_) => break,
+        let p = match P::parse(&mut cursor) {
+            Ok(p) => p,
+            Err(err) => return Step::Stop(err),
         };
 
         let pos = cursor.position();
         input.fast_forward(pos);
-    }
-
-    Ok(output)
+        // the first element isn't preceded by a seperator, so only later elements can repeat
+        let repeats = pos == 0 && !first;
+        first = false;
+        convert(p, repeats)
+    })
 }
 
-/// Parses buffer into a vector, with each value being seperated and trailed by `SeperatedBy` and ignoreing any `Ignore`
-pub fn parse_vec_seperated_by_trailing<P, Q, SeperatedBy, Ignore, T, B>(
+/// Parses buffer into a collection `C`, with each value being seperated and trailed by `SeperatedBy` and ignoreing any `Ignore`.
+/// Parses at most `max` values
+///
+/// # Errors
+/// If fewer than `min` values could be parsed, the error from the value or seperator that failed to parse is kept as the source
+pub fn parse_vec_seperated_by_trailing<P, Q, SeperatedBy, Ignore, T, B, C>(
+    min: usize,
     max: usize,
     input: &mut B,
-) -> eyre::Result<Vec<Q>>
+) -> eyre::Result<C>
 where
     SeperatedBy: Parse<T>,
     Ignore: Parse<T>,
@@ -109,38 +241,40 @@ where
     P: TryInto<Q>,
     <P as TryInto<Q>>::Error: Error + Send + Sync + 'static,
     B: Buffer<T>,
+    C: FromIterator<Q>,
 {
-    let mut output = vec![];
-    loop {
-        if max == output.len() {
-            break;
-        }
-
+    collect(min, max, || {
         let mut cursor = input.cursor();
-        let q: Q = match P::parse(&mut cursor) {
-            Ok(p) => p.try_into()?,
-            Err(_) => break,
+        let q = match P::parse(&mut cursor) {
+            Ok(p) => match convert(p, false) {
+                Step::Next(q, _) => q,
+                step => return step,
+            },
+            Err(err) => return Step::Stop(err),
         };
 
         Vec::<Ignore>::peek(&mut cursor);
-        if !SeperatedBy::peek(&mut cursor) {
-            break;
+        if let Err(err) = SeperatedBy::parse(&mut cursor) {
+            return Step::Stop(err);
         }
         Vec::<Ignore>::peek(&mut cursor);
         let pos = cursor.position();
         input.fast_forward(pos);
 
-        output.push(q);
-    }
-
-    Ok(output)
+        Step::Next(q, pos == 0)
+    })
 }
 
-/// Parses buffer into a vector, with each value being seperated and trailed by `SeperatedBy` and ignoreing any `Ignore`
-pub fn parse_vec_seperated_by_maybe_trailing<P, Q, SeperatedBy, Ignore, T, B>(
+/// Parses buffer into a collection `C`, with each value being seperated and optionally trailed by `SeperatedBy` and ignoreing any `Ignore`.
+/// Parses at most `max` values
+///
+/// # Errors
+/// If fewer than `min` values could be parsed, the error from the value or seperator that failed to parse is kept as the source
+pub fn parse_vec_seperated_by_maybe_trailing<P, Q, SeperatedBy, Ignore, T, B, C>(
+    min: usize,
     max: usize,
     input: &mut B,
-) -> eyre::Result<Vec<Q>>
+) -> eyre::Result<C>
 where
     SeperatedBy: Parse<T>,
     Ignore: Parse<T>,
@@ -148,46 +282,159 @@ where
     P: TryInto<Q>,
     <P as TryInto<Q>>::Error: Error + Send + Sync + 'static,
     B: Buffer<T>,
+    C: FromIterator<Q>,
 {
-    let mut output = vec![];
-    loop {
-        if max == output.len() {
-            break;
+    // a missing seperator ends the collection, but only after the value before it
+    let mut missing_seperator = None;
+    collect(min, max, || {
+        if let Some(err) = missing_seperator.take() {
+            return Step::Stop(err);
         }
 
         let mut cursor = input.cursor();
-        match P::parse(&mut cursor) {
-            Ok(p) => output.push(p.try_into()?),
-            Err(_) => break,
+        let p = match P::parse(&mut cursor) {
+            Ok(p) => p,
+            Err(err) => return Step::Stop(err),
         };
         let pos = cursor.position();
         input.fast_forward(pos);
+        let mut read = pos;
 
         Vec::<Ignore>::peek(input);
 
         let mut cursor = input.cursor();
-        if !SeperatedBy::peek(&mut cursor) {
-            break;
+        match SeperatedBy::parse(&mut cursor) {
+            Ok(_) => {
+                let pos = cursor.position();
+                input.fast_forward(pos);
+                read += pos;
+                Vec::<Ignore>::peek(input);
+            }
+            Err(err) => missing_seperator = Some(err),
         }
-        let pos = cursor.position();
-        input.fast_forward(pos);
 
-        Vec::<Ignore>::peek(input);
+        // a missing seperator ends the collection, so only an element and seperator that read nothing repeat
+        convert(p, read == 0 && missing_seperator.is_none())
+    })
+}
+
+/// A parser that never matches, for a [`parse_vec_recover`] that can only end at a missing separator
+/// or the end of the input
+#[derive(Debug)]
+pub enum Never {}
+
+impl<T> Parse<T> for Never {
+    fn parse(_: &mut impl Buffer<T>) -> eyre::Result<Self> {
+        Err(eyre::eyre!("`Never` can't be parsed"))
+    }
+
+    fn peek(_: &mut impl Buffer<T>) -> bool {
+        false
     }
+}
+
+/// Parses buffer into a collection `C` of `Result`s, with each value being seperated by `SeperatedBy` and ignoring any `Ignore`.
+/// Parses at most `max` values.
+///
+/// A value that fails to parse, or isn't followed by `SeperatedBy`, `Until` or the end of the input,
+/// is collected as its error, and the input is skipped up to the next `SeperatedBy`,
+/// so the values after it are still parsed. The skipping stops early at the end of the input, or where `Until` can be peeked,
+/// such as the suffix of the list. The collection ends at a missing `SeperatedBy`, `Until` or the end of the input.
+///
+/// ```
+/// use nommy::{eyre, text::{AnyOf1, Tag, WhiteSpace}, vec::{parse_vec_recover, Never}, IntoBuf};
+///
+/// let mut input = "1, 2x, 3".chars().into_buf();
+/// let values = parse_vec_recover::<AnyOf1<"0123456789">, usize, Tag<",">, Never, WhiteSpace, _, _, Vec<eyre::Result<usize>>>(
+///     0,
+///     usize::MAX,
+///     &mut input,
+/// )
+/// .unwrap();
+/// assert_eq!(values[0].as_ref().unwrap(), &1);
+/// assert!(values[1].is_err());
+/// assert_eq!(values[2].as_ref().unwrap(), &3);
+/// ```
+///
+/// # Errors
+/// If fewer than `min` values, including the ones that failed, could be parsed
+pub fn parse_vec_recover<P, Q, SeperatedBy, Until, Ignore, T, B, C>(
+    min: usize,
+    max: usize,
+    input: &mut B,
+) -> eyre::Result<C>
+where
+    SeperatedBy: Parse<T>,
+    Until: Parse<T>,
+    Ignore: Parse<T>,
+    P: Parse<T>,
+    P: TryInto<Q>,
+    <P as TryInto<Q>>::Error: Error + Send + Sync + 'static,
+    B: Buffer<T>,
+    C: FromIterator<eyre::Result<Q>>,
+{
+    let mut first = true;
+    collect(min, max, || {
+        let mut cursor = input.cursor();
+        if !first {
+            Vec::<Ignore>::peek(&mut cursor);
+            if let Err(err) = SeperatedBy::parse(&mut cursor) {
+                return Step::Stop(err);
+            }
+        }
+        Vec::<Ignore>::peek(&mut cursor);
+        // nothing more to parse, after a trailing seperator or in an empty collection
+        if Until::peek(&mut cursor.cursor()) || cursor.cursor().next().is_none() {
+            return Step::Stop(eyre::eyre!("reached the end of the collection"));
+        }
+
+        let value = P::parse(&mut cursor).and_then(|p| {
+            // a value that only parsed the start of the element is as wrong as one that failed
+            let mut after = cursor.cursor();
+            Vec::<Ignore>::peek(&mut after);
+            if after.cursor().next().is_none()
+                || SeperatedBy::peek(&mut after.cursor())
+                || Until::peek(&mut after)
+            {
+                Ok(p)
+            } else {
+                Err(eyre::Report::new(Message::Incomplete))
+            }
+        });
+        let mut read = cursor.position();
+        input.fast_forward(read);
+        let value = match value {
+            Ok(p) => p.try_into().map_err(eyre::Report::new),
+            Err(err) => {
+                // skip the rest of the value, up to where the collection could continue or end
+                while !SeperatedBy::peek(&mut input.cursor()) && !Until::peek(&mut input.cursor()) {
+                    if input.next().is_none() {
+                        break;
+                    }
+                    read += 1;
+                }
+                Err(err)
+            }
+        };
 
-    Ok(output)
+        // the first value isn't preceded by a seperator, so only later values can repeat
+        let repeats = read == 0 && !first;
+        first = false;
+        Step::Next(value, repeats)
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use crate::{
-        text::{AnyOf1, Tag, WhiteSpace},
+        message::Message,
+        text::{AnyOf1, OneOf, Tag, WhiteSpace},
         IntoBuf,
     };
 
     use super::{
-        parse_vec, parse_vec_seperated_by, parse_vec_seperated_by_maybe_trailing,
-        parse_vec_seperated_by_trailing,
+        max_len, parse_vec, parse_vec_seperated_by, parse_vec_seperated_by_maybe_trailing,
+        parse_vec_seperated_by_trailing, with_max_len,
     };
 
     #[test]
@@ -199,7 +446,8 @@ mod tests {
             WhiteSpace,                           // ignoring any whitespaces
             _,
             _,
-        >(2, &mut input)
+            Vec<_>,
+        >(0, 2, &mut input)
         .unwrap();
         assert_eq!(res, vec!["foo".to_string(), "bar".to_string()]);
         assert_eq!(input.collect::<String>(), " baz...".to_string())
@@ -215,7 +463,8 @@ mod tests {
             WhiteSpace,                           // ignoring any whitespaces
             _,
             _,
-        >(usize::MAX, &mut input) // parse as many elements that can be found
+            Vec<_>,
+        >(0, usize::MAX, &mut input) // parse as many elements that can be found
         .unwrap();
         assert_eq!(
             res,
@@ -234,7 +483,8 @@ mod tests {
             WhiteSpace,                           // ignoring any whitespaces
             _,
             _,
-        >(2, &mut input) // parse up to 2 elements and no more
+            Vec<_>,
+        >(0, 2, &mut input) // parse up to 2 elements and no more
         .unwrap();
         assert_eq!(res, vec!["foo".to_string(), "bar".to_string()]);
         assert_eq!(input.collect::<String>(), " , baz,...".to_string())
@@ -250,7 +500,8 @@ mod tests {
             WhiteSpace,           // ignoring any whitespaces
             _,
             _,
-        >(usize::MAX, &mut input)
+            Vec<_>,
+        >(0, usize::MAX, &mut input)
         .unwrap();
         assert_eq!(res, vec![123, 321, 0]);
         assert_eq!(input.collect::<String>(), ",...".to_string())
@@ -266,7 +517,8 @@ mod tests {
             WhiteSpace,           // ignoring any whitespaces
             _,
             _,
-        >(usize::MAX, &mut input)
+            Vec<_>,
+        >(0, usize::MAX, &mut input)
         .unwrap();
         assert_eq!(res, vec![123, 321]);
         assert_eq!(input.collect::<String>(), "0...".to_string())
@@ -282,7 +534,8 @@ mod tests {
             WhiteSpace,           // ignoring any whitespaces
             _,
             _,
-        >(usize::MAX, &mut input)
+            Vec<_>,
+        >(0, usize::MAX, &mut input)
         .unwrap();
         assert_eq!(res, vec![123, 321, 0]);
         assert_eq!(input.collect::<String>(), "...".to_string())
@@ -298,9 +551,90 @@ mod tests {
             WhiteSpace,           // ignoring any whitespaces
             _,
             _,
-        >(usize::MAX, &mut input)
+            Vec<_>,
+        >(0, usize::MAX, &mut input)
         .unwrap();
         assert_eq!(res, vec![123, 321, 0]);
         assert_eq!(input.collect::<String>(), "...".to_string())
     }
+
+    #[test]
+    fn sequence_collect() {
+        let mut input = "a, b, c".chars().into_buf();
+        let res: String = parse_vec_seperated_by::<OneOf<"abc">, char, Tag<",">, WhiteSpace, _, _, _>(
+            0,
+            usize::MAX,
+            &mut input,
+        )
+        .unwrap();
+        assert_eq!(res, "abc");
+    }
+
+    #[test]
+    fn sequence_no_progress() {
+        let mut input = "a;b".chars().into_buf();
+        let err = parse_vec::<Option<OneOf<"ab">>, Option<OneOf<"ab">>, WhiteSpace, _, _, Vec<_>>(
+            0,
+            usize::MAX,
+            &mut input,
+        )
+        .unwrap_err();
+        assert_eq!(err.downcast_ref(), Some(&Message::NoProgress));
+
+        // the first element isn't preceded by a seperator, so it can be empty
+        let mut input = ",b".chars().into_buf();
+        let res = parse_vec_seperated_by::<
+            Option<OneOf<"ab">>,
+            Option<OneOf<"ab">>,
+            Tag<",">,
+            WhiteSpace,
+            _,
+            _,
+            Vec<_>,
+        >(0, usize::MAX, &mut input)
+        .unwrap();
+        assert_eq!(res.len(), 2);
+        assert!(res[0].is_none());
+    }
+
+    #[test]
+    fn sequence_min_source() {
+        let mut input = "foo, bar;".chars().into_buf();
+        let err = parse_vec_seperated_by::<
+            AnyOf1<"abcdefghijklmnopqrstuvwxyz">,
+            String,
+            Tag<",">,
+            WhiteSpace,
+            _,
+            _,
+            Vec<_>,
+        >(3, usize::MAX, &mut input)
+        .unwrap_err();
+        assert_eq!(
+            format!("{}", err),
+            "could not parse enough for vec, expected at least 3 but found 2"
+        );
+        assert_eq!(
+            format!("{}", err.root_cause()),
+            "failed to parse tag \",\", found \";\""
+        );
+    }
+
+    #[test]
+    fn nested_max_len() {
+        with_max_len(3, || {
+            assert_eq!(with_max_len(1, max_len), Some(1));
+            assert_eq!(max_len(), Some(3));
+        });
+        assert_eq!(max_len(), None);
+
+        // other threads aren't limited
+        with_max_len(1, || {
+            let len = std::thread::spawn(|| {
+                let mut input = "...".chars().into_buf();
+                crate::Parse::parse(&mut input).map(|tags: Vec<Tag<".">>| tags.len())
+            });
+            assert_eq!(len.join().unwrap().unwrap(), 3);
+        });
+    }
 }